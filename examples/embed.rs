@@ -0,0 +1,68 @@
+// A minimal gpui app embedding time2rust's cards: a self-updating grid, and a
+// single card the app redraws itself. Run with `cargo run --example embed`.
+
+use chrono_tz::Tz;
+use gpui::{
+    App, Application, Bounds, Context, Entity, Window, WindowBounds, WindowOptions, div,
+    prelude::*, px, rgb, size,
+};
+use gpui_component::Root;
+use time2rust::card::{CardOptions, WorldTimeCard, WorldTimeGrid};
+
+struct Dashboard {
+    grid: Entity<WorldTimeGrid>,
+}
+
+impl Render for Dashboard {
+    fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+        div()
+            .size_full()
+            .flex()
+            .flex_col()
+            .gap_6()
+            .p_6()
+            .bg(rgb(0xffffff))
+            .child("Office clocks")
+            .child(self.grid.clone())
+            .child("Status page zone")
+            // Shows the time as of this view's last render; notify from your own
+            // timer, or use a one-city grid, to keep it live
+            .child(WorldTimeCard::new("UTC", Tz::UTC).options(CardOptions {
+                large: true,
+                ..Default::default()
+            }))
+    }
+}
+
+fn main() {
+    Application::new().run(|cx: &mut App| {
+        gpui_component::init(cx);
+        let bounds = Bounds::centered(None, size(px(900.0), px(600.0)), cx);
+        cx.open_window(
+            WindowOptions {
+                window_bounds: Some(WindowBounds::Windowed(bounds)),
+                ..Default::default()
+            },
+            |window, cx| {
+                let grid = cx.new(|cx| {
+                    WorldTimeGrid::new(
+                        vec![
+                            ("Berlin".into(), Tz::Europe__Berlin),
+                            ("New York".into(), Tz::America__New_York),
+                            ("Tokyo".into(), Tz::Asia__Tokyo),
+                        ],
+                        CardOptions {
+                            show_seconds: true,
+                            home: Some(Tz::Europe__Berlin),
+                            ..Default::default()
+                        },
+                        cx,
+                    )
+                });
+                let dashboard = cx.new(|_| Dashboard { grid });
+                cx.new(|cx| Root::new(dashboard, window, cx))
+            },
+        )
+        .unwrap();
+    });
+}
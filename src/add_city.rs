@@ -0,0 +1,295 @@
+use crate::{WorldTimeApp, config::CityConfig, deep_link, zone};
+use chrono::Utc;
+use chrono_tz::Tz;
+use gpui::{App, Context, Entity, SharedString, Task, Window, div, prelude::*, px, rgb};
+use gpui_component::{
+    ActiveTheme as _, Disableable as _, Sizable, WindowExt as _,
+    button::{Button, ButtonVariants as _},
+    dialog::DialogButtonProps,
+    h_flex,
+    input::{Input, InputEvent, InputState},
+    v_flex,
+};
+use std::time::Duration;
+use time2rust::format;
+
+// Wait for a pause in typing before looking the zone up
+const LOOKUP_DELAY: Duration = Duration::from_millis(200);
+
+// What the city form is for
+#[derive(Clone)]
+enum Purpose {
+    Add,
+    // A saved city whose zone didn't resolve; the rest of its settings are kept
+    Repair(Box<CityConfig>),
+}
+
+impl Purpose {
+    fn title(&self) -> &'static str {
+        match self {
+            Purpose::Add => "Add city",
+            Purpose::Repair(_) => "Edit timezone",
+        }
+    }
+
+    fn ok_text(&self) -> &'static str {
+        match self {
+            Purpose::Add => "Add",
+            Purpose::Repair(_) => "Save",
+        }
+    }
+}
+
+// Opens the add-city dialog, optionally prefilled (e.g. from a time2rust://add link)
+pub fn open_add_city(
+    app: Entity<WorldTimeApp>,
+    name: &str,
+    timezone: &str,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    open_city_form(app, name, timezone, Purpose::Add, window, cx);
+}
+
+// The same form for an error card, prefilled with the zone that didn't resolve
+pub fn open_edit_timezone(
+    app: Entity<WorldTimeApp>,
+    broken: CityConfig,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    let (name, timezone) = (broken.name.clone(), broken.timezone.clone());
+    open_city_form(
+        app,
+        &name,
+        &timezone,
+        Purpose::Repair(Box::new(broken)),
+        window,
+        cx,
+    );
+}
+
+fn open_city_form(
+    app: Entity<WorldTimeApp>,
+    name: &str,
+    timezone: &str,
+    purpose: Purpose,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    let home_tz = app.read(cx).home_tz;
+    let form = cx.new(|cx| AddCityForm::new(name, timezone, home_tz, window, cx));
+    window.open_dialog(cx, move |dialog, _window, _cx| {
+        let ok_text = purpose.ok_text();
+        dialog
+            .title(purpose.title())
+            .child(form.clone())
+            .confirm()
+            // The button stays disabled until the timezone resolves
+            .footer({
+                let form = form.clone();
+                move |ok, cancel, window, cx| {
+                    let add = if form.read(cx).city_config(cx).is_ok() {
+                        ok(window, cx)
+                    } else {
+                        Button::new("ok")
+                            .label(ok_text)
+                            .primary()
+                            .disabled(true)
+                            .into_any_element()
+                    };
+                    vec![cancel(window, cx), add]
+                }
+            })
+            .button_props(DialogButtonProps::default().ok_text(ok_text))
+            .on_ok({
+                let form = form.clone();
+                let app = app.clone();
+                let purpose = purpose.clone();
+                move |_, window, cx| match form.read(cx).city_config(cx) {
+                    Ok(city) => {
+                        app.update(cx, |app, cx| match &purpose {
+                            Purpose::Add => app.add_city(city, window, cx),
+                            Purpose::Repair(broken) => {
+                                app.repair_city(&broken.id, city, window, cx)
+                            }
+                        });
+                        true
+                    }
+                    Err(_) => false,
+                }
+            })
+    });
+}
+
+enum Lookup {
+    Empty,
+    Found(Tz),
+    // Not a zone id, but close to this one
+    Suggested(SharedString, Tz),
+    Unknown(SharedString),
+}
+
+fn look_up(query: &str) -> Lookup {
+    let query = query.trim();
+    if query.is_empty() {
+        return Lookup::Empty;
+    }
+    match zone::resolve_zone(query) {
+        Ok(tz) => Lookup::Found(tz),
+        Err(err) => match zone::suggest_zone(query) {
+            Some(tz) => Lookup::Suggested(err.to_string().into(), tz),
+            None => Lookup::Unknown(err.to_string().into()),
+        },
+    }
+}
+
+pub struct AddCityForm {
+    name_input: Entity<InputState>,
+    timezone_input: Entity<InputState>,
+    home_tz: Tz,
+    lookup: Lookup,
+    // Replaced on every keystroke, which cancels the previous lookup
+    pending_lookup: Task<()>,
+}
+
+impl AddCityForm {
+    fn new(
+        name: &str,
+        timezone: &str,
+        home_tz: Tz,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let name = name.to_string();
+        let timezone = timezone.to_string();
+        let name_input = cx.new(|cx| {
+            InputState::new(window, cx)
+                .placeholder("Name, e.g. Singapore office")
+                .default_value(name)
+        });
+        let timezone_input = cx.new(|cx| {
+            InputState::new(window, cx)
+                .placeholder("Timezone, e.g. Asia/Singapore")
+                .default_value(timezone.clone())
+        });
+        // The preview shows the name too
+        cx.subscribe(&name_input, |_, _, event, cx| {
+            if let InputEvent::Change = event {
+                cx.notify();
+            }
+        })
+        .detach();
+        cx.subscribe(&timezone_input, |this, input, event, cx| {
+            if let InputEvent::Change = event {
+                let query = input.read(cx).value().to_string();
+                this.pending_lookup = cx.spawn(async move |this, cx| {
+                    cx.background_executor().timer(LOOKUP_DELAY).await;
+                    this.update(cx, |this, cx| {
+                        this.lookup = look_up(&query);
+                        cx.notify();
+                    })
+                    .ok();
+                });
+            }
+        })
+        .detach();
+
+        AddCityForm {
+            name_input,
+            timezone_input,
+            home_tz,
+            lookup: look_up(&timezone),
+            pending_lookup: Task::ready(()),
+        }
+    }
+
+    // Validates the inputs; the name defaults to the zone's city
+    fn city_config(&self, cx: &App) -> Result<CityConfig, String> {
+        let timezone = self.timezone_input.read(cx).value();
+        let tz = zone::resolve_zone(&timezone).map_err(|err| err.to_string())?;
+        Ok(CityConfig::new(&self.name(tz, cx), tz.name(), false))
+    }
+
+    fn name(&self, tz: Tz, cx: &App) -> String {
+        let name = self.name_input.read(cx).value().trim().to_string();
+        if name.is_empty() {
+            deep_link::default_city_name(tz.name())
+        } else {
+            name
+        }
+    }
+
+    fn use_suggestion(&mut self, tz: Tz, window: &mut Window, cx: &mut Context<Self>) {
+        self.timezone_input
+            .update(cx, |input, cx| input.set_value(tz.name(), window, cx));
+        self.lookup = Lookup::Found(tz);
+        cx.notify();
+    }
+}
+
+// The card as it would look: local time, offset and difference from home
+fn preview(name: String, tz: Tz, home_tz: Tz, cx: &App) -> impl IntoElement {
+    let now = Utc::now();
+    let offset = zone::utc_offset_minutes(tz, now);
+    let diff = offset - zone::utc_offset_minutes(home_tz, now);
+    v_flex()
+        .gap_1()
+        .p_3()
+        .rounded(px(8.0))
+        .border_1()
+        .border_color(cx.theme().border)
+        .child(div().child(name).font_weight(gpui::FontWeight::BOLD))
+        .child(
+            div()
+                .child(format::clock_time(now.with_timezone(&tz).time(), false))
+                .text_2xl(),
+        )
+        .child(
+            div()
+                .child(format!(
+                    "{} · {} · {}",
+                    tz.name(),
+                    zone::format_utc_offset(offset),
+                    format::describe_diff(diff)
+                ))
+                .text_xs()
+                .text_color(rgb(0x6b7280)),
+        )
+}
+
+impl Render for AddCityForm {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let error =
+            |message: SharedString| div().child(message).text_sm().text_color(rgb(0xef4444));
+        v_flex()
+            .gap_2()
+            .child(Input::new(&self.name_input))
+            .child(Input::new(&self.timezone_input))
+            .map(|this| match &self.lookup {
+                Lookup::Empty => this,
+                Lookup::Found(tz) => this.child(preview(self.name(*tz, cx), *tz, self.home_tz, cx)),
+                Lookup::Suggested(message, tz) => {
+                    let tz = *tz;
+                    this.child(error(message.clone())).child(
+                        h_flex()
+                            .gap_2()
+                            .child(
+                                div()
+                                    .child(format!("Did you mean {}?", tz.name()))
+                                    .text_sm(),
+                            )
+                            .child(
+                                Button::new("use-suggestion")
+                                    .label("Use it")
+                                    .small()
+                                    .on_click(cx.listener(move |this, _, window, cx| {
+                                        this.use_suggestion(tz, window, cx)
+                                    })),
+                            ),
+                    )
+                }
+                Lookup::Unknown(message) => this.child(error(message.clone())),
+            })
+    }
+}
@@ -0,0 +1,112 @@
+use crate::{convert, quiet_hours::WhenQuiet};
+use chrono::{DateTime, Days, NaiveTime, Utc};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+use time2rust::format;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Repeat {
+    Once,
+    Daily,
+}
+
+// An alarm at a wall-clock time in one of the cities
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Alarm {
+    pub city: String,
+    pub time: NaiveTime, // in the city's zone
+    pub repeat: Repeat,
+    // Occurrences up to this instant are already handled (creation or last firing)
+    pub armed_since: DateTime<Utc>,
+    #[serde(default)]
+    pub when_quiet: WhenQuiet,
+}
+
+impl Alarm {
+    pub fn new(
+        city: &str,
+        time: NaiveTime,
+        repeat: Repeat,
+        when_quiet: WhenQuiet,
+        now: DateTime<Utc>,
+    ) -> Self {
+        Alarm {
+            city: city.to_string(),
+            time,
+            repeat,
+            armed_since: now,
+            when_quiet,
+        }
+    }
+
+    // First occurrence strictly after `after`. A time skipped by DST rings at the
+    // shifted time, an ambiguous one at its first occurrence.
+    pub fn next_occurrence(&self, tz: Tz, after: DateTime<Utc>) -> DateTime<Utc> {
+        let mut day = after.with_timezone(&tz).date_naive();
+        loop {
+            let instant = convert::resolve_local(day.and_time(self.time), tz).instant;
+            if instant > after {
+                return instant;
+            }
+            match day.checked_add_days(Days::new(1)) {
+                Some(next) => day = next,
+                None => return instant,
+            }
+        }
+    }
+
+    // Due when an occurrence has passed since the alarm was armed. Comparing against
+    // the wall clock rather than counting timer ticks means a machine that slept
+    // through the alarm still rings on wake, and only once however much was missed.
+    pub fn is_due(&self, tz: Tz, now: DateTime<Utc>) -> bool {
+        self.next_occurrence(tz, self.armed_since) <= now
+    }
+
+    pub fn describe(&self) -> String {
+        let when = match self.repeat {
+            Repeat::Once => format!("{} once", format::clock_time(self.time, false)),
+            Repeat::Daily => format!("{} every day", format::clock_time(self.time, false)),
+        };
+        match self.when_quiet {
+            WhenQuiet::Hold => when,
+            WhenQuiet::Drop => format!("{}, skipped in quiet hours", when),
+        }
+    }
+}
+
+// Summary and body for the desktop notification, with the time at home alongside
+pub fn notification_text(
+    alarm: &Alarm,
+    now: DateTime<Utc>,
+    tz: Tz,
+    home: Option<(&str, Tz)>,
+) -> (String, String) {
+    let local = format::clock_time(now.with_timezone(&tz).time(), false);
+    let summary = format!("⏰ {} in {}", local, alarm.city);
+    let body = match home {
+        Some((home_name, home_tz)) if home_name != alarm.city => format!(
+            "{} {} · {} {} (home)",
+            alarm.city,
+            local,
+            home_name,
+            format::clock_time(now.with_timezone(&home_tz).time(), false)
+        ),
+        _ => format!("{} {}", alarm.city, local),
+    };
+    (summary, body)
+}
+
+// Showing can block on the notification service, so it happens off the UI thread
+pub fn show_desktop_notification(summary: String, body: String) {
+    std::thread::spawn(move || {
+        let result = notify_rust::Notification::new()
+            .appname("time2rust")
+            .summary(&summary)
+            .body(&body)
+            .show();
+        if let Err(err) = result {
+            eprintln!("time2rust: couldn't show notification: {}", err);
+        }
+    });
+}
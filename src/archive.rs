@@ -0,0 +1,80 @@
+use crate::{WorldTimeApp, countdown::CountdownConfig, settings_panel::section_title, zone};
+use gpui::{Context, div, prelude::*, px, rgb};
+use gpui_component::{
+    ActiveTheme as _, Sizable,
+    button::{Button, ButtonVariants as _},
+    h_flex, v_flex,
+};
+use time2rust::format;
+
+// When it finished, in the zone it was set in, like "Fri May 2 2025 18:00 Europe/Berlin"
+pub fn describe_completed(countdown: &CountdownConfig) -> String {
+    let Some(completed) = countdown.completed else {
+        return String::new();
+    };
+    match zone::resolve_zone(&countdown.timezone) {
+        Ok(tz) => format!(
+            "{} {}",
+            format::weekday_date_year_time(completed.with_timezone(&tz).naive_local()),
+            countdown.timezone
+        ),
+        Err(_) => format!(
+            "{} UTC",
+            format::weekday_date_year_time(completed.naive_utc())
+        ),
+    }
+}
+
+// Finished countdowns, most recent first; only shown once opened from the header
+pub fn archive_section(
+    archived: &[CountdownConfig],
+    cx: &mut Context<WorldTimeApp>,
+) -> impl IntoElement {
+    let mut rows: Vec<(usize, &CountdownConfig)> = archived.iter().enumerate().collect();
+    rows.sort_by_key(|(_, countdown)| std::cmp::Reverse(countdown.completed));
+
+    v_flex()
+        .gap_2()
+        .p_4()
+        .border_1()
+        .border_color(cx.theme().border)
+        .rounded(px(8.0))
+        .child(section_title("Past countdowns"))
+        .children(rows.into_iter().map(|(index, countdown)| {
+            h_flex()
+                .justify_between()
+                .gap_4()
+                .child(
+                    v_flex()
+                        .child(div().child(countdown.title.clone()).text_sm())
+                        .child(
+                            div()
+                                .child(format!("Finished {}", describe_completed(countdown)))
+                                .text_xs()
+                                .text_color(rgb(0x6b7280)),
+                        ),
+                )
+                .child(
+                    h_flex()
+                        .gap_1()
+                        .child(
+                            Button::new(("duplicate-countdown", index))
+                                .label("Duplicate as new countdown…")
+                                .small()
+                                .ghost()
+                                .on_click(cx.listener(move |this, _, window, cx| {
+                                    this.duplicate_archived(index, window, cx)
+                                })),
+                        )
+                        .child(
+                            Button::new(("delete-archived", index))
+                                .label("Delete")
+                                .small()
+                                .ghost()
+                                .on_click(cx.listener(move |this, _, window, cx| {
+                                    this.delete_archived(index, window, cx)
+                                })),
+                        ),
+                )
+        }))
+}
@@ -0,0 +1,288 @@
+use crate::{WorldTimeApp, config::Config, confirm, settings_panel::hint, zone};
+use chrono::{DateTime, Utc};
+use gpui::{App, Context, Entity, SharedString, Window, div, prelude::*, rgb};
+use gpui_component::{
+    WindowExt as _, button::ButtonVariant, checkbox::Checkbox, dialog::DialogButtonProps, v_flex,
+};
+use serde::Serialize;
+use std::fmt;
+
+// Bumped when the bundle layout changes; read_backup migrates anything older
+pub const VERSION: u64 = 1;
+
+#[derive(Serialize)]
+struct Backup<'a> {
+    app: &'static str,
+    version: u64,
+    exported_at: DateTime<Utc>,
+    config: &'a Config,
+}
+
+pub fn write_backup(config: &Config, now: DateTime<Utc>) -> String {
+    let backup = Backup {
+        app: "time2rust",
+        version: VERSION,
+        exported_at: now,
+        config,
+    };
+    serde_json::to_string_pretty(&backup).unwrap_or_default()
+}
+
+#[derive(Debug)]
+pub enum BackupError {
+    Parse(serde_json::Error),
+    NotABackup,
+    Newer(u64),
+    Invalid(Vec<String>),
+}
+
+impl fmt::Display for BackupError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BackupError::Parse(err) => write!(f, "the file can't be read: {}", err),
+            BackupError::NotABackup => write!(f, "this isn't a time2rust backup"),
+            BackupError::Newer(version) => write!(
+                f,
+                "this backup is format {}, from a newer time2rust; this one reads up to format {}",
+                version, VERSION
+            ),
+            BackupError::Invalid(problems) => {
+                write!(f, "nothing was imported: {}", problems.join("; "))
+            }
+        }
+    }
+}
+
+// The whole bundle is checked before anything is applied, so a bad file never
+// leaves half of it behind
+pub fn read_backup(contents: &str) -> Result<Config, BackupError> {
+    let value: serde_json::Value = serde_json::from_str(contents).map_err(BackupError::Parse)?;
+    let config = match value.get("version").map(serde_json::Value::as_u64) {
+        // A plain config.json, from before there were backups
+        None if value.get("cities").is_some() => value,
+        Some(Some(VERSION)) => value
+            .get("config")
+            .cloned()
+            .ok_or(BackupError::NotABackup)?,
+        Some(Some(version)) if version > VERSION => return Err(BackupError::Newer(version)),
+        _ => return Err(BackupError::NotABackup),
+    };
+    let config: Config = serde_json::from_value(config).map_err(BackupError::Parse)?;
+    let problems = problems(&config);
+    if !problems.is_empty() {
+        return Err(BackupError::Invalid(problems));
+    }
+    Ok(config)
+}
+
+fn problems(config: &Config) -> Vec<String> {
+    let cities = config
+        .cities
+        .iter()
+        .map(|city| (&city.name, &city.timezone));
+    let profile_cities = config
+        .profiles
+        .iter()
+        .flat_map(|profile| &profile.cities)
+        .map(|city| (&city.name, &city.timezone));
+    let countdowns = config
+        .countdowns
+        .iter()
+        .map(|countdown| (&countdown.title, &countdown.timezone));
+    cities
+        .chain(profile_cities)
+        .chain(countdowns)
+        .filter_map(|(name, timezone)| {
+            zone::resolve_zone(timezone)
+                .err()
+                .map(|err| format!("{}: {}", name, err))
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BackupPart {
+    Cities,
+    Profiles,
+    Settings,
+    Alarms,
+    Countdowns,
+    Window,
+}
+
+impl BackupPart {
+    pub const ALL: [BackupPart; 6] = [
+        BackupPart::Cities,
+        BackupPart::Profiles,
+        BackupPart::Settings,
+        BackupPart::Alarms,
+        BackupPart::Countdowns,
+        BackupPart::Window,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            BackupPart::Cities => "Cities",
+            BackupPart::Profiles => "Other profiles",
+            BackupPart::Settings => "Settings and pinned events",
+            BackupPart::Alarms => "Alarms",
+            BackupPart::Countdowns => "Countdowns",
+            BackupPart::Window => "Window placement, floating cards and stacks",
+        }
+    }
+}
+
+// What taking `part` from the backup would change, like "2 added, 1 removed, 3 unchanged"
+pub fn describe_change(part: BackupPart, current: &Config, incoming: &Config) -> String {
+    let count = |now: usize, then: usize| {
+        if now == then {
+            format!("{}, as now", then)
+        } else {
+            format!("{} (now {})", then, now)
+        }
+    };
+    match part {
+        BackupPart::Cities => {
+            let summary = confirm::import_summary(&current.cities, &incoming.cities);
+            format!(
+                "{} added, {} removed, {} unchanged",
+                summary.added.len(),
+                summary.removed.len(),
+                summary.kept
+            )
+        }
+        BackupPart::Profiles => count(current.profiles.len(), incoming.profiles.len()),
+        BackupPart::Settings => {
+            let same = serde_json::to_value(&current.settings).ok()
+                == serde_json::to_value(&incoming.settings).ok();
+            if same { "same as now" } else { "changed" }.to_string()
+        }
+        BackupPart::Alarms => count(current.alarms.len(), incoming.alarms.len()),
+        BackupPart::Countdowns => count(current.countdowns.len(), incoming.countdowns.len()),
+        BackupPart::Window => format!(
+            "{} floating card(s), placement used from the next launch",
+            incoming.floating_cards.len()
+        ),
+    }
+}
+
+// The current config with the chosen parts taken from the backup
+pub fn merge(mut current: Config, incoming: Config, parts: &[BackupPart]) -> Config {
+    for part in parts {
+        match part {
+            BackupPart::Cities => {
+                current.cities = incoming.cities.clone();
+                current.active_profile = incoming.active_profile.clone();
+            }
+            BackupPart::Profiles => current.profiles = incoming.profiles.clone(),
+            BackupPart::Settings => current.settings = incoming.settings.clone(),
+            BackupPart::Alarms => current.alarms = incoming.alarms.clone(),
+            BackupPart::Countdowns => current.countdowns = incoming.countdowns.clone(),
+            BackupPart::Window => {
+                current.window = incoming.window.clone();
+                current.floating_cards = incoming.floating_cards.clone();
+                current.expanded_stacks = incoming.expanded_stacks.clone();
+            }
+        }
+    }
+    // A saved profile can't share its name with the one showing
+    let active = current.active_profile.clone();
+    current.profiles.retain(|profile| profile.name != active);
+    current.needs_onboarding = false;
+    current
+}
+
+pub fn confirm_restore(
+    app: Entity<WorldTimeApp>,
+    incoming: Config,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    let current = app.read(cx).current_config(cx);
+    let changes = BackupPart::ALL
+        .iter()
+        .map(|&part| describe_change(part, &current, &incoming))
+        .collect();
+    let form = cx.new(|_| RestoreForm {
+        parts: BackupPart::ALL.iter().map(|&part| (part, true)).collect(),
+        changes,
+        error: None,
+    });
+    window.open_dialog(cx, move |dialog, _window, _cx| {
+        dialog
+            .title("Restore from backup?")
+            .child(form.clone())
+            .confirm()
+            .button_props(
+                DialogButtonProps::default()
+                    .ok_text("Restore")
+                    .ok_variant(ButtonVariant::Danger),
+            )
+            .on_ok({
+                let app = app.clone();
+                let form = form.clone();
+                let incoming = incoming.clone();
+                move |_, window, cx| {
+                    let parts: Vec<BackupPart> = form
+                        .read(cx)
+                        .parts
+                        .iter()
+                        .filter(|(_, chosen)| *chosen)
+                        .map(|(part, _)| *part)
+                        .collect();
+                    if parts.is_empty() {
+                        form.update(cx, |form, cx| {
+                            form.error = Some("Pick at least one part to restore".into());
+                            cx.notify();
+                        });
+                        return false;
+                    }
+                    app.update(cx, |app, cx| {
+                        let config = merge(app.current_config(cx), incoming.clone(), &parts);
+                        app.restore_backup(config, window, cx)
+                    });
+                    true
+                }
+            })
+    });
+}
+
+pub struct RestoreForm {
+    parts: Vec<(BackupPart, bool)>,
+    changes: Vec<String>,
+    error: Option<SharedString>,
+}
+
+impl Render for RestoreForm {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .gap_2()
+            .children(
+                self.parts
+                    .iter()
+                    .enumerate()
+                    .map(|(index, (part, chosen))| {
+                        v_flex()
+                            .child(
+                                Checkbox::new(("restore-part", index))
+                                    .label(part.label())
+                                    .checked(*chosen)
+                                    .on_click(cx.listener(move |this, checked: &bool, _, cx| {
+                                        this.parts[index].1 = *checked;
+                                        this.error = None;
+                                        cx.notify();
+                                    })),
+                            )
+                            .child(hint(self.changes[index].clone()))
+                    }),
+            )
+            .child(hint(
+                "Parts left unticked stay as they are; undo restores cities and settings",
+            ))
+            .children(
+                self.error
+                    .clone()
+                    .map(|error| div().child(error).text_sm().text_color(rgb(0xef4444))),
+            )
+    }
+}
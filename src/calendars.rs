@@ -0,0 +1,229 @@
+use chrono::{Datelike, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+// A second calendar a card can show its local date in. Dates convert through
+// fixed day numbers (days since 0001-01-01, which is day 1), so another calendar
+// only needs its own to/from-fixed pair.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AltCalendar {
+    Hijri,
+    Hebrew,
+}
+
+impl AltCalendar {
+    pub const ALL: [AltCalendar; 2] = [AltCalendar::Hijri, AltCalendar::Hebrew];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            AltCalendar::Hijri => "Hijri",
+            AltCalendar::Hebrew => "Hebrew",
+        }
+    }
+
+    // "7 Ramadan 1445 AH", "14 Nisan 5784"
+    pub fn format(self, date: NaiveDate) -> String {
+        let fixed = date.num_days_from_ce() as i64;
+        match self {
+            AltCalendar::Hijri => {
+                let (year, month, day) = hijri_from_fixed(fixed);
+                format!("{} {} {} AH", day, HIJRI_MONTHS[month as usize - 1], year)
+            }
+            AltCalendar::Hebrew => {
+                let (year, month, day) = hebrew_from_fixed(fixed);
+                format!("{} {} {}", day, hebrew_month_name(year, month), year)
+            }
+        }
+    }
+}
+
+const HIJRI_MONTHS: [&str; 12] = [
+    "Muharram",
+    "Safar",
+    "Rabi' al-Awwal",
+    "Rabi' al-Thani",
+    "Jumada al-Ula",
+    "Jumada al-Akhirah",
+    "Rajab",
+    "Sha'ban",
+    "Ramadan",
+    "Shawwal",
+    "Dhu al-Qi'dah",
+    "Dhu al-Hijjah",
+];
+
+// Fixed day of 1 Muharram 1 AH (16 July 622, Julian)
+const HIJRI_EPOCH: i64 = 227_015;
+
+// The arithmetic (tabular) Islamic calendar. Observed months begin with the
+// sighting of the new moon, so local usage can differ by a day.
+pub fn fixed_from_hijri(year: i64, month: i64, day: i64) -> i64 {
+    day + 29 * (month - 1)
+        + (6 * month - 1).div_euclid(11)
+        + (year - 1) * 354
+        + (3 + 11 * year).div_euclid(30)
+        + HIJRI_EPOCH
+        - 1
+}
+
+pub fn hijri_from_fixed(fixed: i64) -> (i64, i64, i64) {
+    let year = (30 * (fixed - HIJRI_EPOCH) + 10_646).div_euclid(10_631);
+    let prior_days = fixed - fixed_from_hijri(year, 1, 1);
+    let month = (11 * prior_days + 330).div_euclid(325);
+    let day = fixed - fixed_from_hijri(year, month, 1) + 1;
+    (year, month, day)
+}
+
+// Fixed day of 1 Tishrei AM 1 (7 October 3761 BCE, Julian)
+const HEBREW_EPOCH: i64 = -1_373_427;
+
+// Months count from Nisan = 1; the year begins in Tishrei = 7. Adar II (13) only
+// exists in leap years.
+fn hebrew_leap_year(year: i64) -> bool {
+    (7 * year + 1).rem_euclid(19) < 7
+}
+
+fn last_month_of_hebrew_year(year: i64) -> i64 {
+    if hebrew_leap_year(year) { 13 } else { 12 }
+}
+
+fn hebrew_calendar_elapsed_days(year: i64) -> i64 {
+    let months_elapsed = (235 * year - 234).div_euclid(19);
+    let parts_elapsed = 12_084 + 13_753 * months_elapsed;
+    let days = 29 * months_elapsed + parts_elapsed.div_euclid(25_920);
+    // Tishrei 1 never falls on Sunday, Wednesday or Friday
+    if (3 * (days + 1)).rem_euclid(7) < 3 {
+        days + 1
+    } else {
+        days
+    }
+}
+
+fn hebrew_year_length_correction(year: i64) -> i64 {
+    let previous = hebrew_calendar_elapsed_days(year - 1);
+    let this = hebrew_calendar_elapsed_days(year);
+    let next = hebrew_calendar_elapsed_days(year + 1);
+    if next - this == 356 {
+        2
+    } else if this - previous == 382 {
+        1
+    } else {
+        0
+    }
+}
+
+fn hebrew_new_year(year: i64) -> i64 {
+    HEBREW_EPOCH + hebrew_calendar_elapsed_days(year) + hebrew_year_length_correction(year)
+}
+
+fn days_in_hebrew_year(year: i64) -> i64 {
+    hebrew_new_year(year + 1) - hebrew_new_year(year)
+}
+
+fn last_day_of_hebrew_month(year: i64, month: i64) -> i64 {
+    let short = match month {
+        2 | 4 | 6 | 10 | 13 => true,
+        12 => !hebrew_leap_year(year),
+        8 => !matches!(days_in_hebrew_year(year), 355 | 385),
+        9 => matches!(days_in_hebrew_year(year), 353 | 383),
+        _ => false,
+    };
+    if short { 29 } else { 30 }
+}
+
+pub fn fixed_from_hebrew(year: i64, month: i64, day: i64) -> i64 {
+    let months_before: i64 = if month < 7 {
+        (7..=last_month_of_hebrew_year(year))
+            .chain(1..month)
+            .map(|m| last_day_of_hebrew_month(year, m))
+            .sum()
+    } else {
+        (7..month).map(|m| last_day_of_hebrew_month(year, m)).sum()
+    };
+    hebrew_new_year(year) + months_before + day - 1
+}
+
+pub fn hebrew_from_fixed(fixed: i64) -> (i64, i64, i64) {
+    let approx = ((fixed - HEBREW_EPOCH) as f64 / (35_975_351.0 / 98_496.0)).floor() as i64 + 1;
+    let year = (approx - 1..=approx + 1)
+        .rev()
+        .find(|year| hebrew_new_year(*year) <= fixed)
+        .unwrap_or(approx - 1);
+    let start = if fixed < fixed_from_hebrew(year, 1, 1) {
+        7
+    } else {
+        1
+    };
+    let month = (start..=last_month_of_hebrew_year(year))
+        .find(|month| {
+            fixed <= fixed_from_hebrew(year, *month, last_day_of_hebrew_month(year, *month))
+        })
+        .unwrap_or(start);
+    let day = fixed - fixed_from_hebrew(year, month, 1) + 1;
+    (year, month, day)
+}
+
+fn hebrew_month_name(year: i64, month: i64) -> &'static str {
+    match month {
+        1 => "Nisan",
+        2 => "Iyar",
+        3 => "Sivan",
+        4 => "Tammuz",
+        5 => "Av",
+        6 => "Elul",
+        7 => "Tishrei",
+        8 => "Cheshvan",
+        9 => "Kislev",
+        10 => "Tevet",
+        11 => "Shevat",
+        12 if hebrew_leap_year(year) => "Adar I",
+        12 => "Adar",
+        _ => "Adar II",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixed(year: i32, month: u32, day: u32) -> i64 {
+        NaiveDate::from_ymd_opt(year, month, day)
+            .unwrap()
+            .num_days_from_ce() as i64
+    }
+
+    #[test]
+    fn known_hebrew_dates() {
+        // Rosh Hashanah 5784
+        assert_eq!(hebrew_from_fixed(fixed(2023, 9, 16)), (5784, 7, 1));
+        assert_eq!(fixed_from_hebrew(5784, 7, 1), fixed(2023, 9, 16));
+        // Purim in the leap year 5782 falls in Adar II
+        assert_eq!(hebrew_from_fixed(fixed(2022, 3, 17)), (5782, 13, 14));
+        assert_eq!(fixed_from_hebrew(5782, 13, 14), fixed(2022, 3, 17));
+        assert_eq!(
+            AltCalendar::Hebrew.format(NaiveDate::from_ymd_opt(2023, 9, 16).unwrap()),
+            "1 Tishrei 5784"
+        );
+    }
+
+    #[test]
+    fn known_hijri_dates() {
+        assert_eq!(hijri_from_fixed(fixed(2024, 3, 11)), (1445, 9, 1));
+        assert_eq!(fixed_from_hijri(1445, 9, 1), fixed(2024, 3, 11));
+        assert_eq!(
+            AltCalendar::Hijri.format(NaiveDate::from_ymd_opt(2024, 3, 11).unwrap()),
+            "1 Ramadan 1445 AH"
+        );
+    }
+
+    #[test]
+    fn conversions_round_trip() {
+        let start = fixed(1900, 1, 1);
+        for day in (start..start + 60_000).step_by(7) {
+            let (year, month, day_of_month) = hijri_from_fixed(day);
+            assert_eq!(fixed_from_hijri(year, month, day_of_month), day);
+            let (year, month, day_of_month) = hebrew_from_fixed(day);
+            assert_eq!(fixed_from_hebrew(year, month, day_of_month), day);
+        }
+    }
+}
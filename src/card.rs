@@ -0,0 +1,240 @@
+//! World-time cards for embedding in other gpui apps.
+//!
+//! [`WorldTimeCard`] draws one city the way time2rust does; [`WorldTimeGrid`]
+//! lays out several and keeps them current on its own timer. Call
+//! `gpui_component::init` once at startup, as the cards use its theme and tags.
+
+use crate::format;
+use chrono::{DateTime, Offset, TimeZone, Timelike, Utc};
+use chrono_tz::Tz;
+use gpui::{App, Context, Div, SharedString, Task, Window, div, prelude::*, px, rgb};
+use gpui_component::{Sizable, tag::Tag};
+use std::time::Duration;
+
+/// How a card is drawn.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct CardOptions {
+    /// Show "HH:MM:SS" rather than "HH:MM"; a grid then ticks every second.
+    pub show_seconds: bool,
+    /// The bigger layout time2rust uses in wide windows.
+    pub large: bool,
+    /// Differences are given from this zone, and its card is marked as home.
+    pub home: Option<Tz>,
+}
+
+/// One city's card: name, local time, difference from home and zone id.
+#[derive(IntoElement)]
+pub struct WorldTimeCard {
+    name: SharedString,
+    tz: Tz,
+    options: CardOptions,
+    now: Option<DateTime<Utc>>,
+}
+
+impl WorldTimeCard {
+    /// A card for `tz` labelled `name`, showing the current time.
+    pub fn new(name: impl Into<SharedString>, tz: Tz) -> Self {
+        WorldTimeCard {
+            name: name.into(),
+            tz,
+            options: CardOptions::default(),
+            now: None,
+        }
+    }
+
+    pub fn options(mut self, options: CardOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Show this instant instead of the current time.
+    pub fn at(mut self, now: DateTime<Utc>) -> Self {
+        self.now = Some(now);
+        self
+    }
+}
+
+impl RenderOnce for WorldTimeCard {
+    fn render(self, _window: &mut Window, _cx: &mut App) -> impl IntoElement {
+        let now = self.now.unwrap_or_else(Utc::now);
+        let local = now.with_timezone(&self.tz);
+        let time = format::clock_time(local.time(), self.options.show_seconds);
+        let is_home = self.options.home == Some(self.tz);
+
+        card_frame(is_home, self.options.large).child(
+            div()
+                .flex()
+                .flex_col()
+                .items_center()
+                .gap_1()
+                .child(city_name_header(&self.name, is_home))
+                .child(time_display(&time, self.options.large))
+                .children(self.options.home.map(|home| {
+                    time_difference_display(
+                        utc_offset_minutes(self.tz, now) - utc_offset_minutes(home, now),
+                    )
+                }))
+                .child(timezone_display(self.tz.name())),
+        )
+    }
+}
+
+/// Cards for several cities, wrapping to fit, refreshed on the minute (or the
+/// second with `show_seconds`). Hold it in an entity: `cx.new(|cx| WorldTimeGrid::new(..))`.
+pub struct WorldTimeGrid {
+    cities: Vec<(SharedString, Tz)>,
+    options: CardOptions,
+    _ticker: Task<()>,
+}
+
+impl WorldTimeGrid {
+    /// A grid of `(name, zone)` cards in the given order.
+    pub fn new(
+        cities: Vec<(SharedString, Tz)>,
+        options: CardOptions,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let interval = if options.show_seconds { 1 } else { 60 };
+        let ticker = cx.spawn(async move |this, cx| {
+            loop {
+                cx.background_executor()
+                    .timer(next_tick_delay(Utc::now(), interval))
+                    .await;
+                if this.update(cx, |_, cx| cx.notify()).is_err() {
+                    break;
+                }
+            }
+        });
+        WorldTimeGrid {
+            cities,
+            options,
+            _ticker: ticker,
+        }
+    }
+
+    pub fn set_cities(&mut self, cities: Vec<(SharedString, Tz)>, cx: &mut Context<Self>) {
+        self.cities = cities;
+        cx.notify();
+    }
+}
+
+impl Render for WorldTimeGrid {
+    fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+        let now = Utc::now();
+        div()
+            .flex()
+            .flex_wrap()
+            .gap_4()
+            .children(self.cities.iter().map(|(name, tz)| {
+                WorldTimeCard::new(name.clone(), *tz)
+                    .options(self.options)
+                    .at(now)
+            }))
+    }
+}
+
+// Lands just after the next whole `seconds`, so every card turns over together
+fn next_tick_delay(now: DateTime<Utc>, seconds: u32) -> Duration {
+    let into = (now.second() % seconds) as u64 * 1000 + now.timestamp_subsec_millis() as u64;
+    Duration::from_millis((seconds as u64 * 1000).saturating_sub(into).max(1))
+}
+
+/// A zone's offset from UTC in minutes at `instant`, DST included.
+pub fn utc_offset_minutes(tz: Tz, instant: DateTime<Utc>) -> i32 {
+    tz.offset_from_utc_datetime(&instant.naive_utc())
+        .fix()
+        .local_minus_utc()
+        / 60
+}
+
+/// The border and background every card has, blue for home. `large` widens it
+/// for the bigger layout.
+pub fn card_frame(is_home: bool, large: bool) -> Div {
+    let frame_color = if is_home {
+        rgb(0x3b82f6) // Blue border for home
+    } else {
+        rgb(0x6b7280) // Gray for others
+    };
+
+    let bg_color = if is_home {
+        rgb(0xf0f9ff) // Light blue background for home
+    } else {
+        rgb(0xf9fafb) // Light gray for others
+    };
+
+    div()
+        .flex()
+        .flex_col()
+        .gap_2()
+        .p_4()
+        .min_w(px(if large { 260.0 } else { 180.0 }))
+        .bg(bg_color)
+        .border_2()
+        .border_color(frame_color)
+        .rounded(px(8.0))
+}
+
+/// The city's name in bold, blue with a "Home" tag for the home city.
+pub fn city_name_header(name: &str, is_home: bool) -> impl IntoElement {
+    div()
+        .flex()
+        .items_center()
+        .gap_2()
+        .child(
+            div()
+                .child(name.to_string())
+                .text_lg()
+                .font_weight(gpui::FontWeight::BOLD)
+                .text_color(if is_home {
+                    rgb(0x3b82f6)
+                } else {
+                    rgb(0x111827)
+                }),
+        )
+        .children(is_home.then(|| Tag::secondary().small().child("Home")))
+}
+
+/// The local time as already formatted, in the card's largest text.
+pub fn time_display(time: &str, large: bool) -> impl IntoElement {
+    div().flex().items_center().gap_2().child(
+        div()
+            .child(time.to_string())
+            .map(|text| {
+                if large {
+                    text.text_size(px(44.0))
+                } else {
+                    text.text_3xl()
+                }
+            })
+            .font_weight(gpui::FontWeight::BOLD)
+            .text_color(rgb(0x111827)),
+    )
+}
+
+/// The difference from home as a badge like "Δ +6 hours", green ahead and red behind.
+pub fn time_difference_display(diff_minutes: i32) -> impl IntoElement {
+    time_difference_label(format::diff_badge(diff_minutes), diff_minutes)
+}
+
+/// The difference in any wording, colored by its sign like the badge.
+pub fn time_difference_label(text: String, diff_minutes: i32) -> impl IntoElement {
+    div()
+        .child(text)
+        .text_sm()
+        .font_weight(gpui::FontWeight::BOLD)
+        .text_color(if diff_minutes >= 0 {
+            rgb(0x22c55e)
+        } else {
+            rgb(0xef4444)
+        })
+}
+
+/// The zone id in small gray text, under the time.
+pub fn timezone_display(timezone_id: &str) -> impl IntoElement {
+    div().flex().items_center().gap_1().child(
+        div()
+            .child(timezone_id.to_string())
+            .text_xs()
+            .text_color(rgb(0x6b7280)),
+    )
+}
@@ -0,0 +1,759 @@
+use crate::{
+    WorldTime, WorldTimeApp,
+    alarms::{Alarm, Repeat},
+    browser,
+    calendars::AltCalendar,
+    config::Settings,
+    convert,
+    display_options::{DisplayOption, DisplayOverrides},
+    jet_lag,
+    offset_override::{self, OffsetOverride},
+    people, places,
+    quiet_hours::WhenQuiet,
+    settings_panel::{hint, section_title},
+    snooze, zone,
+};
+use chrono::{DateTime, NaiveDate, Utc};
+use chrono_tz::Tz;
+use gpui::{App, Context, Entity, SharedString, Window, div, prelude::*, rgb};
+use gpui_component::{
+    Disableable as _, Sizable, WindowExt as _,
+    button::{Button, ButtonVariants as _},
+    checkbox::Checkbox,
+    h_flex,
+    input::{Input, InputEvent, InputState},
+    notification::Notification,
+    v_flex,
+};
+use time2rust::format;
+
+// Opened by clicking a card; holds everything about one city that doesn't fit on it
+// With `focus_alarm`, the alarm field takes the keyboard, as for "Add alarm…"
+pub fn open_card_detail(
+    app: Entity<WorldTimeApp>,
+    city: Entity<WorldTime>,
+    focus_alarm: bool,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    let title = city.read(cx).name.clone();
+    let detail = cx.new(|cx| CardDetail::new(app, city, window, cx));
+    if focus_alarm {
+        let alarm_input = detail.read(cx).alarm_input.clone();
+        alarm_input.update(cx, |input, cx| input.focus(window, cx));
+    }
+    window.open_sheet(cx, move |sheet, _window, _cx| {
+        sheet.title(title.clone()).child(detail.clone())
+    });
+}
+
+pub struct CardDetail {
+    app: Entity<WorldTimeApp>,
+    city: Entity<WorldTime>,
+    alarm_input: Entity<InputState>,
+    daily: bool,
+    drop_when_quiet: bool,
+    error: Option<SharedString>,
+    coordinates_input: Entity<InputState>,
+    coordinates_error: Option<SharedString>,
+    person_input: Entity<InputState>,
+    person_error: Option<SharedString>,
+    shift_input: Entity<InputState>,
+    until_input: Entity<InputState>,
+    override_error: Option<SharedString>,
+    snooze_input: Entity<InputState>,
+    snooze_error: Option<SharedString>,
+}
+
+impl CardDetail {
+    fn new(
+        app: Entity<WorldTimeApp>,
+        city: Entity<WorldTime>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let alarm_input = cx.new(|cx| InputState::new(window, cx).placeholder("09:00"));
+        cx.subscribe_in(&alarm_input, window, |this, _, event, window, cx| {
+            if let InputEvent::PressEnter { .. } = event {
+                this.add_alarm(window, cx);
+            }
+        })
+        .detach();
+
+        let (coordinates, placeholder) = {
+            let city = city.read(cx);
+            let looked_up = places::lookup(&city.name, &city.timezone_id);
+            (
+                city.coordinates.map(|coordinates| coordinates.to_string()),
+                looked_up.map_or("Latitude, longitude".to_string(), |coordinates| {
+                    coordinates.to_string()
+                }),
+            )
+        };
+        let coordinates_input = cx.new(|cx| {
+            InputState::new(window, cx)
+                .placeholder(placeholder)
+                .default_value(coordinates.unwrap_or_default())
+        });
+        cx.subscribe_in(
+            &coordinates_input,
+            window,
+            |this, input, event, window, cx| {
+                if let InputEvent::PressEnter { .. } = event {
+                    let value = input.read(cx).value().trim().to_string();
+                    this.set_coordinates(&value, window, cx);
+                }
+            },
+        )
+        .detach();
+
+        let person_input = cx.new(|cx| InputState::new(window, cx).placeholder("Name"));
+        cx.subscribe_in(&person_input, window, |this, _, event, window, cx| {
+            if let InputEvent::PressEnter { .. } = event {
+                this.add_person(window, cx);
+            }
+        })
+        .detach();
+
+        let shift_input = cx.new(|cx| InputState::new(window, cx).placeholder("+1"));
+        let until_input = cx.new(|cx| {
+            let week_out = Utc::now().date_naive() + chrono::Days::new(7);
+            InputState::new(window, cx).placeholder(week_out.format("%Y-%m-%d").to_string())
+        });
+        for input in [&shift_input, &until_input] {
+            cx.subscribe_in(input, window, |this, _, event, window, cx| {
+                if let InputEvent::PressEnter { .. } = event {
+                    this.apply_override(window, cx);
+                }
+            })
+            .detach();
+        }
+
+        let snooze_input = cx.new(|cx| {
+            let week_out = Utc::now().date_naive() + chrono::Days::new(7);
+            InputState::new(window, cx).placeholder(week_out.format("%Y-%m-%d").to_string())
+        });
+        cx.subscribe_in(&snooze_input, window, |this, _, event, window, cx| {
+            if let InputEvent::PressEnter { .. } = event {
+                this.apply_snooze(window, cx);
+            }
+        })
+        .detach();
+
+        cx.observe(&app, |_, _, cx| cx.notify()).detach();
+        cx.observe(&city, |_, _, cx| cx.notify()).detach();
+
+        CardDetail {
+            app,
+            city,
+            alarm_input,
+            daily: false,
+            drop_when_quiet: false,
+            error: None,
+            coordinates_input,
+            coordinates_error: None,
+            person_input,
+            person_error: None,
+            shift_input,
+            until_input,
+            override_error: None,
+            snooze_input,
+            snooze_error: None,
+        }
+    }
+
+    fn add_person(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let name = self.person_input.read(cx).value().to_string();
+        match people::add_person(&self.city.read(cx).people, &name) {
+            Ok(people) => {
+                self.set_people(people, window, cx);
+                self.person_input
+                    .update(cx, |input, cx| input.set_value("", window, cx));
+                self.person_error = None;
+            }
+            Err(err) => self.person_error = Some(err.into()),
+        }
+        cx.notify();
+    }
+
+    fn set_people(&mut self, people: Vec<String>, window: &mut Window, cx: &mut Context<Self>) {
+        let city = self.city.clone();
+        self.app
+            .update(cx, |app, cx| app.set_city_people(&city, people, window, cx));
+    }
+
+    fn apply_override(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let shift = self.shift_input.read(cx).value().to_string();
+        let until = self.until_input.read(cx).value().trim().to_string();
+        let today = Utc::now()
+            .with_timezone(&self.city.read(cx).tz)
+            .date_naive();
+        let offset_override =
+            offset_override::parse_shift(&shift).and_then(
+                |minutes| match NaiveDate::parse_from_str(&until, "%Y-%m-%d") {
+                    Ok(until) if until > today => Ok(OffsetOverride { minutes, until }),
+                    Ok(_) => Err("The override has to last past today".to_string()),
+                    Err(_) => Err(format!("\"{}\" should be a date like 2025-04-05", until)),
+                },
+            );
+        match offset_override {
+            Ok(offset_override) => {
+                self.set_override(Some(offset_override), window, cx);
+                self.override_error = None;
+            }
+            Err(err) => self.override_error = Some(err.into()),
+        }
+        cx.notify();
+    }
+
+    fn city_today(&self, cx: &App) -> NaiveDate {
+        Utc::now()
+            .with_timezone(&self.city.read(cx).tz)
+            .date_naive()
+    }
+
+    fn apply_snooze(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let until = self.snooze_input.read(cx).value().to_string();
+        match snooze::parse_until(&until, self.city_today(cx)) {
+            Ok(until) => {
+                self.set_snooze(Some(until), window, cx);
+                self.snooze_error = None;
+            }
+            Err(err) => self.snooze_error = Some(err.into()),
+        }
+        cx.notify();
+    }
+
+    fn set_display(
+        &mut self,
+        display: DisplayOverrides,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let city = self.city.clone();
+        self.app.update(cx, |app, cx| {
+            app.set_city_display(&city, display, window, cx)
+        });
+    }
+
+    fn set_snooze(
+        &mut self,
+        until: Option<NaiveDate>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let city = self.city.clone();
+        self.app
+            .update(cx, |app, cx| app.set_city_snooze(&city, until, window, cx));
+    }
+
+    fn set_override(
+        &mut self,
+        offset_override: Option<OffsetOverride>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let city = self.city.clone();
+        self.app.update(cx, |app, cx| {
+            app.set_city_override(&city, offset_override, window, cx)
+        });
+    }
+
+    // An empty value goes back to the bundled location, if there is one
+    fn set_coordinates(&mut self, value: &str, window: &mut Window, cx: &mut Context<Self>) {
+        let coordinates = if value.is_empty() {
+            None
+        } else {
+            match places::parse_coordinates(value) {
+                Ok(coordinates) => Some(coordinates),
+                Err(err) => {
+                    self.coordinates_error = Some(err.into());
+                    cx.notify();
+                    return;
+                }
+            }
+        };
+        let city = self.city.clone();
+        self.app.update(cx, |app, cx| {
+            app.set_city_coordinates(&city, coordinates, window, cx)
+        });
+        self.coordinates_error = None;
+        cx.notify();
+    }
+
+    fn add_alarm(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let input = self.alarm_input.read(cx).value().trim().to_string();
+        let Some(time) = convert::parse_time(&input) else {
+            self.error = Some(format!("\"{}\" isn't a time like 09:00", input).into());
+            cx.notify();
+            return;
+        };
+        let repeat = if self.daily {
+            Repeat::Daily
+        } else {
+            Repeat::Once
+        };
+        let when_quiet = if self.drop_when_quiet {
+            WhenQuiet::Drop
+        } else {
+            WhenQuiet::Hold
+        };
+        let alarm = Alarm::new(
+            &self.city.read(cx).name,
+            time,
+            repeat,
+            when_quiet,
+            Utc::now(),
+        );
+        self.app
+            .update(cx, |app, cx| app.add_alarm(alarm, window, cx));
+        self.alarm_input
+            .update(cx, |input, cx| input.set_value("", window, cx));
+        self.error = None;
+        cx.notify();
+    }
+}
+
+impl Render for CardDetail {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let city = self.city.read(cx).clone();
+        let now = Utc::now();
+        let home_tz = self.app.read(cx).home_tz;
+        let shift = jet_lag::shift_minutes(
+            zone::utc_offset_minutes(home_tz, now),
+            zone::utc_offset_minutes(city.tz, now),
+        );
+        let alarms: Vec<(usize, Alarm)> = self
+            .app
+            .read(cx)
+            .alarms
+            .iter()
+            .enumerate()
+            .filter(|(_, alarm)| alarm.city == city.name)
+            .map(|(index, alarm)| (index, alarm.clone()))
+            .collect();
+
+        v_flex()
+            .gap_6()
+            .child(
+                v_flex()
+                    .gap_1()
+                    .child(
+                        div()
+                            .child(format::weekday_date_time(
+                                now.with_timezone(&city.tz).naive_local(),
+                            ))
+                            .text_lg(),
+                    )
+                    .child(hint(format!(
+                        "{} · {} · card id {}",
+                        city.timezone_id,
+                        zone::format_utc_offset(zone::utc_offset_minutes(city.tz, now)),
+                        city.id
+                    )))
+                    .child(hint(zone::describe_standard_shift(city.tz, now)))
+                    .child(hint(match zone::next_offset_change(city.tz, now) {
+                        Some(change) => zone::describe_offset_change(city.tz, &change),
+                        None => "No offset changes in the coming year".to_string(),
+                    }))
+                    .children(
+                        (!city.is_home).then(|| hint(describe_diff_span(home_tz, &city, now))),
+                    ),
+            )
+            .child(
+                v_flex()
+                    .gap_2()
+                    .child(section_title("People"))
+                    .children(city.people.is_empty().then(|| hint("Nobody here yet")))
+                    .children(city.people.iter().enumerate().map(|(index, person)| {
+                        h_flex()
+                            .justify_between()
+                            .child(div().child(person.clone()).text_sm())
+                            .child(
+                                Button::new(("remove-person", index))
+                                    .label("Remove")
+                                    .small()
+                                    .ghost()
+                                    .on_click(cx.listener(move |this, _, window, cx| {
+                                        let mut people = this.city.read(cx).people.clone();
+                                        people.remove(index);
+                                        this.set_people(people, window, cx);
+                                    })),
+                            )
+                    }))
+                    .child(Input::new(&self.person_input))
+                    .children(
+                        self.person_error
+                            .clone()
+                            .map(|error| div().child(error).text_sm().text_color(rgb(0xef4444))),
+                    )
+                    .child(hint("Press Enter to add; names show on the card")),
+            )
+            .child(
+                v_flex()
+                    .gap_2()
+                    .child(section_title("Alarms"))
+                    .children(alarms.is_empty().then(|| hint("No alarms yet")))
+                    .children(alarms.into_iter().map(|(index, alarm)| {
+                        h_flex()
+                            .justify_between()
+                            .child(div().child(alarm.describe()).text_sm())
+                            .child(
+                                Button::new(("delete-alarm", index))
+                                    .label("Delete")
+                                    .small()
+                                    .ghost()
+                                    .on_click(cx.listener(move |this, _, window, cx| {
+                                        this.app.update(cx, |app, cx| {
+                                            app.remove_alarm(index, window, cx)
+                                        });
+                                    })),
+                            )
+                    }))
+                    .child(Input::new(&self.alarm_input))
+                    .child(
+                        h_flex()
+                            .justify_between()
+                            .child(
+                                Checkbox::new("alarm-daily")
+                                    .label("Every day")
+                                    .checked(self.daily)
+                                    .on_click(cx.listener(|this, checked: &bool, _, cx| {
+                                        this.daily = *checked;
+                                        cx.notify();
+                                    })),
+                            )
+                            .child(
+                                Checkbox::new("alarm-drop-when-quiet")
+                                    .label("Skip if in quiet hours")
+                                    .checked(self.drop_when_quiet)
+                                    .on_click(cx.listener(|this, checked: &bool, _, cx| {
+                                        this.drop_when_quiet = *checked;
+                                        cx.notify();
+                                    })),
+                            )
+                            .child(
+                                Button::new("add-alarm")
+                                    .label("Add alarm")
+                                    .small()
+                                    .on_click(cx.listener(|this, _, window, cx| {
+                                        this.add_alarm(window, cx)
+                                    })),
+                            ),
+                    )
+                    .children(
+                        self.error
+                            .clone()
+                            .map(|error| div().child(error).text_sm().text_color(rgb(0xef4444))),
+                    )
+                    .child(hint(format!("Local time in {}", city.name))),
+            )
+            .children((!city.is_home).then(|| {
+                v_flex()
+                    .gap_2()
+                    .child(section_title("Jet lag"))
+                    .child(
+                        div()
+                            .child(format!(
+                                "Travelling here from home: {}",
+                                jet_lag::describe_shift(shift)
+                            ))
+                            .text_sm(),
+                    )
+                    .children(
+                        jet_lag::adjustment(shift)
+                            .map(|adjustment| hint(jet_lag::describe_adjustment(&adjustment))),
+                    )
+            }))
+            .child(
+                v_flex()
+                    .gap_2()
+                    .child(section_title("Display"))
+                    .children(
+                        DisplayOption::ALL
+                            .iter()
+                            .enumerate()
+                            .map(|(index, &option)| {
+                                let overridden = option.is_overridden(&city.display);
+                                h_flex()
+                                    .gap_2()
+                                    .child(div().flex_1().child(option.label()).text_sm())
+                                    .child(
+                                        Button::new(("display-option", index))
+                                            .label(option.value(&city.options))
+                                            .small()
+                                            .when(overridden, |button| button.primary())
+                                            .when(!overridden, |button| button.ghost())
+                                            .on_click(cx.listener(move |this, _, window, cx| {
+                                                let city = this.city.read(cx);
+                                                let display =
+                                                    option.changed(&city.display, &city.options);
+                                                this.set_display(display, window, cx);
+                                            })),
+                                    )
+                                    .child(
+                                        div()
+                                            .w_24()
+                                            .child(if overridden {
+                                                "this card"
+                                            } else {
+                                                "settings"
+                                            })
+                                            .text_xs()
+                                            .text_color(rgb(0x6b7280)),
+                                    )
+                                    .child(
+                                        Button::new(("display-default", index))
+                                            .label("Use default")
+                                            .small()
+                                            .ghost()
+                                            .disabled(!overridden)
+                                            .on_click(cx.listener(move |this, _, window, cx| {
+                                                let display =
+                                                    option.cleared(&this.city.read(cx).display);
+                                                this.set_display(display, window, cx);
+                                            })),
+                                    )
+                            }),
+                    )
+                    .child(hint(
+                        "What this card shows and where each value comes from. Click a value \
+                         to change it for this card only",
+                    )),
+            )
+            .child({
+                let week = cx.global::<Settings>().week;
+                let weekend = city.weekend.unwrap_or(week.weekend);
+                v_flex()
+                    .gap_2()
+                    .child(section_title("Days off"))
+                    .child(
+                        h_flex()
+                            .gap_1()
+                            .children(week.days().into_iter().map(|day| {
+                                let button =
+                                    Button::new(("weekend-day", day.num_days_from_monday()))
+                                        .label(day.to_string())
+                                        .small()
+                                        .on_click(cx.listener(move |this, _, window, cx| {
+                                            let city = this.city.clone();
+                                            this.app.update(cx, |app, cx| {
+                                                let weekend = Some(weekend.toggled(day));
+                                                app.set_city_weekend(&city, weekend, window, cx)
+                                            });
+                                        }));
+                                if weekend.contains(day) {
+                                    button.primary()
+                                } else {
+                                    button.ghost()
+                                }
+                            }))
+                            .children(city.weekend.is_some().then(|| {
+                                Button::new("default-weekend")
+                                    .label("Use default")
+                                    .small()
+                                    .ghost()
+                                    .on_click(cx.listener(|this, _, window, cx| {
+                                        let city = this.city.clone();
+                                        this.app.update(cx, |app, cx| {
+                                            app.set_city_weekend(&city, None, window, cx)
+                                        });
+                                    }))
+                            })),
+                    )
+                    .child(hint(match city.weekend {
+                        Some(_) => {
+                            format!("Only {}; others use the weekend from settings", city.name)
+                        }
+                        None => format!("The weekend from settings: {}", week.weekend.describe()),
+                    }))
+            })
+            .child(
+                v_flex()
+                    .gap_2()
+                    .child(section_title("Second calendar"))
+                    .child(
+                        h_flex().gap_2().children(
+                            std::iter::once(None)
+                                .chain(AltCalendar::ALL.map(Some))
+                                .enumerate()
+                                .map(|(index, calendar)| {
+                                    let button = Button::new(("calendar", index))
+                                        .label(calendar.map_or("None", AltCalendar::label))
+                                        .small()
+                                        .on_click(cx.listener(move |this, _, window, cx| {
+                                            let city = this.city.clone();
+                                            this.app.update(cx, |app, cx| {
+                                                app.set_city_calendar(&city, calendar, window, cx)
+                                            });
+                                        }));
+                                    if calendar == city.calendar {
+                                        button.primary()
+                                    } else {
+                                        button.ghost()
+                                    }
+                                }),
+                        ),
+                    )
+                    .child(hint(
+                        "Adds the local date in this calendar to the card. Hijri dates are \
+                         calculated and can be a day off from the sighted month",
+                    )),
+            )
+            .child(
+                v_flex()
+                    .gap_2()
+                    .child(section_title("Manual offset"))
+                    .children(city.offset_override.filter(|_| city.override_active).map(
+                        |offset_override| {
+                            h_flex()
+                                .justify_between()
+                                .child(div().child(offset_override.describe()).text_sm())
+                                .child(
+                                    Button::new("clear-override")
+                                        .label("Clear")
+                                        .small()
+                                        .ghost()
+                                        .on_click(cx.listener(|this, _, window, cx| {
+                                            this.set_override(None, window, cx)
+                                        })),
+                                )
+                        },
+                    ))
+                    .child(
+                        h_flex()
+                            .gap_2()
+                            .child(div().w_24().child(Input::new(&self.shift_input)))
+                            .child("hours until")
+                            .child(div().flex_1().child(Input::new(&self.until_input)))
+                            .child(
+                                Button::new("apply-override")
+                                    .label("Apply")
+                                    .small()
+                                    .on_click(cx.listener(|this, _, window, cx| {
+                                        this.apply_override(window, cx)
+                                    })),
+                            ),
+                    )
+                    .children(
+                        self.override_error
+                            .clone()
+                            .map(|error| div().child(error).text_sm().text_color(rgb(0xef4444))),
+                    )
+                    .child(hint(
+                        "For when clocks change before the tz database catches up. Shifts this \
+                         card's time and difference, and lapses at midnight on the chosen date",
+                    )),
+            )
+            .child(
+                v_flex()
+                    .gap_2()
+                    .child(section_title("Snooze indicators"))
+                    .children(city.snoozed_until.filter(|_| city.snoozed).map(|until| {
+                        h_flex()
+                            .justify_between()
+                            .child(div().child(snooze::describe(until)).text_sm())
+                            .child(
+                                Button::new("unsnooze")
+                                    .label("Unsnooze")
+                                    .small()
+                                    .ghost()
+                                    .on_click(cx.listener(|this, _, window, cx| {
+                                        this.set_snooze(None, window, cx)
+                                    })),
+                            )
+                    }))
+                    .child(
+                        h_flex()
+                            .gap_2()
+                            .children(snooze::PRESETS.iter().enumerate().map(
+                                |(index, &(label, days))| {
+                                    Button::new(("snooze-preset", index))
+                                        .label(label)
+                                        .small()
+                                        .ghost()
+                                        .on_click(cx.listener(move |this, _, window, cx| {
+                                            let until =
+                                                snooze::preset_until(this.city_today(cx), days);
+                                            this.set_snooze(Some(until), window, cx)
+                                        }))
+                                },
+                            ))
+                            .child(div().flex_1().child(Input::new(&self.snooze_input)))
+                            .child(
+                                Button::new("apply-snooze")
+                                    .label("Snooze")
+                                    .small()
+                                    .on_click(cx.listener(|this, _, window, cx| {
+                                        this.apply_snooze(window, cx)
+                                    })),
+                            ),
+                    )
+                    .children(
+                        self.snooze_error
+                            .clone()
+                            .map(|error| div().child(error).text_sm().text_color(rgb(0xef4444))),
+                    )
+                    .child(hint(
+                        "Hides do not disturb, same-time and call hints and other badges on \
+                         this card; the time and difference stay. Ends at midnight on the date",
+                    )),
+            )
+            .child(
+                v_flex()
+                    .gap_2()
+                    .child(section_title("Location"))
+                    .child(Input::new(&self.coordinates_input))
+                    .children(
+                        self.coordinates_error
+                            .clone()
+                            .map(|error| div().child(error).text_sm().text_color(rgb(0xef4444))),
+                    )
+                    .child(hint(
+                        "Press Enter to save; leave empty to use the built-in location",
+                    ))
+                    .child(
+                        Button::new("open-in-maps")
+                            .label("Open in Maps")
+                            .small()
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                let url = this.city.read(cx).maps_url();
+                                if let Err(err) = browser::open_url(&url) {
+                                    window.push_notification(
+                                        Notification::error(format!("Couldn't open maps: {}", err)),
+                                        cx,
+                                    );
+                                }
+                            })),
+                    ),
+            )
+    }
+}
+
+// "Δ has been -6h since Mar 9; changes to -5h on Mar 30", dated at home
+fn describe_diff_span(home_tz: Tz, city: &WorldTime, now: DateTime<Utc>) -> String {
+    let span = zone::diff_span(home_tz, city.tz, now);
+    let since = match span.since {
+        Some(since) => format!(
+            "since {}",
+            format::month_day(since.with_timezone(&home_tz).date_naive())
+        ),
+        None => "for over a year".to_string(),
+    };
+    let next = match (span.until, span.next_diff_minutes) {
+        (Some(until), Some(next)) => format!(
+            "changes to {}h on {}",
+            format::diff_hours(next),
+            format::month_day(until.with_timezone(&home_tz).date_naive())
+        ),
+        _ => "no change in the coming year".to_string(),
+    };
+    format!(
+        "Δ has been {}h {}; {}",
+        format::diff_hours(
+            zone::utc_offset_minutes(city.tz, now) - zone::utc_offset_minutes(home_tz, now)
+        ),
+        since,
+        next
+    )
+}
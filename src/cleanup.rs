@@ -0,0 +1,255 @@
+use crate::{WorldTimeApp, settings_panel::hint};
+use chrono::{DateTime, Utc};
+use gpui::{App, Context, Entity, Window, div, prelude::*};
+use gpui_component::{
+    Sizable, WindowExt as _,
+    button::{Button, ButtonVariants as _},
+    dialog::DialogButtonProps,
+    h_flex, v_flex,
+};
+
+// How long a card can go untouched before the review offers it, in days
+pub const PERIODS: [u32; 4] = [30, 90, 180, 365];
+
+// One card as the review sees it
+#[derive(Debug, Clone, PartialEq)]
+pub struct Usage {
+    pub name: String,
+    pub is_home: bool,
+    pub ephemeral: bool,
+    pub hidden: bool,
+    pub last_interacted: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Candidate {
+    pub name: String,
+    pub hidden: bool,
+    pub idle_days: i64,
+}
+
+// Whole days since the card was last focused, copied, edited or exported
+pub fn idle_days(last_interacted: DateTime<Utc>, now: DateTime<Utc>) -> i64 {
+    (now - last_interacted).num_days().max(0)
+}
+
+// Cards untouched for more than `period_days`, longest idle first. Home and
+// cities added for this run only are never offered.
+pub fn candidates(cards: &[Usage], now: DateTime<Utc>, period_days: u32) -> Vec<Candidate> {
+    let mut candidates: Vec<Candidate> = cards
+        .iter()
+        .filter(|card| !card.is_home && !card.ephemeral)
+        .map(|card| Candidate {
+            name: card.name.clone(),
+            hidden: card.hidden,
+            idle_days: idle_days(card.last_interacted, now),
+        })
+        .filter(|candidate| candidate.idle_days > period_days as i64)
+        .collect();
+    candidates.sort_by(|a, b| {
+        b.idle_days
+            .cmp(&a.idle_days)
+            .then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase()))
+    });
+    candidates
+}
+
+// "untouched for 45 days", "untouched for 7 months", "untouched for 2 years"
+pub fn describe_idle(days: i64) -> String {
+    match days {
+        ..60 => format!("untouched for {} days", days),
+        60..730 => format!("untouched for {} months", days / 30),
+        _ => format!("untouched for {} years", days / 365),
+    }
+}
+
+// "3 months" for the period buttons
+pub fn describe_period(days: u32) -> String {
+    match days {
+        365 => "a year".to_string(),
+        days if days > 30 && days % 30 == 0 => format!("{} months", days / 30),
+        days => format!("{} days", days),
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Outcome {
+    Hidden,
+    Removed,
+}
+
+// The candidates with a hide and a remove button each; nothing goes without a click
+struct Review {
+    app: Entity<WorldTimeApp>,
+    candidates: Vec<Candidate>,
+    outcomes: Vec<Option<Outcome>>,
+}
+
+impl Render for Review {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .gap_2()
+            .children(
+                self.candidates
+                    .iter()
+                    .enumerate()
+                    .map(|(index, candidate)| {
+                        let actions = match self.outcomes[index] {
+                            Some(Outcome::Hidden) => {
+                                div().child("Hidden").text_sm().into_any_element()
+                            }
+                            Some(Outcome::Removed) => {
+                                div().child("Removed").text_sm().into_any_element()
+                            }
+                            None => h_flex()
+                                .gap_1()
+                                .children((!candidate.hidden).then(|| {
+                                    Button::new(("cleanup-hide", index))
+                                        .label("Hide")
+                                        .small()
+                                        .ghost()
+                                        .on_click(cx.listener(move |this, _, window, cx| {
+                                            let name = this.candidates[index].name.clone();
+                                            this.app.update(cx, |app, cx| {
+                                                app.set_city_hidden(&name, true, window, cx)
+                                            });
+                                            this.outcomes[index] = Some(Outcome::Hidden);
+                                            cx.notify();
+                                        }))
+                                }))
+                                .child(
+                                    Button::new(("cleanup-remove", index))
+                                        .label("Remove")
+                                        .small()
+                                        .danger()
+                                        .on_click(cx.listener(move |this, _, window, cx| {
+                                            let name = this.candidates[index].name.clone();
+                                            this.app.update(cx, |app, cx| {
+                                                app.remove_cities(&[name], window, cx)
+                                            });
+                                            this.outcomes[index] = Some(Outcome::Removed);
+                                            cx.notify();
+                                        })),
+                                )
+                                .into_any_element(),
+                        };
+                        let idle = describe_idle(candidate.idle_days);
+                        h_flex()
+                            .gap_3()
+                            .justify_between()
+                            .child(
+                                v_flex()
+                                    .child(div().child(candidate.name.clone()).text_sm())
+                                    .child(hint(if candidate.hidden {
+                                        format!("{}, hidden", idle)
+                                    } else {
+                                        idle
+                                    })),
+                            )
+                            .child(actions)
+                    }),
+            )
+            .child(hint(
+                "Hidden cities stay in the palette under \"Show again\"; undo brings back either",
+            ))
+    }
+}
+
+pub fn open_review(
+    app: Entity<WorldTimeApp>,
+    candidates: Vec<Candidate>,
+    period_days: u32,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    let outcomes = vec![None; candidates.len()];
+    let review = cx.new(|_| Review {
+        app,
+        candidates,
+        outcomes,
+    });
+    window.open_dialog(cx, move |dialog, _window, _cx| {
+        dialog
+            .title(format!(
+                "Cities untouched for over {}",
+                describe_period(period_days)
+            ))
+            .child(review.clone())
+            .alert()
+            .button_props(DialogButtonProps::default().ok_text("Done"))
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn now() -> DateTime<Utc> {
+        "2026-07-01T12:00:00Z".parse().unwrap()
+    }
+
+    fn usage(name: &str, idle_days: i64) -> Usage {
+        Usage {
+            name: name.to_string(),
+            is_home: false,
+            ephemeral: false,
+            hidden: false,
+            last_interacted: now() - Duration::days(idle_days),
+        }
+    }
+
+    fn names(candidates: &[Candidate]) -> Vec<&str> {
+        candidates
+            .iter()
+            .map(|candidate| candidate.name.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn candidates_are_past_the_period_longest_idle_first() {
+        let cards = [
+            usage("Berlin", 30),
+            usage("tokyo", 45),
+            usage("Austin", 45),
+            usage("Lagos", 400),
+            usage("Lima", 31),
+        ];
+        let offered = candidates(&cards, now(), 30);
+        // Exactly the period isn't past it; ties go by name, ignoring case
+        assert_eq!(names(&offered), ["Lagos", "Austin", "tokyo", "Lima"]);
+        assert_eq!(offered[0].idle_days, 400);
+        assert_eq!(names(&candidates(&cards, now(), 365)), ["Lagos"]);
+    }
+
+    #[test]
+    fn home_and_cities_for_this_run_are_never_offered() {
+        let mut home = usage("Home", 500);
+        home.is_home = true;
+        let mut flag = usage("From --city", 500);
+        flag.ephemeral = true;
+        let mut hidden = usage("Hidden", 500);
+        hidden.hidden = true;
+
+        assert_eq!(
+            candidates(&[home, flag, hidden], now(), 30),
+            [Candidate {
+                name: "Hidden".to_string(),
+                hidden: true,
+                idle_days: 500,
+            }]
+        );
+    }
+
+    #[test]
+    fn idle_time_reads_in_days_months_and_years() {
+        assert_eq!(idle_days(now() + Duration::hours(3), now()), 0);
+        assert_eq!(idle_days(now() - Duration::hours(47), now()), 1);
+        assert_eq!(describe_idle(45), "untouched for 45 days");
+        assert_eq!(describe_idle(210), "untouched for 7 months");
+        assert_eq!(describe_idle(800), "untouched for 2 years");
+
+        let periods: Vec<String> = PERIODS.into_iter().map(describe_period).collect();
+        assert_eq!(periods, ["30 days", "3 months", "6 months", "a year"]);
+    }
+}
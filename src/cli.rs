@@ -0,0 +1,333 @@
+use crate::{
+    config::Config,
+    convert, export,
+    ticker::{self, UpdateInterval},
+    zone,
+};
+use chrono::{DateTime, Utc};
+use clap::{Parser, Subcommand};
+use std::{
+    io::{self, IsTerminal, Write},
+    process::ExitCode,
+    time::Duration,
+};
+use time2rust::format::{self, SnippetCity};
+
+#[derive(Parser)]
+#[command(name = "time2rust", version, about = "World clock for your desktop")]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+    /// Print the current time in every configured city instead of opening the window
+    #[arg(long)]
+    pub print: bool,
+    /// Like --print, but as CSV
+    #[arg(long)]
+    pub csv: bool,
+    /// Like --print, but as JSON
+    #[arg(long, conflicts_with = "csv")]
+    pub json: bool,
+    /// Open a time2rust:// link, in the running instance if there is one
+    #[arg(long, value_name = "URL")]
+    pub open_url: Option<String>,
+    /// Extra city for this run, as name=Area/City (repeatable)
+    #[arg(long = "city", value_name = "NAME=TZ")]
+    pub cities: Vec<String>,
+    /// Show the first-run setup again
+    #[arg(long)]
+    pub reset_onboarding: bool,
+    /// Start full screen, rotating through the cities (any key or click exits)
+    #[arg(long)]
+    pub kiosk: bool,
+    /// Take commands on stdin, one per line: add NAME=TZ, remove NAME, set-home NAME,
+    /// snapshot PATH
+    #[arg(long)]
+    pub stdin_control: bool,
+    /// Seconds between refreshes for this run (1 to 900; default from settings)
+    #[arg(long, value_name = "SECONDS", value_parser = parse_interval)]
+    pub interval: Option<UpdateInterval>,
+}
+
+fn parse_interval(value: &str) -> Result<UpdateInterval, String> {
+    let seconds = value
+        .parse::<u64>()
+        .map_err(|_| format!("expected whole seconds, not {:?}", value))?;
+    UpdateInterval::new(seconds)
+}
+
+impl Cli {
+    // Whether this invocation is handled on the terminal rather than by the GUI
+    pub fn is_terminal_mode(&self) -> bool {
+        self.command.is_some() || self.print || self.csv || self.json
+    }
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Show an instant in every configured city
+    Convert {
+        /// "2025-04-01 15:00", "15:00" (today) or "tomorrow 09:00", optionally followed
+        /// by a city, timezone, "here" or "home" ("15:00 Berlin")
+        time: String,
+        /// City, timezone, "here" or "home" the time is given in (defaults to home)
+        #[arg(long)]
+        from: Option<String>,
+        /// Only show the result in this city or zone
+        #[arg(long)]
+        to: Option<String>,
+    },
+    /// Print one line for a status bar, like "14:32 CST | BER 21:32"
+    Status {
+        /// Placeholders like {home}, {Berlin}, {Berlin:time} or {all}; defaults to the
+        /// paste snippet's template
+        #[arg(long)]
+        format: Option<String>,
+        /// Write times as 14:45, whatever the settings say
+        #[arg(long = "24h", conflicts_with = "twelve_hour")]
+        twenty_four_hour: bool,
+        /// Write times as 2:45 PM
+        #[arg(long = "12h")]
+        twelve_hour: bool,
+        /// Keep printing the line, once at the start of every minute
+        #[arg(long)]
+        follow: bool,
+        /// Print {"text": ..., "tooltip": ...} as bars like waybar read it
+        #[arg(long)]
+        json: bool,
+    },
+    /// Keep redrawing the --print table until Ctrl-C
+    Watch {
+        /// Seconds between refreshes; whole minutes stay aligned to the clock
+        #[arg(long, default_value_t = 60)]
+        interval: u64,
+    },
+}
+
+pub fn run(cli: Cli, config: &Config) -> ExitCode {
+    let result = match cli.command {
+        Some(Command::Convert { time, from, to }) => {
+            run_convert(&time, from.as_deref(), to.as_deref(), config)
+        }
+        Some(Command::Status {
+            format,
+            twenty_four_hour,
+            twelve_hour,
+            follow,
+            json,
+        }) => {
+            let clock = match (twelve_hour, twenty_four_hour) {
+                (true, _) => StatusClock::TwelveHour,
+                (_, true) => StatusClock::TwentyFourHour,
+                _ => StatusClock::Settings,
+            };
+            let template = format.unwrap_or_else(|| config.settings.snippet.template.clone());
+            run_status(&template, clock, follow, json, config)
+        }
+        Some(Command::Watch { interval }) => run_watch(interval, config),
+        None => run_list(cli.csv, cli.json, config),
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("time2rust: {}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run_convert(
+    time: &str,
+    from: Option<&str>,
+    to: Option<&str>,
+    config: &Config,
+) -> Result<(), String> {
+    let home = convert::home_zone(&config.cities);
+    let now = Utc::now();
+    let conversion = match from {
+        Some(from) => {
+            let from = convert::resolve_zone_name(from, &config.cities, &home)?;
+            convert::convert_query(time, &config.cities, &from, now)?
+        }
+        None => convert::convert_query(time, &config.cities, &home, now)?,
+    };
+    let resolved = conversion.resolved;
+    if let Some(note) = &resolved.note {
+        eprintln!("note: {}", note);
+    }
+
+    if let Some(to) = to {
+        let to = convert::resolve_zone_name(to, &config.cities, &home)?;
+        println!(
+            "{}",
+            convert::format_in_zone(&to.label, resolved.instant, to.tz)
+        );
+        return Ok(());
+    }
+
+    for city in &config.cities {
+        match zone::resolve_zone(&city.timezone) {
+            Ok(tz) => println!(
+                "{}",
+                convert::format_in_zone(&city.name, resolved.instant, tz)
+            ),
+            Err(err) => eprintln!("skipping {}: {}", city.name, err),
+        }
+    }
+    Ok(())
+}
+
+fn run_list(csv: bool, json: bool, config: &Config) -> Result<(), String> {
+    let now = Utc::now();
+    let (snapshots, errors) =
+        export::snapshot_cities(&config.cities, now, config.settings.overrides_in_exports);
+    for error in errors {
+        eprintln!("skipping {}", error);
+    }
+    if json {
+        println!(
+            "{}",
+            export::write_json(&snapshots, now, config.settings.include_people)
+        );
+    } else if csv {
+        print!(
+            "{}",
+            export::write_csv(&snapshots, config.settings.include_people)
+        );
+    } else {
+        print!(
+            "{}",
+            export::format_table(&snapshots, config.settings.clock_format)
+        );
+    }
+    Ok(())
+}
+
+#[derive(Debug, Clone, Copy)]
+enum StatusClock {
+    Settings,
+    TwentyFourHour,
+    TwelveHour,
+}
+
+// The same values the paste snippet uses, from the config rather than the cards
+fn status_cities(config: &Config, now: DateTime<Utc>, clock: StatusClock) -> Vec<SnippetCity> {
+    let (snapshots, _) =
+        export::snapshot_cities(&config.cities, now, config.settings.overrides_in_exports);
+    let home = snapshots
+        .iter()
+        .position(|city| city.is_home)
+        .unwrap_or_default();
+    snapshots
+        .iter()
+        .enumerate()
+        .map(|(index, city)| {
+            let time = city.local.time();
+            let zone = zone::resolve_zone(&city.timezone_id)
+                .map(|tz| now.with_timezone(&tz).format("%Z").to_string())
+                .unwrap_or_else(|_| format::short_utc_offset(city.utc_offset_minutes));
+            SnippetCity {
+                name: city.name.clone(),
+                time: match clock {
+                    StatusClock::Settings => {
+                        config
+                            .settings
+                            .clock_format
+                            .format(time, city.utc_offset_minutes, false)
+                    }
+                    StatusClock::TwentyFourHour => format::clock_time(time, false),
+                    StatusClock::TwelveHour => format::clock_time_12h(time),
+                },
+                zone,
+                date: format::weekday_date(city.local.date_naive()),
+                home: index == home,
+                shown: !config
+                    .cities
+                    .iter()
+                    .any(|config| config.id == city.id && config.hidden),
+            }
+        })
+        .collect()
+}
+
+fn run_status(
+    template: &str,
+    clock: StatusClock,
+    follow: bool,
+    json: bool,
+    config: &Config,
+) -> Result<(), String> {
+    let parts = format::parse_snippet(template)?;
+    let (_, errors) = export::snapshot_cities(&config.cities, Utc::now(), false);
+    for error in errors {
+        eprintln!("skipping {}", error);
+    }
+
+    loop {
+        let now = Utc::now();
+        let cities = status_cities(config, now, clock);
+        // Checked on every line, so a bad name fails before a bar ever shows it
+        let text = format::render_snippet(&parts, &cities)?;
+        let line = if json {
+            let tooltip = cities
+                .iter()
+                .map(|city| format!("{} {} {}", city.name, city.time, city.zone))
+                .collect::<Vec<_>>()
+                .join("\n");
+            serde_json::json!({ "text": text, "tooltip": tooltip }).to_string()
+        } else {
+            text
+        };
+        let mut stdout = io::stdout().lock();
+        writeln!(stdout, "{}", line)
+            .and_then(|()| stdout.flush())
+            .map_err(|err| err.to_string())?;
+        drop(stdout);
+        if !follow {
+            return Ok(());
+        }
+
+        std::thread::sleep(ticker::next_tick_delay(Utc::now(), Duration::from_secs(60)));
+    }
+}
+
+fn run_watch(interval: u64, config: &Config) -> Result<(), String> {
+    if interval == 0 {
+        return Err("--interval must be at least 1 second".to_string());
+    }
+    let interval = Duration::from_secs(interval);
+    // Redraw in place on a terminal; when piped, append so the output stays a log
+    let redraw = io::stdout().is_terminal();
+
+    let (_, errors) = export::snapshot_cities(&config.cities, Utc::now(), false);
+    for error in errors {
+        eprintln!("skipping {}", error);
+    }
+
+    loop {
+        let now = Utc::now();
+        let (snapshots, _) =
+            export::snapshot_cities(&config.cities, now, config.settings.overrides_in_exports);
+        let mut stdout = io::stdout().lock();
+        let written = if redraw {
+            write!(
+                stdout,
+                "\x1b[2J\x1b[H{}\nupdated {} UTC · Ctrl-C to quit\n",
+                export::format_table(&snapshots, config.settings.clock_format),
+                format::clock_time(now.time(), true)
+            )
+        } else {
+            write!(
+                stdout,
+                "# {}\n{}",
+                now.format("%Y-%m-%dT%H:%M:%SZ"),
+                export::format_table(&snapshots, config.settings.clock_format)
+            )
+        };
+        written
+            .and_then(|()| stdout.flush())
+            .map_err(|err| err.to_string())?;
+        drop(stdout);
+
+        std::thread::sleep(ticker::next_tick_delay(Utc::now(), interval));
+    }
+}
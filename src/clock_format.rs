@@ -0,0 +1,31 @@
+use chrono::NaiveTime;
+use serde::{Deserialize, Serialize};
+use time2rust::format;
+
+// How times are written on the cards and in what's copied or exported
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ClockFormat {
+    #[default]
+    TwentyFourHour, // "14:45"
+    Military, // "1445A", lettered from the zone's offset
+}
+
+impl ClockFormat {
+    pub const ALL: [ClockFormat; 2] = [ClockFormat::TwentyFourHour, ClockFormat::Military];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ClockFormat::TwentyFourHour => "14:45",
+            ClockFormat::Military => "1445A",
+        }
+    }
+
+    // `offset_minutes` is the city's own UTC offset, which picks the zone letter
+    pub fn format(self, time: NaiveTime, offset_minutes: i32, show_seconds: bool) -> String {
+        match self {
+            ClockFormat::TwentyFourHour => format::clock_time(time, show_seconds),
+            ClockFormat::Military => format::military_time(time, offset_minutes, show_seconds),
+        }
+    }
+}
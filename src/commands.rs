@@ -0,0 +1,409 @@
+use crate::WorldTimeApp;
+use gpui::{
+    App, Context, Entity, KeyDownEvent, Keystroke, SharedString, Window, div, prelude::*, px, rgb,
+};
+use gpui_component::{
+    ActiveTheme as _, WindowExt as _, h_flex,
+    input::{Input, InputEvent, InputState},
+    v_flex,
+};
+
+// Everything the app can do from a button, menu, shortcut or the palette. New
+// features add a variant here and show up in the palette on their own.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AppCommand {
+    OpenPalette,
+    ShowShortcuts,
+    WhatsNew,
+    AddCity,
+    AddCountdown,
+    ToggleArchive,
+    ImportCities,
+    RemoveCities,
+    ReviewUnused,
+    UpdateZoneIds,
+    BackUp,
+    Restore,
+    Undo,
+    ToggleTable,
+    ToggleAutoOrder,
+    ExpandStack(String),
+    CollapseStack(String),
+    PlanMeeting,
+    FindOverlap,
+    ExportCsv,
+    ExportWeek,
+    CopySlack,
+    MiniWidget,
+    Kiosk,
+    OpenSettings,
+    ResetSettings,
+    SaveProfileAs,
+    SwitchProfile(String),
+    CopyTime(String),
+    ShareCard(String),
+    SetHome(String),
+    ShowDetails(String),
+    AddAlarm(String),
+    Snooze(String),
+    Unsnooze(String),
+    OpenInWindow(String),
+    Compare(String),
+    RemoveCity(String),
+    ShowHidden(String),
+    SetMarker,
+    ToggleMarkerOnCards,
+    ClearMarker,
+}
+
+impl AppCommand {
+    pub fn label(&self) -> String {
+        match self {
+            AppCommand::OpenPalette => "Show all commands".to_string(),
+            AppCommand::ShowShortcuts => "Keyboard shortcuts".to_string(),
+            AppCommand::WhatsNew => "What's new".to_string(),
+            AppCommand::AddCity => "Add city…".to_string(),
+            AppCommand::AddCountdown => "Add countdown…".to_string(),
+            AppCommand::ToggleArchive => "Show or hide past countdowns".to_string(),
+            AppCommand::ImportCities => "Replace cities from file…".to_string(),
+            AppCommand::RemoveCities => "Remove several cities…".to_string(),
+            AppCommand::ReviewUnused => "Review unused cities…".to_string(),
+            AppCommand::UpdateZoneIds => "Update outdated timezone names".to_string(),
+            AppCommand::BackUp => "Back up everything…".to_string(),
+            AppCommand::Restore => "Restore from backup…".to_string(),
+            AppCommand::Undo => "Undo".to_string(),
+            AppCommand::ToggleTable => "Switch between cards and table".to_string(),
+            AppCommand::ToggleAutoOrder => "Keep cities ordered by local time (on/off)".to_string(),
+            AppCommand::ExpandStack(zone) => format!("Show {} cards separately", zone),
+            AppCommand::CollapseStack(zone) => format!("Stack {} cards", zone),
+            AppCommand::PlanMeeting => "Plan meeting".to_string(),
+            AppCommand::FindOverlap => "Find overlap…".to_string(),
+            AppCommand::ExportCsv => "Export CSV…".to_string(),
+            AppCommand::ExportWeek => "Export week…".to_string(),
+            AppCommand::CopySlack => "Copy for Slack".to_string(),
+            AppCommand::MiniWidget => "Show mini widget".to_string(),
+            AppCommand::Kiosk => "Start kiosk mode".to_string(),
+            AppCommand::OpenSettings => "Settings".to_string(),
+            AppCommand::ResetSettings => "Reset settings to defaults…".to_string(),
+            AppCommand::SaveProfileAs => "Save current setup as new profile…".to_string(),
+            AppCommand::SwitchProfile(name) => format!("Switch to profile: {}", name),
+            AppCommand::CopyTime(city) => format!("Copy time: {}", city),
+            AppCommand::ShareCard(city) => format!("Share card: {}", city),
+            AppCommand::SetHome(city) => format!("Set home: {}", city),
+            AppCommand::ShowDetails(city) => format!("Details: {}", city),
+            AppCommand::AddAlarm(city) => format!("Add alarm: {}", city),
+            AppCommand::Snooze(city) => format!("Snooze indicators: {}", city),
+            AppCommand::Unsnooze(city) => format!("Unsnooze: {}", city),
+            AppCommand::OpenInWindow(city) => format!("Open in new window: {}", city),
+            AppCommand::Compare(city) => format!("Compare {} with…", city),
+            AppCommand::RemoveCity(city) => format!("Remove: {}", city),
+            AppCommand::ShowHidden(city) => format!("Show again: {}", city),
+            AppCommand::SetMarker => "Set marker (now)".to_string(),
+            AppCommand::ToggleMarkerOnCards => "Show marker on cards (on/off)".to_string(),
+            AppCommand::ClearMarker => "Clear marker".to_string(),
+        }
+    }
+
+    // The shorter wording a card's own menu uses, where the city goes without saying
+    pub fn menu_label(&self) -> String {
+        match self {
+            AppCommand::CopyTime(_) => "Copy time".to_string(),
+            AppCommand::ShareCard(_) => "Share card".to_string(),
+            AppCommand::SetHome(_) => "Set as home".to_string(),
+            AppCommand::ShowDetails(_) => "Details…".to_string(),
+            AppCommand::AddAlarm(_) => "Add alarm…".to_string(),
+            AppCommand::Snooze(_) => "Snooze indicators…".to_string(),
+            AppCommand::Unsnooze(_) => "Unsnooze".to_string(),
+            AppCommand::OpenInWindow(_) => "Open in new window".to_string(),
+            AppCommand::Compare(_) => "Compare with…".to_string(),
+            AppCommand::RemoveCity(_) => "Remove…".to_string(),
+            command => command.label(),
+        }
+    }
+
+    // The city a per-city command acts on
+    pub fn city(&self) -> Option<&str> {
+        match self {
+            AppCommand::CopyTime(city)
+            | AppCommand::ShareCard(city)
+            | AppCommand::SetHome(city)
+            | AppCommand::ShowDetails(city)
+            | AppCommand::AddAlarm(city)
+            | AppCommand::Snooze(city)
+            | AppCommand::Unsnooze(city)
+            | AppCommand::OpenInWindow(city)
+            | AppCommand::Compare(city)
+            | AppCommand::RemoveCity(city)
+            | AppCommand::ShowHidden(city) => Some(city),
+            _ => None,
+        }
+    }
+
+    // In gpui keystroke syntax; "secondary" is cmd on macOS and ctrl elsewhere
+    pub fn shortcuts(&self) -> &'static [&'static str] {
+        match self {
+            AppCommand::OpenPalette => &["secondary-k"],
+            AppCommand::ShowShortcuts => &["?", "f1"],
+            AppCommand::AddCity => &["secondary-n"],
+            AppCommand::Undo => &["secondary-z"],
+            AppCommand::ToggleTable => &["secondary-t"],
+            AppCommand::PlanMeeting => &["secondary-p"],
+            AppCommand::CopySlack => &["secondary-shift-c"],
+            AppCommand::SetMarker => &["secondary-shift-m"],
+            AppCommand::OpenSettings => &["secondary-,"],
+            _ => &[],
+        }
+    }
+
+    // Heading the command is listed under in the shortcut overlay
+    pub fn area(&self) -> &'static str {
+        match self {
+            AppCommand::OpenPalette
+            | AppCommand::ShowShortcuts
+            | AppCommand::WhatsNew
+            | AppCommand::Undo
+            | AppCommand::OpenSettings
+            | AppCommand::ResetSettings
+            | AppCommand::BackUp
+            | AppCommand::Restore
+            | AppCommand::SetMarker
+            | AppCommand::ClearMarker => "General",
+            AppCommand::AddCity
+            | AppCommand::AddCountdown
+            | AppCommand::ImportCities
+            | AppCommand::RemoveCities
+            | AppCommand::ReviewUnused
+            | AppCommand::UpdateZoneIds
+            | AppCommand::SaveProfileAs
+            | AppCommand::SwitchProfile(_)
+            | AppCommand::SetHome(_)
+            | AppCommand::AddAlarm(_)
+            | AppCommand::Snooze(_)
+            | AppCommand::Unsnooze(_)
+            | AppCommand::RemoveCity(_)
+            | AppCommand::ShowHidden(_) => "Cities",
+            AppCommand::ToggleTable
+            | AppCommand::ToggleArchive
+            | AppCommand::ToggleAutoOrder
+            | AppCommand::ExpandStack(_)
+            | AppCommand::CollapseStack(_)
+            | AppCommand::MiniWidget
+            | AppCommand::Kiosk
+            | AppCommand::ShowDetails(_)
+            | AppCommand::OpenInWindow(_)
+            | AppCommand::Compare(_)
+            | AppCommand::ToggleMarkerOnCards => "View",
+            AppCommand::PlanMeeting | AppCommand::FindOverlap => "Planning",
+            AppCommand::ExportCsv
+            | AppCommand::ExportWeek
+            | AppCommand::CopySlack
+            | AppCommand::CopyTime(_)
+            | AppCommand::ShareCard(_) => "Sharing",
+        }
+    }
+}
+
+// What a card's right-click menu offers, in order; the same commands the palette
+// lists for the city
+pub fn card_menu(city: &str, snoozed: bool) -> Vec<AppCommand> {
+    vec![
+        AppCommand::CopyTime(city.to_string()),
+        AppCommand::ShareCard(city.to_string()),
+        AppCommand::SetHome(city.to_string()),
+        AppCommand::ShowDetails(city.to_string()),
+        AppCommand::AddAlarm(city.to_string()),
+        if snoozed {
+            AppCommand::Unsnooze(city.to_string())
+        } else {
+            AppCommand::Snooze(city.to_string())
+        },
+        AppCommand::OpenInWindow(city.to_string()),
+        AppCommand::Compare(city.to_string()),
+        AppCommand::RemoveCity(city.to_string()),
+    ]
+}
+
+// A plain character like "?" would fire while typing, and an input has its own
+// undo, so these only count when no input has focus
+pub fn is_typed(shortcut: &str) -> bool {
+    shortcut.chars().count() == 1 || shortcut == "secondary-z"
+}
+
+// Shortcut as gpui displays it on this platform, like "⌘K" or "ctrl-K"
+pub fn shortcut_label(shortcut: &str) -> String {
+    Keystroke::parse(shortcut).map_or_else(|_| shortcut.to_string(), |key| key.to_string())
+}
+
+// Characters like "?" match whatever the layout needs to type them
+pub fn shortcut_matches(shortcut: &str, pressed: &Keystroke) -> bool {
+    Keystroke::parse(shortcut).is_ok_and(|key| {
+        (key.modifiers == pressed.modifiers && key.key == pressed.key)
+            || (is_typed(shortcut) && pressed.key_char.as_deref() == Some(shortcut))
+    })
+}
+
+// Lower is better; None when the query's characters don't all appear in order.
+// Gaps between matched characters cost, and so does starting late.
+pub fn fuzzy_score(query: &str, text: &str) -> Option<usize> {
+    let text: Vec<char> = text.to_lowercase().chars().collect();
+    let mut score = 0;
+    let mut position = 0;
+    for (index, wanted) in query
+        .to_lowercase()
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .enumerate()
+    {
+        let found = text[position..].iter().position(|&c| c == wanted)?;
+        score += if index == 0 { found } else { found * 2 };
+        position += found + 1;
+    }
+    Some(score)
+}
+
+pub fn open_command_palette(app: Entity<WorldTimeApp>, window: &mut Window, cx: &mut App) {
+    let (commands, search_texts) = {
+        let app = app.read(cx);
+        let commands = app.commands(cx);
+        let search_texts = commands
+            .iter()
+            .map(|command| app.command_search_text(command, cx))
+            .collect();
+        (commands, search_texts)
+    };
+    let palette = cx.new(|cx| CommandPalette::new(app, commands, search_texts, window, cx));
+    window.open_dialog(cx, move |dialog, _window, _cx| {
+        dialog.title("Commands").child(palette.clone())
+    });
+}
+
+pub struct CommandPalette {
+    app: Entity<WorldTimeApp>,
+    input: Entity<InputState>,
+    commands: Vec<AppCommand>,
+    search_texts: Vec<String>, // one per command
+    // Indices into `commands`, best match first
+    matches: Vec<usize>,
+    selected: usize,
+}
+
+impl CommandPalette {
+    fn new(
+        app: Entity<WorldTimeApp>,
+        commands: Vec<AppCommand>,
+        search_texts: Vec<String>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let input = cx.new(|cx| InputState::new(window, cx).placeholder("Type a command"));
+        cx.subscribe_in(
+            &input,
+            window,
+            |this, input, event, window, cx| match event {
+                InputEvent::Change => {
+                    let query = input.read(cx).value().to_string();
+                    this.filter(&query);
+                    cx.notify();
+                }
+                InputEvent::PressEnter { .. } => this.run_selected(window, cx),
+                _ => {}
+            },
+        )
+        .detach();
+        input.update(cx, |input, cx| input.focus(window, cx));
+
+        let matches = (0..commands.len()).collect();
+        CommandPalette {
+            app,
+            input,
+            commands,
+            search_texts,
+            matches,
+            selected: 0,
+        }
+    }
+
+    fn filter(&mut self, query: &str) {
+        let mut scored: Vec<(usize, usize)> = self
+            .search_texts
+            .iter()
+            .enumerate()
+            .filter_map(|(index, text)| fuzzy_score(query, text).map(|score| (score, index)))
+            .collect();
+        scored.sort();
+        self.matches = scored.into_iter().map(|(_, index)| index).collect();
+        self.selected = 0;
+    }
+
+    fn move_selection(&mut self, step: isize, cx: &mut Context<Self>) {
+        if self.matches.is_empty() {
+            return;
+        }
+        let count = self.matches.len() as isize;
+        self.selected = (self.selected as isize + step).rem_euclid(count) as usize;
+        cx.notify();
+    }
+
+    fn run(&mut self, command: AppCommand, window: &mut Window, cx: &mut Context<Self>) {
+        window.close_dialog(cx);
+        self.app
+            .update(cx, |app, cx| app.run_command(command, window, cx));
+    }
+
+    fn run_selected(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(&index) = self.matches.get(self.selected) {
+            let command = self.commands[index].clone();
+            self.run(command, window, cx);
+        }
+    }
+}
+
+impl Render for CommandPalette {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .gap_2()
+            // Ahead of the input, which would otherwise take the arrow keys
+            .capture_key_down(cx.listener(|this, event: &KeyDownEvent, _, cx| {
+                match event.keystroke.key.as_str() {
+                    "up" => this.move_selection(-1, cx),
+                    "down" => this.move_selection(1, cx),
+                    _ => return,
+                }
+                cx.stop_propagation();
+            }))
+            .child(Input::new(&self.input))
+            .child(
+                v_flex()
+                    .id("command-list")
+                    .max_h(px(320.0))
+                    .overflow_y_scroll()
+                    .children(self.matches.iter().enumerate().map(|(row, &index)| {
+                        let command = self.commands[index].clone();
+                        let selected = row == self.selected;
+                        h_flex()
+                            .id(("command", index))
+                            .justify_between()
+                            .px_2()
+                            .py_1()
+                            .rounded(px(4.0))
+                            .cursor_pointer()
+                            .when(selected, |row| row.bg(cx.theme().accent))
+                            .child(div().child(command.label()).text_sm())
+                            .children(command.shortcuts().first().map(|shortcut| {
+                                div()
+                                    .child(shortcut_label(shortcut))
+                                    .text_xs()
+                                    .text_color(rgb(0x6b7280))
+                            }))
+                            .on_click(cx.listener(move |this, _, window, cx| {
+                                this.run(command.clone(), window, cx)
+                            }))
+                    })),
+            )
+            .children(self.matches.is_empty().then(|| {
+                div()
+                    .child(SharedString::from("No matching command"))
+                    .text_sm()
+            }))
+    }
+}
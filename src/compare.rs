@@ -0,0 +1,175 @@
+use crate::{
+    WorldTime, WorldTimeApp,
+    export::DisplayInstant,
+    planner::{self, DoNotDisturb, OverlapWindow, WorkingHours},
+    settings_panel::{hint, section_title},
+    timeline::{self, TimelineAxis, TimelineBand},
+    week::Weekend,
+    zone,
+};
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
+use gpui::{Context, div, prelude::*, px, rgb};
+use gpui_component::{ActiveTheme as _, Sizable, button::Button, h_flex, v_flex};
+use time2rust::format;
+
+const SEARCH_DAYS: i64 = 7;
+const OVERLAPS_SHOWN: usize = 3;
+
+// One side of the comparison, with the hours that apply to it
+pub struct Side {
+    pub city: WorldTime,
+    pub hours: WorkingHours,
+    pub dnd: DoNotDisturb,
+    pub weekend: Weekend,
+}
+
+// "Tokyo is 7 h 30 m ahead of Berlin"
+pub fn describe_pair(first: &str, first_offset: i32, second: &str, second_offset: i32) -> String {
+    let diff = second_offset - first_offset;
+    if diff == 0 {
+        return format!("{} and {} share the same time", first, second);
+    }
+    let (hours, minutes) = (diff.abs() / 60, diff.abs() % 60);
+    let amount = match minutes {
+        0 => format!("{} h", hours),
+        _ => format!("{} h {} m", hours, minutes),
+    };
+    let direction = if diff > 0 { "ahead of" } else { "behind" };
+    format!("{} is {} {} {}", second, amount, direction, first)
+}
+
+// The soonest windows when both are within working hours, earliest first
+pub fn next_overlaps(first: &Side, second: &Side, now: DateTime<Utc>) -> Vec<OverlapWindow> {
+    let zones = [
+        (first.city.tz, first.hours, first.dnd, first.weekend),
+        (second.city.tz, second.hours, second.dnd, second.weekend),
+    ];
+    let overlap = planner::find_overlaps(&zones, now, SEARCH_DAYS, usize::MAX);
+    if !overlap.complete {
+        return Vec::new();
+    }
+    let mut windows = overlap.windows;
+    windows.sort_by_key(|window| window.start);
+    windows.truncate(OVERLAPS_SHOWN);
+    windows
+}
+
+pub fn compare_view(
+    first: Side,
+    second: Side,
+    source: DisplayInstant,
+    cx: &mut Context<WorldTimeApp>,
+) -> impl IntoElement {
+    let now = source.instant();
+    let pair = describe_pair(
+        &first.city.name,
+        zone::utc_offset_minutes(first.city.tz, now),
+        &second.city.name,
+        zone::utc_offset_minutes(second.city.tz, now),
+    );
+    let overlaps = next_overlaps(&first, &second, now);
+    let axis = TimelineAxis::new(
+        now,
+        matches!(source, DisplayInstant::Live(_)),
+        &[
+            (first.city.name.clone(), first.city.tz),
+            (second.city.name.clone(), second.city.tz),
+        ],
+    );
+    let bands = [&first, &second].map(|side| {
+        (
+            side.city.name.clone(),
+            TimelineBand::new(axis.clone(), side.city.tz, &side.hours),
+        )
+    });
+    let (first_tz, second_tz) = (first.city.tz, second.city.tz);
+
+    div()
+        .absolute()
+        .inset_0()
+        .flex()
+        .items_center()
+        .justify_center()
+        .bg(cx.theme().background)
+        .occlude()
+        .child(
+            v_flex()
+                .gap_6()
+                .w(px(640.0))
+                .child(
+                    h_flex()
+                        .justify_around()
+                        .child(side_view(&first.city, now))
+                        .child(side_view(&second.city, now)),
+                )
+                .child(div().child(pair).text_lg().text_center())
+                .child(
+                    v_flex()
+                        .gap_1()
+                        .child(section_title("Next 24 hours"))
+                        .children(bands.iter().map(|(name, band)| {
+                            h_flex()
+                                .gap_2()
+                                .child(div().w_24().child(name.clone()).text_sm())
+                                .child(timeline::timeline_band(&format!("compare-{}", name), band))
+                        })),
+                )
+                .child(
+                    v_flex()
+                        .gap_1()
+                        .child(section_title("Both within working hours"))
+                        .children(overlaps.is_empty().then(|| {
+                            hint(format!(
+                                "No shared working hours in the next {} days",
+                                SEARCH_DAYS
+                            ))
+                        }))
+                        .children(overlaps.iter().map(|window| {
+                            div()
+                                .child(format!(
+                                    "{} · {}",
+                                    local_range(window, first_tz),
+                                    local_range(window, second_tz)
+                                ))
+                                .text_sm()
+                        })),
+                )
+                .child(
+                    h_flex().justify_center().child(
+                        Button::new("exit-compare")
+                            .label("Back to all cities (Esc)")
+                            .small()
+                            .on_click(cx.listener(|this, _, _, cx| this.exit_compare(cx))),
+                    ),
+                ),
+        )
+}
+
+fn local_range(window: &OverlapWindow, tz: Tz) -> String {
+    format!(
+        "{}–{}",
+        format::weekday_time(window.start.with_timezone(&tz).naive_local()),
+        format::clock_time(window.end.with_timezone(&tz).time(), false)
+    )
+}
+
+fn side_view(city: &WorldTime, now: DateTime<Utc>) -> impl IntoElement {
+    let local = now.with_timezone(&city.tz);
+    v_flex()
+        .items_center()
+        .gap_1()
+        .child(
+            div()
+                .child(city.name.clone())
+                .text_size(px(28.0))
+                .text_color(rgb(0x6b7280)),
+        )
+        .child(
+            div()
+                .child(format::clock_time(local.time(), false))
+                .text_size(px(96.0))
+                .font_weight(gpui::FontWeight::BOLD),
+        )
+        .child(hint(format::long_date(local.date_naive())))
+}
@@ -0,0 +1,847 @@
+use crate::{
+    alarms::Alarm,
+    calendars::AltCalendar,
+    clock_format::ClockFormat,
+    config_backups,
+    countdown::CountdownConfig,
+    diff_style::DiffStyle,
+    display_options::DisplayOverrides,
+    floating_card::FloatingCardConfig,
+    i18n::Language,
+    offset_override::OffsetOverride,
+    pinned_events::PinnedEvent,
+    places::Coordinates,
+    planner::{DoNotDisturb, WorkingHours},
+    profiles::{self, Profile},
+    quiet_hours::QuietHours,
+    ticker::UpdateInterval,
+    week::{WeekConfig, Weekend},
+    window_state::{Opacity, WindowState},
+    zone,
+    zoom::ZoomSettings,
+};
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+use gpui::Global;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{HashSet, hash_map::RandomState},
+    fmt, fs,
+    hash::{BuildHasher, Hasher},
+    io,
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+// Everything persisted between launches lives in a single JSON file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub settings: Settings,
+    pub cities: Vec<CityConfig>,
+    pub alarms: Vec<Alarm>,
+    pub countdowns: Vec<CountdownConfig>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub window: Option<WindowState>,
+    // Cards or table, kept with the window for the active profile
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub table_view: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub focused_card: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub floating_cards: Vec<FloatingCardConfig>,
+    // Zones whose cards are shown separately although stacking is on
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub expanded_stacks: Vec<String>,
+    // Set for a fresh install or on request, cleared once the user has set things up
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub needs_onboarding: bool,
+    // Name of the setup in `cities`; the others wait in `profiles`
+    pub active_profile: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub profiles: Vec<Profile>,
+    // The instant the header counts up from, until cleared
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub marker: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub marker_on_cards: bool,
+    // Bumped by every save from the app, so a newer file means someone else saved
+    pub generation: u64,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            settings: Settings::default(),
+            cities: default_cities(),
+            alarms: Vec::new(),
+            countdowns: Vec::new(),
+            window: None,
+            table_view: false,
+            focused_card: None,
+            floating_cards: Vec::new(),
+            expanded_stacks: Vec::new(),
+            needs_onboarding: false,
+            active_profile: profiles::DEFAULT_NAME.to_string(),
+            profiles: Vec::new(),
+            marker: None,
+            marker_on_cards: false,
+            generation: 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CityConfig {
+    // Stable across renames and reorders, for exports, links and scripts; empty
+    // only in a config from before ids, until it's loaded once
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub id: String,
+    pub name: String,
+    pub timezone: String, // IANA id like "Europe/Berlin"
+    #[serde(default)]
+    pub home: bool,
+    // Set by hand; otherwise looked up from the bundled places when needed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub coordinates: Option<Coordinates>,
+    // Falls back to Settings::working_hours
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub working_hours: Option<WorkingHours>,
+    // Falls back to Settings::do_not_disturb
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub do_not_disturb: Option<DoNotDisturb>,
+    // Teammates in this city, shown on the card
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub people: Vec<String>,
+    // A second date line on the card, in this calendar
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub calendar: Option<AltCalendar>,
+    // How the card is drawn, like its difference wording; each falls back to Settings
+    #[serde(flatten)]
+    pub display: DisplayOverrides,
+    // Days off, like Fri and Sat; falls back to Settings::week
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub weekend: Option<Weekend>,
+    // A temporary manual shift while the tz database is behind
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub offset_override: Option<OffsetOverride>,
+    // Badges and warnings stay off on the card until local midnight on this date
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub snoozed_until: Option<NaiveDate>,
+    // Last focused, copied, edited or exported; the cleanup review goes by it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_interacted: Option<DateTime<Utc>>,
+    // Kept but left out of the grid and the table until shown again
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub hidden: bool,
+    // Added for this run only (environment or command line), never written back
+    #[serde(skip)]
+    pub ephemeral: bool,
+    // The saved city an override of the same name stands in for this run; it is
+    // what gets written back
+    #[serde(skip)]
+    pub saved: Option<Box<CityConfig>>,
+}
+
+impl CityConfig {
+    pub fn new(name: &str, timezone: &str, home: bool) -> Self {
+        CityConfig {
+            id: String::new(),
+            name: name.to_string(),
+            timezone: timezone.to_string(),
+            home,
+            coordinates: None,
+            working_hours: None,
+            do_not_disturb: None,
+            people: Vec::new(),
+            calendar: None,
+            display: DisplayOverrides::default(),
+            weekend: None,
+            offset_override: None,
+            snoozed_until: None,
+            last_interacted: None,
+            hidden: false,
+            ephemeral: false,
+            saved: None,
+        }
+    }
+
+    // What the config file should hold for this city: itself, the saved city it
+    // overrides, or nothing for one added for this run only
+    pub fn persisted(self) -> Option<CityConfig> {
+        if self.ephemeral {
+            self.saved.map(|saved| *saved)
+        } else {
+            Some(self)
+        }
+    }
+}
+
+// Eight hex digits from a randomly keyed hash, so two cards are all but certain
+// never to share one; `assign_city_ids` makes sure within a list
+pub fn new_city_id() -> String {
+    static CALLS: AtomicU64 = AtomicU64::new(0);
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u64(CALLS.fetch_add(1, Ordering::Relaxed));
+    hasher.write_i64(Utc::now().timestamp_nanos_opt().unwrap_or_default());
+    format!("{:08x}", hasher.finish() as u32)
+}
+
+// Gives each city without an id, or with one an earlier city already has, a new
+// one; true if anything changed
+pub fn assign_city_ids(cities: &mut [CityConfig]) -> bool {
+    let mut seen: HashSet<String> = HashSet::new();
+    let mut changed = false;
+    for city in cities {
+        if city.id.is_empty() || seen.contains(&city.id) {
+            city.id = std::iter::repeat_with(new_city_id)
+                .find(|id| !seen.contains(id))
+                .unwrap_or_default();
+            changed = true;
+        }
+        seen.insert(city.id.clone());
+    }
+    changed
+}
+
+pub const CITIES_ENV_VAR: &str = "TIME2RUST_CITIES";
+
+// Parses "Tokyo=Asia/Tokyo;Lagos=Africa/Lagos" (entries separated by ';' or
+// newlines). Invalid entries are reported without dropping the valid ones.
+pub fn parse_city_list(input: &str) -> (Vec<CityConfig>, Vec<String>) {
+    let mut cities = Vec::new();
+    let mut errors = Vec::new();
+    for entry in input.split([';', '\n']).map(str::trim) {
+        if entry.is_empty() {
+            continue;
+        }
+        let Some((name, timezone)) = entry.split_once('=') else {
+            errors.push(format!("\"{}\" should look like name=Area/City", entry));
+            continue;
+        };
+        let name = name.trim();
+        if name.is_empty() {
+            errors.push(format!("\"{}\" is missing a name", entry));
+            continue;
+        }
+        match zone::resolve_zone(timezone) {
+            Ok(tz) => cities.push(CityConfig::new(name, tz.name(), false)),
+            Err(err) => errors.push(format!("{}: {}", name, err)),
+        }
+    }
+    (cities, errors)
+}
+
+// Later sources win: a city with an existing name is replaced in place,
+// new names are appended. A saved city replaced for this run is kept aside to
+// be written back unchanged.
+pub fn merge_cities(cities: &mut Vec<CityConfig>, extra: Vec<CityConfig>) {
+    for city in extra {
+        match cities
+            .iter_mut()
+            .find(|existing| existing.name == city.name)
+        {
+            Some(existing) => {
+                if city.ephemeral && !existing.ephemeral {
+                    existing.saved = Some(Box::new(existing.clone()));
+                }
+                existing.timezone = city.timezone;
+                existing.ephemeral = city.ephemeral;
+            }
+            None => cities.push(city),
+        }
+    }
+}
+
+fn default_cities() -> Vec<CityConfig> {
+    vec![
+        CityConfig::new("Austin", "America/Chicago", true),
+        CityConfig::new("NYC", "America/New_York", false),
+        CityConfig::new("London", "Europe/London", false),
+        CityConfig::new("Berlin", "Europe/Berlin", false),
+        CityConfig::new("Bucharest", "Europe/Bucharest", false),
+    ]
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub hotkey: HotkeySettings,
+    pub snippet: SnippetSettings,
+    pub http: HttpSettings,
+    pub backups: BackupSettings,
+    pub week: WeekConfig,
+    pub slack: SlackSettings,
+    pub series: SeriesSettings,
+    pub cleanup: CleanupSettings,
+    pub working_hours: WorkingHours,  // for cities without their own
+    pub do_not_disturb: DoNotDisturb, // likewise
+    pub show_timeline: bool,
+    pub pinned_events: Vec<PinnedEvent>,
+    pub show_relative_phrase: bool,
+    pub language: Language,
+    pub prevent_maximize: bool, // snap back to the default size instead
+    pub always_on_top: bool,
+    pub window_opacity: Opacity,
+    pub zoom: ZoomSettings,
+    pub kiosk: KioskSettings,
+    pub header: HeaderSettings,
+    pub clock_change: ClockChangeSettings,
+    pub clock_sync: ClockSyncSettings,
+    pub update_interval: UpdateInterval,
+    pub show_seconds: bool,
+    pub clock_format: ClockFormat, // on the cards and in copies and exports
+    pub include_people: bool,      // in copied messages and CSV exports
+    pub overrides_in_exports: bool, // manual offset overrides, likewise
+    pub auto_order: bool,          // keep cards sorted by local time
+    pub stack_same_zone: bool,     // one card for cities sharing a zone
+    pub diff_style: DiffStyle,     // for cards without their own
+    pub show_world_map: bool,
+    pub show_day_stats: bool,     // "63% of the day gone" on each card
+    pub show_workday: bool,       // "started 2h ago · ends in 6h" on each card
+    pub hide_overlap_score: bool, // the 0–100 badge on non-home cards
+    pub reduce_motion: bool,      // no animations at all
+    pub quiet_hours: QuietHours,
+    pub mirror: MirrorSettings,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_seen_version: Option<String>, // for "What's new"
+}
+
+impl Global for Settings {}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HotkeySettings {
+    pub enabled: bool,
+    pub shortcut: String, // like "ctrl+alt+t" or "cmd+shift+space"
+}
+
+impl Default for HotkeySettings {
+    fn default() -> Self {
+        HotkeySettings {
+            enabled: true,
+            shortcut: "ctrl+alt+t".to_string(),
+        }
+    }
+}
+
+// A second global shortcut that pastes the current times wherever you're typing
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SnippetSettings {
+    pub enabled: bool,
+    pub shortcut: String,
+    pub template: String, // like "{Chicago} / {Berlin}"; see format::parse_snippet
+}
+
+impl Default for SnippetSettings {
+    fn default() -> Self {
+        SnippetSettings {
+            enabled: false,
+            shortcut: "ctrl+alt+i".to_string(),
+            template: "{all}".to_string(),
+        }
+    }
+}
+
+// How many earlier saves of the config are kept, newest first
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BackupSettings {
+    pub keep: usize, // 0 keeps none
+}
+
+impl Default for BackupSettings {
+    fn default() -> Self {
+        BackupSettings { keep: 5 }
+    }
+}
+
+// The read-only local endpoint for dashboards and scripts
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HttpSettings {
+    pub enabled: bool,
+    pub address: String, // anything but a loopback address is refused
+    pub port: u16,
+}
+
+impl Default for HttpSettings {
+    fn default() -> Self {
+        HttpSettings {
+            enabled: false,
+            address: "127.0.0.1".to_string(),
+            port: 7312,
+        }
+    }
+}
+
+// How "Copy for Slack" lays out its line
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SlackSettings {
+    pub emoji: bool,
+    pub twelve_hour: bool,
+    pub separator: String,
+}
+
+impl Default for SlackSettings {
+    fn default() -> Self {
+        SlackSettings {
+            emoji: true,
+            twelve_hour: true,
+            separator: " · ".to_string(),
+        }
+    }
+}
+
+// Whether exported meetings and Slack messages are a weekly series, and how long
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SeriesSettings {
+    pub weekly: bool,
+    pub occurrences: u32,
+}
+
+impl SeriesSettings {
+    pub const OCCURRENCE_CHOICES: [u32; 4] = [4, 8, 13, 26];
+}
+
+impl Default for SeriesSettings {
+    fn default() -> Self {
+        SeriesSettings {
+            weekly: false,
+            occurrences: 8,
+        }
+    }
+}
+
+// When the cleanup review offers a card
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CleanupSettings {
+    pub after_days: u32,
+}
+
+impl Default for CleanupSettings {
+    fn default() -> Self {
+        CleanupSettings { after_days: 90 }
+    }
+}
+
+// Full-screen rotation for wall displays
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct KioskSettings {
+    pub dwell_seconds: u64, // per slide
+    pub show_all_slide: bool,
+    pub show_header: bool, // the title and UTC clock above the slides
+}
+
+impl Default for KioskSettings {
+    fn default() -> Self {
+        KioskSettings {
+            dwell_seconds: 10,
+            show_all_slide: true,
+            show_header: false,
+        }
+    }
+}
+
+// The line above the cards: hidden, retitled, or with a UTC clock, for shared
+// screens that want their own branding or every pixel
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HeaderSettings {
+    pub hidden: bool,  // the UTC clock, if on, stays as a slim line
+    pub title: String, // empty for the usual one
+    pub utc_clock: bool,
+    pub window_title: bool, // the window's title follows `title` too
+}
+
+impl HeaderSettings {
+    pub const DEFAULT_TITLE: &str = "🌍 World Time Display";
+
+    pub fn title(&self) -> &str {
+        match self.title.trim() {
+            "" => Self::DEFAULT_TITLE,
+            title => title,
+        }
+    }
+
+    pub fn window_title(&self) -> &str {
+        if self.window_title {
+            self.title()
+        } else {
+            Self::DEFAULT_TITLE
+        }
+    }
+}
+
+// The header chip counting down to the home zone's next clock change
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ClockChangeSettings {
+    pub horizon_days: u32, // shown only this close to the change; 0 hides it
+}
+
+impl Default for ClockChangeSettings {
+    fn default() -> Self {
+        ClockChangeSettings { horizon_days: 21 }
+    }
+}
+
+// Compares the system clock with a time server now and then; off unless asked for
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ClockSyncSettings {
+    pub enabled: bool,
+    pub server: String,
+    pub threshold_secs: u32, // drift past this shows in the header
+}
+
+impl ClockSyncSettings {
+    pub const THRESHOLD_CHOICES: [u32; 4] = [2, 5, 10, 30];
+}
+
+impl Default for ClockSyncSettings {
+    fn default() -> Self {
+        ClockSyncSettings {
+            enabled: false,
+            server: "pool.ntp.org".to_string(),
+            threshold_secs: 5,
+        }
+    }
+}
+
+// "their 17:00 = your 10:00" on each card
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MirrorSettings {
+    pub enabled: bool,
+    pub their_time: Option<NaiveTime>, // unset: the end of each city's working day
+}
+
+#[derive(Debug)]
+pub enum ConfigError {
+    NoConfigDir,
+    Io(io::Error),
+    Parse(serde_json::Error),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::NoConfigDir => write!(f, "no config directory available"),
+            ConfigError::Io(err) => write!(f, "couldn't access config file: {}", err),
+            ConfigError::Parse(err) => write!(f, "config file is invalid: {}", err),
+        }
+    }
+}
+
+// ~/.config/time2rust/config.json on Linux, the platform equivalent elsewhere
+pub fn config_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("time2rust").join("config.json"))
+}
+
+impl Config {
+    // A missing file is not an error, it just means defaults and a first run. A
+    // config from before card ids gets them here and is saved straight away, so
+    // they're the same from then on, in the terminal commands too.
+    pub fn load() -> Result<Config, ConfigError> {
+        let path = config_path().ok_or(ConfigError::NoConfigDir)?;
+        Config::load_from(&path, Config::save)
+    }
+
+    // `save` writes back a config whose cities have just been given ids. If it
+    // can't, they're used for this run anyway and given again on the next load.
+    fn load_from(
+        path: &Path,
+        save: impl FnOnce(&Config) -> Result<(), ConfigError>,
+    ) -> Result<Config, ConfigError> {
+        let mut config: Config = match fs::read_to_string(path) {
+            Ok(contents) => serde_json::from_str(&contents).map_err(ConfigError::Parse)?,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                let mut config = Config {
+                    needs_onboarding: true,
+                    ..Config::default()
+                };
+                config.assign_city_ids();
+                return Ok(config);
+            }
+            Err(err) => return Err(ConfigError::Io(err)),
+        };
+        if config.assign_city_ids()
+            && let Err(err) = save(&config)
+        {
+            eprintln!("time2rust: couldn't save the new card ids: {}", err);
+        }
+        Ok(config)
+    }
+
+    // The file as it is now, without filling anything in; None if there isn't one
+    pub fn read_saved() -> Result<Option<Config>, ConfigError> {
+        let path = config_path().ok_or(ConfigError::NoConfigDir)?;
+        match fs::read_to_string(&path) {
+            Ok(contents) => serde_json::from_str(&contents)
+                .map(Some)
+                .map_err(ConfigError::Parse),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(ConfigError::Io(err)),
+        }
+    }
+
+    // Every city, the other profiles' included; ids only need to be unique within
+    // one list of cities
+    pub fn assign_city_ids(&mut self) -> bool {
+        let mut changed = assign_city_ids(&mut self.cities);
+        for profile in &mut self.profiles {
+            changed |= assign_city_ids(&mut profile.cities);
+        }
+        changed
+    }
+
+    // Another time2rust config file, e.g. one copied from a colleague
+    pub fn read_from(path: &Path) -> Result<Config, ConfigError> {
+        let contents = fs::read_to_string(path).map_err(ConfigError::Io)?;
+        serde_json::from_str(&contents).map_err(ConfigError::Parse)
+    }
+
+    // Layers the environment variable and then --city flags over the config file's
+    // cities; returns a message for every entry that couldn't be used
+    pub fn apply_city_overrides(&mut self, env: Option<&str>, flags: &[String]) -> Vec<String> {
+        let mut warnings = Vec::new();
+        let sources = env
+            .map(|value| (CITIES_ENV_VAR, value.to_string()))
+            .into_iter()
+            .chain(flags.iter().map(|flag| ("--city", flag.clone())));
+        for (source, value) in sources {
+            let (mut cities, errors) = parse_city_list(&value);
+            for city in &mut cities {
+                city.ephemeral = true;
+            }
+            merge_cities(&mut self.cities, cities);
+            warnings.extend(errors.into_iter().map(|err| format!("{}: {}", source, err)));
+        }
+        warnings
+    }
+
+    pub fn save(&self) -> Result<(), ConfigError> {
+        let path = config_path().ok_or(ConfigError::NoConfigDir)?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(ConfigError::Io)?;
+        }
+        let contents = serde_json::to_string_pretty(self).map_err(ConfigError::Parse)?;
+        fs::write(&path, &contents).map_err(ConfigError::Io)?;
+        self.keep_backup(&contents)
+    }
+
+    // Keeps this state among the rotated backups without saving it as the config
+    pub fn back_up(&self) -> Result<(), ConfigError> {
+        let contents = serde_json::to_string_pretty(self).map_err(ConfigError::Parse)?;
+        self.keep_backup(&contents)
+    }
+
+    // The part of the config a backup is for: the settings, the cities and the
+    // profiles' cities. When a card was last used and where the window was change
+    // on nearly every save, so they're left out.
+    pub fn backed_up_state(&self) -> serde_json::Value {
+        let without_use = |cities: &[CityConfig]| {
+            cities
+                .iter()
+                .cloned()
+                .map(|mut city| {
+                    city.last_interacted = None;
+                    city
+                })
+                .collect::<Vec<_>>()
+        };
+        let profiles: Vec<_> = self
+            .profiles
+            .iter()
+            .map(|profile| (profile.name.clone(), without_use(&profile.cities)))
+            .collect();
+        serde_json::json!({
+            "settings": self.settings,
+            "cities": without_use(&self.cities),
+            "profiles": profiles,
+        })
+    }
+
+    fn keep_backup(&self, contents: &str) -> Result<(), ConfigError> {
+        let dir = config_backups::backup_dir().ok_or(ConfigError::NoConfigDir)?;
+        config_backups::take(&dir, contents, self.settings.backups.keep, Utc::now())
+            .map_err(ConfigError::Io)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names_and_zones(cities: &[CityConfig]) -> Vec<(&str, &str)> {
+        cities
+            .iter()
+            .map(|city| (city.name.as_str(), city.timezone.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn parse_city_list_keeps_valid_entries_and_reports_the_rest() {
+        let (cities, errors) = parse_city_list(
+            "Tokyo=Asia/Tokyo; Lagos = Africa/Lagos\n\nnope;=Europe/Paris;Mars=Mars/Base",
+        );
+        assert_eq!(
+            names_and_zones(&cities),
+            [("Tokyo", "Asia/Tokyo"), ("Lagos", "Africa/Lagos")]
+        );
+        assert_eq!(errors.len(), 3);
+        assert!(errors[0].contains("\"nope\""));
+        assert!(errors[1].contains("missing a name"));
+        assert!(errors[2].starts_with("Mars: "));
+    }
+
+    #[test]
+    fn merge_cities_replaces_by_name_and_appends_new_ones() {
+        let mut cities = vec![
+            CityConfig::new("Austin", "America/Chicago", true),
+            CityConfig::new("Berlin", "Europe/Berlin", false),
+        ];
+        merge_cities(
+            &mut cities,
+            vec![
+                CityConfig::new("Berlin", "Europe/Paris", false),
+                CityConfig::new("Tokyo", "Asia/Tokyo", false),
+            ],
+        );
+        assert_eq!(
+            names_and_zones(&cities),
+            [
+                ("Austin", "America/Chicago"),
+                ("Berlin", "Europe/Paris"),
+                ("Tokyo", "Asia/Tokyo"),
+            ]
+        );
+    }
+
+    #[test]
+    fn flags_beat_the_environment_which_beats_the_file() {
+        let mut config = Config {
+            cities: vec![
+                CityConfig::new("Austin", "America/Chicago", true),
+                CityConfig::new("Berlin", "Europe/Berlin", false),
+            ],
+            ..Config::default()
+        };
+        let warnings = config.apply_city_overrides(
+            Some("Berlin=Europe/Paris;Tokyo=Asia/Tokyo"),
+            &[
+                "Tokyo=Asia/Seoul".to_string(),
+                "Lima=America/Lima".to_string(),
+            ],
+        );
+        assert!(warnings.is_empty());
+        assert_eq!(
+            names_and_zones(&config.cities),
+            [
+                ("Austin", "America/Chicago"),
+                ("Berlin", "Europe/Paris"),
+                ("Tokyo", "Asia/Seoul"),
+                ("Lima", "America/Lima"),
+            ]
+        );
+        let ephemeral: Vec<bool> = config.cities.iter().map(|city| city.ephemeral).collect();
+        assert_eq!(ephemeral, [false, true, true, true]);
+    }
+
+    #[test]
+    fn overrides_are_labelled_by_source() {
+        let mut config = Config::default();
+        let warnings = config.apply_city_overrides(Some("broken"), &["=Asia/Tokyo".to_string()]);
+        assert_eq!(warnings.len(), 2);
+        assert!(warnings[0].starts_with(CITIES_ENV_VAR));
+        assert!(warnings[1].starts_with("--city"));
+    }
+
+    #[test]
+    fn an_override_of_a_saved_city_writes_the_saved_one_back() {
+        let mut config = Config {
+            cities: vec![
+                CityConfig::new("Austin", "America/Chicago", true),
+                CityConfig::new("Berlin", "Europe/Berlin", false),
+            ],
+            ..Config::default()
+        };
+        config.apply_city_overrides(
+            Some("Austin=Asia/Tokyo"),
+            &["Lima=America/Lima".to_string()],
+        );
+        config.apply_city_overrides(None, &["Austin=Asia/Seoul".to_string()]);
+
+        let austin = &config.cities[0];
+        assert_eq!(austin.timezone, "Asia/Seoul");
+        assert!(austin.ephemeral && austin.home);
+
+        let saved: Vec<CityConfig> = config
+            .cities
+            .iter()
+            .cloned()
+            .filter_map(CityConfig::persisted)
+            .collect();
+        assert_eq!(
+            names_and_zones(&saved),
+            [("Austin", "America/Chicago"), ("Berlin", "Europe/Berlin")]
+        );
+        assert!(saved[0].home && !saved[0].ephemeral);
+    }
+
+    #[test]
+    fn card_ids_are_given_once_and_kept() {
+        let path =
+            std::env::temp_dir().join(format!("time2rust-config-ids-{}.json", std::process::id()));
+        let before_ids = Config {
+            cities: vec![
+                CityConfig::new("Austin", "America/Chicago", true),
+                CityConfig::new("Berlin", "Europe/Berlin", false),
+            ],
+            ..Config::default()
+        };
+        fs::write(&path, serde_json::to_string(&before_ids).unwrap()).unwrap();
+        let ids = |config: &Config| -> Vec<String> {
+            config.cities.iter().map(|city| city.id.clone()).collect()
+        };
+        let saves = std::cell::Cell::new(0);
+        let save = |config: &Config| {
+            saves.set(saves.get() + 1);
+            fs::write(&path, serde_json::to_string(config).unwrap()).map_err(ConfigError::Io)
+        };
+
+        let first = Config::load_from(&path, save).unwrap();
+        let second = Config::load_from(&path, save).unwrap();
+        let _ = fs::remove_file(&path);
+        assert!(ids(&first).iter().all(|id| !id.is_empty()));
+        assert_ne!(first.cities[0].id, first.cities[1].id);
+        assert_eq!(ids(&second), ids(&first));
+        assert_eq!(saves.get(), 1);
+    }
+
+    #[test]
+    fn a_failed_id_save_still_loads() {
+        let path = std::env::temp_dir().join(format!(
+            "time2rust-config-readonly-{}.json",
+            std::process::id()
+        ));
+        let before_ids = Config {
+            cities: vec![CityConfig::new("Austin", "America/Chicago", true)],
+            ..Config::default()
+        };
+        fs::write(&path, serde_json::to_string(&before_ids).unwrap()).unwrap();
+        let config = Config::load_from(&path, |_| {
+            Err(ConfigError::Io(io::Error::from(
+                io::ErrorKind::PermissionDenied,
+            )))
+        });
+        let _ = fs::remove_file(&path);
+        let config = config.unwrap();
+        assert!(!config.cities[0].id.is_empty());
+    }
+}
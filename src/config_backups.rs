@@ -0,0 +1,305 @@
+use crate::{WorldTimeApp, backup, config::Config, settings_panel::hint};
+use chrono::{DateTime, NaiveDateTime, Utc};
+use gpui::{App, Entity, Window, div, prelude::*};
+use gpui_component::{
+    Sizable, WindowExt as _,
+    button::{Button, ButtonVariants as _},
+    h_flex,
+    notification::Notification,
+    v_flex,
+};
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+use time2rust::format;
+
+// Copies of the last few saved configs, so a bad import or restore can be walked back
+const FILE_NAME: &str = "config-%Y%m%dT%H%M%SZ.json";
+
+// Next to config.json
+pub fn backup_dir() -> Option<PathBuf> {
+    crate::config::config_path().map(|path| path.with_file_name("backups"))
+}
+
+// Stores `contents` as the newest backup, unless the newest already has the same
+// cities, profiles and settings, and drops all but the last `keep`. `keep` of 0
+// turns backups off.
+pub fn take(dir: &Path, contents: &str, keep: usize, now: DateTime<Utc>) -> io::Result<()> {
+    if keep == 0 {
+        return Ok(());
+    }
+    let newest = backup_files(dir).pop();
+    if newest.is_some_and(|(path, _)| {
+        fs::read_to_string(path).is_ok_and(|old| same_state(&old, contents))
+    }) {
+        return Ok(());
+    }
+    fs::create_dir_all(dir)?;
+    fs::write(dir.join(now.format(FILE_NAME).to_string()), contents)?;
+    prune(dir, keep)
+}
+
+// Contents that don't read as a config only match exactly
+fn same_state(old: &str, new: &str) -> bool {
+    match (
+        serde_json::from_str::<Config>(old),
+        serde_json::from_str::<Config>(new),
+    ) {
+        (Ok(old), Ok(new)) => old.backed_up_state() == new.backed_up_state(),
+        _ => old == new,
+    }
+}
+
+pub fn prune(dir: &Path, keep: usize) -> io::Result<()> {
+    let files = backup_files(dir);
+    for (path, _) in &files[..files.len().saturating_sub(keep)] {
+        fs::remove_file(path)?;
+    }
+    Ok(())
+}
+
+// Oldest first; files that aren't ours are left alone
+fn backup_files(dir: &Path) -> Vec<(PathBuf, DateTime<Utc>)> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut files: Vec<(PathBuf, DateTime<Utc>)> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().into_string().ok()?;
+            let taken = NaiveDateTime::parse_from_str(&name, FILE_NAME).ok()?;
+            Some((entry.path(), taken.and_utc()))
+        })
+        .collect();
+    files.sort_by_key(|(_, taken)| *taken);
+    files
+}
+
+#[derive(Debug, Clone)]
+pub struct ConfigBackup {
+    pub path: PathBuf,
+    pub taken: DateTime<Utc>,
+    pub cities: usize,
+    pub profiles: usize,
+}
+
+// Newest first, skipping any that no longer read as a config
+pub fn list(dir: &Path) -> Vec<ConfigBackup> {
+    backup_files(dir)
+        .into_iter()
+        .rev()
+        .filter_map(|(path, taken)| {
+            let config = Config::read_from(&path).ok()?;
+            Some(ConfigBackup {
+                path,
+                taken,
+                cities: config.cities.len(),
+                profiles: config.profiles.len(),
+            })
+        })
+        .collect()
+}
+
+// Picking one goes on to the usual restore dialog, where parts can be left out
+pub fn open_backup_picker(app: Entity<WorldTimeApp>, window: &mut Window, cx: &mut App) {
+    let backups = backup_dir().map(|dir| list(&dir)).unwrap_or_default();
+    let home_tz = app.read(cx).home_tz;
+    window.open_dialog(cx, move |dialog, _window, _cx| {
+        dialog
+            .title("Restore an earlier save")
+            .child(
+                v_flex()
+                    .gap_2()
+                    .when(backups.is_empty(), |this| {
+                        this.child(div().child("No saves have been kept yet").text_sm())
+                    })
+                    .children(backups.iter().enumerate().map(|(index, saved)| {
+                        let app = app.clone();
+                        let path = saved.path.clone();
+                        h_flex()
+                            .justify_between()
+                            .gap_4()
+                            .child(
+                                v_flex()
+                                    .child(
+                                        div()
+                                            .child(format::weekday_date_year_time(
+                                                saved.taken.with_timezone(&home_tz).naive_local(),
+                                            ))
+                                            .text_sm(),
+                                    )
+                                    .child(hint(format!(
+                                        "{} cities, {} other profiles",
+                                        saved.cities, saved.profiles
+                                    ))),
+                            )
+                            .child(
+                                Button::new(("restore-save", index))
+                                    .label("Restore…")
+                                    .small()
+                                    .ghost()
+                                    .on_click(move |_, window, cx| {
+                                        window.close_dialog(cx);
+                                        restore_from(app.clone(), &path, window, cx);
+                                    }),
+                            )
+                    }))
+                    .child(hint(
+                        "Taken each time the config is saved; the current state is kept before a restore",
+                    )),
+            )
+    });
+}
+
+fn restore_from(app: Entity<WorldTimeApp>, path: &Path, window: &mut Window, cx: &mut App) {
+    let result = fs::read_to_string(path)
+        .map_err(|err| err.to_string())
+        .and_then(|contents| backup::read_backup(&contents).map_err(|err| err.to_string()));
+    match result {
+        Ok(config) => backup::confirm_restore(app, config, window, cx),
+        Err(err) => window.push_notification(
+            Notification::error(format!("Couldn't restore {}: {}", path.display(), err))
+                .autohide(false),
+            cx,
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{config::CityConfig, window_state::WindowState};
+    use chrono::TimeZone;
+
+    // A fresh directory per test, removed again when it ends
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new(name: &str) -> Self {
+            let dir = std::env::temp_dir().join(format!(
+                "time2rust-backups-{}-{}",
+                std::process::id(),
+                name
+            ));
+            let _ = fs::remove_dir_all(&dir);
+            TempDir(dir)
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn at(minute: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 3, 20, 9, minute, 0).unwrap()
+    }
+
+    fn config_with(cities: usize) -> String {
+        let config = Config {
+            cities: (0..cities)
+                .map(|i| CityConfig::new(&format!("City {}", i), "Europe/Berlin", i == 0))
+                .collect(),
+            ..Config::default()
+        };
+        serde_json::to_string_pretty(&config).unwrap()
+    }
+
+    fn file_names(dir: &Path) -> Vec<String> {
+        backup_files(dir)
+            .into_iter()
+            .map(|(path, _)| path.file_name().unwrap().to_string_lossy().into_owned())
+            .collect()
+    }
+
+    #[test]
+    fn take_skips_repeats_and_keeps_the_newest() {
+        let dir = TempDir::new("take");
+        take(&dir.0, &config_with(1), 3, at(0)).unwrap();
+        // The same contents again add nothing
+        take(&dir.0, &config_with(1), 3, at(1)).unwrap();
+        assert_eq!(file_names(&dir.0), ["config-20260320T090000Z.json"]);
+
+        for minute in 2..6 {
+            take(&dir.0, &config_with(minute as usize), 3, at(minute)).unwrap();
+        }
+        assert_eq!(
+            file_names(&dir.0),
+            [
+                "config-20260320T090300Z.json",
+                "config-20260320T090400Z.json",
+                "config-20260320T090500Z.json",
+            ]
+        );
+    }
+
+    #[test]
+    fn use_and_window_moves_alone_take_no_backup() {
+        let dir = TempDir::new("volatile");
+        let mut config = Config {
+            cities: vec![CityConfig::new("Berlin", "Europe/Berlin", true)],
+            ..Config::default()
+        };
+        let save = |config: &Config, minute| {
+            let contents = serde_json::to_string_pretty(config).unwrap();
+            take(&dir.0, &contents, 5, at(minute)).unwrap();
+        };
+        save(&config, 0);
+
+        config.cities[0].last_interacted = Some(at(1));
+        config.window = Some(WindowState {
+            x: 10.0,
+            y: 20.0,
+            width: 800.0,
+            height: 600.0,
+            display: None,
+            maximized: false,
+        });
+        config.generation += 1;
+        save(&config, 1);
+        assert_eq!(file_names(&dir.0), ["config-20260320T090000Z.json"]);
+
+        config.settings.backups.keep = 4;
+        save(&config, 2);
+        config.cities[0].name = "Home".to_string();
+        save(&config, 3);
+        assert_eq!(file_names(&dir.0).len(), 3);
+    }
+
+    #[test]
+    fn keeping_none_takes_nothing() {
+        let dir = TempDir::new("off");
+        take(&dir.0, &config_with(1), 0, at(0)).unwrap();
+        assert!(!dir.0.exists());
+    }
+
+    #[test]
+    fn prune_leaves_other_files_alone() {
+        let dir = TempDir::new("prune");
+        for minute in 0..3 {
+            take(&dir.0, &config_with(minute as usize + 1), 5, at(minute)).unwrap();
+        }
+        fs::write(dir.0.join("notes.txt"), "mine").unwrap();
+        prune(&dir.0, 1).unwrap();
+        assert_eq!(file_names(&dir.0), ["config-20260320T090200Z.json"]);
+        assert!(dir.0.join("notes.txt").exists());
+    }
+
+    #[test]
+    fn list_is_newest_first_and_skips_unreadable_files() {
+        let dir = TempDir::new("list");
+        take(&dir.0, &config_with(1), 5, at(0)).unwrap();
+        take(&dir.0, &config_with(2), 5, at(1)).unwrap();
+        fs::write(dir.0.join("config-20260320T090200Z.json"), "not json").unwrap();
+
+        let backups = list(&dir.0);
+        let summary: Vec<_> = backups
+            .iter()
+            .map(|backup| (backup.taken, backup.cities))
+            .collect();
+        assert_eq!(summary, [(at(1), 2), (at(0), 1)]);
+        assert!(list(&dir.0.join("missing")).is_empty());
+    }
+}
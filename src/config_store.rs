@@ -0,0 +1,507 @@
+use crate::{
+    alarms::Alarm,
+    config::{CityConfig, Config, ConfigError},
+    countdown::CountdownConfig,
+    profiles::Profile,
+};
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+
+// Every save goes through here. The file carries a generation that each save
+// bumps, so a file with a newer one than we last read or wrote was saved by
+// another window or instance, and is merged with ours instead of overwritten.
+pub struct ConfigStore {
+    base: Config, // what the file held when we last read or wrote it
+}
+
+#[derive(Debug, Clone)]
+pub struct Commit {
+    pub config: Config,
+    // Set when another writer's changes were folded in, so they need showing
+    pub merged: bool,
+    pub conflicts: Vec<String>,
+}
+
+impl ConfigStore {
+    pub fn new(base: Config) -> Self {
+        ConfigStore { base }
+    }
+
+    pub fn generation(&self) -> u64 {
+        self.base.generation
+    }
+
+    pub fn commit(&mut self, ours: Config) -> Result<Commit, ConfigError> {
+        // An unreadable file is overwritten as before; there's nothing to merge
+        let on_disk = Config::read_saved().ok().flatten();
+        let commit = reconcile(&self.base, ours, on_disk);
+        commit.config.save()?;
+        self.base = commit.config.clone();
+        Ok(commit)
+    }
+}
+
+// The config to write: ours, or ours merged with a file someone else has saved
+// since `base`, one generation past both
+pub fn reconcile(base: &Config, ours: Config, on_disk: Option<Config>) -> Commit {
+    let (mut config, merged, conflicts) = match on_disk {
+        Some(theirs) if theirs.generation > base.generation => {
+            let generation = theirs.generation;
+            let (mut config, conflicts) = merge(base, &ours, &theirs);
+            config.generation = generation;
+            (config, true, conflicts)
+        }
+        _ => (ours, false, Vec::new()),
+    };
+    config.generation = config.generation.max(base.generation) + 1;
+    Commit {
+        config,
+        merged,
+        conflicts,
+    }
+}
+
+// A three-way merge: a change made on one side only is kept, whichever side made
+// it. Where both sides changed the same thing differently, ours wins and the
+// conflict is reported. Window placement and view are each window's own, so
+// those are always ours, as is when each card was last used.
+pub fn merge(base: &Config, ours: &Config, theirs: &Config) -> (Config, Vec<String>) {
+    let mut conflicts = Vec::new();
+    let mut config = ours.clone();
+    let settings = merge_value(
+        &to_value(&base.settings),
+        &to_value(&ours.settings),
+        &to_value(&theirs.settings),
+        "",
+        &mut conflicts,
+    );
+    config.settings = serde_json::from_value(settings).unwrap_or_else(|_| ours.settings.clone());
+    config.cities = merge_items(&base.cities, &ours.cities, &theirs.cities, &mut conflicts);
+    config.alarms = merge_items(&base.alarms, &ours.alarms, &theirs.alarms, &mut conflicts);
+    config.countdowns = merge_items(
+        &base.countdowns,
+        &ours.countdowns,
+        &theirs.countdowns,
+        &mut conflicts,
+    );
+    config.profiles = merge_items(
+        &base.profiles,
+        &ours.profiles,
+        &theirs.profiles,
+        &mut conflicts,
+    );
+    config.active_profile = pick(
+        &base.active_profile,
+        &ours.active_profile,
+        &theirs.active_profile,
+        "The active profile",
+        &mut conflicts,
+    );
+    config.marker = pick(
+        &base.marker,
+        &ours.marker,
+        &theirs.marker,
+        "The marker",
+        &mut conflicts,
+    );
+    config.marker_on_cards = pick(
+        &base.marker_on_cards,
+        &ours.marker_on_cards,
+        &theirs.marker_on_cards,
+        "The marker",
+        &mut conflicts,
+    );
+    conflicts.dedup();
+    (config, conflicts)
+}
+
+// Something kept in a list, told apart by a key that survives edits
+trait Item: Serialize + Clone {
+    fn key(&self) -> String;
+    fn label(&self) -> String;
+
+    // Settles both sides changing it where that isn't really a conflict
+    fn reconcile(&self, _other: &Self) -> Option<Self> {
+        None
+    }
+
+    // A copy with `from`'s values for fields that change on nearly every save,
+    // like when a card was last used. Those never make a conflict: the window
+    // saving now has the last word on them.
+    fn volatile_from(&self, _from: &Self) -> Self {
+        self.clone()
+    }
+}
+
+impl Item for CityConfig {
+    fn key(&self) -> String {
+        if self.id.is_empty() {
+            self.name.clone()
+        } else {
+            self.id.clone()
+        }
+    }
+
+    fn label(&self) -> String {
+        self.name.clone()
+    }
+
+    fn volatile_from(&self, from: &Self) -> Self {
+        CityConfig {
+            last_interacted: from.last_interacted,
+            ..self.clone()
+        }
+    }
+}
+
+impl Item for Alarm {
+    fn key(&self) -> String {
+        format!("{} {} {:?}", self.city, self.time, self.repeat)
+    }
+
+    fn label(&self) -> String {
+        format!("The {} alarm for {}", self.time.format("%H:%M"), self.city)
+    }
+
+    // Both windows firing it only moves it on; the later firing counts
+    fn reconcile(&self, other: &Self) -> Option<Self> {
+        let later = self.armed_since.max(other.armed_since);
+        let (mut ours, mut theirs) = (self.clone(), other.clone());
+        ours.armed_since = later;
+        theirs.armed_since = later;
+        (to_value(&ours) == to_value(&theirs)).then_some(ours)
+    }
+}
+
+impl Item for CountdownConfig {
+    fn key(&self) -> String {
+        format!("{} {}", self.title, self.target)
+    }
+
+    fn label(&self) -> String {
+        format!("The \"{}\" countdown", self.title)
+    }
+}
+
+impl Item for Profile {
+    fn key(&self) -> String {
+        self.name.clone()
+    }
+
+    fn label(&self) -> String {
+        format!("The \"{}\" profile", self.name)
+    }
+
+    // Its window placement and view, and its cities' last use
+    fn volatile_from(&self, from: &Self) -> Self {
+        let cities = self
+            .cities
+            .iter()
+            .map(
+                |city| match from.cities.iter().find(|other| other.key() == city.key()) {
+                    Some(other) => city.volatile_from(other),
+                    None => city.clone(),
+                },
+            )
+            .collect();
+        Profile {
+            name: self.name.clone(),
+            cities,
+            layout: from.layout.clone(),
+        }
+    }
+}
+
+// Keeps our order, with what they added at the end. An item removed on one side
+// and changed on the other is kept, as losing the change is the worse outcome.
+fn merge_items<T: Item>(
+    base: &[T],
+    ours: &[T],
+    theirs: &[T],
+    conflicts: &mut Vec<String>,
+) -> Vec<T> {
+    let index = |items: &[T]| -> HashMap<String, T> {
+        items
+            .iter()
+            .map(|item| (item.key(), item.clone()))
+            .collect()
+    };
+    let (base_items, ours_items) = (index(base), index(ours));
+    // Equal but for the volatile fields
+    let same = |a: &T, b: &T| to_value(&a.volatile_from(b)) == to_value(b);
+    let mut merged = Vec::new();
+    for item in ours {
+        let key = item.key();
+        let original = base_items.get(&key);
+        let other = theirs.iter().find(|other| other.key() == key);
+        match (original, other) {
+            // Removed there and untouched here
+            (Some(original), None) if same(original, item) => {}
+            (Some(_), None) => {
+                conflicts.push(format!(
+                    "{} was removed in another window but changed here, so it was kept",
+                    item.label()
+                ));
+                merged.push(item.clone());
+            }
+            (None, None) => merged.push(item.clone()),
+            (original, Some(other)) => {
+                if original.is_some_and(|original| same(original, item)) {
+                    merged.push(other.volatile_from(item));
+                } else if original.is_some_and(|original| same(original, other))
+                    || same(other, item)
+                {
+                    merged.push(item.clone());
+                } else if let Some(settled) = item.reconcile(other) {
+                    merged.push(settled);
+                } else {
+                    conflicts.push(format!(
+                        "{} was changed here and in another window; kept this window's version",
+                        item.label()
+                    ));
+                    merged.push(item.clone());
+                }
+            }
+        }
+    }
+    for item in theirs {
+        let key = item.key();
+        if ours_items.contains_key(&key) {
+            continue;
+        }
+        match base_items.get(&key) {
+            None => merged.push(item.clone()),
+            // Removed here and untouched there
+            Some(original) if same(original, item) => {}
+            Some(_) => {
+                conflicts.push(format!(
+                    "{} was removed here but changed in another window, so it was kept",
+                    item.label()
+                ));
+                merged.push(item.clone());
+            }
+        }
+    }
+    merged
+}
+
+fn pick<T: Serialize + Clone>(
+    base: &T,
+    ours: &T,
+    theirs: &T,
+    label: &str,
+    conflicts: &mut Vec<String>,
+) -> T {
+    let (base_value, ours_value, theirs_value) = (to_value(base), to_value(ours), to_value(theirs));
+    if ours_value == base_value {
+        theirs.clone()
+    } else if theirs_value != base_value && theirs_value != ours_value {
+        conflicts.push(format!(
+            "{} was changed here and in another window; kept this window's choice",
+            label
+        ));
+        ours.clone()
+    } else {
+        ours.clone()
+    }
+}
+
+// Settings merge field by field, so turning one thing on in each window keeps
+// both; `path` names the field for a conflict, like "header.title"
+fn merge_value(
+    base: &Value,
+    ours: &Value,
+    theirs: &Value,
+    path: &str,
+    conflicts: &mut Vec<String>,
+) -> Value {
+    if ours == base || ours == theirs {
+        return theirs.clone();
+    }
+    if theirs == base {
+        return ours.clone();
+    }
+    let (Value::Object(base), Value::Object(ours), Value::Object(theirs)) = (base, ours, theirs)
+    else {
+        conflicts.push(format!(
+            "The {} setting was changed here and in another window; kept this window's",
+            path
+        ));
+        return ours.clone();
+    };
+    let mut merged = Map::new();
+    for key in ours
+        .keys()
+        .chain(theirs.keys().filter(|key| !ours.contains_key(*key)))
+    {
+        let value = merge_value(
+            base.get(key).unwrap_or(&Value::Null),
+            ours.get(key).unwrap_or(&Value::Null),
+            theirs.get(key).unwrap_or(&Value::Null),
+            &if path.is_empty() {
+                key.clone()
+            } else {
+                format!("{}.{}", path, key)
+            },
+            conflicts,
+        );
+        // A field left out for being empty stays left out
+        let explicit = ours.get(key) == Some(&Value::Null) || theirs.get(key) == Some(&Value::Null);
+        if !value.is_null() || explicit {
+            merged.insert(key.clone(), value);
+        }
+    }
+    Value::Object(merged)
+}
+
+fn to_value<T: Serialize>(value: &T) -> Value {
+    serde_json::to_value(value).unwrap_or(Value::Null)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::profiles::Layout;
+    use chrono::{DateTime, Utc};
+
+    fn city(id: &str, name: &str, timezone: &str) -> CityConfig {
+        CityConfig {
+            id: id.to_string(),
+            ..CityConfig::new(name, timezone, id == "a")
+        }
+    }
+
+    fn used(minute: u32) -> Option<DateTime<Utc>> {
+        Some(format!("2026-03-20T09:{:02}:00Z", minute).parse().unwrap())
+    }
+
+    fn base() -> Config {
+        Config {
+            cities: vec![
+                city("a", "Austin", "America/Chicago"),
+                city("b", "Berlin", "Europe/Berlin"),
+            ],
+            generation: 3,
+            ..Config::default()
+        }
+    }
+
+    fn names(config: &Config) -> Vec<&str> {
+        config
+            .cities
+            .iter()
+            .map(|city| city.name.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn edits_to_different_things_are_both_kept() {
+        let base = base();
+        let mut ours = base.clone();
+        ours.cities[1].name = "Berlin office".to_string();
+        let mut theirs = base.clone();
+        theirs.cities.push(city("t", "Tokyo", "Asia/Tokyo"));
+        theirs.settings.backups.keep = 9;
+
+        let (merged, conflicts) = merge(&base, &ours, &theirs);
+        assert_eq!(names(&merged), ["Austin", "Berlin office", "Tokyo"]);
+        assert_eq!(merged.settings.backups.keep, 9);
+        assert!(conflicts.is_empty());
+    }
+
+    #[test]
+    fn the_same_edit_made_differently_keeps_ours() {
+        let base = base();
+        let mut ours = base.clone();
+        ours.cities[1].name = "Berlin HQ".to_string();
+        let mut theirs = base.clone();
+        theirs.cities[1].name = "Berlin office".to_string();
+        theirs.settings.backups.keep = 9;
+        ours.settings.backups.keep = 2;
+
+        let (merged, conflicts) = merge(&base, &ours, &theirs);
+        assert_eq!(names(&merged), ["Austin", "Berlin HQ"]);
+        assert_eq!(merged.settings.backups.keep, 2);
+        assert_eq!(
+            conflicts,
+            [
+                "The backups.keep setting was changed here and in another window; kept this window's",
+                "Berlin HQ was changed here and in another window; kept this window's version",
+            ]
+        );
+    }
+
+    #[test]
+    fn last_use_and_window_placement_never_conflict() {
+        let mut base = base();
+        base.profiles.push(Profile {
+            name: "Travel".to_string(),
+            cities: vec![city("t", "Tokyo", "Asia/Tokyo")],
+            layout: None,
+        });
+        let layout = |table_view| {
+            Some(Layout {
+                table_view,
+                ..Layout::default()
+            })
+        };
+        let mut ours = base.clone();
+        ours.cities[1].last_interacted = used(5);
+        ours.profiles[0].layout = layout(true);
+        ours.profiles[0].cities[0].last_interacted = used(5);
+        let mut theirs = base.clone();
+        theirs.cities[1].last_interacted = used(7);
+        theirs.cities[0].last_interacted = used(7);
+        theirs.cities[0].name = "Austin TX".to_string();
+        theirs.profiles[0].layout = layout(false);
+        theirs.profiles[0].cities[0].last_interacted = used(7);
+
+        let (merged, conflicts) = merge(&base, &ours, &theirs);
+        assert!(conflicts.is_empty(), "{:?}", conflicts);
+        // Their rename comes in; the last use is always this window's
+        assert_eq!(merged.cities[0].name, "Austin TX");
+        assert_eq!(merged.cities[0].last_interacted, None);
+        assert_eq!(merged.cities[1].last_interacted, used(5));
+        assert_eq!(merged.profiles[0].layout, layout(true));
+        assert_eq!(merged.profiles[0].cities[0].last_interacted, used(5));
+
+        // Removing a city the other window only looked at still removes it
+        let mut ours = base.clone();
+        ours.cities.remove(1);
+        let (merged, conflicts) = merge(&base, &ours, &theirs);
+        assert_eq!(names(&merged), ["Austin TX"]);
+        assert!(conflicts.is_empty(), "{:?}", conflicts);
+    }
+
+    #[test]
+    fn without_a_newer_file_ours_is_written_one_generation_on() {
+        let base = base();
+        let mut ours = base.clone();
+        ours.cities.pop();
+
+        // No file, or one nobody else has saved since
+        for on_disk in [None, Some(base.clone())] {
+            let commit = reconcile(&base, ours.clone(), on_disk);
+            assert!(!commit.merged);
+            assert!(commit.conflicts.is_empty());
+            assert_eq!(names(&commit.config), ["Austin"]);
+            assert_eq!(commit.config.generation, 4);
+        }
+    }
+
+    #[test]
+    fn a_newer_file_is_merged_and_bumped_past() {
+        let base = base();
+        let mut ours = base.clone();
+        ours.cities.pop();
+        let mut theirs = base.clone();
+        theirs.cities.push(city("t", "Tokyo", "Asia/Tokyo"));
+        theirs.generation = 5;
+
+        let commit = reconcile(&base, ours, Some(theirs));
+        assert!(commit.merged);
+        assert_eq!(names(&commit.config), ["Austin", "Tokyo"]);
+        assert_eq!(commit.config.generation, 6);
+    }
+}
@@ -0,0 +1,246 @@
+use crate::{WorldTimeApp, config::CityConfig, settings_panel::hint};
+use gpui::{App, Context, Entity, Window, div, prelude::*};
+use gpui_component::{
+    Sizable, WindowExt as _,
+    button::{Button, ButtonVariant},
+    checkbox::Checkbox,
+    dialog::DialogButtonProps,
+    h_flex, v_flex,
+};
+
+// What replacing the city list would do, matched by name and timezone
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportSummary {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    pub kept: usize,
+}
+
+pub fn import_summary(current: &[CityConfig], incoming: &[CityConfig]) -> ImportSummary {
+    let same = |a: &CityConfig, b: &CityConfig| a.name == b.name && a.timezone == b.timezone;
+    ImportSummary {
+        added: incoming
+            .iter()
+            .filter(|city| !current.iter().any(|existing| same(existing, city)))
+            .map(|city| city.name.clone())
+            .collect(),
+        removed: current
+            .iter()
+            .filter(|city| !incoming.iter().any(|new| same(new, city)))
+            .map(|city| city.name.clone())
+            .collect(),
+        kept: incoming
+            .iter()
+            .filter(|city| current.iter().any(|existing| same(existing, city)))
+            .count(),
+    }
+}
+
+fn name_list(names: &[String]) -> String {
+    if names.is_empty() {
+        "none".to_string()
+    } else {
+        names.join(", ")
+    }
+}
+
+pub fn confirm_remove_city(
+    app: Entity<WorldTimeApp>,
+    name: String,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    window.open_dialog(cx, move |dialog, _window, _cx| {
+        dialog
+            .title(format!("Remove {}?", name))
+            .child(hint("Undo brings it back"))
+            .confirm()
+            .button_props(
+                DialogButtonProps::default()
+                    .ok_text("Remove")
+                    .ok_variant(ButtonVariant::Danger),
+            )
+            .on_ok({
+                let app = app.clone();
+                let name = name.clone();
+                move |_, window, cx| {
+                    app.update(cx, |app, cx| app.remove_city(&name, None, window, cx));
+                    true
+                }
+            })
+    });
+}
+
+// The home city can't just go: the user picks its successor, and removing it
+// and moving home happen as one step
+pub fn confirm_remove_home(
+    app: Entity<WorldTimeApp>,
+    name: String,
+    others: Vec<String>,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    window.open_dialog(cx, move |dialog, _window, _cx| {
+        dialog
+            .title(format!("{} is home", name))
+            .child(
+                v_flex()
+                    .gap_3()
+                    .child(div().child("Pick a new home before removing it:").text_sm())
+                    .child(
+                        h_flex()
+                            .flex_wrap()
+                            .gap_2()
+                            .children(others.iter().enumerate().map(|(index, other)| {
+                                let app = app.clone();
+                                let name = name.clone();
+                                let other = other.clone();
+                                Button::new(("new-home", index))
+                                    .label(other.clone())
+                                    .small()
+                                    .on_click(move |_, window, cx| {
+                                        window.close_dialog(cx);
+                                        app.update(cx, |app, cx| {
+                                            app.remove_city(&name, Some(&other), window, cx)
+                                        });
+                                    })
+                            })),
+                    ),
+            )
+            .child(hint("Undo restores both the city and the old home"))
+    });
+}
+
+pub fn confirm_import(
+    app: Entity<WorldTimeApp>,
+    cities: Vec<CityConfig>,
+    summary: ImportSummary,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    window.open_dialog(cx, move |dialog, _window, _cx| {
+        dialog
+            .title("Replace all cities?")
+            .child(
+                v_flex()
+                    .gap_2()
+                    .child(
+                        div()
+                            .child(format!(
+                                "{} added, {} removed, {} unchanged",
+                                summary.added.len(),
+                                summary.removed.len(),
+                                summary.kept
+                            ))
+                            .text_sm(),
+                    )
+                    .child(hint(format!("Added: {}", name_list(&summary.added))))
+                    .child(hint(format!("Removed: {}", name_list(&summary.removed)))),
+            )
+            .confirm()
+            .button_props(
+                DialogButtonProps::default()
+                    .ok_text("Replace")
+                    .ok_variant(ButtonVariant::Danger),
+            )
+            .on_ok({
+                let app = app.clone();
+                let cities = cities.clone();
+                move |_, window, cx| {
+                    app.update(cx, |app, cx| {
+                        app.replace_cities_undoably(&cities, window, cx)
+                    });
+                    true
+                }
+            })
+    });
+}
+
+pub fn confirm_reset_settings(app: Entity<WorldTimeApp>, window: &mut Window, cx: &mut App) {
+    window.open_dialog(cx, move |dialog, _window, _cx| {
+        dialog
+            .title("Reset all settings?")
+            .child(hint(
+                "Cities, alarms and countdowns stay; undo restores the settings",
+            ))
+            .confirm()
+            .button_props(
+                DialogButtonProps::default()
+                    .ok_text("Reset")
+                    .ok_variant(ButtonVariant::Danger),
+            )
+            .on_ok({
+                let app = app.clone();
+                move |_, window, cx| {
+                    app.update(cx, |app, cx| app.reset_settings(window, cx));
+                    true
+                }
+            })
+    });
+}
+
+// Ticks for the cities to remove; home isn't offered
+struct RemovePicker {
+    names: Vec<String>,
+    selected: Vec<bool>,
+}
+
+impl RemovePicker {
+    fn chosen(&self) -> Vec<String> {
+        self.names
+            .iter()
+            .zip(&self.selected)
+            .filter(|(_, selected)| **selected)
+            .map(|(name, _)| name.clone())
+            .collect()
+    }
+}
+
+impl Render for RemovePicker {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .gap_2()
+            .children(self.names.iter().enumerate().map(|(index, name)| {
+                Checkbox::new(("remove-city", index))
+                    .label(name.clone())
+                    .checked(self.selected[index])
+                    .on_click(cx.listener(move |this, checked: &bool, _, cx| {
+                        this.selected[index] = *checked;
+                        cx.notify();
+                    }))
+            }))
+            .child(hint("Undo brings them all back in one step"))
+    }
+}
+
+pub fn confirm_remove_cities(
+    app: Entity<WorldTimeApp>,
+    names: Vec<String>,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    let selected = vec![false; names.len()];
+    let picker = cx.new(|_| RemovePicker { names, selected });
+    window.open_dialog(cx, move |dialog, _window, _cx| {
+        dialog
+            .title("Remove cities")
+            .child(picker.clone())
+            .confirm()
+            .button_props(
+                DialogButtonProps::default()
+                    .ok_text("Remove")
+                    .ok_variant(ButtonVariant::Danger),
+            )
+            .on_ok({
+                let app = app.clone();
+                let picker = picker.clone();
+                move |_, window, cx| {
+                    let names = picker.read(cx).chosen();
+                    if !names.is_empty() {
+                        app.update(cx, |app, cx| app.remove_cities(&names, window, cx));
+                    }
+                    true
+                }
+            })
+    });
+}
@@ -0,0 +1,572 @@
+use crate::{config::CityConfig, zone};
+use chrono::{DateTime, Days, LocalResult, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use std::fmt;
+use time2rust::format;
+
+// What the user typed, before a zone is applied to it
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TimeInput {
+    // Input carried its own offset ("2025-05-01T14:00Z")
+    Instant(DateTime<Utc>),
+    // Wall-clock time that still needs a zone ("15:00", "tomorrow 09:00")
+    Local(NaiveDateTime),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnparseableTime(pub String);
+
+impl fmt::Display for UnparseableTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "couldn't understand \"{}\" (try \"2025-04-01 15:00\", \"15:00\" or \"tomorrow 09:00\")",
+            self.0
+        )
+    }
+}
+
+const DATE_TIME_FORMATS: [&str; 4] = [
+    "%Y-%m-%d %H:%M",
+    "%Y-%m-%d %H:%M:%S",
+    "%Y-%m-%dT%H:%M",
+    "%Y-%m-%dT%H:%M:%S",
+];
+
+const TIME_FORMATS: [&str; 2] = ["%H:%M", "%H:%M:%S"];
+
+// Accepts ISO 8601 (with or without offset), "HH:MM" meaning `today`, and
+// "today/tomorrow/yesterday HH:MM"
+pub fn parse_time_input(input: &str, today: NaiveDate) -> Result<TimeInput, UnparseableTime> {
+    let trimmed = input.trim();
+    let unparseable = || UnparseableTime(trimmed.to_string());
+
+    if let Ok(instant) = DateTime::parse_from_rfc3339(trimmed) {
+        return Ok(TimeInput::Instant(instant.with_timezone(&Utc)));
+    }
+    if let Some(without_z) = trimmed
+        .strip_suffix('Z')
+        .or_else(|| trimmed.strip_suffix('z'))
+    {
+        let naive = parse_date_time(without_z).ok_or_else(unparseable)?;
+        return Ok(TimeInput::Instant(naive.and_utc()));
+    }
+    if let Some(naive) = parse_date_time(trimmed) {
+        return Ok(TimeInput::Local(naive));
+    }
+
+    let (day, time) = match trimmed.split_once(char::is_whitespace) {
+        Some((word, rest)) => {
+            let day = match word.to_ascii_lowercase().as_str() {
+                "today" => today,
+                "tomorrow" => today
+                    .checked_add_days(Days::new(1))
+                    .ok_or_else(unparseable)?,
+                "yesterday" => today
+                    .checked_sub_days(Days::new(1))
+                    .ok_or_else(unparseable)?,
+                _ => return Err(unparseable()),
+            };
+            (day, rest.trim())
+        }
+        None => (today, trimmed),
+    };
+    let time = parse_time(time).ok_or_else(unparseable)?;
+    Ok(TimeInput::Local(day.and_time(time)))
+}
+
+fn parse_date_time(input: &str) -> Option<NaiveDateTime> {
+    DATE_TIME_FORMATS
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(input, format).ok())
+}
+
+pub fn parse_time(input: &str) -> Option<NaiveTime> {
+    TIME_FORMATS
+        .iter()
+        .find_map(|format| NaiveTime::parse_from_str(input, format).ok())
+}
+
+// An instant plus an explanation when the wall-clock time was ambiguous or skipped
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResolvedInstant {
+    pub instant: DateTime<Utc>,
+    pub note: Option<String>,
+}
+
+pub fn resolve_input(input: TimeInput, tz: Tz) -> ResolvedInstant {
+    match input {
+        TimeInput::Instant(instant) => ResolvedInstant {
+            instant,
+            note: None,
+        },
+        TimeInput::Local(local) => resolve_local(local, tz),
+    }
+}
+
+// Applies a zone to a wall-clock time, explaining what happened around DST changes
+pub fn resolve_local(local: NaiveDateTime, tz: Tz) -> ResolvedInstant {
+    match tz.from_local_datetime(&local) {
+        LocalResult::Single(time) => ResolvedInstant {
+            instant: time.with_timezone(&Utc),
+            note: None,
+        },
+        LocalResult::Ambiguous(earliest, latest) => ResolvedInstant {
+            instant: earliest.with_timezone(&Utc),
+            note: Some(format!(
+                "{} happens twice in {} (clocks go back); using the first one ({}), the second is {}",
+                format::date_time(local),
+                tz.name(),
+                zone::format_utc_offset(zone::utc_offset_minutes(tz, earliest.to_utc())),
+                zone::format_utc_offset(zone::utc_offset_minutes(tz, latest.to_utc())),
+            )),
+        },
+        LocalResult::None => {
+            // Inside a spring-forward gap: keep the offset from before the gap,
+            // which lands the same distance past the jump
+            let before_gap = local - chrono::Duration::days(1);
+            let offset_minutes = tz
+                .from_local_datetime(&before_gap)
+                .earliest()
+                .map(|time| zone::utc_offset_minutes(tz, time.to_utc()))
+                .unwrap_or(0);
+            let instant = (local - chrono::Duration::minutes(offset_minutes as i64)).and_utc();
+            ResolvedInstant {
+                instant,
+                note: Some(format!(
+                    "{} doesn't exist in {} (clocks skip forward); using {}",
+                    format::date_time(local),
+                    tz.name(),
+                    format::clock_time(instant.with_timezone(&tz).time(), false),
+                )),
+            }
+        }
+    }
+}
+
+// Where `their` wall-clock time on the city's current day falls in home time, and
+// how many calendar days home is from them then
+pub fn mirror_time(
+    now: DateTime<Utc>,
+    city_tz: Tz,
+    their: NaiveTime,
+    home_tz: Tz,
+) -> (NaiveTime, i64) {
+    let date = now.with_timezone(&city_tz).date_naive();
+    let home = resolve_local(date.and_time(their), city_tz)
+        .instant
+        .with_timezone(&home_tz);
+    (home.time(), (home.date_naive() - date).num_days())
+}
+
+// "their 17:00 = your 10:00", with "+1 day" when home is on another day by then
+pub fn mirror_label(now: DateTime<Utc>, city_tz: Tz, their: NaiveTime, home_tz: Tz) -> String {
+    let (yours, days) = mirror_time(now, city_tz, their, home_tz);
+    let label = format!(
+        "their {} = your {}",
+        format::clock_time(their, false),
+        format::clock_time(yours, false)
+    );
+    match days {
+        0 => label,
+        days => format!("{} {}", label, format::day_offset(days)),
+    }
+}
+
+// One line of conversion output, e.g. "Berlin      Tue 2025-04-01 15:00  CEST (UTC+02:00)"
+pub fn format_in_zone(name: &str, instant: DateTime<Utc>, tz: Tz) -> String {
+    let local = instant.with_timezone(&tz);
+    format!(
+        "{:<12} {}  {} ({})",
+        name,
+        format::weekday_iso_date_time(local.naive_local()),
+        local.format("%Z"),
+        zone::format_utc_offset(zone::utc_offset_minutes(tz, instant)),
+    )
+}
+
+// Where a conversion's wall-clock time was given
+#[derive(Debug, Clone, PartialEq)]
+pub struct NamedZone {
+    pub label: String,
+    pub tz: Tz,
+}
+
+// Resolves "Berlin" (a configured city), "Asia/Tokyo" or "Tokyo" (a zone or its
+// city), "home" (the home city) or "here" (this machine's zone)
+pub fn resolve_zone_name(
+    name: &str,
+    cities: &[CityConfig],
+    home: &NamedZone,
+) -> Result<NamedZone, String> {
+    let name = name.trim();
+    if name.eq_ignore_ascii_case("home") {
+        return Ok(home.clone());
+    }
+    if name.eq_ignore_ascii_case("here") {
+        let tz = zone::system_zone()?;
+        return Ok(NamedZone {
+            label: "here".to_string(),
+            tz,
+        });
+    }
+    if let Some(city) = cities
+        .iter()
+        .find(|city| city.name.eq_ignore_ascii_case(name))
+    {
+        let tz = zone::resolve_zone(&city.timezone).map_err(|err| err.to_string())?;
+        return Ok(NamedZone {
+            label: city.name.clone(),
+            tz,
+        });
+    }
+    zone::resolve_zone(name)
+        .or_else(|err| zone::find_zone_by_city(name).ok_or(err))
+        .map(|tz| NamedZone {
+            label: tz.name().to_string(),
+            tz,
+        })
+        .map_err(|_| {
+            format!(
+                "\"{}\" isn't a city, a timezone, \"here\" or \"home\"",
+                name
+            )
+        })
+}
+
+// The home city (or the first one), UTC when there are none
+pub fn home_zone(cities: &[CityConfig]) -> NamedZone {
+    cities
+        .iter()
+        .find(|city| city.home)
+        .or(cities.first())
+        .and_then(|city| {
+            zone::resolve_zone(&city.timezone).ok().map(|tz| NamedZone {
+                label: city.name.clone(),
+                tz,
+            })
+        })
+        .unwrap_or(NamedZone {
+            label: "UTC".to_string(),
+            tz: chrono_tz::UTC,
+        })
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Conversion {
+    pub from: NamedZone,
+    pub resolved: ResolvedInstant,
+}
+
+// Converts a query like "15:00 Berlin", "tomorrow 09:30 in Asia/Tokyo" or just
+// "15:00" (meaning `default_zone`). The zone is whatever follows the time, so
+// multi-word city names work too.
+pub fn convert_query(
+    query: &str,
+    cities: &[CityConfig],
+    default_zone: &NamedZone,
+    now: DateTime<Utc>,
+) -> Result<Conversion, String> {
+    let query = query.trim();
+    let today_in = |tz: Tz| now.with_timezone(&tz).date_naive();
+
+    if let Ok(input) = parse_time_input(query, today_in(default_zone.tz)) {
+        return Ok(Conversion {
+            from: default_zone.clone(),
+            resolved: resolve_input(input, default_zone.tz),
+        });
+    }
+
+    // Try the longest time prefix first so "tomorrow 09:30 Tokyo" isn't read as "tomorrow"
+    let splits = query
+        .char_indices()
+        .filter(|(_, c)| c.is_whitespace())
+        .map(|(index, _)| index)
+        .rev();
+    let (mut time_error, mut zone_error) = (None, None);
+    for split in splits {
+        let (time, zone_name) = query.split_at(split);
+        let time = time.trim_end();
+        let time = if time.to_ascii_lowercase().ends_with(" in") {
+            &time[..time.len() - 3]
+        } else {
+            time
+        };
+        let from = match resolve_zone_name(zone_name, cities, default_zone) {
+            Ok(from) => from,
+            Err(err) => {
+                zone_error.get_or_insert(err);
+                continue;
+            }
+        };
+        match parse_time_input(time, today_in(from.tz)) {
+            Ok(input) => {
+                return Ok(Conversion {
+                    resolved: resolve_input(input, from.tz),
+                    from,
+                });
+            }
+            Err(err) => {
+                time_error.get_or_insert(err.to_string());
+            }
+        }
+    }
+    Err(time_error
+        .or(zone_error)
+        .unwrap_or_else(|| UnparseableTime(query.to_string()).to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono_tz::{America, Asia, Europe, Pacific};
+
+    fn hm(hour: u32, minute: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(hour, minute, 0).unwrap()
+    }
+
+    fn noon_utc(month: u32, day: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, month, day, 12, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn mirror_time_on_the_same_day() {
+        let now = noon_utc(7, 1);
+        let mirrored = mirror_time(now, Europe::Berlin, hm(17, 0), America::New_York);
+        assert_eq!(mirrored, (hm(11, 0), 0));
+        assert_eq!(
+            mirror_label(now, Europe::Berlin, hm(17, 0), America::New_York),
+            "their 17:00 = your 11:00"
+        );
+    }
+
+    #[test]
+    fn mirror_time_on_another_day() {
+        let now = noon_utc(7, 1);
+        // Tokyo's 09:00 on Jul 1 is still Jun 30 in New York
+        assert_eq!(
+            mirror_label(now, Asia::Tokyo, hm(9, 0), America::New_York),
+            "their 09:00 = your 20:00 -1 day"
+        );
+        // New York's 17:00 on Jul 1 is already Jul 2 in Tokyo
+        assert_eq!(
+            mirror_time(now, America::New_York, hm(17, 0), Asia::Tokyo),
+            (hm(6, 0), 1)
+        );
+        assert_eq!(
+            mirror_label(now, America::New_York, hm(17, 0), Asia::Tokyo),
+            "their 17:00 = your 06:00 +1 day"
+        );
+    }
+
+    #[test]
+    fn mirror_time_inside_a_spring_forward_gap() {
+        // 02:30 doesn't exist in New York on Mar 8; it's read with the offset
+        // from before the gap, 07:30 UTC
+        let mirrored = mirror_time(noon_utc(3, 8), America::New_York, hm(2, 30), Europe::Berlin);
+        assert_eq!(mirrored, (hm(8, 30), 0));
+    }
+
+    fn date(month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, month, day).unwrap()
+    }
+
+    fn local(month: u32, day: u32, hour: u32, minute: u32) -> NaiveDateTime {
+        date(month, day).and_time(hm(hour, minute))
+    }
+
+    fn utc(month: u32, day: u32, hour: u32, minute: u32) -> DateTime<Utc> {
+        local(month, day, hour, minute).and_utc()
+    }
+
+    #[test]
+    fn parse_time_input_accepts_each_form() {
+        let today = date(4, 1);
+        let parse = |input: &str| parse_time_input(input, today).unwrap();
+        assert_eq!(
+            parse("2026-05-01 15:00"),
+            TimeInput::Local(local(5, 1, 15, 0))
+        );
+        assert_eq!(
+            parse("2026-05-01T15:00:30"),
+            TimeInput::Local(local(5, 1, 15, 0) + chrono::Duration::seconds(30))
+        );
+        assert_eq!(
+            parse("2026-05-01T14:00:00+02:00"),
+            TimeInput::Instant(utc(5, 1, 12, 0))
+        );
+        assert_eq!(
+            parse("2026-05-01T14:00Z"),
+            TimeInput::Instant(utc(5, 1, 14, 0))
+        );
+        assert_eq!(parse(" 15:00 "), TimeInput::Local(local(4, 1, 15, 0)));
+        assert_eq!(parse("tomorrow 09:00"), TimeInput::Local(local(4, 2, 9, 0)));
+        assert_eq!(
+            parse("Yesterday 23:30"),
+            TimeInput::Local(local(3, 31, 23, 30))
+        );
+        assert_eq!(parse("TODAY 07:05"), TimeInput::Local(local(4, 1, 7, 5)));
+    }
+
+    #[test]
+    fn parse_time_input_rejects_the_rest() {
+        let today = date(4, 1);
+        for input in [
+            "",
+            "25:00",
+            "noonish",
+            "next week 09:00",
+            "tomorrow",
+            "2026-13-01 10:00",
+            "2026-05-01 15:00+",
+            "15:00Z",
+        ] {
+            assert!(parse_time_input(input, today).is_err(), "{:?}", input);
+        }
+        let err = parse_time_input(" 9 o'clock ", today).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "couldn't understand \"9 o'clock\" (try \"2025-04-01 15:00\", \"15:00\" or \"tomorrow 09:00\")"
+        );
+    }
+
+    #[test]
+    fn a_repeated_local_time_takes_the_first() {
+        // Berlin's clocks go back from 03:00 CEST to 02:00 CET on Oct 25
+        let resolved = resolve_local(local(10, 25, 2, 30), Europe::Berlin);
+        assert_eq!(resolved.instant, utc(10, 25, 0, 30));
+        assert_eq!(
+            resolved.note.as_deref(),
+            Some(
+                "2026-10-25 02:30 happens twice in Europe/Berlin (clocks go back); using the \
+                 first one (UTC+02:00), the second is UTC+01:00"
+            )
+        );
+    }
+
+    #[test]
+    fn a_skipped_local_time_lands_past_the_gap() {
+        // Berlin's clocks jump from 02:00 to 03:00 on Mar 29
+        let resolved = resolve_local(local(3, 29, 2, 30), Europe::Berlin);
+        assert_eq!(resolved.instant, utc(3, 29, 1, 30));
+        assert_eq!(
+            resolved.note.as_deref(),
+            Some(
+                "2026-03-29 02:30 doesn't exist in Europe/Berlin (clocks skip forward); using 03:30"
+            )
+        );
+    }
+
+    #[test]
+    fn ordinary_times_and_instants_resolve_without_a_note() {
+        let resolved = resolve_local(local(7, 1, 9, 0), Asia::Tokyo);
+        assert_eq!(
+            resolved,
+            ResolvedInstant {
+                instant: utc(7, 1, 0, 0),
+                note: None
+            }
+        );
+        let instant = TimeInput::Instant(utc(7, 1, 12, 0));
+        assert_eq!(
+            resolve_input(instant, Europe::Berlin).instant,
+            utc(7, 1, 12, 0)
+        );
+    }
+
+    #[test]
+    fn convert_query_across_the_date_line() {
+        let now = noon_utc(7, 1);
+        let honolulu = NamedZone {
+            label: "Honolulu".to_string(),
+            tz: Pacific::Honolulu,
+        };
+        // "Today" is Kiritimati's, already Jul 2 there: 09:00 is 19:00 UTC on Jul 1
+        let conversion = convert_query("09:00 Pacific/Kiritimati", &[], &honolulu, now).unwrap();
+        assert_eq!(conversion.from.tz, Pacific::Kiritimati);
+        assert_eq!(conversion.resolved.instant, utc(7, 1, 19, 0));
+        let there = conversion
+            .resolved
+            .instant
+            .with_timezone(&Pacific::Honolulu);
+        assert_eq!(there.naive_local(), local(7, 1, 9, 0));
+
+        // And Pago Pago's, still Jul 1: 23:00 there is midnight on Jul 3 in Kiritimati
+        let conversion = convert_query("23:00 Pacific/Pago_Pago", &[], &honolulu, now).unwrap();
+        let there = conversion
+            .resolved
+            .instant
+            .with_timezone(&Pacific::Kiritimati);
+        assert_eq!(there.naive_local(), local(7, 3, 0, 0));
+    }
+
+    #[test]
+    fn convert_query_reads_each_form() {
+        // Wed Jul 1 2026, 12:00 UTC
+        let now = noon_utc(7, 1);
+        let home = NamedZone {
+            label: "Austin".to_string(),
+            tz: America::Chicago,
+        };
+        let cities = [
+            CityConfig::new("Austin", "America/Chicago", true),
+            CityConfig::new("Head Office", "Europe/London", false),
+        ];
+        let convert = |query: &str| {
+            let conversion = convert_query(query, &cities, &home, now).unwrap();
+            (conversion.from.label, conversion.resolved.instant)
+        };
+
+        assert_eq!(
+            convert("15:00 Berlin"),
+            ("Europe/Berlin".to_string(), utc(7, 1, 13, 0))
+        );
+        assert_eq!(
+            convert("tomorrow 09:30 in Asia/Tokyo"),
+            ("Asia/Tokyo".to_string(), utc(7, 2, 0, 30))
+        );
+        assert_eq!(
+            convert("15:00 Buenos Aires"),
+            (
+                "America/Argentina/Buenos_Aires".to_string(),
+                utc(7, 1, 18, 0)
+            )
+        );
+        assert_eq!(
+            convert("15:00 home"),
+            ("Austin".to_string(), utc(7, 1, 20, 0))
+        );
+        assert_eq!(
+            convert("15:00 head office"),
+            ("Head Office".to_string(), utc(7, 1, 14, 0))
+        );
+        assert_eq!(
+            convert("  15:00  "),
+            ("Austin".to_string(), utc(7, 1, 20, 0))
+        );
+    }
+
+    #[test]
+    fn convert_query_says_what_it_couldnt_read() {
+        let now = noon_utc(7, 1);
+        let home = NamedZone {
+            label: "UTC".to_string(),
+            tz: chrono_tz::UTC,
+        };
+        let error = |query: &str| convert_query(query, &[], &home, now).unwrap_err();
+
+        assert_eq!(
+            error("15:00 Atlantis"),
+            "\"Atlantis\" isn't a city, a timezone, \"here\" or \"home\""
+        );
+        // Once the zone is known, the time is what's wrong
+        assert_eq!(
+            error("25:00 Berlin"),
+            UnparseableTime("25:00".to_string()).to_string()
+        );
+        assert_eq!(
+            error("teatime"),
+            UnparseableTime("teatime".to_string()).to_string()
+        );
+    }
+}
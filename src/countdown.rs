@@ -0,0 +1,327 @@
+use crate::{WorldTimeApp, config::CityConfig, convert, settings_panel::hint, zone};
+use chrono::{DateTime, NaiveDateTime, Utc};
+use chrono_tz::Tz;
+use gpui::{App, Context, Entity, EventEmitter, SharedString, Window, div, prelude::*, px, rgb};
+use gpui_component::{
+    WindowExt as _,
+    button::{Button, ButtonVariants as _},
+    dialog::DialogButtonProps,
+    input::{Input, InputState},
+    menu::{ContextMenuExt as _, PopupMenuItem},
+    v_flex,
+};
+use serde::{Deserialize, Serialize};
+use time2rust::format;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CountdownConfig {
+    pub title: String,
+    pub timezone: String, // IANA id the target is given in
+    // Wall-clock target in that zone, kept local so a later rule change still lands on it
+    pub target: NaiveDateTime,
+    // Set once it has finished and moved to the archive
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub completed: Option<DateTime<Utc>>,
+    // The city card it shows on as a line instead of a card of its own
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pinned_to: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Remaining {
+    Counting { minutes: i64 },
+    FinalHour { minutes: i64 },
+    Done,
+}
+
+// Subtracting UTC instants makes a DST change between now and the target count
+// as the hour it really is
+pub fn remaining(now: DateTime<Utc>, target: DateTime<Utc>) -> Remaining {
+    let seconds = (target - now).num_seconds();
+    if seconds <= 0 {
+        return Remaining::Done;
+    }
+    // Round up so the last minute reads "1m" rather than "0m"
+    let minutes = (seconds + 59) / 60;
+    if minutes <= 60 {
+        Remaining::FinalHour { minutes }
+    } else {
+        Remaining::Counting { minutes }
+    }
+}
+
+// "Release freeze in 2d 4h 12m", for a line on the card it's pinned to
+pub fn card_line(title: &str, remaining: Remaining) -> String {
+    match remaining {
+        Remaining::Counting { minutes } | Remaining::FinalHour { minutes } => {
+            format!("{} in {}", title, format::remaining(minutes))
+        }
+        Remaining::Done => format!("{}: done", title),
+    }
+}
+
+// A countdown stands alone unless the card it's pinned to is on show; a hidden,
+// stacked or removed host leaves it as its own card again
+pub fn is_standalone(pinned_to: Option<&str>, shown_cards: &[&str]) -> bool {
+    pinned_to.is_none_or(|city| !shown_cards.contains(&city))
+}
+
+pub enum CountdownEvent {
+    Remove,
+    ChooseCard,
+    Unpin,
+}
+
+#[derive(Debug, Clone)]
+pub struct Countdown {
+    pub config: CountdownConfig,
+    tz: Tz,
+    target: DateTime<Utc>,
+    remaining: Remaining,
+}
+
+impl EventEmitter<CountdownEvent> for Countdown {}
+
+impl Countdown {
+    pub fn new(config: CountdownConfig, now: DateTime<Utc>) -> Result<Self, String> {
+        let tz = zone::resolve_zone(&config.timezone).map_err(|err| err.to_string())?;
+        let target = convert::resolve_local(config.target, tz).instant;
+        Ok(Countdown {
+            config,
+            tz,
+            target,
+            remaining: remaining(now, target),
+        })
+    }
+
+    pub fn update_time(&mut self, now: DateTime<Utc>) {
+        self.remaining = remaining(now, self.target);
+    }
+
+    pub fn target(&self) -> DateTime<Utc> {
+        self.target
+    }
+
+    pub fn remaining(&self) -> Remaining {
+        self.remaining
+    }
+}
+
+impl Render for Countdown {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let (text, color) = match self.remaining {
+            Remaining::Counting { minutes } => (format::remaining(minutes), rgb(0x111827)),
+            Remaining::FinalHour { minutes } => (format::remaining(minutes), rgb(0xef4444)),
+            Remaining::Done => ("done".to_string(), rgb(0x6b7280)),
+        };
+
+        div()
+            .id(SharedString::from(format!(
+                "countdown-{}",
+                self.config.title
+            )))
+            .flex()
+            .flex_col()
+            .items_center()
+            .gap_1()
+            .p_4()
+            .min_w(px(180.0))
+            .bg(rgb(0xfffbeb))
+            .border_2()
+            .border_color(rgb(0xf59e0b))
+            .rounded(px(8.0))
+            .child(
+                div()
+                    .child(self.config.title.clone())
+                    .text_lg()
+                    .font_weight(gpui::FontWeight::BOLD)
+                    .text_color(rgb(0x111827)),
+            )
+            .child(
+                div()
+                    .child(text)
+                    .text_3xl()
+                    .font_weight(gpui::FontWeight::BOLD)
+                    .text_color(color),
+            )
+            .child(
+                div()
+                    .child(format!(
+                        "{} {}",
+                        format::weekday_date_time(
+                            self.target.with_timezone(&self.tz).naive_local()
+                        ),
+                        self.config.timezone
+                    ))
+                    .text_xs()
+                    .text_color(rgb(0x6b7280)),
+            )
+            .context_menu({
+                let countdown = cx.entity();
+                let pinned = self.config.pinned_to.is_some();
+                move |menu, _window, _cx| {
+                    let emit = |event: fn() -> CountdownEvent| {
+                        let countdown = countdown.clone();
+                        move |_: &_, _: &mut Window, cx: &mut App| {
+                            countdown.update(cx, |_, cx| cx.emit(event()))
+                        }
+                    };
+                    let menu = if pinned {
+                        // Its card is hidden or stacked for now
+                        menu.item(
+                            PopupMenuItem::new("Unpin from card")
+                                .on_click(emit(|| CountdownEvent::Unpin)),
+                        )
+                    } else {
+                        menu.item(
+                            PopupMenuItem::new("Pin to a city card…")
+                                .on_click(emit(|| CountdownEvent::ChooseCard)),
+                        )
+                    };
+                    menu.item(
+                        PopupMenuItem::new("Remove").on_click(emit(|| CountdownEvent::Remove)),
+                    )
+                }
+            })
+    }
+}
+
+// `from` pre-fills the form, as when duplicating an archived countdown
+pub fn open_add_countdown(
+    app: Entity<WorldTimeApp>,
+    from: Option<&CountdownConfig>,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    let form = cx.new(|cx| AddCountdownForm::new(from, window, cx));
+    window.open_dialog(cx, move |dialog, _window, _cx| {
+        dialog
+            .title("Add countdown")
+            .child(form.clone())
+            .confirm()
+            .button_props(DialogButtonProps::default().ok_text("Add"))
+            .on_ok({
+                let form = form.clone();
+                let app = app.clone();
+                move |_, window, cx| {
+                    let cities = app.read(cx).city_configs(cx);
+                    match form.read(cx).countdown_config(&cities, cx) {
+                        Ok(countdown) => {
+                            app.update(cx, |app, cx| app.add_countdown(countdown, window, cx));
+                            true
+                        }
+                        Err(err) => {
+                            form.update(cx, |form, cx| {
+                                form.error = Some(err.into());
+                                cx.notify();
+                            });
+                            false
+                        }
+                    }
+                }
+            })
+    });
+}
+
+pub struct AddCountdownForm {
+    title_input: Entity<InputState>,
+    target_input: Entity<InputState>,
+    error: Option<SharedString>,
+}
+
+impl AddCountdownForm {
+    fn new(from: Option<&CountdownConfig>, window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let (title, target) = from.map_or_else(Default::default, |from| {
+            (
+                from.title.clone(),
+                format!("{} {}", format::date_time(from.target), from.timezone),
+            )
+        });
+        AddCountdownForm {
+            title_input: cx.new(|cx| {
+                InputState::new(window, cx)
+                    .placeholder("Title, e.g. Release freeze")
+                    .default_value(title)
+            }),
+            target_input: cx.new(|cx| {
+                InputState::new(window, cx)
+                    .placeholder("When, e.g. 2025-05-02 18:00 Berlin")
+                    .default_value(target)
+            }),
+            error: None,
+        }
+    }
+
+    fn countdown_config(&self, cities: &[CityConfig], cx: &App) -> Result<CountdownConfig, String> {
+        let title = self.title_input.read(cx).value().trim().to_string();
+        if title.is_empty() {
+            return Err("Give the countdown a title".to_string());
+        }
+        let home = convert::home_zone(cities);
+        let target = self.target_input.read(cx).value();
+        let conversion = convert::convert_query(&target, cities, &home, Utc::now())?;
+        Ok(CountdownConfig {
+            title,
+            timezone: conversion.from.tz.name().to_string(),
+            target: conversion
+                .resolved
+                .instant
+                .with_timezone(&conversion.from.tz)
+                .naive_local(),
+            completed: None,
+            pinned_to: None,
+        })
+    }
+}
+
+impl Render for AddCountdownForm {
+    fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .gap_2()
+            .child(Input::new(&self.title_input))
+            .child(Input::new(&self.target_input))
+            .children(
+                self.error
+                    .clone()
+                    .map(|error| div().child(error).text_sm().text_color(rgb(0xef4444))),
+            )
+    }
+}
+
+// A button per card; picking one moves the countdown onto it
+pub fn open_pin_countdown(
+    app: Entity<WorldTimeApp>,
+    countdown: Entity<Countdown>,
+    cities: Vec<String>,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    let title = countdown.read(cx).config.title.clone();
+    window.open_dialog(cx, move |dialog, _window, _cx| {
+        dialog
+            .title(format!("Pin \"{}\" to", title))
+            .child(
+                v_flex()
+                    .gap_1()
+                    .children(cities.iter().enumerate().map(|(index, city)| {
+                        let app = app.clone();
+                        let countdown = countdown.clone();
+                        let city = city.clone();
+                        Button::new(("pin-countdown", index))
+                            .label(city.clone())
+                            .ghost()
+                            .on_click(move |_, window, cx| {
+                                app.update(cx, |app, cx| {
+                                    app.pin_countdown(&countdown, Some(city.clone()), window, cx)
+                                });
+                                window.close_dialog(cx);
+                            })
+                    }))
+                    .child(hint(
+                        "It shows as a line on that card; click the line to see the rest or unpin",
+                    )),
+            )
+            .alert()
+            .button_props(DialogButtonProps::default().ok_text("Cancel"))
+    });
+}
@@ -0,0 +1,108 @@
+use crate::convert;
+use chrono::{DateTime, Duration, NaiveDate, NaiveTime, Utc};
+use chrono_tz::Tz;
+use time2rust::format;
+
+fn resolve(tz: Tz, date: NaiveDate, time: NaiveTime) -> DateTime<Utc> {
+    convert::resolve_local(date.and_time(time), tz).instant
+}
+
+// The city's current calendar day from midnight to midnight; 23 or 25 hours long
+// on DST change days
+pub fn local_day(now: DateTime<Utc>, tz: Tz) -> (DateTime<Utc>, DateTime<Utc>) {
+    let today = now.with_timezone(&tz).date_naive();
+    let tomorrow = today.succ_opt().unwrap_or(today);
+    (
+        resolve(tz, today, NaiveTime::MIN),
+        resolve(tz, tomorrow, NaiveTime::MIN),
+    )
+}
+
+// Share of the local day gone, 0.0 at midnight
+pub fn day_fraction(now: DateTime<Utc>, tz: Tz) -> f64 {
+    let (start, end) = local_day(now, tz);
+    let length = (end - start).num_seconds().max(1) as f64;
+    ((now - start).num_seconds() as f64 / length).clamp(0.0, 1.0)
+}
+
+// Real time until the wall clock next reads `time` in the city
+pub fn until_local_time(now: DateTime<Utc>, tz: Tz, time: NaiveTime) -> Duration {
+    let today = now.with_timezone(&tz).date_naive();
+    today
+        .iter_days()
+        .take(3)
+        .map(|date| resolve(tz, date, time))
+        .find(|at| *at > now)
+        .map_or(Duration::zero(), |at| at - now)
+}
+
+// Like "63% of the day gone · 5h 10m until their morning"
+pub fn stats_line(now: DateTime<Utc>, tz: Tz, morning: NaiveTime, is_home: bool) -> String {
+    let percent = (day_fraction(now, tz) * 100.0).floor();
+    let until = until_local_time(now, tz, morning);
+    // Round up, so the last minute reads "1m" rather than "0m"
+    let minutes = (until.num_seconds() + 59) / 60;
+    format!(
+        "{}% of the day gone · {} until {} morning",
+        percent,
+        format::remaining(minutes),
+        if is_home { "your" } else { "their" }
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn utc(month: u32, day: u32, hour: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, month, day, hour, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn spring_forward_day_is_23_hours() {
+        // Berlin, Sunday Mar 29 2026, at noon CEST
+        let now = utc(3, 29, 10);
+        let (start, end) = local_day(now, chrono_tz::Europe::Berlin);
+        assert_eq!((start, end), (utc(3, 28, 23), utc(3, 29, 22)));
+        assert_eq!((end - start).num_hours(), 23);
+        // 11 of the 23 hours are gone
+        assert!((day_fraction(now, chrono_tz::Europe::Berlin) - 11.0 / 23.0).abs() < 1e-9);
+        assert_eq!(
+            stats_line(
+                now,
+                chrono_tz::Europe::Berlin,
+                NaiveTime::from_hms_opt(9, 0, 0).unwrap(),
+                false
+            ),
+            "47% of the day gone · 21h 0m until their morning"
+        );
+    }
+
+    #[test]
+    fn fall_back_day_is_25_hours() {
+        let now = utc(10, 25, 10);
+        let (start, end) = local_day(now, chrono_tz::Europe::Berlin);
+        assert_eq!((end - start).num_hours(), 25);
+        assert!((day_fraction(now, chrono_tz::Europe::Berlin) - 12.0 / 25.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn a_day_without_a_midnight_starts_at_the_first_valid_time() {
+        // Santiago skips from 00:00 to 01:00 on Sep 6 2026
+        let (start, end) = local_day(utc(9, 6, 12), chrono_tz::America::Santiago);
+        assert_eq!(start, utc(9, 6, 4));
+        assert_eq!((end - start).num_hours(), 23);
+    }
+
+    #[test]
+    fn until_local_time_counts_real_hours() {
+        // 10:00 UTC on Sat Mar 28 is 11:00 CET; 09:00 the next morning is CEST
+        let until = until_local_time(
+            utc(3, 28, 10),
+            chrono_tz::Europe::Berlin,
+            NaiveTime::from_hms_opt(9, 0, 0).unwrap(),
+        );
+        assert_eq!(until, Duration::hours(21));
+    }
+}
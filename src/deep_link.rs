@@ -0,0 +1,278 @@
+use crate::{convert, zone};
+use chrono::{DateTime, Utc};
+use std::fmt;
+use url::Url;
+
+pub const SCHEME: &str = "time2rust";
+
+// Actions reachable from links like time2rust://add?tz=Asia/Singapore&name=SG
+#[derive(Debug, Clone, PartialEq)]
+pub enum DeepLink {
+    AddCity { name: String, timezone: String },
+    Convert { at: DateTime<Utc> },
+    Focus { card: String }, // a card id, or failing that a name
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DeepLinkError {
+    Malformed(String),
+    WrongScheme(String),
+    UnknownAction(String),
+    MissingParameter(&'static str),
+    Invalid(String),
+}
+
+impl fmt::Display for DeepLinkError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DeepLinkError::Malformed(err) => write!(f, "malformed link: {}", err),
+            DeepLinkError::WrongScheme(scheme) => {
+                write!(f, "expected a {}:// link, got {}://", SCHEME, scheme)
+            }
+            DeepLinkError::UnknownAction(action) => {
+                write!(
+                    f,
+                    "unknown action \"{}\" (expected add, convert or focus)",
+                    action
+                )
+            }
+            DeepLinkError::MissingParameter(name) => write!(f, "missing \"{}\" parameter", name),
+            DeepLinkError::Invalid(reason) => write!(f, "{}", reason),
+        }
+    }
+}
+
+pub fn parse_deep_link(input: &str) -> Result<DeepLink, DeepLinkError> {
+    let url = Url::parse(input.trim()).map_err(|err| DeepLinkError::Malformed(err.to_string()))?;
+    if url.scheme() != SCHEME {
+        return Err(DeepLinkError::WrongScheme(url.scheme().to_string()));
+    }
+    let param = |key: &str| {
+        url.query_pairs()
+            .find(|(name, _)| name == key)
+            .map(|(_, value)| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+    };
+
+    match url.host_str().unwrap_or_default() {
+        "add" => {
+            let timezone = param("tz").ok_or(DeepLinkError::MissingParameter("tz"))?;
+            let tz = zone::resolve_zone(&timezone)
+                .map_err(|err| DeepLinkError::Invalid(err.to_string()))?;
+            let name = param("name").unwrap_or_else(|| default_city_name(tz.name()));
+            Ok(DeepLink::AddCity {
+                name,
+                timezone: tz.name().to_string(),
+            })
+        }
+        "convert" => {
+            let at = param("at").ok_or(DeepLinkError::MissingParameter("at"))?;
+            // Links are shared across zones, so times without an offset are read as UTC
+            let today = Utc::now().date_naive();
+            let input = convert::parse_time_input(&at, today)
+                .map_err(|err| DeepLinkError::Invalid(err.to_string()))?;
+            let resolved = convert::resolve_input(input, chrono_tz::UTC);
+            Ok(DeepLink::Convert {
+                at: resolved.instant,
+            })
+        }
+        "focus" => Ok(DeepLink::Focus {
+            card: param("card").ok_or(DeepLinkError::MissingParameter("card"))?,
+        }),
+        action => Err(DeepLinkError::UnknownAction(action.to_string())),
+    }
+}
+
+// The link that adds a card for `timezone`; the name is left out when it's the one
+// the link would pick anyway
+pub fn add_link(name: &str, timezone: &str) -> String {
+    let mut params = vec![("tz", timezone)];
+    if name != default_city_name(timezone) {
+        params.push(("name", name));
+    }
+    Url::parse_with_params(&format!("{}://add", SCHEME), &params)
+        .map(|url| url.to_string())
+        .unwrap_or_default()
+}
+
+// "America/Argentina/Buenos_Aires" -> "Buenos Aires"
+pub fn default_city_name(timezone_id: &str) -> String {
+    timezone_id
+        .rsplit('/')
+        .next()
+        .unwrap_or(timezone_id)
+        .replace('_', " ")
+}
+
+// Makes the OS hand time2rust:// links to this executable. macOS reads the
+// scheme from the bundle's Info.plist instead (see package.metadata.bundle).
+pub fn register_url_scheme() -> Result<(), String> {
+    #[cfg(target_os = "linux")]
+    return register_linux();
+    #[cfg(target_os = "windows")]
+    return register_windows();
+    #[cfg(not(any(target_os = "linux", target_os = "windows")))]
+    Ok(())
+}
+
+#[cfg(target_os = "linux")]
+fn register_linux() -> Result<(), String> {
+    use std::{fs, process::Command};
+
+    let exe = std::env::current_exe().map_err(|err| err.to_string())?;
+    let applications = dirs::data_dir()
+        .ok_or("no data directory available")?
+        .join("applications");
+    let desktop_file = applications.join("time2rust-url-handler.desktop");
+    let contents = format!(
+        "[Desktop Entry]\nType=Application\nName=time2rust\nExec=\"{}\" --open-url %u\nNoDisplay=true\nMimeType=x-scheme-handler/{};\n",
+        exe.display(),
+        SCHEME
+    );
+    if fs::read_to_string(&desktop_file).ok().as_deref() == Some(contents.as_str()) {
+        return Ok(());
+    }
+
+    fs::create_dir_all(&applications).map_err(|err| err.to_string())?;
+    fs::write(&desktop_file, contents).map_err(|err| err.to_string())?;
+    Command::new("xdg-mime")
+        .args([
+            "default",
+            "time2rust-url-handler.desktop",
+            &format!("x-scheme-handler/{}", SCHEME),
+        ])
+        .status()
+        .map_err(|err| format!("couldn't run xdg-mime: {}", err))?;
+    Ok(())
+}
+
+#[cfg(target_os = "windows")]
+fn register_windows() -> Result<(), String> {
+    use std::process::Command;
+
+    let exe = std::env::current_exe().map_err(|err| err.to_string())?;
+    let key = format!(r"HKCU\Software\Classes\{}", SCHEME);
+    let command = format!("\"{}\" --open-url \"%1\"", exe.display());
+    let entries = [
+        vec![key.as_str(), "/ve", "/d", "URL:time2rust"],
+        vec![key.as_str(), "/v", "URL Protocol", "/d", ""],
+    ];
+    let command_key = format!(r"{}\shell\open\command", key);
+    let command_entry = vec![command_key.as_str(), "/ve", "/d", command.as_str()];
+    for entry in entries.iter().chain([&command_entry]) {
+        let status = Command::new("reg")
+            .arg("add")
+            .args(entry)
+            .arg("/f")
+            .status()
+            .map_err(|err| format!("couldn't run reg: {}", err))?;
+        if !status.success() {
+            return Err(format!("reg add {} failed", entry[0]));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_links_it_cant_act_on() {
+        let error = |link: &str| parse_deep_link(link).unwrap_err();
+
+        assert!(matches!(error("not a link"), DeepLinkError::Malformed(_)));
+        assert_eq!(
+            error("https://add?tz=Asia/Tokyo"),
+            DeepLinkError::WrongScheme("https".to_string())
+        );
+        assert_eq!(
+            error("time2rust://remove?card=a1"),
+            DeepLinkError::UnknownAction("remove".to_string())
+        );
+        assert_eq!(
+            error("time2rust://add?name=Tokyo"),
+            DeepLinkError::MissingParameter("tz")
+        );
+        assert_eq!(
+            error("time2rust://add?tz=%20&name=Tokyo"),
+            DeepLinkError::MissingParameter("tz")
+        );
+        assert!(matches!(
+            error("time2rust://add?tz=Mars/Base"),
+            DeepLinkError::Invalid(_)
+        ));
+        assert_eq!(
+            error("time2rust://convert"),
+            DeepLinkError::MissingParameter("at")
+        );
+        assert!(matches!(
+            error("time2rust://convert?at=25:99"),
+            DeepLinkError::Invalid(_)
+        ));
+    }
+
+    #[test]
+    fn bad_percent_encoding_never_names_a_zone() {
+        // A stray % is kept as it is, and bytes that aren't UTF-8 become U+FFFD
+        for link in [
+            "time2rust://add?tz=Asia%2FTok%ZZo",
+            "time2rust://add?tz=Asia%2F%E0%A4",
+            "time2rust://add?tz=Asia%2",
+        ] {
+            assert!(
+                matches!(parse_deep_link(link), Err(DeepLinkError::Invalid(_))),
+                "{}",
+                link
+            );
+        }
+        assert_eq!(
+            parse_deep_link("time2rust://add?tz=Asia%2FTokyo"),
+            Ok(DeepLink::AddCity {
+                name: "Tokyo".to_string(),
+                timezone: "Asia/Tokyo".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn add_links_round_trip() {
+        for (name, timezone) in [
+            ("Buenos Aires", "America/Argentina/Buenos_Aires"),
+            ("SG", "Asia/Singapore"),
+            ("Zürich & Co. #2", "Europe/Zurich"),
+            ("São Paulo, office", "America/Sao_Paulo"),
+        ] {
+            let link = add_link(name, timezone);
+            assert_eq!(
+                parse_deep_link(&link),
+                Ok(DeepLink::AddCity {
+                    name: name.to_string(),
+                    timezone: timezone.to_string(),
+                }),
+                "{}",
+                link
+            );
+        }
+        assert_eq!(
+            add_link("Buenos Aires", "America/Argentina/Buenos_Aires"),
+            "time2rust://add?tz=America%2FArgentina%2FBuenos_Aires"
+        );
+    }
+
+    #[test]
+    fn focus_and_convert_links() {
+        assert_eq!(
+            parse_deep_link(" time2rust://focus?card=a1b2 "),
+            Ok(DeepLink::Focus {
+                card: "a1b2".to_string()
+            })
+        );
+        assert_eq!(
+            parse_deep_link("time2rust://convert?at=2026-03-20T15:00:00%2B01:00"),
+            Ok(DeepLink::Convert {
+                at: "2026-03-20T14:00:00Z".parse().unwrap()
+            })
+        );
+    }
+}
@@ -0,0 +1,125 @@
+use crate::window_state::{MIN_HEIGHT, MIN_WIDTH};
+
+// Below this window width the grid gives way to a one-column list of compact rows
+pub const COMPACT_WIDTH: f32 = 520.0;
+// Above this window width cards get bigger; narrower windows just wrap to fewer columns
+pub const LARGE_WIDTH: f32 = 1400.0;
+// Cards stop growing here, so a few cities in a wide window keep sensible proportions
+pub const MAX_CARD_WIDTH: f32 = 400.0;
+pub const GRID_GAP: f32 = 32.0;
+
+// How the window lays itself out at a given size
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Tier {
+    Tiny, // smaller than the window is meant to get, as a tiling WM may force: home only
+    Compact,
+    Regular,
+    Large,
+}
+
+pub fn tier(width: f32, height: f32) -> Tier {
+    if width < MIN_WIDTH || height < MIN_HEIGHT {
+        Tier::Tiny
+    } else if width < COMPACT_WIDTH {
+        Tier::Compact
+    } else if width >= LARGE_WIDTH {
+        Tier::Large
+    } else {
+        Tier::Regular
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Density {
+    Compact,
+    Grid { columns: usize, card_width: f32 },
+}
+
+// How `count` cards of at least `min_card` wide share `available` pixels. Rows are
+// filled evenly, so five cards that fit four across go 3+2 rather than 4+1
+pub fn density(available: f32, min_card: f32, count: usize) -> Density {
+    if available < COMPACT_WIDTH {
+        return Density::Compact;
+    }
+    let fits = (((available + GRID_GAP) / (min_card + GRID_GAP)).floor() as usize).max(1);
+    let fits = fits.min(count.max(1));
+    let rows = count.max(1).div_ceil(fits);
+    let columns = count.max(1).div_ceil(rows);
+    let card_width = (available - GRID_GAP * (columns - 1) as f32) / columns as f32;
+    Density::Grid {
+        columns,
+        card_width: card_width.floor().clamp(min_card, MAX_CARD_WIDTH),
+    }
+}
+
+// Width of a full row, which keeps further cards from squeezing into it
+pub fn row_width(columns: usize, card_width: f32) -> f32 {
+    columns as f32 * card_width + (columns.saturating_sub(1)) as f32 * GRID_GAP
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tiers_by_window_size() {
+        assert_eq!(tier(MIN_WIDTH - 1.0, 600.0), Tier::Tiny);
+        assert_eq!(tier(800.0, MIN_HEIGHT - 1.0), Tier::Tiny);
+        assert_eq!(tier(MIN_WIDTH, MIN_HEIGHT), Tier::Compact);
+        assert_eq!(tier(COMPACT_WIDTH - 0.5, 600.0), Tier::Compact);
+        assert_eq!(tier(COMPACT_WIDTH, 600.0), Tier::Regular);
+        assert_eq!(tier(LARGE_WIDTH - 1.0, 800.0), Tier::Regular);
+        assert_eq!(tier(LARGE_WIDTH, 800.0), Tier::Large);
+        // Too short is tiny however wide
+        assert_eq!(tier(LARGE_WIDTH, MIN_HEIGHT - 1.0), Tier::Tiny);
+    }
+
+    #[test]
+    fn narrow_space_is_a_list() {
+        assert_eq!(density(COMPACT_WIDTH - 1.0, 180.0, 5), Density::Compact);
+        assert_eq!(
+            density(COMPACT_WIDTH, 180.0, 5),
+            Density::Grid {
+                columns: 2,
+                card_width: 244.0
+            }
+        );
+    }
+
+    #[test]
+    fn rows_are_filled_evenly() {
+        // Four fit across, so five go 3+2 and twelve 4+4+4
+        assert_eq!(
+            density(1000.0, 180.0, 5),
+            Density::Grid {
+                columns: 3,
+                card_width: 312.0
+            }
+        );
+        assert_eq!(
+            density(1400.0, 260.0, 12),
+            Density::Grid {
+                columns: 4,
+                card_width: 326.0
+            }
+        );
+        assert_eq!(row_width(3, 312.0), 1000.0);
+    }
+
+    #[test]
+    fn cards_stop_growing_at_the_maximum() {
+        let single = Density::Grid {
+            columns: 1,
+            card_width: MAX_CARD_WIDTH,
+        };
+        assert_eq!(density(1000.0, 180.0, 1), single);
+        assert_eq!(density(1000.0, 180.0, 0), single);
+        assert_eq!(
+            density(2400.0, 260.0, 3),
+            Density::Grid {
+                columns: 3,
+                card_width: MAX_CARD_WIDTH
+            }
+        );
+    }
+}
@@ -0,0 +1,474 @@
+use crate::{
+    WorldTimeApp,
+    config::{CityConfig, Config},
+    settings_panel::hint,
+    ticker,
+    window_state::{self, WindowState},
+    zone::{self, OffsetChange},
+    zone_aliases,
+};
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
+use gpui::{App, Bounds, ClipboardItem, Entity, Pixels, Window, div, prelude::*, rgb};
+use gpui_component::{
+    Sizable, WindowExt as _,
+    button::{Button, ButtonVariants as _},
+    h_flex,
+    notification::Notification,
+    v_flex,
+};
+use std::{fs, io, path::Path, time::Duration};
+use time2rust::format;
+
+// One line of the report. Each check is a function of what it looks at, so a new
+// one is another function and another entry in the list.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Outcome {
+    Pass,
+    Warn, // works, but worth a look
+    Fail,
+}
+
+impl Outcome {
+    fn label(self) -> &'static str {
+        match self {
+            Outcome::Pass => "PASS",
+            Outcome::Warn => "WARN",
+            Outcome::Fail => "FAIL",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Check {
+    pub name: &'static str,
+    pub outcome: Outcome,
+    pub detail: String,
+}
+
+impl Check {
+    fn new(name: &'static str, outcome: Outcome, detail: impl Into<String>) -> Self {
+        Check {
+            name,
+            outcome,
+            detail: detail.into(),
+        }
+    }
+}
+
+pub fn zones_resolve(cities: &[CityConfig]) -> Check {
+    let failed: Vec<String> = cities
+        .iter()
+        .filter_map(|city| {
+            let err = zone::resolve_zone(&city.timezone).err()?;
+            Some(format!("{}: {}", city.name, err))
+        })
+        .collect();
+    if failed.is_empty() {
+        Check::new(
+            "Timezones",
+            Outcome::Pass,
+            format!("All {} resolve", cities.len()),
+        )
+    } else {
+        Check::new("Timezones", Outcome::Fail, failed.join("; "))
+    }
+}
+
+// Old spellings still work, but are worth replacing
+pub fn aliases(cities: &[CityConfig]) -> Check {
+    let old: Vec<String> = cities
+        .iter()
+        .filter_map(|city| {
+            let canonical = zone_aliases::canonical_id(&city.timezone)?;
+            Some(format!(
+                "{}: {} is now {}",
+                city.name, city.timezone, canonical
+            ))
+        })
+        .collect();
+    if old.is_empty() {
+        Check::new("Zone names", Outcome::Pass, "None use an old name")
+    } else {
+        Check::new("Zone names", Outcome::Warn, old.join("; "))
+    }
+}
+
+// Zones without summer time have no change to find, which is fine; a change that
+// isn't ahead or doesn't change anything is not
+pub fn transitions(cities: &[CityConfig], now: DateTime<Utc>) -> Check {
+    check_transitions(cities, now, zone::next_offset_change)
+}
+
+fn check_transitions(
+    cities: &[CityConfig],
+    now: DateTime<Utc>,
+    next_change: impl Fn(Tz, DateTime<Utc>) -> Option<OffsetChange>,
+) -> Check {
+    let mut bad = Vec::new();
+    let mut found = 0;
+    for city in cities {
+        let Ok(tz) = zone::resolve_zone(&city.timezone) else {
+            continue;
+        };
+        if let Some(change) = next_change(tz, now) {
+            if change.at <= now || change.from_minutes == change.to_minutes {
+                bad.push(format!("{}: odd change at {}", city.name, change.at));
+            } else {
+                found += 1;
+            }
+        }
+    }
+    if bad.is_empty() {
+        Check::new(
+            "Clock changes",
+            Outcome::Pass,
+            format!("Next change found for {} zones; the rest have none", found),
+        )
+    } else {
+        Check::new("Clock changes", Outcome::Fail, bad.join("; "))
+    }
+}
+
+// Read, parsed, and written back the same way twice, so nothing is lost or keeps
+// changing from one save to the next
+pub fn config_file(path: &Path) -> Check {
+    const NAME: &str = "Config file";
+    let contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => {
+            return Check::new(NAME, Outcome::Warn, "Not saved yet");
+        }
+        Err(err) => return Check::new(NAME, Outcome::Fail, err.to_string()),
+    };
+    let round_trip = |contents: &str| -> Result<String, String> {
+        let config: Config = serde_json::from_str(contents).map_err(|err| err.to_string())?;
+        serde_json::to_string_pretty(&config).map_err(|err| err.to_string())
+    };
+    match round_trip(&contents).and_then(|once| Ok((round_trip(&once)?, once))) {
+        Ok((twice, once)) if twice == once => Check::new(
+            NAME,
+            Outcome::Pass,
+            format!("{} parses and round-trips", path.display()),
+        ),
+        Ok(_) => Check::new(NAME, Outcome::Fail, "Saving it twice gives different files"),
+        Err(err) => Check::new(NAME, Outcome::Fail, format!("Doesn't parse: {}", err)),
+    }
+}
+
+// Uses the same placement as startup: on-screen when that would leave it as saved
+pub fn window_bounds<D: Copy + PartialEq>(
+    state: Option<&WindowState>,
+    displays: &[(D, Bounds<Pixels>)],
+) -> Check {
+    const NAME: &str = "Window position";
+    let Some(state) = state else {
+        return Check::new(NAME, Outcome::Pass, "None saved; opens centered");
+    };
+    let saved = state.bounds();
+    match window_state::place_on_displays(saved, None, displays) {
+        Some((_, placed)) if placed == saved => {
+            Check::new(NAME, Outcome::Pass, "The saved bounds are on a display")
+        }
+        Some(_) => Check::new(
+            NAME,
+            Outcome::Fail,
+            "The saved bounds are off-screen; the next launch moves the window back",
+        ),
+        None => Check::new(NAME, Outcome::Warn, "No displays reported"),
+    }
+}
+
+pub fn timer(last_tick: DateTime<Utc>, now: DateTime<Utc>, interval: Duration) -> Check {
+    let ago = (now - last_tick).num_seconds();
+    if ticker::is_stale(last_tick, now, interval) {
+        Check::new(
+            "Timer",
+            Outcome::Fail,
+            format!(
+                "Last tick {}s ago, every {}s expected",
+                ago,
+                interval.as_secs()
+            ),
+        )
+    } else {
+        Check::new("Timer", Outcome::Pass, format!("Last tick {}s ago", ago))
+    }
+}
+
+// A block to paste into a bug report
+pub fn report(checks: &[Check], now: DateTime<Utc>) -> String {
+    let mut lines = vec![format!(
+        "time2rust {} diagnostics, {} UTC, {}",
+        env!("CARGO_PKG_VERSION"),
+        format::date_time(now.naive_utc()),
+        std::env::consts::OS
+    )];
+    lines.extend(checks.iter().map(|check| {
+        format!(
+            "[{}] {}: {}",
+            check.outcome.label(),
+            check.name,
+            check.detail
+        )
+    }));
+    lines.join("\n")
+}
+
+pub fn open_diagnostics(app: Entity<WorldTimeApp>, window: &mut Window, cx: &mut App) {
+    let now = Utc::now();
+    let checks = app.read(cx).diagnostics(now, cx);
+    let report = report(&checks, now);
+    let failed = checks
+        .iter()
+        .filter(|check| check.outcome == Outcome::Fail)
+        .count();
+    window.open_dialog(cx, move |dialog, _window, _cx| {
+        let report = report.clone();
+        dialog.title("Diagnostics").child(
+            v_flex()
+                .gap_2()
+                .child(
+                    div()
+                        .child(match failed {
+                            0 => "Everything checked out".to_string(),
+                            1 => "1 check failed".to_string(),
+                            failed => format!("{} checks failed", failed),
+                        })
+                        .text_sm(),
+                )
+                .children(checks.iter().map(|check| {
+                    let color = match check.outcome {
+                        Outcome::Pass => rgb(0x16a34a),
+                        Outcome::Warn => rgb(0xb45309),
+                        Outcome::Fail => rgb(0xef4444),
+                    };
+                    h_flex()
+                        .gap_2()
+                        .items_start()
+                        .child(
+                            div()
+                                .w_12()
+                                .flex_none()
+                                .child(check.outcome.label())
+                                .text_xs()
+                                .text_color(color),
+                        )
+                        .child(
+                            v_flex()
+                                .child(div().child(check.name).text_sm())
+                                .child(hint(check.detail.clone())),
+                        )
+                }))
+                .child(
+                    Button::new("copy-report")
+                        .label("Copy report")
+                        .small()
+                        .ghost()
+                        .on_click(move |_, window, cx| {
+                            cx.write_to_clipboard(ClipboardItem::new_string(report.clone()));
+                            window
+                                .push_notification(Notification::success("Copied the report"), cx);
+                        }),
+                ),
+        )
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::{point, px, size};
+
+    fn now() -> DateTime<Utc> {
+        "2026-03-20T12:00:00Z".parse().unwrap()
+    }
+
+    fn outcome_and_detail(check: Check) -> (Outcome, String) {
+        (check.outcome, check.detail)
+    }
+
+    // A fresh directory per test, removed again when it ends
+    struct TempDir(std::path::PathBuf);
+
+    impl TempDir {
+        fn new(name: &str) -> Self {
+            let dir = std::env::temp_dir().join(format!(
+                "time2rust-diagnostics-{}-{}",
+                std::process::id(),
+                name
+            ));
+            let _ = fs::remove_dir_all(&dir);
+            fs::create_dir_all(&dir).unwrap();
+            TempDir(dir)
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    #[test]
+    fn zones_resolve_names_the_ones_that_dont() {
+        let good = [
+            CityConfig::new("Austin", "America/Chicago", true),
+            CityConfig::new("Tokyo", "asia/tokyo", false),
+        ];
+        assert_eq!(
+            outcome_and_detail(zones_resolve(&good)),
+            (Outcome::Pass, "All 2 resolve".to_string())
+        );
+        let bad = [good[0].clone(), CityConfig::new("Base", "Mars/Base", false)];
+        assert_eq!(
+            outcome_and_detail(zones_resolve(&bad)),
+            (
+                Outcome::Fail,
+                "Base: unknown timezone \"Mars/Base\"".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn aliases_warn_about_old_names() {
+        let current = [CityConfig::new("Kolkata", "Asia/Kolkata", true)];
+        assert_eq!(aliases(&current).outcome, Outcome::Pass);
+        let old = [CityConfig::new("Kyiv", "Europe/Kiev", true)];
+        assert_eq!(
+            outcome_and_detail(aliases(&old)),
+            (
+                Outcome::Warn,
+                "Kyiv: Europe/Kiev is now Europe/Kyiv".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn transitions_must_lie_ahead_and_change_something() {
+        let cities = [
+            CityConfig::new("Berlin", "Europe/Berlin", true),
+            CityConfig::new("Tokyo", "Asia/Tokyo", false),
+            CityConfig::new("Base", "Mars/Base", false),
+        ];
+        assert_eq!(
+            outcome_and_detail(transitions(&cities, now())),
+            (
+                Outcome::Pass,
+                "Next change found for 1 zones; the rest have none".to_string()
+            )
+        );
+
+        // A zone database that reports a change already past
+        let past = |_, now: DateTime<Utc>| {
+            Some(OffsetChange {
+                at: now - chrono::Duration::days(1),
+                from_minutes: 60,
+                to_minutes: 120,
+            })
+        };
+        let check = check_transitions(&cities[..1], now(), past);
+        assert_eq!(check.outcome, Outcome::Fail);
+        assert!(check.detail.starts_with("Berlin: odd change at 2026-03-19"));
+    }
+
+    #[test]
+    fn config_file_parses_and_round_trips() {
+        let dir = TempDir::new("config");
+        let path = dir.0.join("config.json");
+        assert_eq!(
+            outcome_and_detail(config_file(&path)),
+            (Outcome::Warn, "Not saved yet".to_string())
+        );
+
+        fs::write(&path, serde_json::to_string(&Config::default()).unwrap()).unwrap();
+        assert_eq!(config_file(&path).outcome, Outcome::Pass);
+
+        fs::write(&path, "{ not json").unwrap();
+        let check = config_file(&path);
+        assert_eq!(check.outcome, Outcome::Fail);
+        assert!(check.detail.starts_with("Doesn't parse: "));
+
+        // A directory where the file should be can't be read at all
+        assert_eq!(config_file(&dir.0).outcome, Outcome::Fail);
+    }
+
+    #[test]
+    fn window_bounds_must_be_on_a_display() {
+        let display = [(
+            1,
+            Bounds::new(point(px(0.0), px(0.0)), size(px(1920.0), px(1080.0))),
+        )];
+        let state = |x| WindowState {
+            x,
+            y: 100.0,
+            width: 800.0,
+            height: 600.0,
+            display: None,
+            maximized: false,
+        };
+        assert_eq!(window_bounds(None, &display).outcome, Outcome::Pass);
+        assert_eq!(
+            window_bounds(Some(&state(100.0)), &display).outcome,
+            Outcome::Pass
+        );
+        assert_eq!(
+            window_bounds(Some(&state(5000.0)), &display).outcome,
+            Outcome::Fail
+        );
+        assert_eq!(
+            window_bounds::<u32>(Some(&state(100.0)), &[]).outcome,
+            Outcome::Warn
+        );
+    }
+
+    #[test]
+    fn the_timer_fails_once_a_tick_is_missed() {
+        let interval = Duration::from_secs(1);
+        let check = timer(now() - chrono::Duration::seconds(1), now(), interval);
+        assert_eq!(
+            outcome_and_detail(check),
+            (Outcome::Pass, "Last tick 1s ago".to_string())
+        );
+        let check = timer(now() - chrono::Duration::seconds(60), now(), interval);
+        assert_eq!(
+            outcome_and_detail(check),
+            (
+                Outcome::Fail,
+                "Last tick 60s ago, every 1s expected".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn the_report_has_a_line_per_check() {
+        let checks = [
+            Check::new("Timer", Outcome::Pass, "Last tick 1s ago"),
+            Check::new(
+                "Zone names",
+                Outcome::Warn,
+                "Kyiv: Europe/Kiev is now Europe/Kyiv",
+            ),
+            Check::new("Timezones", Outcome::Fail, "Base: unknown timezone"),
+        ];
+        let report = report(&checks, now());
+        let lines: Vec<&str> = report.lines().collect();
+        assert_eq!(
+            lines[0],
+            format!(
+                "time2rust {} diagnostics, {} UTC, {}",
+                env!("CARGO_PKG_VERSION"),
+                format::date_time(now().naive_utc()),
+                std::env::consts::OS
+            )
+        );
+        assert_eq!(
+            lines[1..],
+            [
+                "[PASS] Timer: Last tick 1s ago",
+                "[WARN] Zone names: Kyiv: Europe/Kiev is now Europe/Kyiv",
+                "[FAIL] Timezones: Base: unknown timezone",
+            ]
+        );
+    }
+}
@@ -0,0 +1,66 @@
+use crate::i18n::{self, Language};
+use chrono::{Duration, NaiveTime, Timelike};
+use serde::{Deserialize, Serialize};
+use time2rust::format;
+
+// How a card writes its difference from home. Every style reads the card's one
+// diff_minutes, so switching changes only the wording
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DiffStyle {
+    #[default]
+    Delta, // "Δ 7 hours"
+    AheadBehind, // "7h ahead"
+    Offset,      // "+07:00"
+    Mirror,      // "their 09:00 = my 02:00"
+}
+
+impl DiffStyle {
+    pub const ALL: [DiffStyle; 4] = [
+        DiffStyle::Delta,
+        DiffStyle::AheadBehind,
+        DiffStyle::Offset,
+        DiffStyle::Mirror,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            DiffStyle::Delta => "Δ hours",
+            DiffStyle::AheadBehind => "Ahead / behind",
+            DiffStyle::Offset => "Offset",
+            DiffStyle::Mirror => "Their time = mine",
+        }
+    }
+
+    // The one after this, back to the first after the last
+    pub fn next(self) -> DiffStyle {
+        let index = DiffStyle::ALL.iter().position(|&style| style == self);
+        DiffStyle::ALL[index.map_or(0, |index| (index + 1) % DiffStyle::ALL.len())]
+    }
+
+    // `local` is the card's own time of day. Only the hours wording is translated
+    // so far; the others are mostly numbers.
+    pub fn describe(self, diff_minutes: i32, local: NaiveTime, language: Language) -> String {
+        match self {
+            DiffStyle::Delta => i18n::diff_badge(language, diff_minutes),
+            DiffStyle::AheadBehind => format::ahead_behind(diff_minutes),
+            DiffStyle::Offset => format::offset_diff(diff_minutes),
+            DiffStyle::Mirror => {
+                let mine = local - Duration::minutes(diff_minutes as i64);
+                // Differences reach past a day, UTC−11 against UTC+14, so "my" day can
+                // be two off from theirs
+                let days = (local.num_seconds_from_midnight() as i64 / 60 - diff_minutes as i64)
+                    .div_euclid(1440);
+                let label = format!(
+                    "their {} = my {}",
+                    format::clock_time(local, false),
+                    format::clock_time(mine, false)
+                );
+                match days {
+                    0 => label,
+                    days => format!("{} {}", label, format::day_offset(days)),
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,263 @@
+use crate::{clock_format::ClockFormat, config::Settings, diff_style::DiffStyle, i18n::Language};
+use serde::{Deserialize, Serialize};
+
+// How one card is drawn, settled in one place from the settings and the city's
+// own choices; the card's helpers read only this
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct DisplayOptions {
+    pub show_seconds: bool,
+    pub clock_format: ClockFormat, // from the settings only
+    pub language: Language,        // likewise
+    pub diff_style: DiffStyle,
+    pub show_relative_phrase: bool,
+    pub show_day_stats: bool,
+    pub show_workday: bool,
+    pub show_timeline: bool,
+    pub show_mirror: bool,
+    pub standard_diff: bool, // the card's own choice only
+}
+
+// A city's own choices; None follows the settings. Flattened into the city's
+// config, where `diff_style` has always been.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct DisplayOverrides {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub show_seconds: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub diff_style: Option<DiffStyle>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub show_relative_phrase: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub show_day_stats: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub show_workday: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub show_timeline: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub show_mirror: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub standard_diff: Option<bool>,
+}
+
+// The city's own choice wins; otherwise the settings decide
+pub fn resolve(settings: &Settings, overrides: &DisplayOverrides) -> DisplayOptions {
+    DisplayOptions {
+        show_seconds: overrides.show_seconds.unwrap_or(settings.show_seconds),
+        clock_format: settings.clock_format,
+        language: settings.language,
+        diff_style: overrides.diff_style.unwrap_or(settings.diff_style),
+        show_relative_phrase: overrides
+            .show_relative_phrase
+            .unwrap_or(settings.show_relative_phrase),
+        show_day_stats: overrides.show_day_stats.unwrap_or(settings.show_day_stats),
+        show_workday: overrides.show_workday.unwrap_or(settings.show_workday),
+        show_timeline: overrides.show_timeline.unwrap_or(settings.show_timeline),
+        show_mirror: overrides.show_mirror.unwrap_or(settings.mirror.enabled),
+        standard_diff: overrides.standard_diff.unwrap_or(false),
+    }
+}
+
+// One row of the inspector in the card detail
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DisplayOption {
+    ShowSeconds,
+    DiffStyle,
+    RelativePhrase,
+    DayStats,
+    Workday,
+    Timeline,
+    Mirror,
+    StandardDiff,
+}
+
+impl DisplayOption {
+    pub const ALL: [DisplayOption; 8] = [
+        DisplayOption::ShowSeconds,
+        DisplayOption::DiffStyle,
+        DisplayOption::RelativePhrase,
+        DisplayOption::DayStats,
+        DisplayOption::Workday,
+        DisplayOption::Timeline,
+        DisplayOption::Mirror,
+        DisplayOption::StandardDiff,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            DisplayOption::ShowSeconds => "Seconds",
+            DisplayOption::DiffStyle => "Difference wording",
+            DisplayOption::RelativePhrase => "Time of day phrase",
+            DisplayOption::DayStats => "Share of the day gone",
+            DisplayOption::Workday => "Workday progress",
+            DisplayOption::Timeline => "Timeline",
+            DisplayOption::Mirror => "Mirror time",
+            DisplayOption::StandardDiff => "Difference from standard time",
+        }
+    }
+
+    // The setting a bool option reads; None for the difference wording
+    fn flag(self, overrides: &mut DisplayOverrides) -> Option<&mut Option<bool>> {
+        match self {
+            DisplayOption::ShowSeconds => Some(&mut overrides.show_seconds),
+            DisplayOption::DiffStyle => None,
+            DisplayOption::RelativePhrase => Some(&mut overrides.show_relative_phrase),
+            DisplayOption::DayStats => Some(&mut overrides.show_day_stats),
+            DisplayOption::Workday => Some(&mut overrides.show_workday),
+            DisplayOption::Timeline => Some(&mut overrides.show_timeline),
+            DisplayOption::Mirror => Some(&mut overrides.show_mirror),
+            DisplayOption::StandardDiff => Some(&mut overrides.standard_diff),
+        }
+    }
+
+    // "on", "off", or the wording's name
+    pub fn value(self, options: &DisplayOptions) -> &'static str {
+        let on = match self {
+            DisplayOption::ShowSeconds => options.show_seconds,
+            DisplayOption::DiffStyle => return options.diff_style.label(),
+            DisplayOption::RelativePhrase => options.show_relative_phrase,
+            DisplayOption::DayStats => options.show_day_stats,
+            DisplayOption::Workday => options.show_workday,
+            DisplayOption::Timeline => options.show_timeline,
+            DisplayOption::Mirror => options.show_mirror,
+            DisplayOption::StandardDiff => options.standard_diff,
+        };
+        if on { "on" } else { "off" }
+    }
+
+    pub fn is_overridden(self, overrides: &DisplayOverrides) -> bool {
+        let mut overrides = *overrides;
+        match self.flag(&mut overrides) {
+            Some(flag) => flag.is_some(),
+            None => overrides.diff_style.is_some(),
+        }
+    }
+
+    // The card's own choice flipped from what it shows now: off for on, the next
+    // wording for the difference
+    pub fn changed(
+        self,
+        overrides: &DisplayOverrides,
+        options: &DisplayOptions,
+    ) -> DisplayOverrides {
+        let mut changed = *overrides;
+        match self.flag(&mut changed) {
+            Some(flag) => *flag = Some(self.value(options) == "off"),
+            None => changed.diff_style = Some(options.diff_style.next()),
+        }
+        changed
+    }
+
+    pub fn cleared(self, overrides: &DisplayOverrides) -> DisplayOverrides {
+        let mut cleared = *overrides;
+        match self.flag(&mut cleared) {
+            Some(flag) => *flag = None,
+            None => cleared.diff_style = None,
+        }
+        cleared
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Every setting turned away from its default
+    fn settings() -> Settings {
+        let mut settings = Settings {
+            show_seconds: true,
+            clock_format: ClockFormat::Military,
+            language: Language::German,
+            diff_style: DiffStyle::Offset,
+            show_relative_phrase: true,
+            show_day_stats: true,
+            show_workday: true,
+            show_timeline: true,
+            ..Settings::default()
+        };
+        settings.mirror.enabled = true;
+        settings
+    }
+
+    #[test]
+    fn without_overrides_the_settings_decide() {
+        let options = resolve(&settings(), &DisplayOverrides::default());
+        assert_eq!(
+            options,
+            DisplayOptions {
+                show_seconds: true,
+                clock_format: ClockFormat::Military,
+                language: Language::German,
+                diff_style: DiffStyle::Offset,
+                show_relative_phrase: true,
+                show_day_stats: true,
+                show_workday: true,
+                show_timeline: true,
+                show_mirror: true,
+                standard_diff: false,
+            }
+        );
+        let options = resolve(&Settings::default(), &DisplayOverrides::default());
+        assert_eq!(options, DisplayOptions::default());
+    }
+
+    #[test]
+    fn the_city_choice_beats_the_setting_either_way() {
+        let off = DisplayOverrides {
+            show_seconds: Some(false),
+            diff_style: Some(DiffStyle::AheadBehind),
+            show_relative_phrase: Some(false),
+            show_day_stats: Some(false),
+            show_workday: Some(false),
+            show_timeline: Some(false),
+            show_mirror: Some(false),
+            standard_diff: Some(true),
+        };
+        let options = resolve(&settings(), &off);
+        assert_eq!(
+            options,
+            DisplayOptions {
+                show_seconds: false,
+                clock_format: ClockFormat::Military,
+                language: Language::German,
+                diff_style: DiffStyle::AheadBehind,
+                show_relative_phrase: false,
+                show_day_stats: false,
+                show_workday: false,
+                show_timeline: false,
+                show_mirror: false,
+                standard_diff: true,
+            }
+        );
+
+        let on = DisplayOverrides {
+            show_seconds: Some(true),
+            show_mirror: Some(true),
+            ..DisplayOverrides::default()
+        };
+        let options = resolve(&Settings::default(), &on);
+        assert!(options.show_seconds && options.show_mirror);
+        assert!(!options.show_day_stats);
+    }
+
+    #[test]
+    fn changing_and_clearing_an_option_from_the_inspector() {
+        let settings = settings();
+        let none = DisplayOverrides::default();
+        let shown = resolve(&settings, &none);
+
+        let changed = DisplayOption::DayStats.changed(&none, &shown);
+        assert!(DisplayOption::DayStats.is_overridden(&changed));
+        assert!(!DisplayOption::Workday.is_overridden(&changed));
+        assert_eq!(
+            DisplayOption::DayStats.value(&resolve(&settings, &changed)),
+            "off"
+        );
+
+        let changed = DisplayOption::DiffStyle.changed(&none, &shown);
+        assert_eq!(changed.diff_style, Some(DiffStyle::Mirror));
+
+        let cleared = DisplayOption::DiffStyle.cleared(&changed);
+        assert_eq!(cleared, none);
+        assert_eq!(resolve(&settings, &cleared), shown);
+    }
+}
@@ -0,0 +1,860 @@
+use crate::{
+    clock_format::ClockFormat,
+    config::{CityConfig, SlackSettings},
+    convert, offset_override, people,
+    planner::{SLOT_MINUTES, WorkingHours},
+    week::{self, Weekend},
+    zone,
+};
+use chrono::{DateTime, Days, Duration, FixedOffset, NaiveDate, NaiveTime, SecondsFormat, Utc};
+use chrono_tz::Tz;
+use time2rust::format;
+
+// The instant a copy or export from the window is about: the live clock, or a time
+// picked in the planner, the hour table or a link. Exports never look at the clock
+// themselves, so a planned meeting can't quietly go out as "now".
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DisplayInstant {
+    Live(DateTime<Utc>),
+    Picked(DateTime<Utc>),
+}
+
+impl DisplayInstant {
+    // The time picked in the planner if there is one, else `now`
+    pub fn new(picked: Option<DateTime<Utc>>, now: DateTime<Utc>) -> Self {
+        match picked {
+            Some(instant) => DisplayInstant::Picked(instant),
+            None => DisplayInstant::Live(now),
+        }
+    }
+
+    pub fn instant(self) -> DateTime<Utc> {
+        match self {
+            DisplayInstant::Live(instant) | DisplayInstant::Picked(instant) => instant,
+        }
+    }
+
+    // For the confirmation, like "as of now" or "as of Thu Mar 20 09:05 at home"
+    pub fn describe(self, home_tz: Tz) -> String {
+        match self {
+            DisplayInstant::Live(_) => "as of now".to_string(),
+            DisplayInstant::Picked(instant) => format!(
+                "as of {} at home",
+                format::weekday_date_time(instant.with_timezone(&home_tz).naive_local())
+            ),
+        }
+    }
+}
+
+// A city's state at one instant; the common input for every text export
+#[derive(Debug, Clone)]
+pub struct CitySnapshot {
+    pub id: String,
+    pub name: String,
+    pub timezone_id: String,
+    pub local: DateTime<FixedOffset>,
+    pub utc_offset_minutes: i32,
+    pub diff_minutes: i32, // relative to the home city
+    pub is_dst: bool,
+    pub standard_shift_minutes: i32, // from the tz data, overrides aside
+    pub is_home: bool,
+    pub people: Vec<String>,
+}
+
+// Snapshots every city whose zone resolves; the rest are returned as error messages.
+// Manual offset overrides only count `with_overrides`.
+pub fn snapshot_cities(
+    cities: &[CityConfig],
+    now: DateTime<Utc>,
+    with_overrides: bool,
+) -> (Vec<CitySnapshot>, Vec<String>) {
+    let offset = |city: &CityConfig, tz: Tz| {
+        let shift = if with_overrides {
+            offset_override::active_minutes(city.offset_override.as_ref(), tz, now)
+        } else {
+            0
+        };
+        zone::utc_offset_minutes(tz, now) + shift
+    };
+    let home_offset = cities
+        .iter()
+        .find(|city| city.home)
+        .or(cities.first())
+        .and_then(|city| Some(offset(city, zone::resolve_zone(&city.timezone).ok()?)))
+        .unwrap_or(0);
+
+    let mut snapshots = Vec::new();
+    let mut errors = Vec::new();
+    for city in cities {
+        match zone::resolve_zone(&city.timezone) {
+            Ok(tz) => {
+                let utc_offset_minutes = offset(city, tz);
+                let fixed = FixedOffset::east_opt(utc_offset_minutes * 60)
+                    .unwrap_or(FixedOffset::east_opt(0).unwrap());
+                snapshots.push(CitySnapshot {
+                    id: city.id.clone(),
+                    name: city.name.clone(),
+                    timezone_id: city.timezone.clone(),
+                    local: now.with_timezone(&fixed),
+                    utc_offset_minutes,
+                    diff_minutes: utc_offset_minutes - home_offset,
+                    is_dst: zone::is_dst(tz, now),
+                    standard_shift_minutes: zone::standard_shift_minutes(tz, now),
+                    is_home: city.home,
+                    people: city.people.clone(),
+                });
+            }
+            Err(err) => errors.push(format!("{}: {}", city.name, err)),
+        }
+    }
+    (snapshots, errors)
+}
+
+// Quotes a CSV field only when it needs it (RFC 4180)
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+// `include_people` adds a column with each city's people, separated by "; ". The
+// card id comes last, so columns that were there before it keep their places.
+pub fn write_csv(snapshots: &[CitySnapshot], include_people: bool) -> String {
+    let mut csv =
+        String::from("name,timezone,local_time,utc_offset_minutes,diff_from_home_minutes,dst");
+    if include_people {
+        csv.push_str(",people");
+    }
+    csv.push_str(",id\n");
+    for city in snapshots {
+        let mut fields = vec![
+            csv_field(&city.name),
+            csv_field(&city.timezone_id),
+            city.local.to_rfc3339_opts(SecondsFormat::Secs, false),
+            city.utc_offset_minutes.to_string(),
+            city.diff_minutes.to_string(),
+            city.is_dst.to_string(),
+        ];
+        if include_people {
+            fields.push(csv_field(&city.people.join("; ")));
+        }
+        fields.push(csv_field(&city.id));
+        csv.push_str(&fields.join(","));
+        csv.push('\n');
+    }
+    csv
+}
+
+// The same fields as the CSV, for `--json` and the local HTTP endpoint
+pub fn write_json(snapshots: &[CitySnapshot], now: DateTime<Utc>, include_people: bool) -> String {
+    let cities: Vec<serde_json::Value> = snapshots
+        .iter()
+        .map(|city| {
+            let mut value = serde_json::json!({
+                "id": city.id,
+                "name": city.name,
+                "timezone": city.timezone_id,
+                "local_time": city.local.to_rfc3339_opts(SecondsFormat::Secs, false),
+                "utc_offset_minutes": city.utc_offset_minutes,
+                "diff_from_home_minutes": city.diff_minutes,
+                "dst": city.is_dst,
+                "offset_from_standard_minutes": city.standard_shift_minutes,
+                "home": city.is_home,
+            });
+            if include_people {
+                value["people"] = serde_json::json!(city.people);
+            }
+            value
+        })
+        .collect();
+    let json = serde_json::json!({
+        "generated_at": now.to_rfc3339_opts(SecondsFormat::Secs, true),
+        "cities": cities,
+    });
+    serde_json::to_string_pretty(&json).unwrap_or_default()
+}
+
+// Plain aligned table for terminal output
+pub fn format_table(snapshots: &[CitySnapshot], clock: ClockFormat) -> String {
+    let name_width = snapshots
+        .iter()
+        .map(|city| city.name.chars().count())
+        .max()
+        .unwrap_or(0)
+        .max(4);
+    let mut table = String::new();
+    for city in snapshots {
+        table.push_str(&format!(
+            "{:<width$}  {}  {:<9}  {:>+6}m  {}{}\n",
+            city.name,
+            weekday_clock(city, clock),
+            zone::format_utc_offset(city.utc_offset_minutes),
+            city.diff_minutes,
+            city.timezone_id,
+            if city.is_home { "  (home)" } else { "" },
+            width = name_width,
+        ));
+    }
+    table
+}
+
+// "Thu 14:45", or "Thu 1445A" in military time
+fn weekday_clock(city: &CitySnapshot, clock: ClockFormat) -> String {
+    format!(
+        "{} {}",
+        city.local.format("%a"),
+        clock.format(city.local.time(), city.utc_offset_minutes, false)
+    )
+}
+
+// A meeting repeated weekly at the same wall time at home, `count` times in all
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WeeklySeries {
+    pub home: Tz,
+    pub count: u32,
+}
+
+// A city whose local time for the series moves when its clocks change and home's
+// don't, or the other way around
+#[derive(Debug, Clone, PartialEq)]
+pub struct SeriesShift {
+    pub city: String,
+    pub from: NaiveDate, // the city's date of the first occurrence at the new time
+    pub was: NaiveTime,
+    pub now: NaiveTime,
+}
+
+// Each occurrence keeps the first one's wall time at home, like a calendar app
+// repeating an event in its organizer's zone
+pub fn weekly_occurrences(start: DateTime<Utc>, series: WeeklySeries) -> Vec<DateTime<Utc>> {
+    let first = start.with_timezone(&series.home).naive_local();
+    (0..series.count as u64)
+        .filter_map(|week| first.checked_add_days(Days::new(week * 7)))
+        .map(|local| convert::resolve_local(local, series.home).instant)
+        .collect()
+}
+
+// Every occurrence where a city's local time differs from the one before; a city
+// can move and move back within the series
+pub fn weekly_shifts(
+    cities: &[(String, Tz)],
+    start: DateTime<Utc>,
+    series: WeeklySeries,
+) -> Vec<SeriesShift> {
+    let occurrences = weekly_occurrences(start, series);
+    cities
+        .iter()
+        .flat_map(|(name, tz)| {
+            occurrences.windows(2).filter_map(move |pair| {
+                let was = pair[0].with_timezone(tz);
+                let now = pair[1].with_timezone(tz);
+                (was.time() != now.time()).then(|| SeriesShift {
+                    city: name.clone(),
+                    from: now.date_naive(),
+                    was: was.time(),
+                    now: now.time(),
+                })
+            })
+        })
+        .collect()
+}
+
+// "note: from Mar 30 this is 17:00 in Berlin, not 16:00"
+pub fn describe_series_shift(shift: &SeriesShift, twelve_hour: bool) -> String {
+    let time = |time| {
+        if twelve_hour {
+            format::clock_time_12h(time)
+        } else {
+            format::clock_time(time, false)
+        }
+    };
+    format!(
+        "note: from {} this is {} in {}, not {}",
+        format::month_day(shift.from),
+        time(shift.now),
+        shift.city,
+        time(shift.was)
+    )
+}
+
+// The warnings for a series over the snapshot's cities, home excluded
+pub fn series_notes(
+    snapshots: &[CitySnapshot],
+    start: DateTime<Utc>,
+    series: WeeklySeries,
+    twelve_hour: bool,
+) -> Vec<String> {
+    let cities: Vec<(String, Tz)> = snapshots
+        .iter()
+        .filter(|city| !city.is_home)
+        .filter_map(|city| {
+            Some((
+                city.name.clone(),
+                zone::resolve_zone(&city.timezone_id).ok()?,
+            ))
+        })
+        .collect();
+    weekly_shifts(&cities, start, series)
+        .iter()
+        .map(|shift| describe_series_shift(shift, twelve_hour))
+        .collect()
+}
+
+// "📅 Thu Mar 20 — 9:00 AM Austin (home) · 10:00 AM NYC", dated in the home city.
+// A weekly series reads "Thu Mar 20, weekly × 8" and adds a line per clock change.
+// With `include_people`, cities read "NYC (Anna, Tom)".
+pub fn format_slack_message(
+    snapshots: &[CitySnapshot],
+    settings: &SlackSettings,
+    include_people: bool,
+    clock: ClockFormat,
+    series: Option<WeeklySeries>,
+) -> String {
+    let date = snapshots
+        .iter()
+        .find(|city| city.is_home)
+        .or(snapshots.first())
+        .map(|city| format::weekday_date(city.local.date_naive()))
+        .unwrap_or_default();
+    let times = snapshots
+        .iter()
+        .map(|city| {
+            let mut name = if include_people {
+                people::with_people(&city.name, &city.people)
+            } else {
+                city.name.clone()
+            };
+            if city.is_home {
+                name.push_str(" (home)");
+            }
+            // Military time is a deliberate choice, so it beats the 12-hour default
+            let time = match clock {
+                ClockFormat::TwentyFourHour if settings.twelve_hour => {
+                    format::clock_time_12h(city.local.time())
+                }
+                clock => clock.format(city.local.time(), city.utc_offset_minutes, false),
+            };
+            format!("{} {}", time, name)
+        })
+        .collect::<Vec<_>>()
+        .join(&settings.separator);
+    let emoji = if settings.emoji { "📅 " } else { "" };
+    let when = match series {
+        Some(series) => format!("{}, weekly × {}", date, series.count),
+        None => date,
+    };
+    let mut message = format!("{}{} — {}", emoji, when, times);
+    if let (Some(series), Some(first)) = (series, snapshots.first()) {
+        for note in series_notes(
+            snapshots,
+            first.local.to_utc(),
+            series,
+            settings.twelve_hour,
+        ) {
+            message.push('\n');
+            message.push_str(&note);
+        }
+    }
+    message
+}
+
+// Escapes TEXT values per RFC 5545
+fn ics_text(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+// A single-event calendar file; the description lists the slot in every city
+pub fn write_ics(
+    snapshots: &[CitySnapshot],
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    summary: &str,
+    series: Option<WeeklySeries>,
+    clock: ClockFormat,
+    now: DateTime<Utc>,
+) -> String {
+    let stamp = format::ics_stamp;
+    let mut description = snapshots
+        .iter()
+        .map(|city| format!("{} {}", city.name, weekday_clock(city, clock)))
+        .collect::<Vec<_>>()
+        .join("\n");
+    // A series repeats in home's zone, so it keeps its wall time there across DST
+    let times = match series {
+        Some(series) => {
+            for note in series_notes(snapshots, start, series, false) {
+                description.push('\n');
+                description.push_str(&note);
+            }
+            let local = |instant: DateTime<Utc>| {
+                let local = instant.with_timezone(&series.home).naive_local();
+                format!(
+                    "TZID={}:{}",
+                    series.home.name(),
+                    local.format("%Y%m%dT%H%M%S")
+                )
+            };
+            vec![
+                format!("DTSTART;{}", local(start)),
+                format!("DTEND;{}", local(end)),
+                format!("RRULE:FREQ=WEEKLY;COUNT={}", series.count),
+            ]
+        }
+        None => vec![
+            format!("DTSTART:{}", stamp(start)),
+            format!("DTEND:{}", stamp(end)),
+        ],
+    };
+    [
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//time2rust//EN".to_string(),
+        "BEGIN:VEVENT".to_string(),
+        format!("UID:{}-{}@time2rust", stamp(start), now.timestamp_millis()),
+        format!("DTSTAMP:{}", stamp(now)),
+    ]
+    .into_iter()
+    .chain(times)
+    .chain([
+        format!("SUMMARY:{}", ics_text(summary)),
+        format!("DESCRIPTION:{}", ics_text(&description)),
+        "END:VEVENT".to_string(),
+        "END:VCALENDAR".to_string(),
+    ])
+    .map(|line| fold_ics_line(&line))
+    .collect()
+}
+
+// Content lines longer than 75 octets continue on lines starting with a space
+fn fold_ics_line(line: &str) -> String {
+    let mut folded = String::new();
+    let mut length = 0;
+    for c in line.chars() {
+        if length + c.len_utf8() > 75 {
+            folded.push_str("\r\n ");
+            length = 1;
+        }
+        folded.push(c);
+        length += c.len_utf8();
+    }
+    folded.push_str("\r\n");
+    folded
+}
+
+// Seven home days, with each city's working hours drawn as one character per home hour
+#[derive(Debug, Clone, PartialEq)]
+pub struct WeekGrid {
+    pub days: Vec<NaiveDate>,
+    pub rows: Vec<(String, Vec<String>)>, // city name, one strip per day
+}
+
+// "#" working the whole home hour, "+" part of it, "." not at all, days off included. Every hour is
+// checked in the city's own zone, so a DST change mid-week shows up where it happens.
+pub fn week_grid(
+    cities: &[(String, Tz, WorkingHours, Weekend)],
+    home_tz: Tz,
+    first_day: NaiveDate,
+) -> WeekGrid {
+    let days: Vec<NaiveDate> = (0..7)
+        .filter_map(|day| first_day.checked_add_days(Days::new(day)))
+        .collect();
+    let rows = cities
+        .iter()
+        .map(|(name, tz, hours, weekend)| {
+            let strips = days
+                .iter()
+                .map(|day| {
+                    (0..24)
+                        .map(|hour| {
+                            let start = convert::resolve_local(
+                                day.and_hms_opt(hour, 0, 0).unwrap_or_default(),
+                                home_tz,
+                            )
+                            .instant;
+                            let working = (0..60 / SLOT_MINUTES)
+                                .filter(|slot| {
+                                    let instant = start + Duration::minutes(slot * SLOT_MINUTES);
+                                    week::working_at(
+                                        instant.with_timezone(tz).naive_local(),
+                                        hours,
+                                        *weekend,
+                                    )
+                                })
+                                .count() as i64;
+                            match working {
+                                0 => '.',
+                                n if n == 60 / SLOT_MINUTES => '#',
+                                _ => '+',
+                            }
+                        })
+                        .collect()
+                })
+                .collect();
+            (name.clone(), strips)
+        })
+        .collect();
+    WeekGrid { days, rows }
+}
+
+// Marks home hours 0, 6, 12 and 18 above the strips
+const HOUR_RULER: &str = "0     6     12    18    ";
+
+pub fn write_week_markdown(grid: &WeekGrid, home_name: &str) -> String {
+    let mut markdown = String::new();
+    let days: Vec<String> = grid
+        .days
+        .iter()
+        .map(|&day| format::weekday_date(day))
+        .collect();
+    markdown.push_str(&format!("| City | {} |\n", days.join(" | ")));
+    markdown.push_str(&format!("|---|{}\n", "---|".repeat(days.len())));
+    let ruler = vec![format!("`{}`", HOUR_RULER); days.len()];
+    markdown.push_str(&format!(
+        "| _{} time_ | {} |\n",
+        home_name.replace('|', "\\|"),
+        ruler.join(" | ")
+    ));
+    for (name, strips) in &grid.rows {
+        let strips: Vec<String> = strips.iter().map(|strip| format!("`{}`", strip)).collect();
+        markdown.push_str(&format!(
+            "| {} | {} |\n",
+            name.replace('|', "\\|"),
+            strips.join(" | ")
+        ));
+    }
+    markdown
+}
+
+pub fn write_week_csv(grid: &WeekGrid, home_name: &str) -> String {
+    let mut csv = String::from("city");
+    for day in &grid.days {
+        csv.push(',');
+        csv.push_str(&day.format("%Y-%m-%d").to_string());
+    }
+    csv.push('\n');
+    let ruler = std::iter::once(csv_field(&format!("{} time", home_name)))
+        .chain(grid.days.iter().map(|_| HOUR_RULER.to_string()));
+    csv.push_str(&ruler.collect::<Vec<_>>().join(","));
+    csv.push('\n');
+    for (name, strips) in &grid.rows {
+        csv.push_str(&csv_field(name));
+        for strip in strips {
+            csv.push(',');
+            csv.push_str(strip);
+        }
+        csv.push('\n');
+    }
+    csv
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A day's strip with `#` for the given home hours and `+` for the partial ones
+    fn strip(full: std::ops::Range<usize>, partial: &[usize]) -> String {
+        (0..24)
+            .map(|hour| {
+                if full.contains(&hour) {
+                    '#'
+                } else if partial.contains(&hour) {
+                    '+'
+                } else {
+                    '.'
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn snapshots_leave_out_cities_that_dont_resolve() {
+        let now = "2026-07-01T12:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        let cities = [
+            CityConfig::new("Austin", "America/Chicago", true),
+            CityConfig::new("Base", "Mars/Base", false),
+            CityConfig::new("Berlin", "Europe/Berlin", false),
+        ];
+        let (snapshots, errors) = snapshot_cities(&cities, now, false);
+        let rows: Vec<(&str, i32)> = snapshots
+            .iter()
+            .map(|city| (city.name.as_str(), city.diff_minutes))
+            .collect();
+        assert_eq!(rows, [("Austin", 0), ("Berlin", 420)]);
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].starts_with("Base: "));
+        assert!(
+            write_csv(&snapshots, false)
+                .lines()
+                .all(|line| !line.contains("Base"))
+        );
+
+        // Without a home that resolves, differences are from UTC
+        let cities = [
+            CityConfig::new("Base", "Mars/Base", true),
+            CityConfig::new("Berlin", "Europe/Berlin", false),
+        ];
+        let (snapshots, errors) = snapshot_cities(&cities, now, false);
+        assert_eq!(snapshots.len(), 1);
+        assert_eq!(snapshots[0].diff_minutes, 120);
+        assert_eq!(errors.len(), 1);
+    }
+
+    #[test]
+    fn exports_use_the_scrubbed_time_and_otherwise_now() {
+        let cities = [
+            CityConfig::new("Berlin", "Europe/Berlin", true),
+            CityConfig::new("Tokyo", "Asia/Tokyo", false),
+        ];
+        let now = "2026-03-20T09:05:00Z".parse::<DateTime<Utc>>().unwrap();
+        let scrubbed = "2026-03-20T15:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        let export = |source: DisplayInstant| {
+            let start = source.instant();
+            let (snapshots, _) = snapshot_cities(&cities, start, false);
+            let ics = write_ics(
+                &snapshots,
+                start,
+                start + Duration::hours(1),
+                "Meeting",
+                None,
+                ClockFormat::default(),
+                now,
+            );
+            let times: Vec<String> = snapshots
+                .iter()
+                .map(|city| city.local.format("%H:%M").to_string())
+                .collect();
+            (ics, times, source.describe(chrono_tz::Europe::Berlin))
+        };
+
+        let (ics, times, note) = export(DisplayInstant::new(Some(scrubbed), now));
+        assert!(ics.contains("DTSTART:20260320T150000Z"));
+        assert!(ics.contains("DTSTAMP:20260320T090500Z"));
+        assert_eq!(times, ["16:00", "00:00"]);
+        assert_eq!(note, "as of Fri Mar 20 16:00 at home");
+
+        let (ics, times, note) = export(DisplayInstant::new(None, now));
+        assert!(ics.contains("DTSTART:20260320T090500Z"));
+        assert_eq!(times, ["10:05", "18:05"]);
+        assert_eq!(note, "as of now");
+    }
+
+    #[test]
+    fn csv_quotes_fields_that_need_it() {
+        let now = "2026-07-01T12:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        let mut paris = CityConfig::new("Paris, \"Rive Gauche\"", "Europe/Paris", false);
+        paris.id = "b2".to_string();
+        paris.people = vec!["Ana".to_string(), "Bo\nBo".to_string()];
+        let mut austin = CityConfig::new("Austin", "America/Chicago", true);
+        austin.id = "a1".to_string();
+        let (snapshots, _) = snapshot_cities(&[austin, paris], now, false);
+
+        assert_eq!(
+            write_csv(&snapshots, true),
+            "name,timezone,local_time,utc_offset_minutes,diff_from_home_minutes,dst,people,id\n\
+             Austin,America/Chicago,2026-07-01T07:00:00-05:00,-300,0,true,,a1\n\
+             \"Paris, \"\"Rive Gauche\"\"\",Europe/Paris,2026-07-01T14:00:00+02:00,120,420,true,\"Ana; Bo\nBo\",b2\n"
+        );
+    }
+
+    #[test]
+    fn csv_people_column_only_when_asked() {
+        let now = "2026-07-01T12:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        let mut tokyo = CityConfig::new("Tokyo", "Asia/Tokyo", true);
+        tokyo.id = "t1".to_string();
+        tokyo.people = vec!["Ken".to_string()];
+        let (snapshots, _) = snapshot_cities(&[tokyo], now, false);
+
+        assert_eq!(
+            write_csv(&snapshots, false),
+            "name,timezone,local_time,utc_offset_minutes,diff_from_home_minutes,dst,id\n\
+             Tokyo,Asia/Tokyo,2026-07-01T21:00:00+09:00,540,0,false,t1\n"
+        );
+        assert_eq!(
+            write_csv(&snapshots, true),
+            "name,timezone,local_time,utc_offset_minutes,diff_from_home_minutes,dst,people,id\n\
+             Tokyo,Asia/Tokyo,2026-07-01T21:00:00+09:00,540,0,false,Ken,t1\n"
+        );
+    }
+
+    // Austin at home, then New York, London and Berlin at 14:00 UTC on Fri Mar 20
+    fn slack_snapshots() -> Vec<CitySnapshot> {
+        let now = "2026-03-20T14:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        let mut austin = CityConfig::new("Austin", "America/Chicago", true);
+        austin.people = vec!["Ana".to_string()];
+        let cities = [
+            austin,
+            CityConfig::new("NYC", "America/New_York", false),
+            CityConfig::new("London", "Europe/London", false),
+            CityConfig::new("Berlin", "Europe/Berlin", false),
+        ];
+        snapshot_cities(&cities, now, false).0
+    }
+
+    #[test]
+    fn slack_message_layout() {
+        let snapshots = slack_snapshots();
+        let message = |settings: &SlackSettings, include_people, clock| {
+            format_slack_message(&snapshots, settings, include_people, clock, None)
+        };
+
+        assert_eq!(
+            message(
+                &SlackSettings::default(),
+                false,
+                ClockFormat::TwentyFourHour
+            ),
+            "📅 Fri Mar 20 — 9:00 AM Austin (home) · 10:00 AM NYC · 2:00 PM London · 3:00 PM Berlin"
+        );
+        let plain = SlackSettings {
+            emoji: false,
+            twelve_hour: false,
+            separator: " | ".to_string(),
+        };
+        assert_eq!(
+            message(&plain, true, ClockFormat::TwentyFourHour),
+            "Fri Mar 20 — 09:00 Austin (Ana) (home) | 10:00 NYC | 14:00 London | 15:00 Berlin"
+        );
+    }
+
+    #[test]
+    fn slack_message_marks_the_home_city_wherever_it_sits() {
+        let mut snapshots = slack_snapshots();
+        snapshots.rotate_left(2);
+        let message = format_slack_message(
+            &snapshots,
+            &SlackSettings::default(),
+            false,
+            ClockFormat::TwentyFourHour,
+            None,
+        );
+        assert!(message.contains(" 9:00 AM Austin (home)"));
+        assert_eq!(message.matches("(home)").count(), 1);
+
+        // Without a home there's nothing to mark
+        for city in &mut snapshots {
+            city.is_home = false;
+        }
+        let message = format_slack_message(
+            &snapshots,
+            &SlackSettings::default(),
+            false,
+            ClockFormat::TwentyFourHour,
+            None,
+        );
+        assert!(!message.contains("(home)"));
+    }
+
+    #[test]
+    fn series_notes_flag_a_clock_change_inside_the_series() {
+        // Tuesdays at 15:00 in New York from Mar 3 2026; the US springs forward on
+        // Mar 8, Europe not until Mar 29
+        let start = "2026-03-03T20:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        let cities = [
+            CityConfig::new("New York", "America/New_York", true),
+            CityConfig::new("Berlin", "europe/berlin", false),
+            CityConfig::new("Phoenix", "America/Phoenix", false),
+        ];
+        let (snapshots, _) = snapshot_cities(&cities, start, false);
+        let series = WeeklySeries {
+            home: chrono_tz::America::New_York,
+            count: 4,
+        };
+        assert_eq!(
+            series_notes(&snapshots, start, series, false),
+            [
+                "note: from Mar 10 this is 20:00 in Berlin, not 21:00",
+                "note: from Mar 10 this is 12:00 in Phoenix, not 13:00",
+            ]
+        );
+
+        let message = format_slack_message(
+            &snapshots,
+            &SlackSettings::default(),
+            false,
+            ClockFormat::TwentyFourHour,
+            Some(series),
+        );
+        assert_eq!(
+            message.lines().collect::<Vec<_>>(),
+            [
+                "📅 Tue Mar 3, weekly × 4 — 3:00 PM New York (home) · 9:00 PM Berlin · 1:00 PM Phoenix",
+                "note: from Mar 10 this is 8:00 PM in Berlin, not 9:00 PM",
+                "note: from Mar 10 this is 12:00 PM in Phoenix, not 1:00 PM",
+            ]
+        );
+    }
+
+    #[test]
+    fn series_notes_stay_empty_between_clock_changes() {
+        let start = "2026-06-02T19:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        let cities = [
+            CityConfig::new("New York", "America/New_York", true),
+            CityConfig::new("Berlin", "Europe/Berlin", false),
+            CityConfig::new("Tokyo", "Asia/Tokyo", false),
+        ];
+        let (snapshots, _) = snapshot_cities(&cities, start, false);
+        let series = WeeklySeries {
+            home: chrono_tz::America::New_York,
+            count: 8,
+        };
+        assert!(series_notes(&snapshots, start, series, false).is_empty());
+    }
+
+    #[test]
+    fn week_grid_follows_a_dst_change_inside_the_week() {
+        // From New York, Wed Mar 25 to Tue Mar 31 2026. Europe springs forward on
+        // Sunday the 29th, so Berlin's 9-17 moves an hour earlier for New York.
+        let cities = vec![
+            (
+                "Berlin".to_string(),
+                chrono_tz::Europe::Berlin,
+                WorkingHours::default(),
+                Weekend::default(),
+            ),
+            (
+                "Kolkata".to_string(),
+                chrono_tz::Asia::Kolkata,
+                WorkingHours::default(),
+                Weekend::default(),
+            ),
+        ];
+        let first_day = NaiveDate::from_ymd_opt(2026, 3, 25).unwrap();
+        let grid = week_grid(&cities, chrono_tz::America::New_York, first_day);
+
+        assert_eq!(grid.days.len(), 7);
+        assert_eq!(grid.days[6], NaiveDate::from_ymd_opt(2026, 3, 31).unwrap());
+
+        let (name, berlin) = &grid.rows[0];
+        assert_eq!(name, "Berlin");
+        let before = strip(4..12, &[]);
+        let after = strip(3..11, &[]);
+        let off = strip(0..0, &[]);
+        assert_eq!(
+            berlin,
+            &[
+                before.clone(),
+                before.clone(),
+                before,
+                off.clone(),
+                off,
+                after.clone(),
+                after
+            ]
+        );
+
+        // Half an hour off New York: the edges are partly working hours, and
+        // Kolkata's Monday starts late on New York's Sunday
+        let (_, kolkata) = &grid.rows[1];
+        let weekday = strip(0..7, &[7, 23]);
+        assert_eq!(kolkata[0], weekday);
+        assert_eq!(kolkata[2], strip(0..7, &[7]));
+        assert_eq!(kolkata[3], strip(0..0, &[]));
+        assert_eq!(kolkata[4], strip(0..0, &[23]));
+    }
+}
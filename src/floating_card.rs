@@ -0,0 +1,116 @@
+use crate::{
+    WorldTime, WorldTimeApp,
+    config::Settings,
+    window_state::{self, WindowState},
+};
+use gpui::{
+    App, Bounds, Context, Entity, SharedString, TitlebarOptions, Window, WindowBounds, WindowKind,
+    WindowOptions, div, prelude::*, px, size,
+};
+use gpui_component::{ActiveTheme as _, Root};
+use serde::{Deserialize, Serialize};
+
+const DEFAULT_WIDTH: f32 = 260.0;
+const DEFAULT_HEIGHT: f32 = 240.0;
+
+// A card open in its own window, and where that window was left
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FloatingCardConfig {
+    pub city: String,
+    #[serde(flatten)]
+    pub window: WindowState,
+}
+
+// The window renders the same card entity as the main grid, so the app's ticker
+// keeps it current. Holding the app keeps that ticker running if the main window
+// is closed first.
+pub fn open_floating_card(
+    app: Entity<WorldTimeApp>,
+    card: Entity<WorldTime>,
+    saved: Option<&WindowState>,
+    cx: &mut App,
+) -> Option<WindowState> {
+    let (bounds, display_id) = match saved {
+        Some(saved) => window_state::initial_bounds(Some(saved), cx),
+        None => (
+            WindowBounds::Windowed(Bounds::centered(
+                None,
+                size(px(DEFAULT_WIDTH), px(DEFAULT_HEIGHT)),
+                cx,
+            )),
+            None,
+        ),
+    };
+    let settings = cx.global::<Settings>();
+    let options = WindowOptions {
+        window_bounds: Some(bounds),
+        display_id,
+        kind: if settings.always_on_top {
+            WindowKind::PopUp
+        } else {
+            WindowKind::Normal
+        },
+        window_background: settings.window_opacity.background(),
+        titlebar: Some(TitlebarOptions {
+            title: Some(SharedString::from(card.read(cx).name.clone())),
+            ..Default::default()
+        }),
+        show: true,
+        ..Default::default()
+    };
+
+    let handle = cx
+        .open_window(options, |window, cx| {
+            let floating = cx.new(|cx| FloatingCard::new(app, card, window, cx));
+            cx.new(|cx| Root::new(floating, window, cx))
+        })
+        .ok()?;
+    handle
+        .update(cx, |_, window, cx| WindowState::capture(window, cx))
+        .ok()
+}
+
+pub struct FloatingCard {
+    card: Entity<WorldTime>,
+}
+
+impl FloatingCard {
+    fn new(
+        app: Entity<WorldTimeApp>,
+        card: Entity<WorldTime>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let city = card.read(cx).name.clone();
+        cx.observe_window_bounds(window, {
+            let app = app.clone();
+            let city = city.clone();
+            move |_, window, cx| {
+                let state = WindowState::capture(window, cx);
+                app.update(cx, |app, cx| {
+                    app.floating_card_moved(&city, state, window, cx)
+                });
+            }
+        })
+        .detach();
+        // Only an explicit close forgets the window; quitting keeps it for next launch
+        window.on_window_should_close(cx, move |window, cx| {
+            app.update(cx, |app, cx| app.floating_card_closed(&city, window, cx));
+            true
+        });
+        FloatingCard { card }
+    }
+}
+
+impl Render for FloatingCard {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        div()
+            .size_full()
+            .p_3()
+            .bg(cx
+                .global::<Settings>()
+                .window_opacity
+                .apply(cx.theme().background))
+            .child(self.card.clone())
+    }
+}
@@ -0,0 +1,744 @@
+//! Every piece of text the cards and exports show for a time or a difference.
+//!
+//! Pure functions of their inputs, so the window, the terminal table and the
+//! copied or exported text always agree.
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+
+/// "09:05", or "09:05:30" with seconds.
+pub fn clock_time(time: NaiveTime, show_seconds: bool) -> String {
+    time.format(if show_seconds { "%H:%M:%S" } else { "%H:%M" })
+        .to_string()
+}
+
+/// "9:05 AM".
+pub fn clock_time_12h(time: NaiveTime) -> String {
+    time.format("%-I:%M %p").to_string()
+}
+
+/// The military zone letter for a UTC offset: "Z" at UTC, "A"–"M" east with "J"
+/// (local time) skipped, "N"–"Y" west. None for offsets that aren't whole hours
+/// or lie beyond ±12h.
+pub fn military_zone_letter(offset_minutes: i32) -> Option<char> {
+    if offset_minutes % 60 != 0 {
+        return None;
+    }
+    let hours = offset_minutes / 60;
+    let letter = match hours {
+        0 => b'Z',
+        1..=9 => b'A' + (hours - 1) as u8,
+        10..=12 => b'K' + (hours - 10) as u8,
+        -12..=-1 => b'N' + (-hours - 1) as u8,
+        _ => return None,
+    };
+    Some(letter as char)
+}
+
+/// "1445A" at UTC+1, or "144530A" with seconds; where no letter fits, the offset
+/// itself, as in "1445+0530".
+pub fn military_time(time: NaiveTime, offset_minutes: i32, show_seconds: bool) -> String {
+    let clock = time.format(if show_seconds { "%H%M%S" } else { "%H%M" });
+    match military_zone_letter(offset_minutes) {
+        Some(letter) => format!("{}{}", clock, letter),
+        None => {
+            let sign = if offset_minutes < 0 { '-' } else { '+' };
+            let minutes = offset_minutes.abs();
+            format!("{}{}{:02}{:02}", clock, sign, minutes / 60, minutes % 60)
+        }
+    }
+}
+
+/// "Thu 09:05".
+pub fn weekday_time(local: NaiveDateTime) -> String {
+    local.format("%a %H:%M").to_string()
+}
+
+/// "Thu Mar 20".
+pub fn weekday_date(date: NaiveDate) -> String {
+    date.format("%a %b %-d").to_string()
+}
+
+/// "Thursday, March 20".
+pub fn long_date(date: NaiveDate) -> String {
+    date.format("%A, %B %-d").to_string()
+}
+
+/// "Mar 20".
+pub fn month_day(date: NaiveDate) -> String {
+    date.format("%b %-d").to_string()
+}
+
+/// "Thu Mar 20 09:05".
+pub fn weekday_date_time(local: NaiveDateTime) -> String {
+    local.format("%a %b %-d %H:%M").to_string()
+}
+
+/// "Thu Mar 20 2025 09:05".
+pub fn weekday_date_year_time(local: NaiveDateTime) -> String {
+    local.format("%a %b %-d %Y %H:%M").to_string()
+}
+
+/// "2025-03-20 09:05", which the converter also reads back.
+pub fn date_time(local: NaiveDateTime) -> String {
+    local.format("%Y-%m-%d %H:%M").to_string()
+}
+
+/// "Thu 2025-03-20 09:05".
+pub fn weekday_iso_date_time(local: NaiveDateTime) -> String {
+    local.format("%a %Y-%m-%d %H:%M").to_string()
+}
+
+/// "20250320T090500Z", as iCalendar wants UTC stamps.
+pub fn ics_stamp(instant: DateTime<Utc>) -> String {
+    instant.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+/// Whole hours print as "7", half-hour zones as "5.5".
+pub fn diff_hours(diff_minutes: i32) -> String {
+    if diff_minutes % 60 == 0 {
+        (diff_minutes / 60).to_string()
+    } else {
+        format!("{}", diff_minutes as f32 / 60.0)
+    }
+}
+
+/// "+7h" or "-3.5h".
+pub fn signed_diff_hours(diff_minutes: i32) -> String {
+    let sign = if diff_minutes < 0 { "-" } else { "+" };
+    format!("{}{}h", sign, diff_hours(diff_minutes.abs()))
+}
+
+/// The card's "Δ 7 hours" line, "Δ 1 hour" for exactly one.
+pub fn diff_badge(diff_minutes: i32) -> String {
+    let unit = if diff_minutes.abs() == 60 {
+        "hour"
+    } else {
+        "hours"
+    };
+    format!("Δ {} {}", diff_hours(diff_minutes), unit)
+}
+
+/// "7 hours ahead of home", "1 hour behind home" or "same time as home".
+pub fn describe_diff(diff_minutes: i32) -> String {
+    let hours = diff_hours(diff_minutes.abs());
+    let unit = if diff_minutes.abs() == 60 {
+        "hour"
+    } else {
+        "hours"
+    };
+    match diff_minutes.signum() {
+        0 => "same time as home".to_string(),
+        1 => format!("{} {} ahead of home", hours, unit),
+        _ => format!("{} {} behind home", hours, unit),
+    }
+}
+
+/// "1d 1h", "1d 0h 45m" or "5h 45m", the size of a difference split into days.
+pub fn day_hours(diff_minutes: i32) -> String {
+    let minutes = diff_minutes.abs();
+    let (days, hours, minutes) = (minutes / 1440, minutes / 60 % 24, minutes % 60);
+    let mut text = if days > 0 {
+        format!("{}d {}h", days, hours)
+    } else {
+        format!("{}h", hours)
+    };
+    if minutes > 0 {
+        text.push_str(&format!(" {}m", minutes));
+    }
+    text
+}
+
+/// "7h ahead", "3.5h behind" or "same time"; from a day on, "1d 1h ahead".
+pub fn ahead_behind(diff_minutes: i32) -> String {
+    let hours = if diff_minutes.abs() >= 1440 {
+        day_hours(diff_minutes)
+    } else {
+        format!("{}h", diff_hours(diff_minutes.abs()))
+    };
+    match diff_minutes.signum() {
+        0 => "same time".to_string(),
+        1 => format!("{} ahead", hours),
+        _ => format!("{} behind", hours),
+    }
+}
+
+/// "+07:00" or "-03:30", the difference written like a UTC offset.
+pub fn offset_diff(diff_minutes: i32) -> String {
+    let sign = if diff_minutes < 0 { '-' } else { '+' };
+    let minutes = diff_minutes.abs();
+    format!("{}{:02}:{:02}", sign, minutes / 60, minutes % 60)
+}
+
+/// "+1 day", "-2 days"; empty on the same day.
+pub fn day_offset(days: i64) -> String {
+    match days {
+        0 => String::new(),
+        1 | -1 => format!("{:+} day", days),
+        days => format!("{:+} days", days),
+    }
+}
+
+/// Like "2d 4h 13m", dropping leading zero units.
+pub fn remaining(minutes: i64) -> String {
+    let (days, hours, minutes) = (minutes / 1440, minutes / 60 % 24, minutes % 60);
+    match (days, hours) {
+        (0, 0) => format!("{}m", minutes),
+        (0, hours) => format!("{}h {}m", hours, minutes),
+        (days, hours) => format!("{}d {}h {}m", days, hours, minutes),
+    }
+}
+
+/// One city's values for a text snippet, already formatted as the cards show
+/// them.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SnippetCity {
+    pub name: String,
+    pub time: String, // "14:32"
+    pub zone: String, // "CET"
+    pub date: String, // "Thu Mar 20"
+    pub home: bool,
+    pub shown: bool, // hidden cards are left out of {all}
+}
+
+/// Which city a placeholder names: `{all}`, `{home}` or a card's name.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SnippetTarget {
+    All,
+    Home,
+    City(String),
+}
+
+/// What a placeholder prints: `{Berlin}` gives "20:32 CET", `{Berlin:time}`,
+/// `{Berlin:zone}` and `{Berlin:date}` one part each.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SnippetField {
+    TimeZone,
+    Time,
+    Zone,
+    Date,
+}
+
+/// A snippet template, split into literal text and placeholders.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SnippetPart {
+    Text(String),
+    Placeholder(SnippetTarget, SnippetField),
+}
+
+/// Splits a template like "{Chicago} / {Berlin:time}" into its parts; `{{` and
+/// `}}` stand for literal braces. Names are checked against the cards only when
+/// rendering.
+pub fn parse_snippet(template: &str) -> Result<Vec<SnippetPart>, String> {
+    let mut parts = Vec::new();
+    let mut text = String::new();
+    let mut chars = template.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                text.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                text.push('}');
+            }
+            '}' => return Err("A } has no opening {; write }} for a brace".to_string()),
+            '{' => {
+                let mut inner = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some('{') | None => {
+                            return Err(format!("{{{} isn't closed with a }}", inner.trim_end()));
+                        }
+                        Some(c) => inner.push(c),
+                    }
+                }
+                if !text.is_empty() {
+                    parts.push(SnippetPart::Text(std::mem::take(&mut text)));
+                }
+                parts.push(parse_placeholder(&inner)?);
+            }
+            c => text.push(c),
+        }
+    }
+    if !text.is_empty() {
+        parts.push(SnippetPart::Text(text));
+    }
+    Ok(parts)
+}
+
+fn parse_placeholder(inner: &str) -> Result<SnippetPart, String> {
+    let (name, field) = match inner.rsplit_once(':') {
+        Some((name, field)) => (name.trim(), Some(field.trim())),
+        None => (inner.trim(), None),
+    };
+    let field = match field.map(str::to_lowercase).as_deref() {
+        None => SnippetField::TimeZone,
+        Some("time") => SnippetField::Time,
+        Some("zone") => SnippetField::Zone,
+        Some("date") => SnippetField::Date,
+        Some(other) => {
+            return Err(format!(
+                "{{{}}}: \"{}\" isn't time, zone or date",
+                inner, other
+            ));
+        }
+    };
+    let target = match name.to_lowercase().as_str() {
+        "" => return Err("A {} names no city".to_string()),
+        "all" => SnippetTarget::All,
+        "home" => SnippetTarget::Home,
+        _ => SnippetTarget::City(name.to_string()),
+    };
+    Ok(SnippetPart::Placeholder(target, field))
+}
+
+/// Fills in a parsed template; `{all}` joins every shown city with " / ".
+/// Names match regardless of case; an unknown one is an error listing the
+/// names there are.
+pub fn render_snippet(parts: &[SnippetPart], cities: &[SnippetCity]) -> Result<String, String> {
+    let value = |city: &SnippetCity, field: SnippetField| match field {
+        SnippetField::TimeZone => format!("{} {}", city.time, city.zone),
+        SnippetField::Time => city.time.clone(),
+        SnippetField::Zone => city.zone.clone(),
+        SnippetField::Date => city.date.clone(),
+    };
+    let mut snippet = String::new();
+    for part in parts {
+        match part {
+            SnippetPart::Text(text) => snippet.push_str(text),
+            SnippetPart::Placeholder(SnippetTarget::All, field) => snippet.push_str(
+                &cities
+                    .iter()
+                    .filter(|city| city.shown)
+                    .map(|city| value(city, *field))
+                    .collect::<Vec<_>>()
+                    .join(" / "),
+            ),
+            SnippetPart::Placeholder(SnippetTarget::Home, field) => {
+                let home = cities
+                    .iter()
+                    .find(|city| city.home)
+                    .ok_or("There's no home city for {home}")?;
+                snippet.push_str(&value(home, *field));
+            }
+            SnippetPart::Placeholder(SnippetTarget::City(name), field) => {
+                let city = cities
+                    .iter()
+                    .find(|city| city.name.eq_ignore_ascii_case(name))
+                    .ok_or_else(|| {
+                        let names: Vec<&str> =
+                            cities.iter().map(|city| city.name.as_str()).collect();
+                        format!(
+                            "There's no city named \"{}\"; use all, home or one of: {}",
+                            name,
+                            names.join(", ")
+                        )
+                    })?;
+                snippet.push_str(&value(city, *field));
+            }
+        }
+    }
+    Ok(snippet)
+}
+
+/// "UTC+2", "UTC+5:30", "UTC-3" or just "UTC", the short offset a message reads
+/// better with.
+pub fn short_utc_offset(minutes: i32) -> String {
+    if minutes == 0 {
+        return "UTC".to_string();
+    }
+    let sign = if minutes < 0 { '-' } else { '+' };
+    let minutes = minutes.abs();
+    match minutes % 60 {
+        0 => format!("UTC{}{}", sign, minutes / 60),
+        rest => format!("UTC{}{}:{:02}", sign, minutes / 60, rest),
+    }
+}
+
+/// What a shared card says, taken from the card and its saved settings.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SharedCard {
+    pub name: String,
+    pub time: String, // in the chosen clock format
+    pub zone: String, // "EET"
+    pub utc_offset_minutes: i32,
+    pub diff_minutes: i32,
+    pub home: Option<String>, // home's name; None on the home card itself
+    pub working_hours: Option<(NaiveTime, NaiveTime)>,
+    pub link: Option<String>, // a time2rust://add link for the same card
+}
+
+/// "Bucharest — 21:45 EET (UTC+2), 8h ahead of Austin, working hours
+/// 10:00–18:00", with the add link on a line of its own.
+pub fn share_card(card: &SharedCard) -> String {
+    let mut parts = vec![format!(
+        "{} — {} {} ({})",
+        card.name,
+        card.time,
+        card.zone,
+        short_utc_offset(card.utc_offset_minutes)
+    )];
+    parts.push(match (&card.home, card.diff_minutes) {
+        (None, _) => "home".to_string(),
+        (Some(home), 0) => format!("same time as {}", home),
+        (Some(home), diff) if diff > 0 => format!("{} of {}", ahead_behind(diff), home),
+        (Some(home), diff) => format!("{} {}", ahead_behind(diff), home),
+    });
+    if let Some((start, end)) = card.working_hours {
+        parts.push(format!(
+            "working hours {}–{}",
+            clock_time(start, false),
+            clock_time(end, false)
+        ));
+    }
+    let line = parts.join(", ");
+    match &card.link {
+        Some(link) => format!("{}\nAdd it to time2rust: {}", line, link),
+        None => line,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hm(hour: u32, minute: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(hour, minute, 0).unwrap()
+    }
+
+    #[test]
+    fn clock_times_at_midnight_noon_and_dst_boundaries() {
+        assert_eq!(clock_time(hm(0, 0), false), "00:00");
+        assert_eq!(clock_time(hm(12, 0), false), "12:00");
+        assert_eq!(clock_time(hm(0, 0), true), "00:00:00");
+        // Berlin either side of the spring-forward gap and the fall-back repeat
+        let berlin = |instant: &str| {
+            let utc: DateTime<Utc> = instant.parse().unwrap();
+            utc.with_timezone(&chrono_tz::Europe::Berlin).time()
+        };
+        assert_eq!(clock_time(berlin("2026-03-29T00:59:59Z"), true), "01:59:59");
+        assert_eq!(clock_time(berlin("2026-03-29T01:00:00Z"), false), "03:00");
+        assert_eq!(clock_time(berlin("2026-10-25T00:30:00Z"), false), "02:30");
+        assert_eq!(clock_time(berlin("2026-10-25T01:30:00Z"), false), "02:30");
+        assert_eq!(clock_time_12h(berlin("2026-03-29T01:00:00Z")), "3:00 AM");
+
+        assert_eq!(clock_time_12h(hm(0, 0)), "12:00 AM");
+        assert_eq!(clock_time_12h(hm(12, 0)), "12:00 PM");
+        assert_eq!(clock_time_12h(hm(1, 59)), "1:59 AM");
+        assert_eq!(clock_time_12h(hm(23, 5)), "11:05 PM");
+    }
+
+    #[test]
+    fn military_letters_skip_j() {
+        let letters: String = (1..=12)
+            .map(|hours| military_zone_letter(hours * 60).unwrap())
+            .collect();
+        assert_eq!(letters, "ABCDEFGHIKLM");
+        assert_eq!(military_zone_letter(9 * 60), Some('I'));
+        assert_eq!(military_zone_letter(10 * 60), Some('K'));
+        assert_eq!(military_zone_letter(12 * 60), Some('M'));
+        assert_eq!(military_zone_letter(0), Some('Z'));
+    }
+
+    #[test]
+    fn military_letters_west_of_utc() {
+        let letters: String = (1..=12)
+            .map(|hours| military_zone_letter(-hours * 60).unwrap())
+            .collect();
+        assert_eq!(letters, "NOPQRSTUVWXY");
+    }
+
+    #[test]
+    fn military_time_falls_back_to_the_offset() {
+        let time = hm(14, 45);
+        assert_eq!(military_zone_letter(330), None);
+        assert_eq!(military_zone_letter(14 * 60), None);
+        assert_eq!(military_zone_letter(-13 * 60), None);
+        assert_eq!(military_time(time, 330, false), "1445+0530");
+        assert_eq!(military_time(time, 14 * 60, false), "1445+1400");
+        assert_eq!(military_time(time, -570, false), "1445-0930");
+        assert_eq!(military_time(time, 60, false), "1445A");
+        assert_eq!(
+            military_time(NaiveTime::from_hms_opt(14, 45, 30).unwrap(), 60, true),
+            "144530A"
+        );
+    }
+
+    #[test]
+    fn diff_hours_and_signs() {
+        assert_eq!(diff_hours(0), "0");
+        assert_eq!(diff_hours(420), "7");
+        assert_eq!(diff_hours(-180), "-3");
+        assert_eq!(diff_hours(330), "5.5");
+        assert_eq!(diff_hours(345), "5.75");
+        assert_eq!(diff_hours(-210), "-3.5");
+
+        assert_eq!(signed_diff_hours(0), "+0h");
+        assert_eq!(signed_diff_hours(420), "+7h");
+        assert_eq!(signed_diff_hours(-210), "-3.5h");
+        assert_eq!(signed_diff_hours(330), "+5.5h");
+    }
+
+    #[test]
+    fn diff_badges_and_descriptions() {
+        assert_eq!(diff_badge(0), "Δ 0 hours");
+        assert_eq!(diff_badge(60), "Δ 1 hour");
+        assert_eq!(diff_badge(-60), "Δ -1 hour");
+        assert_eq!(diff_badge(420), "Δ 7 hours");
+        assert_eq!(diff_badge(-210), "Δ -3.5 hours");
+
+        assert_eq!(describe_diff(0), "same time as home");
+        assert_eq!(describe_diff(60), "1 hour ahead of home");
+        assert_eq!(describe_diff(-60), "1 hour behind home");
+        assert_eq!(describe_diff(330), "5.5 hours ahead of home");
+        assert_eq!(describe_diff(-210), "3.5 hours behind home");
+    }
+
+    #[test]
+    fn ahead_behind_and_offset_diff() {
+        assert_eq!(ahead_behind(0), "same time");
+        assert_eq!(ahead_behind(420), "7h ahead");
+        assert_eq!(ahead_behind(-210), "3.5h behind");
+        assert_eq!(ahead_behind(345), "5.75h ahead");
+        assert_eq!(ahead_behind(1500), "1d 1h ahead");
+        assert_eq!(ahead_behind(-1440), "1d 0h behind");
+
+        assert_eq!(offset_diff(0), "+00:00");
+        assert_eq!(offset_diff(420), "+07:00");
+        assert_eq!(offset_diff(-210), "-03:30");
+        assert_eq!(offset_diff(345), "+05:45");
+        assert_eq!(offset_diff(-720), "-12:00");
+    }
+
+    #[test]
+    fn day_hours_splits_off_whole_days() {
+        assert_eq!(day_hours(0), "0h");
+        assert_eq!(day_hours(345), "5h 45m");
+        assert_eq!(day_hours(1440), "1d 0h");
+        assert_eq!(day_hours(1500), "1d 1h");
+        assert_eq!(day_hours(-1500), "1d 1h");
+        assert_eq!(day_hours(1485), "1d 0h 45m");
+    }
+
+    #[test]
+    fn remaining_drops_leading_zero_units() {
+        assert_eq!(remaining(0), "0m");
+        assert_eq!(remaining(59), "59m");
+        assert_eq!(remaining(60), "1h 0m");
+        assert_eq!(remaining(61), "1h 1m");
+        assert_eq!(remaining(1440), "1d 0h 0m");
+        assert_eq!(remaining(3133), "2d 4h 13m");
+    }
+
+    fn bucharest() -> SharedCard {
+        SharedCard {
+            name: "Bucharest".to_string(),
+            time: "21:45".to_string(),
+            zone: "EET".to_string(),
+            utc_offset_minutes: 120,
+            diff_minutes: 480,
+            home: Some("Austin".to_string()),
+            working_hours: None,
+            link: None,
+        }
+    }
+
+    #[test]
+    fn share_card_ahead_behind_and_same_time() {
+        assert_eq!(
+            share_card(&bucharest()),
+            "Bucharest — 21:45 EET (UTC+2), 8h ahead of Austin"
+        );
+        let behind = SharedCard {
+            name: "Caracas".to_string(),
+            time: "09:15".to_string(),
+            zone: "-04".to_string(),
+            utc_offset_minutes: -240,
+            diff_minutes: -90,
+            ..bucharest()
+        };
+        assert_eq!(
+            share_card(&behind),
+            "Caracas — 09:15 -04 (UTC-4), 1.5h behind Austin"
+        );
+        let same = SharedCard {
+            name: "Chicago".to_string(),
+            zone: "CST".to_string(),
+            utc_offset_minutes: -360,
+            diff_minutes: 0,
+            ..bucharest()
+        };
+        assert_eq!(
+            share_card(&same),
+            "Chicago — 21:45 CST (UTC-6), same time as Austin"
+        );
+    }
+
+    #[test]
+    fn share_card_for_home_with_hours_and_link() {
+        let home = SharedCard {
+            name: "Kolkata".to_string(),
+            time: "08:05".to_string(),
+            zone: "IST".to_string(),
+            utc_offset_minutes: 330,
+            diff_minutes: 0,
+            home: None,
+            ..bucharest()
+        };
+        assert_eq!(share_card(&home), "Kolkata — 08:05 IST (UTC+5:30), home");
+
+        let with_hours = SharedCard {
+            working_hours: Some((hm(10, 0), hm(18, 0))),
+            ..bucharest()
+        };
+        assert_eq!(
+            share_card(&with_hours),
+            "Bucharest — 21:45 EET (UTC+2), 8h ahead of Austin, working hours 10:00–18:00"
+        );
+
+        let link = "time2rust://add?name=Bucharest&tz=Europe/Bucharest".to_string();
+        let with_link = SharedCard {
+            link: Some(link.clone()),
+            ..with_hours
+        };
+        assert_eq!(
+            share_card(&with_link),
+            format!(
+                "Bucharest — 21:45 EET (UTC+2), 8h ahead of Austin, working hours \
+                 10:00–18:00\nAdd it to time2rust: {}",
+                link
+            )
+        );
+        let home_link = SharedCard {
+            link: Some(link.clone()),
+            ..home
+        };
+        assert_eq!(
+            share_card(&home_link),
+            format!(
+                "Kolkata — 08:05 IST (UTC+5:30), home\nAdd it to time2rust: {}",
+                link
+            )
+        );
+    }
+
+    #[test]
+    fn short_offsets() {
+        assert_eq!(short_utc_offset(0), "UTC");
+        assert_eq!(short_utc_offset(120), "UTC+2");
+        assert_eq!(short_utc_offset(-210), "UTC-3:30");
+        assert_eq!(short_utc_offset(345), "UTC+5:45");
+    }
+
+    fn snippet_city(name: &str, time: &str, zone: &str, home: bool) -> SnippetCity {
+        SnippetCity {
+            name: name.to_string(),
+            time: time.to_string(),
+            zone: zone.to_string(),
+            date: "Fri Mar 20".to_string(),
+            home,
+            shown: true,
+        }
+    }
+
+    fn snippet_cities() -> Vec<SnippetCity> {
+        vec![
+            snippet_city("Chicago", "13:32", "CDT", true),
+            snippet_city("Berlin", "19:32", "CET", false),
+            SnippetCity {
+                shown: false,
+                ..snippet_city("Helsinki", "20:32", "EET", false)
+            },
+        ]
+    }
+
+    fn render(template: &str, cities: &[SnippetCity]) -> Result<String, String> {
+        render_snippet(&parse_snippet(template)?, cities)
+    }
+
+    #[test]
+    fn snippets_fill_in_each_field() {
+        let cities = snippet_cities();
+        assert_eq!(
+            render(
+                "{Chicago} / {berlin:time} {BERLIN:zone}, {home:date}",
+                &cities
+            ),
+            Ok("13:32 CDT / 19:32 CET, Fri Mar 20".to_string())
+        );
+        // Hidden cards are left out of {all}, but can still be named
+        assert_eq!(
+            render("{all}", &cities),
+            Ok("13:32 CDT / 19:32 CET".to_string())
+        );
+        assert_eq!(render("{Helsinki:time}", &cities), Ok("20:32".to_string()));
+    }
+
+    #[test]
+    fn doubled_braces_are_literal() {
+        assert_eq!(
+            parse_snippet("{{home}} is {home}}}"),
+            Ok(vec![
+                SnippetPart::Text("{home} is ".to_string()),
+                SnippetPart::Placeholder(SnippetTarget::Home, SnippetField::TimeZone),
+                SnippetPart::Text("}".to_string()),
+            ])
+        );
+        assert_eq!(
+            render("{{{Berlin:time}}}", &snippet_cities()),
+            Ok("{19:32}".to_string())
+        );
+    }
+
+    #[test]
+    fn malformed_templates_are_errors() {
+        assert_eq!(
+            parse_snippet("{Berlin"),
+            Err("{Berlin isn't closed with a }".to_string())
+        );
+        assert_eq!(
+            parse_snippet("{Berlin {Chicago}"),
+            Err("{Berlin isn't closed with a }".to_string())
+        );
+        assert_eq!(parse_snippet("{}"), Err("A {} names no city".to_string()));
+        assert_eq!(
+            parse_snippet("{ :time}"),
+            Err("A {} names no city".to_string())
+        );
+        assert_eq!(
+            parse_snippet("Berlin}"),
+            Err("A } has no opening {; write }} for a brace".to_string())
+        );
+        assert_eq!(
+            parse_snippet("{Berlin:clock}"),
+            Err("{Berlin:clock}: \"clock\" isn't time, zone or date".to_string())
+        );
+    }
+
+    #[test]
+    fn unknown_names_and_a_missing_home_are_errors() {
+        let cities = snippet_cities();
+        assert_eq!(
+            render("{Tokyo}", &cities),
+            Err(
+                "There's no city named \"Tokyo\"; use all, home or one of: Chicago, Berlin, \
+                 Helsinki"
+                    .to_string()
+            )
+        );
+        let no_home: Vec<SnippetCity> = cities
+            .into_iter()
+            .map(|city| SnippetCity {
+                home: false,
+                ..city
+            })
+            .collect();
+        assert_eq!(
+            render("{home}", &no_home),
+            Err("There's no home city for {home}".to_string())
+        );
+        assert_eq!(render("{all}", &[]), Ok(String::new()));
+    }
+}
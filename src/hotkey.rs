@@ -0,0 +1,86 @@
+use global_hotkey::{GlobalHotKeyEvent, GlobalHotKeyManager, HotKeyState, hotkey::HotKey};
+
+// What a system-wide shortcut does
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ShortcutAction {
+    ToggleWindow,
+    InsertSnippet,
+}
+
+// The system-wide shortcuts: one summons or hides the main window, the other
+// pastes the current times into whatever app is in front
+pub struct GlobalShortcut {
+    manager: GlobalHotKeyManager,
+    registered: Vec<(ShortcutAction, HotKey)>,
+    applied: Vec<(ShortcutAction, Option<String>)>,
+}
+
+impl GlobalShortcut {
+    pub fn new() -> Result<Self, String> {
+        let manager = GlobalHotKeyManager::new()
+            .map_err(|err| format!("Global shortcuts are unavailable: {}", err))?;
+        Ok(GlobalShortcut {
+            manager,
+            registered: Vec::new(),
+            applied: Vec::new(),
+        })
+    }
+
+    // Drops the action's previous registration and registers `shortcut`, if any
+    pub fn apply(&mut self, action: ShortcutAction, shortcut: Option<&str>) -> Result<(), String> {
+        let wanted = shortcut.map(str::to_string);
+        match self
+            .applied
+            .iter_mut()
+            .find(|(applied, _)| *applied == action)
+        {
+            Some((_, applied)) if *applied == wanted => return Ok(()),
+            Some((_, applied)) => *applied = wanted,
+            None => self.applied.push((action, wanted)),
+        }
+        if let Some(index) = self
+            .registered
+            .iter()
+            .position(|(registered, _)| *registered == action)
+        {
+            let (_, previous) = self.registered.remove(index);
+            let _ = self.manager.unregister(previous);
+        }
+        let Some(shortcut) = shortcut else {
+            return Ok(());
+        };
+
+        let hotkey: HotKey = shortcut
+            .parse()
+            .map_err(|err| format!("Invalid shortcut \"{}\": {}", shortcut, err))?;
+        self.manager.register(hotkey).map_err(|err| {
+            format!(
+                "Couldn't register {} (is it used by another app?): {}",
+                shortcut, err
+            )
+        })?;
+        self.registered.push((action, hotkey));
+        Ok(())
+    }
+
+    // Drains pending events and reports which of our shortcuts were pressed
+    pub fn poll_pressed(&self) -> Vec<ShortcutAction> {
+        let mut pressed = Vec::new();
+        while let Ok(event) = GlobalHotKeyEvent::receiver().try_recv() {
+            if event.state() != HotKeyState::Pressed {
+                continue;
+            }
+            let action = self
+                .registered
+                .iter()
+                .find(|(_, hotkey)| hotkey.id() == event.id())
+                .map(|(action, _)| *action);
+            if let Some(action) = action
+                && !pressed.contains(&action)
+            {
+                pressed.push(action);
+            }
+        }
+        pressed
+    }
+}
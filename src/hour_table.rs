@@ -0,0 +1,153 @@
+use crate::{
+    WorldTimeApp,
+    export::DisplayInstant,
+    planner::{self, DayPart, WorkingHours},
+    week::{self, Weekend},
+};
+use chrono::{DateTime, Duration, DurationRound, Utc};
+use chrono_tz::Tz;
+use gpui::{Context, SharedString, div, prelude::*, px, relative, rgb};
+use gpui_component::{
+    Sizable,
+    button::{Button, ButtonVariants as _},
+};
+use time2rust::format;
+
+pub const ROWS: i64 = 24;
+
+// What the table needs to know about each column
+pub struct Column {
+    pub name: String,
+    pub tz: Tz,
+    pub hours: WorkingHours,
+    pub weekend: Weekend,
+    pub is_home: bool,
+}
+
+fn cell_color(part: DayPart) -> gpui::Rgba {
+    match part {
+        DayPart::Night => rgb(0xdbeafe),
+        DayPart::Working => rgb(0xdcfce7),
+        DayPart::Evening => rgb(0xfef3c7),
+    }
+}
+
+fn cell(content: impl Into<SharedString>) -> gpui::Div {
+    div()
+        .flex_1()
+        .min_w(px(90.0))
+        .px_2()
+        .py_1()
+        .text_sm()
+        .child(content.into())
+}
+
+// Across the current hour's row, as far down as the hour has gone. Rows are
+// absolute hours, so a DST change in the window shortens nothing under the line.
+// Grey and still while showing a picked time rather than the clock.
+fn now_line(now: DateTime<Utc>, hour_start: DateTime<Utc>, live: bool) -> impl IntoElement {
+    let fraction = ((now - hour_start).num_seconds() as f32 / 3600.0).clamp(0.0, 1.0);
+    div()
+        .absolute()
+        .left_0()
+        .right_0()
+        .top(relative(fraction))
+        .h(px(2.0))
+        .bg(if live { rgb(0xef4444) } else { rgb(0x9ca3af) })
+}
+
+// Rows are whole hours starting with the one containing `now`; clicking a row
+// selects it for copying or saving as a calendar event
+pub fn hour_table(
+    columns: &[Column],
+    source: DisplayInstant,
+    selected: Option<DateTime<Utc>>,
+    cx: &mut Context<WorldTimeApp>,
+) -> impl IntoElement {
+    let now = source.instant();
+    let first = now.duration_trunc(Duration::hours(1)).unwrap_or(now);
+    let live = matches!(source, DisplayInstant::Live(_));
+    let header = div()
+        .flex()
+        .font_weight(gpui::FontWeight::BOLD)
+        .children(columns.iter().map(|column| {
+            cell(if column.is_home {
+                format!("{} (home)", column.name)
+            } else {
+                column.name.clone()
+            })
+        }));
+
+    let rows = (0..ROWS).map(|row| {
+        let instant = first + Duration::hours(row);
+        let is_selected = selected == Some(instant);
+        div()
+            .id(("hour-row", row as usize))
+            .relative()
+            .flex()
+            .items_center()
+            .cursor_pointer()
+            .border_1()
+            .border_color(if row == 0 {
+                rgb(0xef4444) // the current hour
+            } else if is_selected {
+                rgb(0x3b82f6)
+            } else {
+                gpui::transparent_black().into()
+            })
+            .on_click(cx.listener(move |this, _, _, cx| {
+                this.table_selection = if is_selected { None } else { Some(instant) };
+                cx.notify();
+            }))
+            .children(columns.iter().map(|column| {
+                let local = instant.with_timezone(&column.tz);
+                // Working hours on a day off shade like an evening
+                let part = match planner::day_part(local.time(), &column.hours) {
+                    DayPart::Working
+                        if !week::working_at(
+                            local.naive_local(),
+                            &column.hours,
+                            column.weekend,
+                        ) =>
+                    {
+                        DayPart::Evening
+                    }
+                    part => part,
+                };
+                cell(format::weekday_time(local.naive_local())).bg(cell_color(part))
+            }))
+            .children((row == 0).then(|| now_line(now, first, live)))
+            .children(is_selected.then(|| {
+                div()
+                    .flex()
+                    .gap_1()
+                    .child(
+                        Button::new("table-copy")
+                            .label("Copy for Slack")
+                            .small()
+                            .ghost()
+                            .on_click(cx.listener(move |this, _, window, cx| {
+                                this.copy_slack_message(DisplayInstant::Picked(instant), window, cx)
+                            })),
+                    )
+                    .child(
+                        Button::new("table-ics")
+                            .label("Save .ics…")
+                            .small()
+                            .ghost()
+                            .on_click(cx.listener(move |this, _, window, cx| {
+                                this.export_ics(DisplayInstant::Picked(instant), window, cx)
+                            })),
+                    )
+            }))
+    });
+
+    div()
+        .id("hour-table")
+        .flex()
+        .flex_col()
+        .flex_1()
+        .overflow_y_scroll()
+        .child(header)
+        .children(rows)
+}
@@ -0,0 +1,146 @@
+use crate::{
+    config::{CityConfig, HttpSettings, Settings},
+    export,
+};
+use chrono::Utc;
+use std::{
+    io::{self, BufRead, BufReader, Write},
+    net::{IpAddr, TcpListener, TcpStream},
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicBool, Ordering},
+    },
+    thread,
+    time::Duration,
+};
+
+// What GET /times reports on, replaced whenever the app saves its config
+#[derive(Default)]
+struct Published {
+    cities: Vec<CityConfig>,
+    with_overrides: bool,
+    include_people: bool,
+}
+
+// A read-only listener for dashboards and scripts; it stops when dropped
+pub struct HttpEndpoint {
+    settings: HttpSettings,
+    published: Arc<Mutex<Published>>,
+    stop: Arc<AtomicBool>,
+}
+
+impl HttpEndpoint {
+    pub fn start(settings: &HttpSettings) -> Result<Self, String> {
+        let address: IpAddr = settings
+            .address
+            .parse()
+            .map_err(|_| format!("{:?} isn't an IP address", settings.address))?;
+        // The times and city names aren't for the rest of the network
+        if !address.is_loopback() {
+            return Err(format!(
+                "refusing to listen on {}; use a loopback address like 127.0.0.1",
+                address
+            ));
+        }
+        let listener = TcpListener::bind((address, settings.port))
+            .and_then(|listener| listener.set_nonblocking(true).map(|()| listener))
+            .map_err(|err| format!("couldn't listen on {}:{}: {}", address, settings.port, err))?;
+
+        let published = Arc::new(Mutex::new(Published::default()));
+        let stop = Arc::new(AtomicBool::new(false));
+        thread::spawn({
+            let published = published.clone();
+            let stop = stop.clone();
+            move || serve(listener, &published, &stop)
+        });
+        Ok(HttpEndpoint {
+            settings: settings.clone(),
+            published,
+            stop,
+        })
+    }
+
+    pub fn settings(&self) -> &HttpSettings {
+        &self.settings
+    }
+
+    pub fn publish(&self, cities: Vec<CityConfig>, settings: &Settings) {
+        if let Ok(mut published) = self.published.lock() {
+            *published = Published {
+                cities,
+                with_overrides: settings.overrides_in_exports,
+                include_people: settings.include_people,
+            };
+        }
+    }
+}
+
+impl Drop for HttpEndpoint {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+// Polls rather than blocks in accept, so a stop is noticed within a tenth of a second
+fn serve(listener: TcpListener, published: &Mutex<Published>, stop: &AtomicBool) {
+    while !stop.load(Ordering::Relaxed) {
+        match listener.accept() {
+            Ok((stream, _)) => {
+                let _ = respond(stream, published);
+            }
+            Err(_) => thread::sleep(Duration::from_millis(100)),
+        }
+    }
+}
+
+fn respond(stream: TcpStream, published: &Mutex<Published>) -> io::Result<()> {
+    stream.set_nonblocking(false)?;
+    stream.set_read_timeout(Some(Duration::from_secs(2)))?;
+    let mut reader = BufReader::new(&stream);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    // Headers are read and ignored; there's nothing a client can ask for with them
+    let mut header = String::new();
+    while reader.read_line(&mut header)? > 2 {
+        header.clear();
+    }
+
+    let (status, body) = route(&request_line, published);
+    write!(
+        &stream,
+        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    )
+}
+
+fn route(request_line: &str, published: &Mutex<Published>) -> (&'static str, String) {
+    let mut parts = request_line.split_whitespace();
+    let (Some(method), Some(target)) = (parts.next(), parts.next()) else {
+        return ("400 Bad Request", error_body("malformed request"));
+    };
+    if method != "GET" {
+        return ("405 Method Not Allowed", error_body("only GET is served"));
+    }
+    match target.split('?').next().unwrap_or_default() {
+        "/times" => {
+            let Ok(published) = published.lock() else {
+                return ("500 Internal Server Error", error_body("state unavailable"));
+            };
+            let now = Utc::now();
+            let (snapshots, _) =
+                export::snapshot_cities(&published.cities, now, published.with_overrides);
+            (
+                "200 OK",
+                export::write_json(&snapshots, now, published.include_people),
+            )
+        }
+        "/health" => ("200 OK", r#"{"status":"ok"}"#.to_string()),
+        _ => ("404 Not Found", error_body("try /times or /health")),
+    }
+}
+
+fn error_body(message: &str) -> String {
+    serde_json::json!({ "error": message }).to_string()
+}
@@ -0,0 +1,263 @@
+use crate::relative::{DayBucket, RelativeDay};
+use serde::{Deserialize, Serialize};
+use time2rust::format;
+
+// Languages for generated text; everything else in the UI is English for now
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Language {
+    #[default]
+    English,
+    German,
+    Romanian,
+    Polish,
+}
+
+impl Language {
+    pub const ALL: [Language; 4] = [
+        Language::English,
+        Language::German,
+        Language::Romanian,
+        Language::Polish,
+    ];
+
+    // In the language itself, for the picker
+    pub fn name(self) -> &'static str {
+        match self {
+            Language::English => "English",
+            Language::German => "Deutsch",
+            Language::Romanian => "Română",
+            Language::Polish => "Polski",
+        }
+    }
+
+    fn decimal_separator(self) -> char {
+        match self {
+            Language::English => '.',
+            Language::German | Language::Romanian | Language::Polish => ',',
+        }
+    }
+}
+
+// CLDR plural categories; each language uses some of them
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Plural {
+    One,
+    Few,
+    Many,
+    Other,
+}
+
+// The category for a count with `fraction_digits` digits after the point, as CLDR
+// defines it: 1.5 is never "one", and Polish sends fractions to "other"
+pub fn plural(language: Language, count: u64, fraction_digits: u32) -> Plural {
+    let whole = fraction_digits == 0;
+    match language {
+        Language::English | Language::German => {
+            if count == 1 && whole {
+                Plural::One
+            } else {
+                Plural::Other
+            }
+        }
+        // 2–19 and their hundreds (102–119, …) are "few", as is 0; from 20 the
+        // noun takes "de"
+        Language::Romanian => {
+            if count == 1 && whole {
+                Plural::One
+            } else if !whole || count == 0 || (1..=19).contains(&(count % 100)) {
+                Plural::Few
+            } else {
+                Plural::Other
+            }
+        }
+        Language::Polish => {
+            let (last, last_two) = (count % 10, count % 100);
+            if !whole {
+                Plural::Other
+            } else if count == 1 {
+                Plural::One
+            } else if (2..=4).contains(&last) && !(12..=14).contains(&last_two) {
+                Plural::Few
+            } else {
+                Plural::Many
+            }
+        }
+    }
+}
+
+// "-5.5" in English, "-5,5" elsewhere, with the whole hours and the number of
+// digits after the point that the plural rules go by
+fn hours_number(language: Language, minutes: i32) -> (String, u64, u32) {
+    let text = format::diff_hours(minutes).replace('.', &language.decimal_separator().to_string());
+    let fraction_digits = text
+        .split_once(language.decimal_separator())
+        .map_or(0, |(_, fraction)| fraction.len() as u32);
+    (text, minutes.unsigned_abs() as u64 / 60, fraction_digits)
+}
+
+// "7 hours", "1 Stunde", "22 de ore", "5 godzin"; the sign stays on the number
+pub fn hours(language: Language, minutes: i32) -> String {
+    let (number, count, fraction_digits) = hours_number(language, minutes);
+    let unit = match (language, plural(language, count, fraction_digits)) {
+        (Language::English, Plural::One) => "hour",
+        (Language::English, _) => "hours",
+        (Language::German, Plural::One) => "Stunde",
+        (Language::German, _) => "Stunden",
+        (Language::Romanian, Plural::One) => "oră",
+        (Language::Romanian, Plural::Few) => "ore",
+        (Language::Romanian, _) => "de ore",
+        (Language::Polish, Plural::One) => "godzina",
+        (Language::Polish, Plural::Few | Plural::Other) => "godziny",
+        (Language::Polish, Plural::Many) => "godzin",
+    };
+    format!("{} {}", number, unit)
+}
+
+// The card's "Δ 7 hours" line
+pub fn diff_badge(language: Language, diff_minutes: i32) -> String {
+    format!("Δ {}", hours(language, diff_minutes))
+}
+
+fn bucket(language: Language, bucket: DayBucket) -> &'static str {
+    match (language, bucket) {
+        (Language::English, DayBucket::Night) => "night",
+        (Language::English, DayBucket::EarlyMorning) => "early morning",
+        (Language::English, DayBucket::Morning) => "morning",
+        (Language::English, DayBucket::Afternoon) => "afternoon",
+        (Language::English, DayBucket::Evening) => "evening",
+        (Language::English, DayBucket::LateNight) => "late evening",
+        (Language::German, DayBucket::Night) => "Nacht",
+        (Language::German, DayBucket::EarlyMorning) => "früh",
+        (Language::German, DayBucket::Morning) => "Vormittag",
+        (Language::German, DayBucket::Afternoon) => "Nachmittag",
+        (Language::German, DayBucket::Evening) => "Abend",
+        (Language::German, DayBucket::LateNight) => "spätabends",
+        (Language::Romanian, DayBucket::Night) => "noaptea",
+        (Language::Romanian, DayBucket::EarlyMorning) => "dimineața devreme",
+        (Language::Romanian, DayBucket::Morning) => "dimineața",
+        (Language::Romanian, DayBucket::Afternoon) => "după-amiaza",
+        (Language::Romanian, DayBucket::Evening) => "seara",
+        (Language::Romanian, DayBucket::LateNight) => "seara târziu",
+        (Language::Polish, DayBucket::Night) => "w nocy",
+        (Language::Polish, DayBucket::EarlyMorning) => "wcześnie rano",
+        (Language::Polish, DayBucket::Morning) => "rano",
+        (Language::Polish, DayBucket::Afternoon) => "po południu",
+        (Language::Polish, DayBucket::Evening) => "wieczorem",
+        (Language::Polish, DayBucket::LateNight) => "późnym wieczorem",
+    }
+}
+
+fn day(language: Language, day: RelativeDay) -> String {
+    match (language, day) {
+        (Language::English, RelativeDay::Yesterday) => "yesterday".to_string(),
+        (Language::English, RelativeDay::Today) => "today".to_string(),
+        (Language::English, RelativeDay::Tomorrow) => "tomorrow".to_string(),
+        (Language::English, RelativeDay::Days(days)) if days > 0 => format!("in {} days", days),
+        (Language::English, RelativeDay::Days(days)) => format!("{} days ago", -days),
+        (Language::German, RelativeDay::Yesterday) => "gestern".to_string(),
+        (Language::German, RelativeDay::Today) => "heute".to_string(),
+        (Language::German, RelativeDay::Tomorrow) => "morgen".to_string(),
+        (Language::German, RelativeDay::Days(days)) if days > 0 => format!("in {} Tagen", days),
+        (Language::German, RelativeDay::Days(days)) => format!("vor {} Tagen", -days),
+        (Language::Romanian, RelativeDay::Yesterday) => "ieri".to_string(),
+        (Language::Romanian, RelativeDay::Today) => "azi".to_string(),
+        (Language::Romanian, RelativeDay::Tomorrow) => "mâine".to_string(),
+        (Language::Romanian, RelativeDay::Days(days)) if days > 0 => {
+            format!("peste {}", romanian_days(days))
+        }
+        (Language::Romanian, RelativeDay::Days(days)) => format!("acum {}", romanian_days(-days)),
+        // Never one day here, and every other whole count takes "dni"
+        (Language::Polish, RelativeDay::Yesterday) => "wczoraj".to_string(),
+        (Language::Polish, RelativeDay::Today) => "dziś".to_string(),
+        (Language::Polish, RelativeDay::Tomorrow) => "jutro".to_string(),
+        (Language::Polish, RelativeDay::Days(days)) if days > 0 => format!("za {} dni", days),
+        (Language::Polish, RelativeDay::Days(days)) => format!("{} dni temu", -days),
+    }
+}
+
+// "3 zile", "22 de zile"
+fn romanian_days(days: i64) -> String {
+    match plural(Language::Romanian, days.unsigned_abs(), 0) {
+        Plural::One => format!("{} zi", days),
+        Plural::Other => format!("{} de zile", days),
+        _ => format!("{} zile", days),
+    }
+}
+
+// "early morning tomorrow" / "morgen früh"; word order differs per language
+pub fn relative_phrase(language: Language, part: DayBucket, relative: RelativeDay) -> String {
+    match language {
+        Language::English => format!("{} {}", bucket(language, part), day(language, relative)),
+        Language::German | Language::Romanian | Language::Polish => {
+            format!("{} {}", day(language, relative), bucket(language, part))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hours_in_every_language() {
+        let counts = [0, 1, 2, 5, 22];
+        let expected = [
+            (
+                Language::English,
+                ["0 hours", "1 hour", "2 hours", "5 hours", "22 hours"],
+            ),
+            (
+                Language::German,
+                [
+                    "0 Stunden",
+                    "1 Stunde",
+                    "2 Stunden",
+                    "5 Stunden",
+                    "22 Stunden",
+                ],
+            ),
+            (
+                Language::Romanian,
+                ["0 ore", "1 oră", "2 ore", "5 ore", "22 de ore"],
+            ),
+            (
+                Language::Polish,
+                [
+                    "0 godzin",
+                    "1 godzina",
+                    "2 godziny",
+                    "5 godzin",
+                    "22 godziny",
+                ],
+            ),
+        ];
+        for (language, words) in expected {
+            let got: Vec<String> = counts
+                .iter()
+                .map(|count| hours(language, count * 60))
+                .collect();
+            assert_eq!(got, words, "{:?}", language);
+        }
+    }
+
+    #[test]
+    fn fractions_and_signs() {
+        assert_eq!(hours(Language::English, 330), "5.5 hours");
+        assert_eq!(hours(Language::German, 330), "5,5 Stunden");
+        assert_eq!(hours(Language::Romanian, 90), "1,5 ore");
+        assert_eq!(hours(Language::Polish, 90), "1,5 godziny");
+        assert_eq!(hours(Language::English, -60), "-1 hour");
+        assert_eq!(diff_badge(Language::Polish, -300), "Δ -5 godzin");
+    }
+
+    #[test]
+    fn plural_categories_past_twenty() {
+        assert_eq!(plural(Language::Romanian, 101, 0), Plural::Few);
+        assert_eq!(plural(Language::Romanian, 120, 0), Plural::Other);
+        assert_eq!(plural(Language::Polish, 12, 0), Plural::Many);
+        assert_eq!(plural(Language::Polish, 24, 0), Plural::Few);
+        assert_eq!(plural(Language::Polish, 112, 0), Plural::Many);
+        assert_eq!(plural(Language::English, 1, 1), Plural::Other);
+    }
+}
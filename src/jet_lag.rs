@@ -0,0 +1,147 @@
+use time2rust::format;
+
+// Body-clock shift for a trip, picking whichever direction around the world is shorter
+pub fn shift_minutes(home_offset: i32, destination_offset: i32) -> i32 {
+    let shift = (destination_offset - home_offset).rem_euclid(24 * 60);
+    // Exactly 12 hours either way counts as eastward
+    if shift > 12 * 60 {
+        shift - 24 * 60
+    } else {
+        shift
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Adjustment {
+    pub days: i32,
+    pub minutes_per_day: i32,
+    pub earlier: bool, // eastward trips mean sleeping earlier
+}
+
+// Roughly an hour a day, spread evenly so half-hour zones don't leave a remainder
+pub fn adjustment(shift_minutes: i32) -> Option<Adjustment> {
+    if shift_minutes == 0 {
+        return None;
+    }
+    let total = shift_minutes.abs();
+    let days = (total + 59) / 60;
+    Some(Adjustment {
+        days,
+        minutes_per_day: (total + days - 1) / days,
+        earlier: shift_minutes > 0,
+    })
+}
+
+// "+7h, eastward" or "−5.5h, westward"
+pub fn describe_shift(shift_minutes: i32) -> String {
+    if shift_minutes == 0 {
+        return "No time difference".to_string();
+    }
+    let hours = format::diff_hours(shift_minutes.abs());
+    if shift_minutes > 0 {
+        format!("+{}h, eastward", hours)
+    } else {
+        format!("−{}h, westward", hours)
+    }
+}
+
+// "Shift sleep 1h earlier per day for 5 days before departure"
+pub fn describe_adjustment(adjustment: &Adjustment) -> String {
+    let step = if adjustment.minutes_per_day % 60 == 0 {
+        format!("{}h", adjustment.minutes_per_day / 60)
+    } else {
+        format!("{}m", adjustment.minutes_per_day)
+    };
+    format!(
+        "Shift sleep {} {} per day for {} day{} before departure",
+        step,
+        if adjustment.earlier {
+            "earlier"
+        } else {
+            "later"
+        },
+        adjustment.days,
+        if adjustment.days == 1 { "" } else { "s" }
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn eastward_and_westward_trips() {
+        // New York to Berlin in summer, and back
+        assert_eq!(shift_minutes(-240, 120), 360);
+        assert_eq!(shift_minutes(120, -240), -360);
+        assert_eq!(
+            adjustment(360),
+            Some(Adjustment {
+                days: 6,
+                minutes_per_day: 60,
+                earlier: true,
+            })
+        );
+        assert_eq!(
+            adjustment(-360),
+            Some(Adjustment {
+                days: 6,
+                minutes_per_day: 60,
+                earlier: false,
+            })
+        );
+        assert_eq!(describe_shift(360), "+6h, eastward");
+        assert_eq!(describe_shift(-360), "−6h, westward");
+    }
+
+    #[test]
+    fn austin_to_auckland_goes_the_short_way_round() {
+        // CDT to NZST is 17h ahead on the clock, but 7h back for the body
+        assert_eq!(shift_minutes(-300, 720), -420);
+        // CST to NZDT: 19h ahead, 5h back
+        assert_eq!(shift_minutes(-360, 780), -300);
+        assert_eq!(
+            describe_adjustment(&adjustment(-420).unwrap()),
+            "Shift sleep 1h later per day for 7 days before departure"
+        );
+    }
+
+    #[test]
+    fn twelve_hours_counts_as_eastward() {
+        assert_eq!(shift_minutes(0, 720), 720);
+        assert_eq!(shift_minutes(720, 0), 720);
+    }
+
+    #[test]
+    fn fractional_hour_destination() {
+        // Austin (CDT) to Kolkata, 10.5h east
+        let shift = shift_minutes(-300, 330);
+        assert_eq!(shift, 630);
+        let adjustment = adjustment(shift).unwrap();
+        assert_eq!(
+            adjustment,
+            Adjustment {
+                days: 11,
+                minutes_per_day: 58,
+                earlier: true,
+            }
+        );
+        assert_eq!(describe_shift(shift), "+10.5h, eastward");
+        assert_eq!(
+            describe_adjustment(&adjustment),
+            "Shift sleep 58m earlier per day for 11 days before departure"
+        );
+        // Half an hour is a single day
+        assert_eq!(
+            describe_adjustment(&super::adjustment(-30).unwrap()),
+            "Shift sleep 30m later per day for 1 day before departure"
+        );
+    }
+
+    #[test]
+    fn no_difference_needs_no_adjustment() {
+        assert_eq!(shift_minutes(60, 60), 0);
+        assert_eq!(adjustment(0), None);
+        assert_eq!(describe_shift(0), "No time difference");
+    }
+}
@@ -0,0 +1,162 @@
+use crate::{WorldTime, WorldTimeApp, config::Settings, zone};
+use chrono::{DateTime, Utc};
+use gpui::{Context, MouseButton, div, prelude::*, px, rgb};
+use gpui_component::{ActiveTheme as _, h_flex, v_flex};
+use std::time::{Duration, Instant};
+use time2rust::format;
+
+// Which slide is up and since when; the poll loop advances it
+pub struct Kiosk {
+    pub slide: usize,
+    pub shown_at: Instant,
+    // Whether kiosk mode put the window into full screen, so leaving undoes only that
+    pub entered_fullscreen: bool,
+}
+
+impl Kiosk {
+    pub fn new(entered_fullscreen: bool) -> Self {
+        Kiosk {
+            slide: 0,
+            shown_at: Instant::now(),
+            entered_fullscreen,
+        }
+    }
+
+    // Moves on once the dwell time is up; true when the slide changed
+    pub fn advance(&mut self, slide_count: usize, dwell: Duration) -> bool {
+        if self.shown_at.elapsed() < dwell {
+            return false;
+        }
+        self.slide = (self.slide + 1) % slide_count.max(1);
+        self.shown_at = Instant::now();
+        true
+    }
+}
+
+// One slide per city, then an overview of all of them if enabled
+pub fn slide_count(cities: usize, show_all_slide: bool) -> usize {
+    if show_all_slide && cities > 1 {
+        cities + 1
+    } else {
+        cities
+    }
+}
+
+pub fn kiosk_view(
+    slide: usize,
+    cities: Vec<WorldTime>,
+    now: DateTime<Utc>,
+    cx: &mut Context<WorldTimeApp>,
+) -> impl IntoElement {
+    div()
+        .absolute()
+        .inset_0()
+        .flex()
+        .items_center()
+        .justify_center()
+        .bg(cx.theme().background)
+        .on_mouse_down(
+            MouseButton::Left,
+            cx.listener(|this, _, window, cx| this.exit_kiosk(window, cx)),
+        )
+        .children(header_line(now, cx))
+        .child(match cities.get(slide) {
+            Some(city) => city_slide(city, now).into_any_element(),
+            None => all_cities_slide(&cities, now).into_any_element(),
+        })
+}
+
+// Off by default, since a wall display rarely wants it; the header's own title
+// and UTC clock when asked for
+fn header_line(now: DateTime<Utc>, cx: &mut Context<WorldTimeApp>) -> Option<impl IntoElement> {
+    let settings = cx.global::<Settings>();
+    if !settings.kiosk.show_header {
+        return None;
+    }
+    let header = &settings.header;
+    Some(
+        h_flex()
+            .absolute()
+            .top_4()
+            .left_6()
+            .right_6()
+            .justify_between()
+            .child(
+                div()
+                    .child(header.title().to_string())
+                    .text_xl()
+                    .font_weight(gpui::FontWeight::BOLD),
+            )
+            .children(header.utc_clock.then(|| {
+                div()
+                    .child(format!("{} UTC", format::clock_time(now.time(), false)))
+                    .text_xl()
+                    .text_color(rgb(0x6b7280))
+            })),
+    )
+}
+
+// In the chosen clock format, without seconds even if the cards show them
+fn kiosk_time(city: &WorldTime, now: DateTime<Utc>) -> String {
+    let local = now.with_timezone(&city.tz).time();
+    city.options
+        .clock_format
+        .format(local, zone::utc_offset_minutes(city.tz, now), false)
+}
+
+fn city_slide(city: &WorldTime, now: DateTime<Utc>) -> impl IntoElement {
+    let local = now.with_timezone(&city.tz);
+    v_flex()
+        .items_center()
+        .gap_4()
+        .child(
+            div()
+                .child(city.name.clone())
+                .text_size(px(56.0))
+                .text_color(rgb(0x6b7280)),
+        )
+        .child(
+            div()
+                .child(kiosk_time(city, now))
+                .text_size(px(200.0))
+                .font_weight(gpui::FontWeight::BOLD)
+                .text_color(rgb(0x111827)),
+        )
+        .child(
+            div()
+                .child(format::long_date(local.date_naive()))
+                .text_size(px(40.0))
+                .text_color(rgb(0x6b7280)),
+        )
+}
+
+fn all_cities_slide(cities: &[WorldTime], now: DateTime<Utc>) -> impl IntoElement {
+    h_flex()
+        .flex_wrap()
+        .justify_center()
+        .gap_16()
+        .children(cities.iter().map(|city| {
+            let local = now.with_timezone(&city.tz);
+            v_flex()
+                .items_center()
+                .child(
+                    div()
+                        .child(city.name.clone())
+                        .text_size(px(32.0))
+                        .text_color(rgb(0x6b7280)),
+                )
+                .child(
+                    div()
+                        .child(kiosk_time(city, now))
+                        .text_size(px(96.0))
+                        .font_weight(gpui::FontWeight::BOLD)
+                        .text_color(rgb(0x111827)),
+                )
+                .child(
+                    div()
+                        .child(format::weekday_date(local.date_naive()))
+                        .text_size(px(24.0))
+                        .text_color(rgb(0x6b7280)),
+                )
+        }))
+}
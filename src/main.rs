@@ -1,239 +1,5070 @@
-use chrono::Utc;
+mod add_city;
+mod alarms;
+mod archive;
+mod backup;
+mod browser;
+mod calendars;
+mod card_detail;
+mod cleanup;
+mod cli;
+mod clock_format;
+mod commands;
+mod compare;
+mod config;
+mod config_backups;
+mod config_store;
+mod confirm;
+mod convert;
+mod countdown;
+mod day_stats;
+mod deep_link;
+mod density;
+mod diagnostics;
+mod diff_style;
+mod display_options;
+mod export;
+mod floating_card;
+mod hotkey;
+mod hour_table;
+mod http_endpoint;
+mod i18n;
+mod jet_lag;
+mod kiosk;
+mod marker;
+mod mini_widget;
+mod ntp;
+mod offset_override;
+mod onboarding;
+mod ordering;
+mod overlap_finder;
+mod paste;
+mod people;
+mod pinned_events;
+mod places;
+mod planner;
+mod profiles;
+mod quiet_hours;
+mod relative;
+mod same_time;
+mod settings_panel;
+mod shortcut_overlay;
+mod single_instance;
+mod snooze;
+mod stacks;
+mod stdin_control;
+mod sun;
+mod ticker;
+mod timeline;
+mod undo;
+mod week;
+mod whats_new;
+mod window_state;
+mod workday;
+mod world_map;
+mod zone;
+mod zone_aliases;
+mod zoom;
+
+use alarms::{Alarm, Repeat};
+use calendars::AltCalendar;
+use chrono::{DateTime, Local, NaiveDate, NaiveTime, Utc};
+use chrono_tz::Tz;
+use clap::Parser;
+use cli::Cli;
+use commands::AppCommand;
+use config::{CityConfig, ClockSyncSettings, Config, Settings};
+use config_store::ConfigStore;
+use convert::Conversion;
+use countdown::{Countdown, CountdownConfig, CountdownEvent};
+use deep_link::DeepLink;
+use density::{Density, Tier};
+use diff_style::DiffStyle;
+use display_options::{DisplayOptions, DisplayOverrides};
+use export::{CitySnapshot, DisplayInstant, WeeklySeries};
+use floating_card::FloatingCardConfig;
 use gpui::{
-    App, Application, Bounds, Context, Entity, SharedString, TitlebarOptions, Window, WindowBounds,
-    WindowOptions, div, prelude::*, px, rgb, size,
+    Animation, AnimationExt as _, AnyElement, AnyWindowHandle, App, Application, Bounds,
+    ClipboardItem, Context, DismissEvent, Entity, EntityId, EventEmitter, FocusHandle, Focusable,
+    KeyDownEvent, MouseButton, PathPromptOptions, Pixels, SharedString, Task, TitlebarOptions,
+    Window, WindowKind, WindowOptions, anchored, canvas, deferred, div, ease_out_quint, prelude::*,
+    px, rgb, size,
+};
+use gpui_component::{
+    ActiveTheme as _, Root, Sizable, WindowExt as _,
+    button::{Button, ButtonVariants as _},
+    h_flex,
+    input::{Input, InputEvent, InputState},
+    menu::{ContextMenuExt as _, PopupMenu, PopupMenuItem},
+    notification::Notification,
+    slider::{Slider, SliderEvent, SliderState},
+    tooltip::Tooltip,
+};
+use hotkey::{GlobalShortcut, ShortcutAction};
+use http_endpoint::HttpEndpoint;
+use kiosk::Kiosk;
+use offset_override::OffsetOverride;
+use onboarding::{Onboarding, OnboardingEvent};
+use places::Coordinates;
+use planner::{DoNotDisturb, OverlapScore, WorkStatus, WorkWeek, WorkingHours};
+use profiles::{Layout, Profile};
+use quiet_hours::{Suppressed, WhenQuiet};
+use relative::RelativeDay;
+use shortcut_overlay::ShortcutRow;
+use single_instance::Instance;
+use std::{
+    cell::Cell,
+    path::Path,
+    process::ExitCode,
+    rc::Rc,
+    sync::mpsc::{self, Receiver},
+    time::{Duration, Instant},
+};
+use stdin_control::ControlCommand;
+use ticker::{ClockWatch, TickerHealth, UpdateInterval};
+use time2rust::card::{
+    card_frame, city_name_header, time_difference_label, time_display, timezone_display,
 };
-use gpui_component::{ActiveTheme as _, Sizable, tag::Tag};
+use time2rust::format;
+use timeline::{TimelineAxis, TimelineBand};
+use undo::{Snapshot, UndoStack};
+use week::Weekend;
+use window_state::WindowState;
 
 #[derive(Debug, Clone)]
 pub struct WorldTime {
+    id: String, // stable, for exports, links and scripts
     name: String,
-    time: String,        // HH:MM format
-    diff_hours: i32,     // hours difference from home time
+    time: String, // HH:MM format
+    local_time: NaiveTime,
+    diff_minutes: i32,             // minutes difference from home time
+    standard_shift: i32,           // off the zone's standard offset, like +60 in summer time
+    standard_diff_minutes: i32,    // between the two standard offsets, summer time left out
+    display: DisplayOverrides,     // this card's own choices
+    options: DisplayOptions,       // resolved with the settings on every refresh
+    diff_flash: Option<DiffStyle>, // names a newly picked style for a moment
+    diff_flashes: usize,
     is_home: bool,       // true if this is your home location
     timezone_id: String, // like "Europe/Berlin" or "America/Chicago"
+    tz: Tz,
+    alias_of: Option<&'static str>, // the current id when `timezone_id` is an old spelling
+    coordinates: Option<Coordinates>, // only when set by hand
+    working_hours: Option<WorkingHours>,
+    do_not_disturb: Option<DoNotDisturb>,
+    weekend: Option<Weekend>,
+    in_do_not_disturb: bool,
+    people: Vec<String>,
+    calendar: Option<AltCalendar>,
+    calendar_line: Option<String>, // the local date in `calendar`
+    offset_override: Option<OffsetOverride>,
+    override_active: bool, // shown as a badge; lapses by itself
+    snoozed_until: Option<NaiveDate>,
+    snoozed: bool, // refreshed with the time, so the snooze lapses by itself
+    work_status: Option<WorkStatus>, // only while planning
+    timeline: Option<TimelineBand>, // when enabled in settings
+    call_hint: Option<String>, // on non-home cards
+    pinned_lines: Vec<String>, // one per pinned event
+    countdowns: Vec<(EntityId, String)>, // pinned to this card, soonest first
+    countdowns_open: bool, // all of them rather than the soonest
+    relative_phrase: Option<String>, // "early morning tomorrow", when enabled
+    relative_day: RelativeDay, // the calendar day against home's, as a badge
+    day_stats: Option<String>, // share of the day gone, time until morning
+    workday: Option<String>, // "started 2h ago · ends in 6h"
+    mirror: Option<String>, // "their 17:00 = your 10:00"
+    marker_line: Option<String>, // "started 03:12 their time", when asked for
+    ephemeral: bool, // from TIME2RUST_CITIES or --city, not saved
+    saved: Option<Box<CityConfig>>, // the saved city this one overrides, if any
+    last_interacted: DateTime<Utc>, // a city from before this was kept counts from now
+    overlap: Option<OverlapScore>, // against home's working week, on non-home cards
+    overlap_inputs: Option<(NaiveDate, WorkWeek, WorkWeek)>, // what `overlap` was scored from
+    hidden: bool,
+    zone_abbreviation: String,        // like "CET", refreshed with the time
+    same_time_as: Option<String>,     // another card showing this time from a different zone
+    focus_handle: FocusHandle,        // cards are tab stops
+    bounds: Rc<Cell<Bounds<Pixels>>>, // where the card was last painted
+    keyboard_menu: Option<Entity<PopupMenu>>,
 }
 
 impl WorldTime {
-    fn new(name: &str, timezone_id: &str, is_home: bool, home_offset: i32) -> Self {
-        WorldTime {
-            name: name.to_string(),
-            time: Self::calculate_time_from_austin(home_offset),
-            diff_hours: home_offset,
-            is_home,
-            timezone_id: timezone_id.to_string(),
+    fn new(config: &CityConfig, tz: Tz, home_tz: Tz, focus_handle: FocusHandle) -> Self {
+        let mut city = WorldTime {
+            id: if config.id.is_empty() {
+                config::new_city_id()
+            } else {
+                config.id.clone()
+            },
+            name: config.name.clone(),
+            time: String::new(),
+            local_time: NaiveTime::MIN,
+            diff_minutes: 0,
+            standard_shift: 0,
+            standard_diff_minutes: 0,
+            display: config.display,
+            options: DisplayOptions::default(),
+            diff_flash: None,
+            diff_flashes: 0,
+            is_home: config.home,
+            timezone_id: config.timezone.clone(),
+            tz,
+            alias_of: zone_aliases::canonical_id(&config.timezone),
+            coordinates: config.coordinates,
+            working_hours: config.working_hours,
+            do_not_disturb: config.do_not_disturb,
+            weekend: config.weekend,
+            in_do_not_disturb: false,
+            people: config.people.clone(),
+            calendar: config.calendar,
+            calendar_line: None,
+            offset_override: config.offset_override,
+            override_active: false,
+            snoozed_until: config.snoozed_until,
+            snoozed: false,
+            work_status: None,
+            timeline: None,
+            call_hint: None,
+            pinned_lines: Vec::new(),
+            countdowns: Vec::new(),
+            countdowns_open: false,
+            relative_phrase: None,
+            relative_day: RelativeDay::Today,
+            day_stats: None,
+            workday: None,
+            mirror: None,
+            marker_line: None,
+            ephemeral: config.ephemeral,
+            saved: config.saved.clone(),
+            last_interacted: config.last_interacted.unwrap_or_else(Utc::now),
+            hidden: config.hidden,
+            overlap: None,
+            overlap_inputs: None,
+            zone_abbreviation: String::new(),
+            same_time_as: None,
+            focus_handle,
+            bounds: Rc::default(),
+            keyboard_menu: None,
+        };
+        let now = Utc::now();
+        city.update_time(
+            now,
+            zone::utc_offset_minutes(home_tz, now),
+            zone::standard_offset_minutes(home_tz, now),
+        );
+        city
+    }
+
+    // The same menu from the Menu key or Shift+F10, over the card's middle; the
+    // right-click one only opens at the mouse
+    fn open_keyboard_menu(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let items = MenuItems::for_card(self, cx);
+        let menu = PopupMenu::build(window, cx, move |menu, _, _| items.build(menu));
+        cx.subscribe_in(&menu, window, |this, _, _: &DismissEvent, window, cx| {
+            this.keyboard_menu = None;
+            window.focus(&this.focus_handle);
+            cx.notify();
+        })
+        .detach();
+        menu.focus_handle(cx).focus(window);
+        self.keyboard_menu = Some(menu);
+        cx.notify();
+    }
+
+    // The mirror wording compares clock times, so it stays on the live difference
+    fn anchored_to_standard(&self) -> bool {
+        self.options.standard_diff && self.options.diff_style != DiffStyle::Mirror
+    }
+
+    fn shown_diff_minutes(&self) -> i32 {
+        if self.anchored_to_standard() {
+            self.standard_diff_minutes
+        } else {
+            self.diff_minutes
+        }
+    }
+
+    fn diff_text(&self) -> String {
+        let text = self.options.diff_style.describe(
+            self.shown_diff_minutes(),
+            self.local_time,
+            self.options.language,
+        );
+        if self.anchored_to_standard() {
+            format!("{} (standard time)", text)
+        } else {
+            text
         }
     }
 
-    // Calculate Austin's time (UTC-6) as the base
-    fn get_austin_time() -> chrono::DateTime<Utc> {
-        Utc::now() + chrono::Duration::hours(-6) // Austin is UTC-6
+    fn resolve_options(&mut self, settings: &Settings) {
+        self.options = display_options::resolve(settings, &self.display);
+    }
+
+    // Steps this card to the next wording and names it briefly beside the value
+    fn cycle_diff_style(&mut self, cx: &mut Context<Self>) {
+        let style = self.options.diff_style.next();
+        self.display.diff_style = Some(style);
+        self.options.diff_style = style;
+        self.diff_flash = Some(style);
+        self.diff_flashes += 1;
+        let flash = self.diff_flashes;
+        cx.spawn(async move |this, cx| {
+            cx.background_executor()
+                .timer(Duration::from_millis(1500))
+                .await;
+            this.update(cx, |this, cx| {
+                if this.diff_flashes == flash {
+                    this.diff_flash = None;
+                    cx.notify();
+                }
+            })
+            .ok();
+        })
+        .detach();
+        cx.emit(CardEvent::DiffStyleChanged);
+        cx.notify();
+    }
+
+    // The soonest pinned countdown, with how many more; a click lists them all,
+    // each with a way off the card
+    fn countdown_lines(&self, cx: &mut Context<Self>) -> Option<impl IntoElement> {
+        let more = self.countdowns.len().checked_sub(1)?;
+        let open = self.countdowns_open;
+        let shown = if open { self.countdowns.len() } else { 1 };
+        Some(
+            div().flex().flex_col().items_center().children(
+                self.countdowns
+                    .iter()
+                    .take(shown)
+                    .enumerate()
+                    .map(|(index, (id, line))| {
+                        let id = *id;
+                        let text = match (open, more) {
+                            (false, 1..) => format!("⏳ {} · +{} more", line, more),
+                            _ => format!("⏳ {}", line),
+                        };
+                        h_flex()
+                            .gap_1()
+                            .child(
+                                div()
+                                    .id(("countdown-line", index))
+                                    .child(text)
+                                    .text_xs()
+                                    .text_color(rgb(0xb45309))
+                                    .on_click(cx.listener(|this, _, _, cx| {
+                                        cx.stop_propagation();
+                                        this.countdowns_open = !this.countdowns_open;
+                                        cx.notify();
+                                    })),
+                            )
+                            .when(open, |this| {
+                                this.child(
+                                    Button::new(("unpin-countdown", index))
+                                        .label("Unpin")
+                                        .xsmall()
+                                        .ghost()
+                                        .on_click(cx.listener(move |this, _, _, cx| {
+                                            cx.stop_propagation();
+                                            this.countdowns_open = false;
+                                            cx.emit(CardEvent::UnpinCountdown(id));
+                                        })),
+                                )
+                            })
+                    }),
+            ),
+        )
+    }
+
+    // Click or right-click the value to try the next wording
+    fn diff_control(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        h_flex()
+            .id("diff")
+            .gap_1()
+            .cursor_pointer()
+            .child(time_difference_label(
+                self.diff_text(),
+                self.shown_diff_minutes(),
+            ))
+            .children(
+                self.diff_flash
+                    .map(|style| call_hint_display(style.label())),
+            )
+            .on_click(cx.listener(|this, _, _, cx| {
+                cx.stop_propagation();
+                this.cycle_diff_style(cx);
+            }))
+            .on_mouse_down(
+                MouseButton::Right,
+                cx.listener(|this, _, _, cx| {
+                    cx.stop_propagation();
+                    this.cycle_diff_style(cx);
+                }),
+            )
+    }
+
+    // `home_offset` is home's offset as displayed, its own override included;
+    // `home_standard` is its zone's standard offset, which no override touches
+    fn update_time(&mut self, now: DateTime<Utc>, home_offset: i32, home_standard: i32) {
+        let shift = offset_override::active_minutes(self.offset_override.as_ref(), self.tz, now);
+        self.override_active = shift != 0;
+        self.snoozed = snooze::is_active(self.snoozed_until, self.tz, now);
+        let local = (now + chrono::Duration::minutes(shift as i64)).with_timezone(&self.tz);
+        let offset = zone::utc_offset_minutes(self.tz, now) + shift;
+        self.time =
+            self.options
+                .clock_format
+                .format(local.time(), offset, self.options.show_seconds);
+        self.local_time = local.time();
+        self.zone_abbreviation = local.format("%Z").to_string();
+        // Every tick rather than at midnight only, so it also follows the planner
+        self.calendar_line = self
+            .calendar
+            .map(|calendar| calendar.format(local.date_naive()));
+        self.diff_minutes = offset - home_offset;
+        self.standard_shift = zone::standard_shift_minutes(self.tz, now);
+        self.standard_diff_minutes =
+            zone::utc_offset_minutes(self.tz, now) - self.standard_shift - home_standard;
+    }
+
+    // What the card says in one sentence, for its tooltip: "Berlin, 21:45, 7 hours
+    // ahead of home, CET (Europe/Berlin)". Built from the fields the ticker
+    // refreshes, so it changes no more often than the times do.
+    fn description(&self) -> String {
+        let relation = if self.is_home {
+            "home".to_string()
+        } else {
+            format::describe_diff(self.diff_minutes)
+        };
+        format!(
+            "{}, {}, {}, {} ({})",
+            self.name, self.time, relation, self.zone_abbreviation, self.timezone_id
+        )
+    }
+
+    // Set by hand, or looked up from the name and zone
+    fn place(&self) -> Option<Coordinates> {
+        self.coordinates
+            .or_else(|| places::lookup(&self.name, &self.timezone_id))
     }
 
-    // Calculate time relative to Austin's time
-    fn calculate_time_from_austin(austin_offset: i32) -> String {
-        let austin_time = Self::get_austin_time();
-        let adjusted_time = austin_time + chrono::Duration::hours(austin_offset as i64);
-        adjusted_time.format("%H:%M").to_string()
+    fn maps_url(&self) -> String {
+        places::maps_url(&self.name, self.place())
+    }
+
+    fn to_config(&self) -> CityConfig {
+        CityConfig {
+            id: self.id.clone(),
+            coordinates: self.coordinates,
+            working_hours: self.working_hours,
+            do_not_disturb: self.do_not_disturb,
+            weekend: self.weekend,
+            people: self.people.clone(),
+            calendar: self.calendar,
+            display: self.display,
+            // A lapsed override is dropped on the next save
+            offset_override: self
+                .offset_override
+                .filter(|offset_override| offset_override.is_active(self.tz, Utc::now())),
+            snoozed_until: self.snoozed_until.filter(|_| self.snoozed),
+            last_interacted: Some(self.last_interacted),
+            hidden: self.hidden,
+            ephemeral: self.ephemeral,
+            saved: self.saved.clone(),
+            ..CityConfig::new(&self.name, &self.timezone_id, self.is_home)
+        }
+    }
+}
+
+// What a card's menu needs, taken from the card so the menu can be built outside it
+#[derive(Clone)]
+struct MenuItems {
+    card: Entity<WorldTime>,
+    commands: Vec<AppCommand>,
+    is_home: bool,
+    maps_url: String,
+}
+
+impl MenuItems {
+    fn for_card(city: &WorldTime, cx: &mut Context<WorldTime>) -> Self {
+        MenuItems {
+            card: cx.entity(),
+            commands: commands::card_menu(&city.name, city.snoozed),
+            is_home: city.is_home,
+            maps_url: city.maps_url(),
+        }
     }
 
-    fn update_time(&mut self) {
-        self.time = Self::calculate_time_from_austin(self.diff_hours);
+    fn build(&self, menu: PopupMenu) -> PopupMenu {
+        let menu = self.commands.iter().fold(menu, |menu, command| {
+            let card = self.card.clone();
+            let command = command.clone();
+            // Nothing to do for the home card itself
+            let disabled = self.is_home && matches!(command, AppCommand::SetHome(_));
+            menu.item(
+                PopupMenuItem::new(command.menu_label())
+                    .disabled(disabled)
+                    .on_click(move |_, _, cx| {
+                        let command = command.clone();
+                        card.update(cx, |_, cx| cx.emit(CardEvent::Command(command)))
+                    }),
+            )
+        });
+        let maps_url = self.maps_url.clone();
+        menu.separator().item(
+            PopupMenuItem::new("Open in Maps").on_click(move |_, window, cx| {
+                if let Err(err) = browser::open_url(&maps_url) {
+                    window.push_notification(
+                        Notification::error(format!("Couldn't open maps: {}", err)),
+                        cx,
+                    );
+                }
+            }),
+        )
     }
 }
 
-// Extracted component functions for WorldTime
-fn city_name_header(name: &str, is_home: bool) -> impl IntoElement {
+pub enum CardEvent {
+    OpenDetail,
+    Command(AppCommand),
+    UpdateZoneId,
+    DiffStyleChanged,
+    UnpinCountdown(EntityId),
+}
+
+impl EventEmitter<CardEvent> for WorldTime {}
+
+// "+1 day" when the card's calendar day isn't home's
+fn day_badge(day: RelativeDay) -> impl IntoElement {
+    div()
+        .child(format::day_offset(day.days()))
+        .px_1()
+        .rounded(px(4.0))
+        .bg(rgb(0xdbeafe))
+        .text_xs()
+        .text_color(rgb(0x1e40af))
+}
+
+// Cards that read alike can still part ways at the next DST change
+fn same_time_marker(name: &str, other: &str) -> impl IntoElement {
+    let tooltip = format!(
+        "Same time as {} right now, but different timezone rules",
+        other
+    );
+    div()
+        .id(SharedString::from(format!("same-time-{}", name)))
+        .child("=")
+        .px_1()
+        .rounded(px(4.0))
+        .bg(rgb(0xe5e7eb))
+        .text_xs()
+        .font_weight(gpui::FontWeight::BOLD)
+        .text_color(rgb(0x4b5563))
+        .tooltip(move |window, cx| Tooltip::new(tooltip.clone()).build(window, cx))
+}
+
+fn work_status_display(status: WorkStatus) -> impl IntoElement {
+    let (label, color) = match status {
+        WorkStatus::Working => ("Working hours", rgb(0x22c55e)),
+        WorkStatus::Edge => ("Early or late", rgb(0xf59e0b)),
+        WorkStatus::Off => ("Outside hours", rgb(0xef4444)),
+        WorkStatus::DoNotDisturb => ("Do not disturb", rgb(0x7c3aed)),
+    };
     div()
         .flex()
         .items_center()
-        .gap_2()
-        .child(
-            div()
-                .child(name.to_string())
-                .text_lg()
-                .font_weight(gpui::FontWeight::BOLD)
-                .text_color(if is_home {
-                    rgb(0x3b82f6)
-                } else {
-                    rgb(0x111827)
-                }),
-        )
-        .children(is_home.then(|| Tag::secondary().small().child("Home")))
+        .gap_1()
+        .child(div().size(px(8.0)).rounded_full().bg(color))
+        .child(div().child(label).text_xs().text_color(color))
 }
 
-fn time_display(time: &str) -> impl IntoElement {
-    div().flex().items_center().gap_2().child(
-        div()
-            .child(time.to_string())
-            .text_3xl()
-            .font_weight(gpui::FontWeight::BOLD)
-            .text_color(rgb(0x111827)),
-    )
+fn do_not_disturb_marker() -> impl IntoElement {
+    div()
+        .child("🌙 do not disturb")
+        .text_xs()
+        .font_weight(gpui::FontWeight::BOLD)
+        .text_color(rgb(0x7c3aed))
 }
 
-fn time_difference_display(diff_hours: i32) -> impl IntoElement {
+// "+1h ST": the zone is off its standard offset, as in summer time
+fn standard_shift_chip(name: &str, tz: Tz, minutes: i32) -> impl IntoElement {
+    let tooltip = zone::describe_standard_shift(tz, Utc::now());
     div()
-        .child(format!("Δ {} hours", diff_hours).to_string())
-        .text_sm()
+        .id(SharedString::from(format!("standard-shift-{}", name)))
+        .child(format!("{} ST", offset_override::format_shift(minutes)))
+        .px_1()
+        .rounded(px(4.0))
+        .bg(rgb(0xfef3c7))
+        .text_xs()
+        .text_color(rgb(0x92400e))
+        .tooltip(move |window, cx| Tooltip::new(tooltip.clone()).build(window, cx))
+}
+
+// How much of home's working week this city shares, 0–100
+fn overlap_badge(name: &str, overlap: OverlapScore) -> impl IntoElement {
+    let tooltip = planner::describe_overlap(&overlap, name);
+    let (background, color) = match overlap.score {
+        50.. => (rgb(0xdcfce7), rgb(0x166534)),
+        15..50 => (rgb(0xfef3c7), rgb(0x92400e)),
+        _ => (rgb(0xe5e7eb), rgb(0x4b5563)),
+    };
+    div()
+        .id(SharedString::from(format!("overlap-{}", name)))
+        .child(format!("⇄ {}", overlap.score))
+        .px_1()
+        .rounded(px(4.0))
+        .bg(background)
+        .text_xs()
+        .text_color(color)
+        .tooltip(move |window, cx| Tooltip::new(tooltip.clone()).build(window, cx))
+}
+
+// Stands in for the badges a snoozed card leaves out
+fn snoozed_marker(name: &str, until: NaiveDate) -> impl IntoElement {
+    let tooltip = snooze::describe(until);
+    div()
+        .id(SharedString::from(format!("snoozed-{}", name)))
+        .child("🔕")
+        .text_xs()
+        .opacity(0.5)
+        .tooltip(move |window, cx| Tooltip::new(tooltip.clone()).build(window, cx))
+}
+
+// The time shown isn't the tz database's, so say so plainly
+fn override_badge(offset_override: OffsetOverride) -> impl IntoElement {
+    div()
+        .child(format!("⚠ manual override {}", offset_override.describe()))
+        .px_2()
+        .rounded(px(4.0))
+        .bg(rgb(0xffedd5))
+        .text_xs()
         .font_weight(gpui::FontWeight::BOLD)
-        .text_color(if diff_hours >= 0 {
-            rgb(0x22c55e)
-        } else {
-            rgb(0xef4444)
-        })
+        .text_color(rgb(0xc2410c))
 }
 
-fn timezone_display(timezone_id: &str) -> impl IntoElement {
-    div().flex().items_center().gap_1().child(
-        div()
-            .child(timezone_id.to_string())
-            .text_xs()
-            .text_color(rgb(0x6b7280)),
-    )
+fn call_hint_display(hint: &str) -> impl IntoElement {
+    div()
+        .child(hint.to_string())
+        .text_xs()
+        .text_color(rgb(0x6b7280))
 }
 
 impl Render for WorldTime {
-    fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
-        let frame_color = if self.is_home {
-            rgb(0x3b82f6) // Blue border for home
-        } else {
-            rgb(0x6b7280) // Gray for others
-        };
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let viewport = window.viewport_size();
+        let tier = density::tier(f32::from(viewport.width), f32::from(viewport.height));
+        let large = tier == Tier::Large;
+        let compact = matches!(tier, Tier::Compact | Tier::Tiny);
 
-        let bg_color = if self.is_home {
-            rgb(0xf0f9ff) // Light blue background for home
-        } else {
-            rgb(0xf9fafb) // Light gray for others
-        };
+        card_frame(self.is_home, large)
+            .id(SharedString::from(format!("card-{}", self.name)))
+            .cursor_pointer()
+            .track_focus(&self.focus_handle)
+            .focus(|style| style.border_color(rgb(0x2563eb)))
+            .tooltip({
+                let description = self.description();
+                move |window, cx| Tooltip::new(description.clone()).build(window, cx)
+            })
+            .on_click(cx.listener(|_, _, _, cx| cx.emit(CardEvent::OpenDetail)))
+            .on_key_down(cx.listener(|this, event: &KeyDownEvent, window, cx| {
+                let keystroke = &event.keystroke;
+                let menu_key = keystroke.key == "menu"
+                    || (keystroke.key == "f10" && keystroke.modifiers.shift);
+                if matches!(keystroke.key.as_str(), "enter" | "space") {
+                    cx.emit(CardEvent::OpenDetail);
+                    cx.stop_propagation();
+                } else if menu_key {
+                    this.open_keyboard_menu(window, cx);
+                    cx.stop_propagation();
+                }
+            }))
+            .context_menu({
+                let items = MenuItems::for_card(self, cx);
+                move |menu, _window, _cx| items.build(menu)
+            })
+            .children(self.keyboard_menu.clone().map(|menu| {
+                deferred(
+                    anchored()
+                        .position(self.bounds.get().center())
+                        .snap_to_window_with_margin(px(8.0))
+                        .child(div().occlude().child(menu)),
+                )
+                .with_priority(1)
+            }))
+            .when(compact, |this| this.p_2().child(self.compact_row()))
+            .when(!compact, |this| {
+                this.child(
+                    div()
+                        .flex()
+                        .flex_col()
+                        .items_center()
+                        .gap_1()
+                        .child(city_name_header(&self.name, self.is_home))
+                        .when(!self.people.is_empty(), |this| {
+                            this.child(people::people_chips(self.people.clone()))
+                        })
+                        .child(time_display(&self.time, large))
+                        .child(
+                            h_flex()
+                                .gap_1()
+                                .child(self.diff_control(cx))
+                                .children(
+                                    (self.relative_day != RelativeDay::Today)
+                                        .then(|| day_badge(self.relative_day)),
+                                )
+                                .children((self.standard_shift != 0 && !self.snoozed).then(|| {
+                                    standard_shift_chip(&self.name, self.tz, self.standard_shift)
+                                }))
+                                .children(
+                                    self.overlap
+                                        .filter(|_| !self.snoozed)
+                                        .map(|overlap| overlap_badge(&self.name, overlap)),
+                                )
+                                .children(
+                                    self.same_time_as
+                                        .as_deref()
+                                        .filter(|_| !self.snoozed)
+                                        .map(|other| same_time_marker(&self.name, other)),
+                                )
+                                .children(
+                                    self.snoozed_until
+                                        .filter(|_| self.snoozed)
+                                        .map(|until| snoozed_marker(&self.name, until)),
+                                ),
+                        )
+                        .when(self.in_do_not_disturb && !self.snoozed, |this| {
+                            this.child(do_not_disturb_marker())
+                        })
+                        .children(
+                            self.offset_override
+                                .filter(|_| self.override_active)
+                                .map(override_badge),
+                        )
+                        .children(self.calendar_line.as_deref().map(call_hint_display))
+                        .children(self.relative_phrase.as_deref().map(call_hint_display))
+                        .children(self.day_stats.as_deref().map(call_hint_display))
+                        .children(self.workday.as_deref().map(call_hint_display))
+                        .children(self.mirror.as_deref().map(call_hint_display))
+                        .children(self.marker_line.as_deref().map(call_hint_display))
+                        .children(self.work_status.map(work_status_display))
+                        .children(
+                            self.call_hint
+                                .as_deref()
+                                .filter(|_| !self.snoozed)
+                                .map(call_hint_display),
+                        )
+                        .children(self.pinned_lines.iter().map(|line| call_hint_display(line)))
+                        .children(self.countdown_lines(cx))
+                        .children(
+                            self.timeline
+                                .as_ref()
+                                .map(|band| timeline::timeline_band(&self.name, band)),
+                        )
+                        .child(timezone_display(&self.timezone_id))
+                        .children(
+                            self.alias_of
+                                .filter(|_| !self.snoozed)
+                                .map(|canonical| alias_hint(canonical, cx)),
+                        ),
+                )
+            })
+            .child({
+                let bounds = self.bounds.clone();
+                canvas(move |painted, _, _| bounds.set(painted), |_, _, _, _| {})
+                    .absolute()
+                    .size_full()
+            })
+    }
+}
 
-        div()
-            .flex()
-            .flex_col()
-            .gap_2()
-            .p_4()
-            .min_w(px(180.0))
-            .bg(bg_color)
-            .border_2()
-            .border_color(frame_color)
-            .rounded(px(8.0))
+impl WorldTime {
+    // One line per city for narrow windows: name, then time and difference
+    fn compact_row(&self) -> impl IntoElement {
+        h_flex()
+            .justify_between()
+            .gap_4()
+            .child(city_name_header(&self.name, self.is_home))
             .child(
-                div()
-                    .flex()
-                    .flex_col()
-                    .items_center()
-                    .gap_1()
-                    .child(city_name_header(&self.name, self.is_home))
-                    .child(time_display(&self.time))
-                    .child(time_difference_display(self.diff_hours))
-                    .child(timezone_display(&self.timezone_id)),
+                h_flex()
+                    .gap_2()
+                    .child(
+                        div()
+                            .child(self.time.clone())
+                            .text_xl()
+                            .font_weight(gpui::FontWeight::BOLD)
+                            .text_color(rgb(0x111827)),
+                    )
+                    .children(
+                        (self.relative_day != RelativeDay::Today)
+                            .then(|| day_badge(self.relative_day)),
+                    )
+                    .child(time_difference_label(
+                        self.diff_text(),
+                        self.shown_diff_minutes(),
+                    )),
             )
     }
 }
+
+// Old ids still work, so this only offers the update
+fn alias_hint(canonical: &str, cx: &mut Context<WorldTime>) -> impl IntoElement {
+    div()
+        .id("alias-hint")
+        .child(format!("alias of {} — click to update", canonical))
+        .text_xs()
+        .text_color(rgb(0x2563eb))
+        .cursor_pointer()
+        .on_click(cx.listener(|_, _, _, cx| {
+            cx.stop_propagation();
+            cx.emit(CardEvent::UpdateZoneId);
+        }))
+}
+
+// What the header shows beside the title
+struct HeaderBadges {
+    always_on_top: bool,
+    clock_change: Option<String>,
+    suppressed: Option<(String, String)>,
+    marker: Option<(DateTime<Utc>, bool)>, // and whether cards show it
+    clock_drift: Option<ntp::Drift>,       // only once past the threshold
+}
+
+// "14:32 UTC", right-aligned in the header or standing in for it
+fn utc_clock(now: DateTime<Utc>, show_seconds: bool) -> impl IntoElement {
+    div()
+        .child(format!(
+            "{} UTC",
+            format::clock_time(now.time(), show_seconds)
+        ))
+        .text_sm()
+        .text_color(rgb(0x6b7280))
+}
+
 // Extracted header component
-fn app_header(cx: &mut Context<WorldTimeApp>) -> impl IntoElement {
+fn app_header(
+    title: &str,
+    utc_now: Option<(DateTime<Utc>, bool)>, // with seconds; when the clock is on
+    quick_convert_input: &Entity<InputState>,
+    table_view: bool,
+    badges: HeaderBadges,
+    archived: usize,
+    cx: &mut Context<WorldTimeApp>,
+) -> impl IntoElement {
     div()
+        .flex()
+        .items_center()
+        .justify_between()
         .child(
             div()
                 .flex()
                 .items_center()
                 .gap_2()
-                .child("🌍 World Time Display"),
+                .child(title.to_string())
+                .children(badges.always_on_top.then(on_top_pin))
+                .children(badges.clock_change.map(clock_change_chip))
+                .children(badges.clock_drift.map(clock_drift_chip))
+                .children(
+                    badges
+                        .suppressed
+                        .map(|(label, details)| suppressed_badge(label, details, cx)),
+                )
+                .children(
+                    badges
+                        .marker
+                        .map(|(marker, on_cards)| marker_chip(marker, on_cards, cx)),
+                )
+                .text_2xl()
+                .font_weight(gpui::FontWeight::BOLD)
+                .text_color(cx.theme().accent_foreground),
+        )
+        .child(
+            div()
+                .flex()
+                .items_center()
+                .gap_1()
+                .children(utc_now.map(|(now, show_seconds)| utc_clock(now, show_seconds)))
+                .child(
+                    div()
+                        .w(px(200.0))
+                        .child(Input::new(quick_convert_input).small()),
+                )
+                .child(command_button(
+                    "add-city",
+                    "Add city",
+                    AppCommand::AddCity,
+                    cx,
+                ))
+                .child(command_button(
+                    "add-countdown",
+                    "Add countdown",
+                    AppCommand::AddCountdown,
+                    cx,
+                ))
+                .children((archived > 0).then(|| {
+                    Button::new("toggle-archive")
+                        .label(format!("Archive ({})", archived))
+                        .small()
+                        .ghost()
+                        .tooltip(AppCommand::ToggleArchive.label())
+                        .on_click(cx.listener(|this, _, window, cx| {
+                            this.run_command(AppCommand::ToggleArchive, window, cx)
+                        }))
+                }))
+                .child(command_button(
+                    "toggle-view",
+                    if table_view { "Cards" } else { "Table" },
+                    AppCommand::ToggleTable,
+                    cx,
+                ))
+                .child(command_button(
+                    "plan-meeting",
+                    "Plan meeting",
+                    AppCommand::PlanMeeting,
+                    cx,
+                ))
+                .child(command_button(
+                    "find-overlap",
+                    "Find overlap",
+                    AppCommand::FindOverlap,
+                    cx,
+                ))
+                .child(command_button(
+                    "export-csv",
+                    "Export CSV…",
+                    AppCommand::ExportCsv,
+                    cx,
+                ))
+                .child(command_button(
+                    "export-week",
+                    "Export week…",
+                    AppCommand::ExportWeek,
+                    cx,
+                ))
+                .child(command_button(
+                    "copy-slack",
+                    "Copy for Slack",
+                    AppCommand::CopySlack,
+                    cx,
+                ))
+                .child(command_button(
+                    "mini-widget",
+                    "Mini",
+                    AppCommand::MiniWidget,
+                    cx,
+                ))
+                .child(command_button(
+                    "open-settings",
+                    "Settings",
+                    AppCommand::OpenSettings,
+                    cx,
+                )),
+        )
+}
+
+// The system clock is off; every time shown is off with it
+fn clock_drift_chip(drift: ntp::Drift) -> impl IntoElement {
+    let tooltip = format!(
+        "{}, checked at {}",
+        drift.describe(),
+        format::clock_time(drift.checked.with_timezone(&Local).time(), false)
+    );
+    div()
+        .id("clock-drift")
+        .child("⚠ clock drift")
+        .px_2()
+        .py_0p5()
+        .rounded(px(10.0))
+        .bg(rgb(0xffedd5))
+        .text_color(rgb(0xc2410c))
+        .text_xs()
+        .font_weight(gpui::FontWeight::NORMAL)
+        .tooltip(move |window, cx| Tooltip::new(tooltip.clone()).build(window, cx))
+}
+
+// The home zone's next DST change, once it's near
+fn clock_change_chip(text: String) -> impl IntoElement {
+    div()
+        .child(format!("⏰ {}", text))
+        .px_2()
+        .py_0p5()
+        .rounded(px(10.0))
+        .bg(rgb(0xfef3c7))
+        .text_color(rgb(0x92400e))
+        .text_xs()
+        .font_weight(gpui::FontWeight::NORMAL)
+}
+
+// Alarms quiet hours kept silent overnight; clicking clears it
+fn suppressed_badge(
+    label: String,
+    details: String,
+    cx: &mut Context<WorldTimeApp>,
+) -> impl IntoElement {
+    div()
+        .id("suppressed-alarms")
+        .child(label)
+        .px_2()
+        .py_0p5()
+        .rounded(px(10.0))
+        .bg(rgb(0xe0e7ff))
+        .text_color(rgb(0x3730a3))
+        .text_xs()
+        .font_weight(gpui::FontWeight::NORMAL)
+        .cursor_pointer()
+        .tooltip(move |window, cx| {
+            Tooltip::new(format!("{}\nClick to dismiss", details)).build(window, cx)
+        })
+        .on_click(cx.listener(|this, _, _, cx| this.dismiss_suppressed_alarms(cx)))
+}
+
+// Counts up from the marker; its menu shows it on the cards or clears it
+fn marker_chip(
+    marker: DateTime<Utc>,
+    on_cards: bool,
+    cx: &mut Context<WorldTimeApp>,
+) -> impl IntoElement {
+    let app = cx.entity();
+    div()
+        .id("marker")
+        .child(marker::header_label(marker, Utc::now()))
+        .px_2()
+        .py_0p5()
+        .rounded(px(10.0))
+        .bg(rgb(0xfee2e2))
+        .text_color(rgb(0x991b1b))
+        .text_xs()
+        .font_weight(gpui::FontWeight::NORMAL)
+        .tooltip(|window, cx| Tooltip::new("Right-click for options").build(window, cx))
+        .context_menu(move |menu, _window, _cx| {
+            [
+                AppCommand::SetMarker,
+                AppCommand::ToggleMarkerOnCards,
+                AppCommand::ClearMarker,
+            ]
+            .into_iter()
+            .fold(menu, |menu, command| {
+                let app = app.clone();
+                let label = match command {
+                    AppCommand::SetMarker => "Reset to now",
+                    AppCommand::ToggleMarkerOnCards if on_cards => "Hide from cards",
+                    AppCommand::ToggleMarkerOnCards => "Show on cards",
+                    _ => "Clear marker",
+                };
+                menu.item(PopupMenuItem::new(label).on_click(move |_, window, cx| {
+                    let command = command.clone();
+                    app.update(cx, |app, cx| app.run_command(command, window, cx))
+                }))
+            })
+        })
+}
+
+// Reminds why the window stays above everything else
+fn on_top_pin() -> impl IntoElement {
+    div()
+        .id("on-top-pin")
+        .child("📌")
+        .text_lg()
+        .tooltip(|window, cx| Tooltip::new("Always on top (change in Settings)").build(window, cx))
+}
+
+// Header buttons run the same commands as the palette and shortcuts
+fn command_button(
+    id: &'static str,
+    label: &'static str,
+    command: AppCommand,
+    cx: &mut Context<WorldTimeApp>,
+) -> impl IntoElement {
+    let tooltip = match command.shortcuts().first() {
+        Some(shortcut) => format!(
+            "{} ({})",
+            command.label(),
+            commands::shortcut_label(shortcut)
+        ),
+        None => command.label(),
+    };
+    Button::new(id)
+        .label(label)
+        .small()
+        .ghost()
+        .tooltip(tooltip)
+        .on_click(
+            cx.listener(move |this, _, window, cx| this.run_command(command.clone(), window, cx)),
         )
-        .text_2xl()
-        .font_weight(gpui::FontWeight::BOLD)
-        .text_color(cx.theme().accent_foreground)
-        .text_center()
 }
 
-// Extracted city grid component
-fn city_grid(cities: &[Entity<WorldTime>]) -> impl IntoElement {
+// Shown while the cards display a chosen instant rather than the live time
+fn simulated_banner(
+    instant: DateTime<Utc>,
+    home_tz: Tz,
+    cx: &mut Context<WorldTimeApp>,
+) -> impl IntoElement {
     div()
         .flex()
-        .flex_wrap()
-        .gap_8()
-        .justify_center()
-        .children(cities.iter().map(|city| city.clone()))
+        .items_center()
+        .justify_between()
+        .px_3()
+        .py_2()
+        .rounded(px(6.0))
+        .bg(rgb(0xfef3c7))
+        .text_color(rgb(0x92400e))
+        .text_sm()
+        .child(format!(
+            "Showing {} at home, not the live time",
+            format::weekday_date_time(instant.with_timezone(&home_tz).naive_local())
+        ))
+        .child(
+            Button::new("back-to-live")
+                .label("Back to live")
+                .small()
+                .on_click(cx.listener(|this, _, _, cx| this.set_simulated_instant(None, cx))),
+        )
 }
 
-struct WorldTimeApp {
-    cities: Vec<Entity<WorldTime>>,
-    last_update: std::time::Instant,
+fn compare_pick_banner(first: String, cx: &mut Context<WorldTimeApp>) -> impl IntoElement {
+    div()
+        .flex()
+        .items_center()
+        .justify_between()
+        .px_3()
+        .py_2()
+        .rounded(px(6.0))
+        .bg(rgb(0xdbeafe))
+        .text_color(rgb(0x1e40af))
+        .text_sm()
+        .child(format!("Click a second card to compare with {}", first))
+        .child(
+            Button::new("cancel-compare")
+                .label("Cancel")
+                .small()
+                .on_click(cx.listener(|this, _, _, cx| this.exit_compare(cx))),
+        )
 }
 
-impl Render for WorldTimeApp {
-    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
-        // Update times every minute
-        let now = std::time::Instant::now();
-        if now.duration_since(self.last_update).as_secs() >= 60 {
-            for city in &self.cities {
-                city.update(cx, |city, _cx| {
-                    city.update_time();
-                });
-            }
-            self.last_update = now;
+fn stale_banner(last_tick: DateTime<Utc>, home_tz: Tz) -> impl IntoElement {
+    div()
+        .px_3()
+        .py_2()
+        .rounded(px(6.0))
+        .bg(rgb(0xfee2e2))
+        .text_color(rgb(0x991b1b))
+        .text_sm()
+        .child(format!(
+            "Times may be stale — last updated {}",
+            format::clock_time(last_tick.with_timezone(&home_tz).time(), false)
+        ))
+}
+
+// The answer to a quick conversion, in every city
+fn quick_convert_answer(
+    result: &Result<Conversion, String>,
+    cities: &[Entity<WorldTime>],
+    cx: &mut Context<WorldTimeApp>,
+) -> impl IntoElement {
+    let body = match result {
+        Ok(conversion) => {
+            let instant = conversion.resolved.instant;
+            let lines = cities
+                .iter()
+                .map(|city| {
+                    let city = city.read(cx);
+                    format!(
+                        "{} {}{}",
+                        city.name,
+                        format::weekday_time(instant.with_timezone(&city.tz).naive_local()),
+                        if city.is_home { " (home)" } else { "" }
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join(" · ");
+            div()
+                .flex()
+                .flex_col()
+                .gap_1()
+                .child(
+                    div()
+                        .child(format!(
+                            "{} in {}",
+                            format::weekday_date_time(
+                                instant.with_timezone(&conversion.from.tz).naive_local()
+                            ),
+                            conversion.from.label
+                        ))
+                        .font_weight(gpui::FontWeight::BOLD),
+                )
+                .child(lines)
+                .children(
+                    conversion
+                        .resolved
+                        .note
+                        .clone()
+                        .map(|note| div().child(note).text_color(rgb(0x92400e))),
+                )
         }
+        Err(err) => div().child(err.clone()).text_color(rgb(0xef4444)),
+    };
+    let instant = result
+        .as_ref()
+        .ok()
+        .map(|conversion| conversion.resolved.instant);
+
+    div()
+        .flex()
+        .items_start()
+        .justify_between()
+        .gap_2()
+        .px_3()
+        .py_2()
+        .rounded(px(6.0))
+        .bg(rgb(0xf3f4f6))
+        .text_sm()
+        .child(body)
+        .child(
+            div()
+                .flex()
+                .gap_1()
+                .children(instant.map(|instant| {
+                    Button::new("quick-convert-show")
+                        .label("Show on cards")
+                        .small()
+                        .ghost()
+                        .on_click(cx.listener(move |this, _, _, cx| {
+                            this.set_simulated_instant(Some(instant), cx)
+                        }))
+                }))
+                .child(
+                    Button::new("quick-convert-close")
+                        .label("Close")
+                        .small()
+                        .ghost()
+                        .on_click(cx.listener(|this, _, window, cx| {
+                            this.quick_convert = None;
+                            this.quick_convert_input
+                                .update(cx, |input, cx| input.set_value("", window, cx));
+                            cx.notify();
+                        })),
+                ),
+        )
+}
+
+// Shown instead of the simulated banner while planning a meeting
+fn planner_bar(
+    slider: &Entity<SliderState>,
+    offset_minutes: i64,
+    instant: DateTime<Utc>,
+    home_tz: Tz,
+    cx: &mut Context<WorldTimeApp>,
+) -> impl IntoElement {
+    let offset = if offset_minutes == 0 {
+        "now".to_string()
+    } else {
+        format::signed_diff_hours(offset_minutes as i32)
+    };
+    div()
+        .flex()
+        .flex_col()
+        .gap_2()
+        .px_3()
+        .py_2()
+        .rounded(px(6.0))
+        .bg(rgb(0xeff6ff))
+        .child(
+            div()
+                .flex()
+                .items_center()
+                .gap_2()
+                .child(
+                    Button::new("planner-earlier")
+                        .label("−1h")
+                        .small()
+                        .ghost()
+                        .on_click(cx.listener(move |this, _, window, cx| {
+                            this.set_planner_offset(offset_minutes - 60, window, cx)
+                        })),
+                )
+                .child(div().flex_1().child(Slider::new(slider).horizontal()))
+                .child(
+                    Button::new("planner-later")
+                        .label("+1h")
+                        .small()
+                        .ghost()
+                        .on_click(cx.listener(move |this, _, window, cx| {
+                            this.set_planner_offset(offset_minutes + 60, window, cx)
+                        })),
+                ),
+        )
+        .child(
+            div()
+                .flex()
+                .items_center()
+                .justify_between()
+                .child(
+                    div()
+                        .child(format!(
+                            "{} at home ({})",
+                            format::weekday_date_time(
+                                instant.with_timezone(&home_tz).naive_local()
+                            ),
+                            offset
+                        ))
+                        .text_sm()
+                        .font_weight(gpui::FontWeight::BOLD),
+                )
+                .child(
+                    div()
+                        .flex()
+                        .gap_1()
+                        .child(
+                            Button::new("planner-copy")
+                                .label("Copy for Slack")
+                                .small()
+                                .ghost()
+                                .on_click(cx.listener(move |this, _, window, cx| {
+                                    this.copy_slack_message(
+                                        DisplayInstant::Picked(instant),
+                                        window,
+                                        cx,
+                                    )
+                                })),
+                        )
+                        .child(
+                            Button::new("planner-ics")
+                                .label("Save .ics…")
+                                .small()
+                                .ghost()
+                                .on_click(cx.listener(move |this, _, window, cx| {
+                                    this.export_ics(DisplayInstant::Picked(instant), window, cx)
+                                })),
+                        )
+                        .child(
+                            Button::new("planner-reset")
+                                .label("Reset")
+                                .small()
+                                .ghost()
+                                .on_click(cx.listener(|this, _, window, cx| {
+                                    this.set_planner_offset(0, window, cx)
+                                })),
+                        )
+                        .child(Button::new("planner-done").label("Done").small().on_click(
+                            cx.listener(|this, _, window, cx| this.toggle_planner(window, cx)),
+                        )),
+                ),
+        )
+}
+
+// Every animation checks this, so "Reduce motion" stills all of them
+fn animations_enabled(cx: &App) -> bool {
+    !cx.global::<Settings>().reduce_motion
+}
+
+// Cards sharing a zone, shown as one: a single time and a chip per city
+struct ZoneStack {
+    zone_id: String,
+    time: String,
+    diff_minutes: i32,
+    is_home: bool,
+    names: Vec<String>, // alphabetical
+}
+
+enum GridEntry {
+    Card(Entity<WorldTime>),
+    Stack(ZoneStack),
+}
+
+// Extracted city grid component; countdowns follow the clocks. After an automatic
+// reorder the cards fade in at their new places rather than jumping there.
+fn city_grid(
+    entries: Vec<GridEntry>,
+    trailing: Vec<AnyElement>, // placeholders and error cards, after the real ones
+    countdowns: &[Entity<Countdown>],
+    reorder_generation: usize,
+    animate: bool,
+    available_width: Pixels,
+    cx: &mut Context<WorldTimeApp>,
+) -> impl IntoElement {
+    let large = available_width >= px(density::LARGE_WIDTH);
+    let count = entries.len() + trailing.len() + countdowns.len();
+    let density = density::density(
+        available_width.into(),
+        if large { 260.0 } else { 180.0 },
+        count,
+    );
+    // Every item gets the same slot, so rows line up whatever is in them
+    let slot = move || match density {
+        Density::Compact => div().flex().flex_col().w_full(),
+        Density::Grid { card_width, .. } => div().flex().flex_col().w(px(card_width)),
+    };
+    let items = div()
+        .flex()
+        .gap(px(density::GRID_GAP))
+        .children(entries.into_iter().enumerate().map(|(index, entry)| {
+            let card = match entry {
+                GridEntry::Card(card) => slot().child(card),
+                GridEntry::Stack(stack) => slot().child(zone_stack(stack, cx)),
+            };
+            if reorder_generation == 0 || !animate {
+                return card.into_any_element();
+            }
+            card.with_animation(
+                ("card-reorder", reorder_generation * 1000 + index),
+                Animation::new(Duration::from_millis(300)).with_easing(ease_out_quint()),
+                |card, delta| card.opacity(0.3 + 0.7 * delta),
+            )
+            .into_any_element()
+        }))
+        .children(trailing.into_iter().map(|card| slot().child(card)))
+        .children(
+            countdowns
+                .iter()
+                .map(|countdown| slot().child(countdown.clone())),
+        );
+    match density {
+        Density::Compact => items.flex_col().gap_2(),
+        Density::Grid {
+            columns,
+            card_width,
+        } => items
+            .flex_wrap()
+            .justify_center()
+            .max_w(px(density::row_width(columns, card_width)))
+            .mx_auto(),
+    }
+}
 
+fn zone_stack(stack: ZoneStack, cx: &mut Context<WorldTimeApp>) -> impl IntoElement {
+    let zone = stack.zone_id.clone();
+    card_frame(stack.is_home, false).child(
         div()
             .flex()
             .flex_col()
-            .gap_4()
-            .p_6()
-            .bg(cx.theme().background)
-            .size_full()
-            .child(app_header(cx))
-            .child(city_grid(&self.cities))
+            .items_center()
+            .gap_1()
+            .child(h_flex().flex_wrap().justify_center().gap_1().children(
+                stack.names.into_iter().map(|name| {
+                    Button::new(SharedString::from(format!("stack-city-{}", name)))
+                        .label(name.clone())
+                        .small()
+                        .on_click(cx.listener(move |this, _, window, cx| {
+                            this.card_clicked(name.clone(), window, cx)
+                        }))
+                }),
+            ))
+            .child(time_display(&stack.time, false))
+            .child(time_difference_label(
+                i18n::diff_badge(cx.global::<Settings>().language, stack.diff_minutes),
+                stack.diff_minutes,
+            ))
+            .child(timezone_display(&stack.zone_id))
+            .child(
+                Button::new(SharedString::from(format!("expand-{}", zone)))
+                    .label("Show separately")
+                    .small()
+                    .ghost()
+                    .on_click(cx.listener(move |this, _, window, cx| {
+                        this.set_stack_expanded(zone.clone(), true, window, cx)
+                    })),
+            ),
+    )
+}
+
+// Cards the city loader builds per frame
+const CITY_BATCH: usize = 8;
+
+fn resolve_city_zones(cities: &[CityConfig]) -> Vec<Result<Tz, String>> {
+    cities
+        .iter()
+        .map(|city| zone::resolve_zone(&city.timezone).map_err(|err| err.to_string()))
+        .collect()
+}
+
+// The cities whose zone resolved, with it, in config order; the rest go to
+// `broken` with the reason. Only the first become cards, so diffs, ordering,
+// scores and exports never see the others.
+fn split_by_zone(
+    cities: Vec<CityConfig>,
+    zones: &[Result<Tz, String>],
+    broken: &mut Vec<(CityConfig, String)>,
+) -> Vec<(CityConfig, Tz)> {
+    let mut resolved = Vec::new();
+    for (city, zone) in cities.into_iter().zip(zones) {
+        match zone {
+            Ok(tz) => resolved.push((city, *tz)),
+            Err(err) => broken.push((city, err.clone())),
+        }
     }
+    resolved
 }
-fn main() {
-    Application::new().run(|cx: &mut App| {
-        // This must be called before using any GPUI Component features.
-        gpui_component::init(cx);
 
-        // Handle window closing - quit app when last window closes
-        cx.on_window_closed(|cx| {
-            if cx.windows().is_empty() {
-                cx.quit();
-            }
-        })
-        .detach();
+// Takes the next batch off the front of `pending`, one city per zone result; the
+// cities still pending keep their placeholders
+fn take_batch(
+    pending: &mut Vec<CityConfig>,
+    zones: &[Result<Tz, String>],
+    broken: &mut Vec<(CityConfig, String)>,
+) -> Vec<(CityConfig, Tz)> {
+    let count = zones.len().min(pending.len());
+    split_by_zone(pending.drain(..count).collect(), zones, broken)
+}
 
-        let bounds = Bounds::centered(None, size(px(800.0), px(600.0)), cx);
-        cx.open_window(
-            WindowOptions {
-                window_bounds: Some(WindowBounds::Windowed(bounds)),
-                titlebar: Some(TitlebarOptions {
-                    title: Some(SharedString::from("🌍 World Time Display")),
-                    ..Default::default()
-                }),
-                show: true,
-                ..Default::default()
-            },
-            |window, cx| {
-                cx.new(|cx| {
-                    // Prevent window maximization
-                    cx.observe_window_bounds(window, move |_, window, _cx| {
-                        if window.is_maximized() {
-                            // Restore to original size when maximized
-                            window.resize(size(px(800.0), px(600.0)));
-                        }
-                    })
-                    .detach();
+// Stands in for a card until the startup pass has built it
+fn placeholder_card(city: &CityConfig) -> impl IntoElement {
+    card_frame(city.home, false).child(
+        div()
+            .flex()
+            .flex_col()
+            .items_center()
+            .gap_1()
+            .child(city_name_header(&city.name, city.home))
+            .child(div().child("--:--").text_3xl().text_color(rgb(0xd1d5db))),
+    )
+}
+
+// A city whose zone didn't resolve: what was saved, why it failed, and the two
+// ways out
+fn error_card(city: &CityConfig, reason: &str, cx: &mut Context<WorldTimeApp>) -> impl IntoElement {
+    let id = city.id.clone();
+    let broken = city.clone();
+    card_frame(city.home, false)
+        .border_dashed()
+        .border_color(rgb(0xef4444))
+        .bg(rgb(0xfef2f2))
+        .child(
+            div()
+                .flex()
+                .flex_col()
+                .items_center()
+                .gap_1()
+                .child(city_name_header(&city.name, city.home))
+                .child(
+                    div()
+                        .child(city.timezone.clone())
+                        .text_sm()
+                        .text_color(rgb(0xb91c1c)),
+                )
+                .child(
+                    div()
+                        .child(format!("Not shown: {}", reason))
+                        .text_xs()
+                        .text_color(rgb(0x6b7280)),
+                )
+                .child(
+                    h_flex()
+                        .gap_1()
+                        .child(
+                            Button::new(SharedString::from(format!("edit-zone-{}", city.id)))
+                                .label("Edit timezone")
+                                .small()
+                                .on_click(cx.listener(move |_, _, window, cx| {
+                                    add_city::open_edit_timezone(
+                                        cx.entity(),
+                                        broken.clone(),
+                                        window,
+                                        cx,
+                                    )
+                                })),
+                        )
+                        .child(
+                            Button::new(SharedString::from(format!("remove-broken-{}", city.id)))
+                                .label("Remove")
+                                .small()
+                                .ghost()
+                                .on_click(cx.listener(move |this, _, window, cx| {
+                                    this.remove_broken_city(&id, window, cx)
+                                })),
+                        ),
+                ),
+        )
+}
+
+struct WorldTimeApp {
+    cities: Vec<Entity<WorldTime>>,
+    // Configured cities not built into cards yet; the loader works through them in order
+    pending_cities: Vec<CityConfig>,
+    // Configured cities whose zone didn't resolve, with the reason; shown as error
+    // cards after the others and saved as they were, but left out of everything else
+    broken_cities: Vec<(CityConfig, String)>,
+    city_loader: Task<()>,
+    countdowns: Vec<Entity<Countdown>>,
+    // Finished countdowns, kept for reference and hidden until asked for
+    archived_countdowns: Vec<CountdownConfig>,
+    show_archive: bool,
+    home_tz: Tz,
+    global_shortcut: Option<GlobalShortcut>,
+    // Serves GET /times on loopback while enabled in settings
+    http_endpoint: Option<HttpEndpoint>,
+    // Links and activation requests from other launches or the OS
+    inbox: Receiver<String>,
+    // Scripted commands, with --stdin-control
+    control: Option<Receiver<ControlCommand>>,
+    // When set, cards show this instant instead of the live time
+    simulated_instant: Option<DateTime<Utc>>,
+    alarms: Vec<Alarm>,
+    // The meeting planner's slider, while planning; its offset is in minutes from now
+    planner: Option<Entity<SliderState>>,
+    planner_offset_minutes: i64,
+    // Header input answering "15:00 Berlin is what here?"
+    quick_convert_input: Entity<InputState>,
+    quick_convert: Option<Result<Conversion, String>>,
+    // Hour-by-hour table instead of cards, and the row picked in it
+    table_view: bool,
+    table_selection: Option<DateTime<Utc>>,
+    // Saved a moment after the user stops moving or resizing the window
+    window_state: Option<WindowState>,
+    window_state_save_pending: bool,
+    config_store: ConfigStore,
+    // As the window was opened; gpui can only set the level at creation
+    always_on_top: bool,
+    // The frameless widget standing in for this window, while shown
+    mini_widget: Option<AnyWindowHandle>,
+    // Cards open in windows of their own
+    floating_cards: Vec<FloatingCardConfig>,
+    // Zones whose same-zone stack the user opened into separate cards
+    expanded_stacks: Vec<String>,
+    // "Incident started": the header counts up from it until cleared
+    marker: Option<DateTime<Utc>>,
+    marker_on_cards: bool,
+    // Saved floating cards, reopened once their cities have loaded
+    floating_cards_to_restore: Vec<FloatingCardConfig>,
+    // The card that last had keyboard focus, given it back after a reload
+    focused_card: Option<String>,
+    // First-run setup, shown instead of the grid
+    onboarding: Option<Entity<Onboarding>>,
+    // Keeps keyboard shortcuts working when no input has focus
+    focus_handle: FocusHandle,
+    show_shortcuts: bool,
+    // Full-screen rotation through the cities, while running
+    kiosk: Option<Kiosk>,
+    // Snapshots taken before removals, imports and resets
+    undo: UndoStack,
+    // Refreshes the cards; replacing the task restarts it
+    ticker: Task<()>,
+    ticker_interval: Duration,
+    // From --interval, for this run only
+    interval_override: Option<UpdateInterval>,
+    // The banner shows while the ticker is stalled, until it recovers
+    ticker_health: TickerHealth,
+    // Ticks folded into a later one because a refresh or the machine ran late
+    skipped_ticks: u64,
+    clock_watch: ClockWatch,
+    // Header chip for the home zone's next clock change, while within the horizon
+    clock_change: Option<String>,
+    // The background check against a time server, while enabled, and what it last found
+    clock_sync: Option<(ClockSyncSettings, Task<()>)>,
+    clock_drift: Option<ntp::Drift>,
+    // Alarms that came due in quiet hours; badged once they're over
+    suppressed_alarms: Vec<Suppressed>,
+    // The first city of a comparison while the second is being picked
+    compare_pick: Option<String>,
+    compare: Option<(String, String)>,
+    // The saved city setups that aren't showing, and the name of the one that is
+    profiles: Vec<Profile>,
+    active_profile: String,
+    // Bumped whenever auto-ordering moves cards, to replay their animation
+    reorder_generation: usize,
+}
+
+impl WorldTimeApp {
+    // Settings are already installed as a global; the rest of the config seeds the app
+    fn new(
+        config: Config,
+        mut warnings: Vec<String>,
+        inbox: Receiver<String>,
+        interval_override: Option<UpdateInterval>,
+        control: Option<Receiver<ControlCommand>>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        // As saved: cities from --city or the environment are this run's only
+        let config_store = ConfigStore::new(Config {
+            cities: config
+                .cities
+                .iter()
+                .cloned()
+                .filter_map(CityConfig::persisted)
+                .collect(),
+            ..config.clone()
+        });
+        let cities = &config.cities;
+        let home_tz = cities
+            .iter()
+            .find(|city| city.home)
+            .or(cities.first())
+            .and_then(|city| zone::resolve_zone(&city.timezone).ok())
+            .unwrap_or(chrono_tz::UTC);
 
-                    let austin = cx.new(|_| WorldTime::new("Austin", "America/Chicago", true, 0));
-                    let nyc = cx.new(|_| WorldTime::new("NYC", "America/New_York", false, 1));
-                    let london = cx.new(|_| WorldTime::new("London", "Europe/London", false, 6));
-                    let berlin = cx.new(|_| WorldTime::new("Berlin", "Europe/Berlin", false, 7));
-                    let bucharest =
-                        cx.new(|_| WorldTime::new("Bucharest", "Europe/Bucharest", false, 8));
+        // Cards are built after the window is up, so a long list doesn't hold it back
+        let pending_cities = config.cities.clone();
+        let city_loader = Self::start_city_loader(pending_cities.clone(), window, cx);
 
-                    WorldTimeApp {
-                        cities: vec![austin, nyc, london, berlin, bucharest],
-                        last_update: std::time::Instant::now(),
+        let (archived_countdowns, running): (Vec<_>, Vec<_>) = config
+            .countdowns
+            .into_iter()
+            .partition(|countdown| countdown.completed.is_some());
+        let countdowns = running
+            .iter()
+            .filter_map(
+                |countdown| match Self::new_countdown(countdown.clone(), window, cx) {
+                    Ok(countdown) => Some(countdown),
+                    Err(err) => {
+                        warnings.push(format!("Skipped countdown {}: {}", countdown.title, err));
+                        None
                     }
-                })
+                },
+            )
+            .collect();
+
+        let quick_convert_input =
+            cx.new(|cx| InputState::new(window, cx).placeholder("15:00 Berlin"));
+        cx.subscribe_in(
+            &quick_convert_input,
+            window,
+            |this, input, event, _, cx| match event {
+                InputEvent::PressEnter { .. } => {
+                    let query = input.read(cx).value().to_string();
+                    this.quick_convert(&query, cx);
+                }
+                InputEvent::Change if input.read(cx).value().is_empty() => {
+                    this.quick_convert = None;
+                    cx.notify();
+                }
+                _ => {}
             },
         )
-        .unwrap();
-    });
+        .detach();
+
+        // Poll for global shortcut presses and forwarded links; registration happens
+        // once the Root exists so that failures can be shown as notifications
+        let floating_cards = config.floating_cards;
+        let needs_onboarding = config.needs_onboarding;
+        let focus_handle = cx.focus_handle();
+        cx.defer_in(window, move |this, window, cx| {
+            window.focus(&this.focus_handle);
+            if needs_onboarding {
+                this.start_onboarding(window, cx);
+            }
+            for message in warnings {
+                window.push_notification(Notification::warning(message), cx);
+            }
+            this.apply_global_shortcut(window, cx);
+            this.apply_http_endpoint(window, cx);
+            this.apply_clock_sync(cx);
+            Self::apply_zoom(window, cx);
+            this.catch_up_on_release(needs_onboarding, window, cx);
+            this.floating_cards_to_restore = floating_cards;
+        });
+        cx.spawn_in(window, async move |this, cx| {
+            loop {
+                cx.background_executor()
+                    .timer(Duration::from_millis(100))
+                    .await;
+                let polled = this.update_in(cx, |this, window, cx| {
+                    let pressed = this
+                        .global_shortcut
+                        .as_ref()
+                        .map(|shortcut| shortcut.poll_pressed())
+                        .unwrap_or_default();
+                    for action in pressed {
+                        match action {
+                            ShortcutAction::ToggleWindow => {
+                                this.toggle_window_visibility(window, cx)
+                            }
+                            ShortcutAction::InsertSnippet => this.insert_snippet(window, cx),
+                        }
+                    }
+                    while let Ok(message) = this.inbox.try_recv() {
+                        this.handle_message(&message, window, cx);
+                    }
+                    while let Some(command) = this
+                        .control
+                        .as_ref()
+                        .and_then(|control| control.try_recv().ok())
+                    {
+                        this.run_control_command(command, window, cx);
+                    }
+                    this.check_clock(window, cx);
+                    // Checked here rather than on the minute ticker, whose timer
+                    // doesn't advance while the machine sleeps
+                    this.check_alarms(Utc::now(), window, cx);
+                    this.archive_finished(Utc::now(), window, cx);
+                    this.advance_kiosk(cx);
+                    this.check_ticker(cx);
+                });
+                if polled.is_err() {
+                    break;
+                }
+            }
+        })
+        .detach();
+
+        let options: Vec<DisplayOptions> = config
+            .cities
+            .iter()
+            .map(|city| display_options::resolve(&config.settings, &city.display))
+            .collect();
+        let ticker_interval = ticker::effective_interval(
+            options.iter().any(|options| options.show_seconds) || config.marker.is_some(),
+            options.iter().any(|options| options.show_timeline),
+            &config.settings,
+            interval_override,
+        );
+        let ticker = Self::start_ticker(ticker_interval, cx);
+
+        WorldTimeApp {
+            cities: Vec::new(),
+            pending_cities,
+            broken_cities: Vec::new(),
+            city_loader,
+            floating_cards_to_restore: Vec::new(),
+            focused_card: config.focused_card.clone(),
+            countdowns,
+            archived_countdowns,
+            show_archive: false,
+            home_tz,
+            global_shortcut: None,
+            http_endpoint: None,
+            inbox,
+            control,
+            simulated_instant: None,
+            alarms: config.alarms,
+            planner: None,
+            planner_offset_minutes: 0,
+            quick_convert_input,
+            quick_convert: None,
+            table_view: config.table_view,
+            table_selection: None,
+            window_state: config.window,
+            window_state_save_pending: false,
+            config_store,
+            always_on_top: config.settings.always_on_top,
+            mini_widget: None,
+            floating_cards: Vec::new(),
+            expanded_stacks: config.expanded_stacks,
+            marker: config.marker,
+            marker_on_cards: config.marker_on_cards,
+            profiles: config.profiles,
+            active_profile: config.active_profile,
+            onboarding: None,
+            focus_handle,
+            show_shortcuts: false,
+            kiosk: None,
+            undo: UndoStack::default(),
+            ticker,
+            ticker_interval,
+            interval_override,
+            ticker_health: TickerHealth::new(Utc::now()),
+            skipped_ticks: 0,
+            clock_watch: ClockWatch::new(Utc::now(), Instant::now()),
+            clock_change: None,
+            clock_sync: None,
+            clock_drift: None,
+            suppressed_alarms: Vec::new(),
+            compare_pick: None,
+            compare: None,
+            reorder_generation: 0,
+        }
+    }
+
+    fn start_ticker(interval: Duration, cx: &mut Context<Self>) -> Task<()> {
+        cx.spawn(async move |this, cx| {
+            loop {
+                let now = Utc::now();
+                let delay = ticker::next_tick_delay(now, interval);
+                let due = now + chrono::Duration::from_std(delay).unwrap_or_default();
+                cx.background_executor().timer(delay).await;
+                // One refresh at a time: the next wait starts only once this one
+                // is done, however long it took
+                if this.update(cx, |this, cx| this.tick(due, cx)).is_err() {
+                    break;
+                }
+            }
+        })
+    }
+
+    fn tick(&mut self, due: DateTime<Utc>, cx: &mut Context<Self>) {
+        if self.ticker_health.ticked(Utc::now()) {
+            cx.notify();
+        }
+        self.refresh_times(cx);
+        let missed = ticker::missed_ticks(due, Utc::now(), self.ticker_interval);
+        if missed > 0 {
+            self.skipped_ticks += missed;
+            eprintln!(
+                "time2rust: refresh ran late, folded {} tick(s) into one ({} so far)",
+                missed, self.skipped_ticks
+            );
+        }
+    }
+
+    // After the system time is changed, everything is worked out again from the
+    // new time: the ticker's alignment, alarm arming and the cards
+    fn check_clock(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let now = Utc::now();
+        let Some(jump) = self.clock_watch.check(now, Instant::now()) else {
+            return;
+        };
+        eprintln!("time2rust: system clock jumped by {}s", jump.num_seconds());
+        // Left in the future, an alarm wouldn't ring until the clock caught up
+        for alarm in &mut self.alarms {
+            alarm.armed_since = alarm.armed_since.min(now);
+        }
+        self.ticker_health.reset(now);
+        self.ticker = Self::start_ticker(self.ticker_interval, cx);
+        self.refresh_times(cx);
+        // Forward jumps are also what waking from sleep looks like, so only a
+        // step back is worth telling the user about
+        if jump < chrono::Duration::zero() {
+            let minutes = (-jump.num_seconds() + 59) / 60;
+            window.push_notification(
+                Notification::warning(format!(
+                    "System clock changed by −{}",
+                    format::remaining(minutes)
+                )),
+                cx,
+            );
+        }
+    }
+
+    // A clock showing old times is worse than none, so a stalled ticker is both
+    // flagged and restarted; the next successful tick clears the flag
+    fn check_ticker(&mut self, cx: &mut Context<Self>) {
+        if !self.ticker_health.check(Utc::now(), self.ticker_interval) {
+            return;
+        }
+        self.ticker = Self::start_ticker(self.ticker_interval, cx);
+        cx.spawn(async move |this, cx| {
+            this.update(cx, |this, cx| this.tick(Utc::now(), cx)).ok();
+        })
+        .detach();
+        cx.notify();
+    }
+
+    fn new_card(
+        city: &CityConfig,
+        tz: Tz,
+        home_tz: Tz,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Entity<WorldTime> {
+        let card = cx.new(|cx| {
+            let focus_handle = cx.focus_handle().tab_index(0).tab_stop(true);
+            WorldTime::new(city, tz, home_tz, focus_handle)
+        });
+        cx.subscribe_in(&card, window, |this, card, event, window, cx| match event {
+            CardEvent::OpenDetail => {
+                let name = card.read(cx).name.clone();
+                this.card_clicked(name, window, cx)
+            }
+            CardEvent::Command(command) => this.run_command(command.clone(), window, cx),
+            CardEvent::UpdateZoneId => this.update_zone_ids(std::slice::from_ref(card), window, cx),
+            CardEvent::DiffStyleChanged => this.save_config(window, cx),
+            CardEvent::UnpinCountdown(id) => {
+                if let Some(countdown) = this.countdown_by_id(*id) {
+                    this.pin_countdown(&countdown, None, window, cx);
+                }
+            }
+        })
+        .detach();
+        let focus_handle = card.read(cx).focus_handle.clone();
+        cx.on_focus(&focus_handle, window, {
+            let card = card.downgrade();
+            move |this, window, cx| {
+                if let Some(card) = card.upgrade() {
+                    this.focused_card = Some(card.read(cx).name.clone());
+                    this.save_config_soon(window, cx);
+                }
+            }
+        })
+        .detach();
+        card
+    }
+
+    // Zones resolve off the UI thread; cards are then built a batch per frame,
+    // in config order, with cities whose zone doesn't resolve kept as error cards
+    fn start_city_loader(
+        cities: Vec<CityConfig>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<()> {
+        cx.spawn_in(window, async move |this, cx| {
+            let zones = cx
+                .background_spawn(async move { resolve_city_zones(&cities) })
+                .await;
+            for batch in zones.chunks(CITY_BATCH) {
+                let loaded =
+                    this.update_in(cx, |this, window, cx| this.load_cities(batch, window, cx));
+                if loaded.is_err() {
+                    return;
+                }
+                cx.background_executor().timer(Duration::ZERO).await;
+            }
+            let _ = this.update_in(cx, |this, window, cx| this.cities_loaded(window, cx));
+        })
+    }
+
+    fn load_cities(
+        &mut self,
+        zones: &[Result<Tz, String>],
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let home_tz = self.home_tz;
+        for (city, tz) in take_batch(&mut self.pending_cities, zones, &mut self.broken_cities) {
+            let card = Self::new_card(&city, tz, home_tz, window, cx);
+            self.cities.push(card);
+        }
+        self.refresh_times(cx);
+    }
+
+    fn cities_loaded(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let floating_cards = std::mem::take(&mut self.floating_cards_to_restore);
+        self.open_floating_cards(floating_cards, window, cx);
+        self.restore_focus(window, cx);
+    }
+
+    fn restore_focus(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let names: Vec<&str> = self
+            .cities
+            .iter()
+            .map(|card| card.read(cx).name.as_str())
+            .collect();
+        self.focused_card = profiles::restore_focus(self.focused_card.take(), &names);
+        if let Some(card) = self
+            .focused_card
+            .as_deref()
+            .and_then(|name| self.card_named(name, cx))
+        {
+            window.focus(&card.read(cx).focus_handle);
+        }
+    }
+
+    // Skips cities that are gone or already have a window
+    fn open_floating_cards(
+        &mut self,
+        floating_cards: Vec<FloatingCardConfig>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        for floating in floating_cards {
+            let open = self
+                .floating_cards
+                .iter()
+                .any(|existing| existing.city == floating.city);
+            if let Some(card) = self.card_named(&floating.city, cx)
+                && !open
+            {
+                self.open_floating_card(card, Some(&floating.window), window, cx);
+            }
+        }
+    }
+
+    fn new_countdown(
+        config: CountdownConfig,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Result<Entity<Countdown>, String> {
+        let countdown = Countdown::new(config, Utc::now())?;
+        let countdown = cx.new(|_| countdown);
+        cx.subscribe_in(
+            &countdown,
+            window,
+            |this, countdown, event, window, cx| match event {
+                CountdownEvent::Remove => {
+                    this.countdowns.retain(|existing| existing != countdown);
+                    this.refresh_times(cx);
+                    this.save_config(window, cx);
+                }
+                CountdownEvent::ChooseCard => {
+                    let cities = this
+                        .cities
+                        .iter()
+                        .map(|card| card.read(cx))
+                        .filter(|card| !card.hidden)
+                        .map(|card| card.name.clone())
+                        .collect();
+                    countdown::open_pin_countdown(
+                        cx.entity(),
+                        countdown.clone(),
+                        cities,
+                        window,
+                        cx,
+                    );
+                }
+                CountdownEvent::Unpin => this.pin_countdown(countdown, None, window, cx),
+            },
+        )
+        .detach();
+        Ok(countdown)
+    }
+
+    fn add_countdown(
+        &mut self,
+        config: CountdownConfig,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        match Self::new_countdown(config, window, cx) {
+            Ok(countdown) => {
+                self.countdowns.push(countdown);
+                self.refresh_times(cx);
+                self.save_config(window, cx);
+            }
+            Err(err) => window.push_notification(Notification::error(err), cx),
+        }
+    }
+
+    // None puts it back as a card of its own
+    fn pin_countdown(
+        &mut self,
+        countdown: &Entity<Countdown>,
+        city: Option<String>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        countdown.update(cx, |countdown, cx| {
+            countdown.config.pinned_to = city;
+            cx.notify();
+        });
+        self.refresh_times(cx);
+        self.save_config(window, cx);
+    }
+
+    fn countdown_by_id(&self, id: EntityId) -> Option<Entity<Countdown>> {
+        self.countdowns
+            .iter()
+            .find(|countdown| countdown.entity_id() == id)
+            .cloned()
+    }
+
+    // Each card's pinned countdowns as lines, soonest first
+    fn attach_countdowns(&mut self, cx: &mut Context<Self>) {
+        let mut pinned: Vec<(DateTime<Utc>, String, EntityId, String)> = self
+            .countdowns
+            .iter()
+            .filter_map(|countdown| {
+                let id = countdown.entity_id();
+                let countdown = countdown.read(cx);
+                let city = countdown.config.pinned_to.clone()?;
+                let line = countdown::card_line(&countdown.config.title, countdown.remaining());
+                Some((countdown.target(), city, id, line))
+            })
+            .collect();
+        pinned.sort_by_key(|(target, ..)| *target);
+        for card in &self.cities {
+            card.update(cx, |card, cx| {
+                let countdowns: Vec<(EntityId, String)> = pinned
+                    .iter()
+                    .filter(|(_, city, ..)| *city == card.name)
+                    .map(|(_, _, id, line)| (*id, line.clone()))
+                    .collect();
+                if countdowns.is_empty() {
+                    card.countdowns_open = false;
+                }
+                if countdowns != card.countdowns {
+                    card.countdowns = countdowns;
+                    cx.notify();
+                }
+            });
+        }
+    }
+
+    // Against the real clock, so planning at a later time doesn't archive anything
+    fn archive_finished(
+        &mut self,
+        now: DateTime<Utc>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let (finished, running): (Vec<_>, Vec<_>) = self
+            .countdowns
+            .drain(..)
+            .partition(|countdown| countdown.read(cx).target() <= now);
+        self.countdowns = running;
+        if finished.is_empty() {
+            return;
+        }
+        for countdown in finished {
+            let countdown = countdown.read(cx);
+            self.archived_countdowns.push(CountdownConfig {
+                completed: Some(countdown.target()),
+                ..countdown.config.clone()
+            });
+        }
+        self.save_config(window, cx);
+        cx.notify();
+    }
+
+    fn duplicate_archived(&mut self, index: usize, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(archived) = self.archived_countdowns.get(index) {
+            countdown::open_add_countdown(cx.entity(), Some(archived), window, cx);
+        }
+    }
+
+    fn delete_archived(&mut self, index: usize, window: &mut Window, cx: &mut Context<Self>) {
+        if index < self.archived_countdowns.len() {
+            self.archived_countdowns.remove(index);
+            if self.archived_countdowns.is_empty() {
+                self.show_archive = false;
+            }
+            self.save_config(window, cx);
+            cx.notify();
+        }
+    }
+
+    // The instant the cards currently show
+    fn display_instant(&self) -> DateTime<Utc> {
+        self.display_source().instant()
+    }
+
+    // The same, saying whether it's the live clock; what copies and exports use
+    fn display_source(&self) -> DisplayInstant {
+        DisplayInstant::new(self.simulated_instant, Utc::now())
+    }
+
+    fn set_simulated_instant(&mut self, instant: Option<DateTime<Utc>>, cx: &mut Context<Self>) {
+        self.simulated_instant = instant;
+        self.refresh_times(cx);
+    }
+
+    fn refresh_times(&mut self, cx: &mut Context<Self>) {
+        let now = self.display_instant();
+        let home_tz = self.home_tz;
+        let planning = self.planner.is_some();
+        let default_hours = cx.global::<Settings>().working_hours;
+        let default_dnd = cx.global::<Settings>().do_not_disturb;
+        let settings = cx.global::<Settings>().clone();
+        for city in &self.cities {
+            city.update(cx, |city, _| city.resolve_options(&settings));
+        }
+        let any_timeline = self
+            .cities
+            .iter()
+            .any(|city| city.read(cx).options.show_timeline);
+        let axis = any_timeline.then(|| {
+            let cities: Vec<(String, Tz)> = self
+                .cities
+                .iter()
+                .map(|city| {
+                    let city = city.read(cx);
+                    (city.name.clone(), city.tz)
+                })
+                .collect();
+            TimelineAxis::new(now, self.simulated_instant.is_none(), &cities)
+        });
+        let (home_hours, home_dnd) = self
+            .cities
+            .iter()
+            .map(|city| city.read(cx))
+            .find(|city| city.is_home)
+            .map_or((default_hours, default_dnd), |city| {
+                (
+                    city.working_hours.unwrap_or(default_hours),
+                    city.do_not_disturb.unwrap_or(default_dnd),
+                )
+            });
+        let home_offset = zone::utc_offset_minutes(home_tz, now);
+        let home_display_offset = home_offset
+            + self
+                .cities
+                .iter()
+                .map(|city| city.read(cx))
+                .find(|city| city.is_home)
+                .map_or(0, |home| {
+                    offset_override::active_minutes(home.offset_override.as_ref(), home.tz, now)
+                });
+        let home_standard = zone::standard_offset_minutes(home_tz, now);
+        let pinned_events = &settings.pinned_events;
+        let mirror = &settings.mirror;
+        let default_weekend = settings.week.weekend;
+        let marker = self.marker.filter(|_| self.marker_on_cards);
+        // Scored over the week from home's midnight, so it changes once a day
+        let home_week = self
+            .cities
+            .iter()
+            .map(|city| city.read(cx))
+            .find(|city| city.is_home)
+            .map(|home| WorkWeek {
+                tz: home.tz,
+                hours: home_hours,
+                weekend: home.weekend.unwrap_or(default_weekend),
+            });
+        let home_today = now.with_timezone(&home_tz).date_naive();
+        let week_from =
+            convert::resolve_local(home_today.and_time(NaiveTime::MIN), home_tz).instant;
+        for city in &self.cities {
+            city.update(cx, |city, cx| {
+                let options = city.options;
+                let hours = city.working_hours.unwrap_or(default_hours);
+                let dnd = city.do_not_disturb.unwrap_or(default_dnd);
+                let local = now.with_timezone(&city.tz).time();
+                city.in_do_not_disturb = dnd.contains(local);
+                city.relative_day = relative::relative_day(home_tz, city.tz, now);
+                city.relative_phrase = options.show_relative_phrase.then(|| {
+                    let (part, day) = relative::relative_phrase(home_tz, city.tz, now);
+                    i18n::relative_phrase(settings.language, part, day)
+                });
+                city.day_stats = options
+                    .show_day_stats
+                    .then(|| day_stats::stats_line(now, city.tz, hours.start, city.is_home));
+                let weekend = city.weekend.unwrap_or(default_weekend);
+                city.workday = options
+                    .show_workday
+                    .then(|| workday::workday_line(now, city.tz, &hours, weekend));
+                city.mirror = (options.show_mirror && !city.is_home).then(|| {
+                    let their = mirror.their_time.unwrap_or(hours.end);
+                    convert::mirror_label(now, city.tz, their, home_tz)
+                });
+                city.marker_line = marker.map(|marker| marker::card_line(marker, city.tz, now));
+                city.pinned_lines = pinned_events
+                    .iter()
+                    .filter_map(|event| event.card_line(now, city.tz))
+                    .collect();
+                city.call_hint = (!city.is_home).then(|| {
+                    let offset = zone::utc_offset_minutes(city.tz, now);
+                    match planner::call_window(
+                        home_offset,
+                        (&home_hours, &home_dnd),
+                        offset,
+                        (&hours, &dnd),
+                    ) {
+                        Some((start, end)) if start == end => "good to call: any time".to_string(),
+                        Some((start, end)) => format!(
+                            "good to call: {}–{} your time",
+                            format::clock_time(start, false),
+                            format::clock_time(end, false)
+                        ),
+                        None => "no good overlap today".to_string(),
+                    }
+                });
+                let own_week = WorkWeek {
+                    tz: city.tz,
+                    hours,
+                    weekend,
+                };
+                let inputs = home_week
+                    .filter(|_| !city.is_home && !settings.hide_overlap_score)
+                    .map(|home_week| (home_today, home_week, own_week));
+                if inputs != city.overlap_inputs {
+                    city.overlap = inputs.and_then(|(_, home_week, own_week)| {
+                        planner::overlap_score(&home_week, &own_week, week_from)
+                    });
+                    city.overlap_inputs = inputs;
+                }
+                city.timeline = axis
+                    .clone()
+                    .filter(|_| options.show_timeline)
+                    .map(|axis| TimelineBand::new(axis, city.tz, &hours));
+                city.update_time(now, home_display_offset, home_standard);
+                city.work_status = planning.then(|| planner::work_status(local, &hours, &dnd));
+                cx.notify();
+            });
+        }
+        self.mark_same_times(cx);
+        if cx.global::<Settings>().auto_order {
+            self.apply_order(now, cx);
+        }
+        for countdown in &self.countdowns {
+            countdown.update(cx, |countdown, cx| {
+                countdown.update_time(now);
+                cx.notify();
+            });
+        }
+        self.attach_countdowns(cx);
+        // About the real clock, even while planning at another time
+        let real_now = Utc::now();
+        let horizon =
+            chrono::Duration::days(cx.global::<Settings>().clock_change.horizon_days as i64);
+        self.clock_change = zone::next_offset_change(home_tz, real_now)
+            .filter(|change| change.at - real_now <= horizon)
+            .map(|change| zone::describe_change_countdown(&change, real_now));
+        cx.notify();
+    }
+
+    fn mark_same_times(&mut self, cx: &mut Context<Self>) {
+        let partners = {
+            let cards: Vec<&WorldTime> = self.cities.iter().map(|city| city.read(cx)).collect();
+            let shown: Vec<(&str, &str, &str)> = cards
+                .iter()
+                .map(|city| {
+                    (
+                        city.name.as_str(),
+                        city.time.as_str(),
+                        city.timezone_id.as_str(),
+                    )
+                })
+                .collect();
+            same_time::same_time_partners(&shown)
+        };
+        for (city, partner) in self.cities.iter().zip(partners) {
+            city.update(cx, |city, cx| {
+                if city.same_time_as != partner {
+                    city.same_time_as = partner;
+                    cx.notify();
+                }
+            });
+        }
+    }
+
+    fn quick_convert(&mut self, query: &str, cx: &mut Context<Self>) {
+        self.quick_convert = if query.trim().is_empty() {
+            None
+        } else {
+            let cities = self.city_configs(cx);
+            let home = convert::home_zone(&cities);
+            Some(convert::convert_query(query, &cities, &home, Utc::now()))
+        };
+        cx.notify();
+    }
+
+    // Opening starts at the current slot; closing returns every card to live time
+    fn toggle_planner(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if self.planner.take().is_some() {
+            self.set_simulated_instant(None, cx);
+            return;
+        }
+        let step = planner::SLOT_MINUTES as f32 / 60.0;
+        let slider = cx.new(|_| SliderState::new().min(-12.0).max(36.0).step(step));
+        cx.subscribe_in(
+            &slider,
+            window,
+            |this, _, event: &SliderEvent, window, cx| {
+                let SliderEvent::Change(value) = event;
+                let offset_minutes = (value.start() * 60.0).round() as i64;
+                this.set_planner_offset(offset_minutes, window, cx);
+            },
+        )
+        .detach();
+        self.planner = Some(slider);
+        self.set_planner_offset(0, window, cx);
+    }
+
+    fn set_planner_offset(
+        &mut self,
+        offset_minutes: i64,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(slider) = self.planner.clone() else {
+            return;
+        };
+        let offset_minutes = offset_minutes.clamp(-12 * 60, 36 * 60);
+        slider.update(cx, |slider, cx| {
+            slider.set_value(offset_minutes as f32 / 60.0, window, cx)
+        });
+        self.planner_offset_minutes = offset_minutes;
+        self.set_simulated_instant(Some(planner::slot_at(Utc::now(), offset_minutes)), cx);
+    }
+
+    fn add_city(&mut self, city: CityConfig, window: &mut Window, cx: &mut Context<Self>) {
+        match zone::resolve_zone(&city.timezone) {
+            Ok(tz) => {
+                let home_tz = self.home_tz;
+                let card = Self::new_card(&city, tz, home_tz, window, cx);
+                self.cities.push(card);
+                self.refresh_times(cx);
+                self.save_config(window, cx);
+            }
+            Err(err) => window.push_notification(Notification::error(err.to_string()), cx),
+        }
+    }
+
+    // Everything "Run diagnostics" looks at, in the order it's listed
+    fn diagnostics(&self, now: DateTime<Utc>, cx: &App) -> Vec<diagnostics::Check> {
+        let cities = self.city_configs(cx);
+        let displays: Vec<_> = cx
+            .displays()
+            .iter()
+            .map(|display| (display.id(), display.bounds()))
+            .collect();
+        let mut checks = vec![
+            diagnostics::zones_resolve(&cities),
+            diagnostics::aliases(&cities),
+            diagnostics::transitions(&cities, now),
+        ];
+        checks.extend(
+            config::config_path()
+                .as_deref()
+                .map(diagnostics::config_file),
+        );
+        checks.push(diagnostics::window_bounds(
+            self.window_state.as_ref(),
+            &displays,
+        ));
+        checks.push(diagnostics::timer(
+            self.ticker_health.last_tick(),
+            now,
+            self.ticker_interval,
+        ));
+        checks
+    }
+
+    // Swaps an error card for a working one, keeping everything else the city had
+    fn repair_city(
+        &mut self,
+        id: &str,
+        fixed: CityConfig,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(index) = self
+            .broken_cities
+            .iter()
+            .position(|(city, _)| city.id == id)
+        else {
+            return;
+        };
+        let Ok(tz) = zone::resolve_zone(&fixed.timezone) else {
+            return;
+        };
+        self.push_undo(&format!("fixing {}'s timezone", fixed.name), cx);
+        let (mut city, _) = self.broken_cities.remove(index);
+        city.name = fixed.name;
+        city.timezone = fixed.timezone;
+        if city.home {
+            self.home_tz = tz;
+        }
+        let home_tz = self.home_tz;
+        let card = Self::new_card(&city, tz, home_tz, window, cx);
+        self.cities.push(card);
+        self.refresh_times(cx);
+        self.save_config(window, cx);
+    }
+
+    fn remove_broken_city(&mut self, id: &str, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(index) = self
+            .broken_cities
+            .iter()
+            .position(|(city, _)| city.id == id)
+        else {
+            return;
+        };
+        let name = self.broken_cities[index].0.name.clone();
+        self.push_undo(&format!("removing {}", name), cx);
+        self.broken_cities.remove(index);
+        self.save_config(window, cx);
+        cx.notify();
+    }
+
+    // Re-sorts by the offsets at `now`, so a DST change or a new card moves cards
+    // on the next refresh. The home card keeps its highlight, not a fixed place.
+    fn apply_order(&mut self, now: DateTime<Utc>, cx: &mut Context<Self>) {
+        let cities: Vec<(String, i32)> = self
+            .cities
+            .iter()
+            .map(|card| {
+                let card = card.read(cx);
+                (card.name.clone(), zone::utc_offset_minutes(card.tz, now))
+            })
+            .collect();
+        let order = ordering::by_local_time(&cities);
+        if order
+            .iter()
+            .enumerate()
+            .all(|(position, &index)| position == index)
+        {
+            return;
+        }
+        self.cities = order
+            .into_iter()
+            .map(|index| self.cities[index].clone())
+            .collect();
+        self.reorder_generation += 1;
+        cx.notify();
+    }
+
+    // The cards as the grid shows them, with same-zone cards stacked when enabled
+    fn grid_entries(&self, cx: &App) -> Vec<GridEntry> {
+        let shown: Vec<&Entity<WorldTime>> = self
+            .cities
+            .iter()
+            .filter(|city| !city.read(cx).hidden)
+            .collect();
+        if !cx.global::<Settings>().stack_same_zone {
+            return shown.into_iter().cloned().map(GridEntry::Card).collect();
+        }
+        let cards: Vec<&WorldTime> = shown.iter().map(|city| city.read(cx)).collect();
+        let zones: Vec<&str> = cards.iter().map(|city| city.timezone_id.as_str()).collect();
+        stacks::grid_items(&zones, &self.expanded_stacks)
+            .into_iter()
+            .map(|item| match item {
+                stacks::GridItem::Card(index) => GridEntry::Card(shown[index].clone()),
+                stacks::GridItem::Stack { zone, members } => {
+                    let first = cards[members[0]];
+                    let mut names: Vec<String> = members
+                        .iter()
+                        .map(|&index| cards[index].name.clone())
+                        .collect();
+                    names.sort_by_key(|name| name.to_lowercase());
+                    GridEntry::Stack(ZoneStack {
+                        zone_id: zone,
+                        time: first.time.clone(),
+                        diff_minutes: first.diff_minutes,
+                        is_home: members.iter().any(|&index| cards[index].is_home),
+                        names,
+                    })
+                }
+            })
+            .collect()
+    }
+
+    fn set_stack_expanded(
+        &mut self,
+        zone: String,
+        expanded: bool,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.expanded_stacks.retain(|open| *open != zone);
+        if expanded {
+            self.expanded_stacks.push(zone);
+        }
+        self.save_config(window, cx);
+        cx.notify();
+    }
+
+    fn set_city_calendar(
+        &mut self,
+        city: &Entity<WorldTime>,
+        calendar: Option<AltCalendar>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        city.update(cx, |city, cx| {
+            city.calendar = calendar;
+            cx.notify();
+        });
+        self.refresh_times(cx);
+        self.save_config(window, cx);
+    }
+
+    fn set_city_weekend(
+        &mut self,
+        city: &Entity<WorldTime>,
+        weekend: Option<Weekend>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        city.update(cx, |city, cx| {
+            city.weekend = weekend;
+            cx.notify();
+        });
+        self.refresh_times(cx);
+        self.save_config(window, cx);
+    }
+
+    // Back to the default wording from settings on every card
+    fn reset_diff_styles(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        for city in &self.cities {
+            city.update(cx, |city, cx| {
+                city.display.diff_style = None;
+                cx.notify();
+            });
+        }
+        self.refresh_times(cx);
+        self.save_config(window, cx);
+    }
+
+    fn set_city_display(
+        &mut self,
+        city: &Entity<WorldTime>,
+        display: DisplayOverrides,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        city.update(cx, |city, cx| {
+            city.display = display;
+            cx.notify();
+        });
+        self.refresh_times(cx);
+        self.apply_ticker_interval(cx);
+        self.save_config(window, cx);
+    }
+
+    // Every second while any card shows seconds or the marker counts up, and every
+    // minute or faster while the table or a timeline shows its now line
+    fn apply_ticker_interval(&mut self, cx: &mut Context<Self>) {
+        let any_seconds = self.marker.is_some()
+            || self
+                .cities
+                .iter()
+                .any(|city| city.read(cx).options.show_seconds);
+        let now_line = self.table_view
+            || self
+                .cities
+                .iter()
+                .any(|city| city.read(cx).options.show_timeline);
+        let interval = ticker::effective_interval(
+            any_seconds,
+            now_line,
+            cx.global::<Settings>(),
+            self.interval_override,
+        );
+        if interval != self.ticker_interval {
+            self.ticker_interval = interval;
+            self.ticker = Self::start_ticker(interval, cx);
+        }
+    }
+
+    fn set_city_snooze(
+        &mut self,
+        city: &Entity<WorldTime>,
+        until: Option<NaiveDate>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        city.update(cx, |city, cx| {
+            city.snoozed_until = until;
+            city.snoozed = snooze::is_active(until, city.tz, Utc::now());
+            cx.notify();
+        });
+        self.save_config(window, cx);
+    }
+
+    fn set_city_override(
+        &mut self,
+        city: &Entity<WorldTime>,
+        offset_override: Option<OffsetOverride>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        city.update(cx, |city, cx| {
+            city.offset_override = offset_override;
+            cx.notify();
+        });
+        self.refresh_times(cx);
+        self.save_config(window, cx);
+    }
+
+    fn set_city_people(
+        &mut self,
+        city: &Entity<WorldTime>,
+        people: Vec<String>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        city.update(cx, |city, cx| {
+            city.people = people;
+            cx.notify();
+        });
+        self.save_config(window, cx);
+    }
+
+    // What the palette matches a command against: its label, plus the people in
+    // the city it acts on so typing a teammate's name finds their city
+    fn command_search_text(&self, command: &AppCommand, cx: &App) -> String {
+        let people = command
+            .city()
+            .and_then(|name| self.card_named(name, cx))
+            .map(|card| card.read(cx).people.join(" "))
+            .unwrap_or_default();
+        format!("{} {}", command.label(), people)
+    }
+
+    fn set_city_coordinates(
+        &mut self,
+        city: &Entity<WorldTime>,
+        coordinates: Option<Coordinates>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        city.update(cx, |city, cx| {
+            city.coordinates = coordinates;
+            cx.notify();
+        });
+        self.save_config(window, cx);
+    }
+
+    fn add_alarm(&mut self, alarm: Alarm, window: &mut Window, cx: &mut Context<Self>) {
+        self.alarms.push(alarm);
+        self.save_config(window, cx);
+        cx.notify();
+    }
+
+    fn remove_alarm(&mut self, index: usize, window: &mut Window, cx: &mut Context<Self>) {
+        if index < self.alarms.len() {
+            self.alarms.remove(index);
+            self.save_config(window, cx);
+            cx.notify();
+        }
+    }
+
+    // Rings every due alarm once; one-shot alarms are then dropped, daily ones re-armed
+    fn check_alarms(&mut self, now: DateTime<Utc>, window: &mut Window, cx: &mut Context<Self>) {
+        let quiet_hours = cx.global::<Settings>().quiet_hours;
+        let quiet = quiet_hours::is_quiet(&quiet_hours, self.home_tz, now);
+        if !quiet {
+            self.deliver_held_alarms(cx);
+        }
+        if self.alarms.is_empty() {
+            return;
+        }
+        let cities: Vec<WorldTime> = self
+            .cities
+            .iter()
+            .map(|city| city.read(cx).clone())
+            .collect();
+        let home = cities
+            .iter()
+            .find(|city| city.is_home)
+            .or(cities.first())
+            .map(|city| (city.name.as_str(), city.tz));
+
+        let mut fired = false;
+        let mut suppressed = Vec::new();
+        self.alarms.retain_mut(|alarm| {
+            let Some(city) = cities.iter().find(|city| city.name == alarm.city) else {
+                return true;
+            };
+            if !alarm.is_due(city.tz, now) {
+                return true;
+            }
+            let (summary, body) = alarms::notification_text(alarm, now, city.tz, home);
+            if quiet {
+                suppressed.push(Suppressed {
+                    summary,
+                    body,
+                    when_quiet: alarm.when_quiet,
+                    delivered: false,
+                });
+            } else {
+                alarms::show_desktop_notification(summary, body);
+            }
+            fired = true;
+            alarm.armed_since = now;
+            alarm.repeat == Repeat::Daily
+        });
+        self.suppressed_alarms.extend(suppressed);
+        if fired {
+            self.save_config(window, cx);
+            cx.notify();
+        }
+    }
+
+    // Held alarms ring once quiet hours are over; dropped ones only count in the badge
+    fn deliver_held_alarms(&mut self, cx: &mut Context<Self>) {
+        let mut delivered = false;
+        for alarm in &mut self.suppressed_alarms {
+            if alarm.when_quiet == WhenQuiet::Hold && !alarm.delivered {
+                alarms::show_desktop_notification(alarm.summary.clone(), alarm.body.clone());
+                alarm.delivered = true;
+                delivered = true;
+            }
+        }
+        if delivered {
+            cx.notify();
+        }
+    }
+
+    fn dismiss_suppressed_alarms(&mut self, cx: &mut Context<Self>) {
+        self.suppressed_alarms.clear();
+        cx.notify();
+    }
+
+    // Only once quiet hours are over, so it greets the morning rather than the night
+    fn suppressed_badge(&self, cx: &App) -> Option<(String, String)> {
+        let quiet_hours = cx.global::<Settings>().quiet_hours;
+        if self.suppressed_alarms.is_empty()
+            || quiet_hours::is_quiet(&quiet_hours, self.home_tz, Utc::now())
+        {
+            return None;
+        }
+        Some(quiet_hours::describe_suppressed(&self.suppressed_alarms))
+    }
+
+    // Messages come from the single-instance channel or the OS's open-url hook
+    fn handle_message(&mut self, message: &str, window: &mut Window, cx: &mut Context<Self>) {
+        if message == single_instance::ACTIVATE {
+            self.refresh_times(cx);
+            cx.activate(true);
+            window.activate_window();
+            return;
+        }
+        match deep_link::parse_deep_link(message) {
+            Ok(DeepLink::AddCity { name, timezone }) => {
+                add_city::open_add_city(cx.entity(), &name, &timezone, window, cx);
+            }
+            Ok(DeepLink::Convert { at }) => self.set_simulated_instant(Some(at), cx),
+            Ok(DeepLink::Focus { card }) => match self.card_by_ref(&card, cx) {
+                Some(found) => {
+                    let name = found.read(cx).name.clone();
+                    cx.activate(true);
+                    window.activate_window();
+                    self.focus_card(&name, window, cx);
+                }
+                None => window.push_notification(
+                    Notification::warning(format!("Ignored link {}: no such card", message)),
+                    cx,
+                ),
+            },
+            Err(err) => {
+                window.push_notification(
+                    Notification::error(format!("Ignored link {}: {}", message, err))
+                        .autohide(false),
+                    cx,
+                );
+            }
+        }
+    }
+
+    // Scripted changes take the UI's own steps, so undo and saving behave the same;
+    // what can't be done is reported on stderr
+    fn run_control_command(
+        &mut self,
+        command: ControlCommand,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let result = match command {
+            ControlCommand::Add(city) => {
+                if self.card_named(&city.name, cx).is_some() {
+                    Err(format!("there's already a city called {}", city.name))
+                } else {
+                    self.add_city(city, window, cx);
+                    Ok(())
+                }
+            }
+            // All or nothing, and one undo step for the lot
+            ControlCommand::Remove(references) => references
+                .iter()
+                .map(|reference| match self.card_by_ref(reference, cx) {
+                    None => Err(format!("no city called {}", reference)),
+                    Some(card) if card.read(cx).is_home => Err(format!(
+                        "{} is home; set-home another city before removing it",
+                        reference
+                    )),
+                    Some(card) => Ok(card.read(cx).name.clone()),
+                })
+                .collect::<Result<Vec<String>, String>>()
+                .map(|names| self.remove_cities(&names, window, cx)),
+            ControlCommand::SetHome(reference) => match self.card_by_ref(&reference, cx) {
+                None => Err(format!("no city called {}", reference)),
+                Some(card) => {
+                    let name = card.read(cx).name.clone();
+                    self.set_home(&name, window, cx);
+                    Ok(())
+                }
+            },
+            ControlCommand::Snapshot(path) => {
+                let now = self.display_instant();
+                let settings = cx.global::<Settings>();
+                let (snapshots, _) = export::snapshot_cities(
+                    &self.city_configs(cx),
+                    now,
+                    settings.overrides_in_exports,
+                );
+                std::fs::write(
+                    &path,
+                    export::write_json(&snapshots, now, settings.include_people),
+                )
+                .map_err(|err| format!("couldn't write {}: {}", path.display(), err))
+            }
+        };
+        if let Err(err) = result {
+            eprintln!("time2rust: {}", err);
+        }
+    }
+
+    fn city_configs(&self, cx: &App) -> Vec<CityConfig> {
+        self.cities
+            .iter()
+            .map(|city| city.read(cx).to_config())
+            .chain(self.pending_cities.iter().cloned())
+            .chain(self.broken_cities.iter().map(|(city, _)| city.clone()))
+            .collect()
+    }
+
+    // What exports and copies cover: the cards on screen, not the hidden ones
+    fn shown_cards(&self, cx: &App) -> Vec<Entity<WorldTime>> {
+        self.cities
+            .iter()
+            .filter(|city| !city.read(cx).hidden)
+            .cloned()
+            .collect()
+    }
+
+    fn shown_city_configs(&self, cx: &App) -> Vec<CityConfig> {
+        self.city_configs(cx)
+            .into_iter()
+            .filter(|city| !city.hidden)
+            .collect()
+    }
+
+    // Without the cities added for this run only
+    fn saved_city_configs(&self, cx: &App) -> Vec<CityConfig> {
+        self.city_configs(cx)
+            .into_iter()
+            .filter_map(CityConfig::persisted)
+            .collect()
+    }
+
+    fn save_profile_as(&mut self, name: String, window: &mut Window, cx: &mut Context<Self>) {
+        let cities = self.saved_city_configs(cx);
+        profiles::save_copy(&mut self.profiles, name.clone(), &cities);
+        self.save_config(window, cx);
+        window.push_notification(
+            Notification::success(format!("Saved as the \"{}\" profile", name)),
+            cx,
+        );
+        cx.notify();
+    }
+
+    fn switch_profile(&mut self, name: &str, window: &mut Window, cx: &mut Context<Self>) {
+        let current = self.saved_city_configs(cx);
+        let layout = Layout {
+            window: self.window_state.clone(),
+            table_view: self.table_view,
+            focused_card: self.focused_card.clone(),
+        };
+        let Some(profile) = profiles::switch(
+            &mut self.profiles,
+            &mut self.active_profile,
+            current,
+            layout,
+            name,
+        ) else {
+            return;
+        };
+        self.floating_cards
+            .retain(|floating| profile.cities.iter().any(|city| city.name == floating.city));
+        if let Some(layout) = &profile.layout {
+            self.focused_card = layout.focused_card.clone();
+        }
+        self.replace_cities(&profile.cities, window, cx);
+        // A profile not used since layouts were kept takes the current one over
+        if let Some(layout) = profile.layout {
+            if let Some(state) = &layout.window {
+                window_state::apply(state, window, cx);
+            }
+            self.window_state = layout.window.or(self.window_state.take());
+            self.table_view = layout.table_view;
+            self.table_selection = None;
+            self.apply_ticker_interval(cx);
+        }
+        self.save_config(window, cx);
+    }
+
+    fn rename_profile(
+        &mut self,
+        from: &str,
+        to: String,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.active_profile == from {
+            self.active_profile = to;
+        } else if let Some(profile) = self
+            .profiles
+            .iter_mut()
+            .find(|profile| profile.name == from)
+        {
+            profile.name = to;
+        }
+        self.save_config(window, cx);
+        cx.notify();
+    }
+
+    fn delete_profile(&mut self, name: &str, window: &mut Window, cx: &mut Context<Self>) {
+        self.profiles.retain(|profile| profile.name != name);
+        self.save_config(window, cx);
+        cx.notify();
+    }
+
+    fn open_save_profile(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let app = cx.entity();
+        profiles::open_name_dialog(
+            "Save current setup as new profile",
+            "Save",
+            "",
+            {
+                let app = app.clone();
+                move |name, cx| {
+                    let app = app.read(cx);
+                    profiles::validate_name(name, &app.active_profile, &app.profiles, None)
+                }
+            },
+            move |name, window, cx| app.update(cx, |app, cx| app.save_profile_as(name, window, cx)),
+            window,
+            cx,
+        );
+    }
+
+    fn open_rename_profile(&mut self, name: String, window: &mut Window, cx: &mut Context<Self>) {
+        let app = cx.entity();
+        let initial = name.clone();
+        profiles::open_name_dialog(
+            "Rename profile",
+            "Rename",
+            &initial,
+            {
+                let app = app.clone();
+                let name = name.clone();
+                move |new_name, cx| {
+                    let app = app.read(cx);
+                    profiles::validate_name(
+                        new_name,
+                        &app.active_profile,
+                        &app.profiles,
+                        Some(&name),
+                    )
+                }
+            },
+            move |new_name, window, cx| {
+                app.update(cx, |app, cx| {
+                    app.rename_profile(&name, new_name, window, cx)
+                })
+            },
+            window,
+            cx,
+        );
+    }
+
+    fn export_csv(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let source = self.display_source();
+        let (snapshots, _) = export::snapshot_cities(
+            &self.shown_city_configs(cx),
+            source.instant(),
+            cx.global::<Settings>().overrides_in_exports,
+        );
+        let csv = export::write_csv(&snapshots, cx.global::<Settings>().include_people);
+        self.touch(&self.shown_cards(cx), window, cx);
+        let note = source.describe(self.home_tz);
+        self.save_export(move |_| csv, "time2rust.csv", Some(note), window, cx);
+    }
+
+    // Working hours for this week in home time, from the configured first day;
+    // saving as .csv picks CSV, anything else Markdown
+    fn export_week(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let default_hours = cx.global::<Settings>().working_hours;
+        let week = cx.global::<Settings>().week;
+        let cities: Vec<(String, Tz, WorkingHours, Weekend)> = self
+            .shown_cards(cx)
+            .iter()
+            .map(|city| {
+                let city = city.read(cx);
+                (
+                    city.name.clone(),
+                    city.tz,
+                    city.working_hours.unwrap_or(default_hours),
+                    city.weekend.unwrap_or(week.weekend),
+                )
+            })
+            .collect();
+        let home = convert::home_zone(&self.city_configs(cx));
+        let source = self.display_source();
+        let today = week.week_start(source.instant().with_timezone(&self.home_tz).date_naive());
+        let grid = export::week_grid(&cities, self.home_tz, today);
+        let mut markdown = export::write_week_markdown(&grid, &home.label);
+        if let Some(line) = self.marker_export_line() {
+            markdown = format!("{}\n{}\n", markdown.trim_end(), line);
+        }
+        let csv = export::write_week_csv(&grid, &home.label);
+        self.touch(&self.shown_cards(cx), window, cx);
+
+        self.save_export(
+            move |path| {
+                let is_csv = path
+                    .extension()
+                    .is_some_and(|extension| extension.eq_ignore_ascii_case("csv"));
+                if is_csv { csv } else { markdown }
+            },
+            "week.md",
+            Some(format!("week from {}", format::weekday_date(today))),
+            window,
+            cx,
+        );
+    }
+
+    // The series exports repeat as, when the settings ask for one
+    fn weekly_series(&self, cx: &App) -> Option<WeeklySeries> {
+        let series = cx.global::<Settings>().series;
+        series.weekly.then_some(WeeklySeries {
+            home: self.home_tz,
+            count: series.occurrences,
+        })
+    }
+
+    // Before a series goes out, says which cities' times move during it; the
+    // export itself carries the same lines
+    fn warn_series_shifts(
+        &self,
+        snapshots: &[CitySnapshot],
+        start: DateTime<Utc>,
+        twelve_hour: bool,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(series) = self.weekly_series(cx) else {
+            return;
+        };
+        let notes = export::series_notes(snapshots, start, series, twelve_hour);
+        if !notes.is_empty() {
+            window.push_notification(Notification::warning(notes.join("\n")).autohide(false), cx);
+        }
+    }
+
+    // A one-hour calendar event at the planned slot, or a weekly series from it
+    fn export_ics(&mut self, source: DisplayInstant, window: &mut Window, cx: &mut Context<Self>) {
+        let start = source.instant();
+        let (snapshots, _) = export::snapshot_cities(
+            &self.shown_city_configs(cx),
+            start,
+            cx.global::<Settings>().overrides_in_exports,
+        );
+        let ics = export::write_ics(
+            &snapshots,
+            start,
+            start + chrono::Duration::hours(1),
+            "Meeting",
+            self.weekly_series(cx),
+            cx.global::<Settings>().clock_format,
+            Utc::now(),
+        );
+        self.warn_series_shifts(&snapshots, start, false, window, cx);
+        self.touch(&self.shown_cards(cx), window, cx);
+        let note = source.describe(self.home_tz);
+        self.save_export(move |_| ics, "meeting.ics", Some(note), window, cx);
+    }
+
+    // Asks where to save, then writes in the background and reports the outcome,
+    // with `note` saying which time it was for. The contents may depend on the
+    // chosen path, e.g. its extension.
+    fn save_export(
+        &mut self,
+        contents: impl FnOnce(&Path) -> String + 'static,
+        suggested_name: &str,
+        note: Option<String>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let directory = dirs::document_dir()
+            .or_else(dirs::home_dir)
+            .unwrap_or_default();
+        let path = cx.prompt_for_new_path(&directory, Some(suggested_name));
+        cx.spawn_in(window, async move |_, cx| {
+            let Ok(Ok(Some(path))) = path.await else {
+                return;
+            };
+            let result = std::fs::write(&path, contents(&path));
+            cx.update(|window, cx| {
+                let notification = match result {
+                    Ok(()) => Notification::success(match &note {
+                        Some(note) => format!("Exported to {}, {}", path.display(), note),
+                        None => format!("Exported to {}", path.display()),
+                    }),
+                    Err(err) => Notification::error(format!("Export failed: {}", err)),
+                };
+                window.push_notification(notification, cx);
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    // Copies a one-line announcement of `instant` in every city, in display order
+    fn copy_slack_message(
+        &mut self,
+        source: DisplayInstant,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let (snapshots, _) = export::snapshot_cities(
+            &self.shown_city_configs(cx),
+            source.instant(),
+            cx.global::<Settings>().overrides_in_exports,
+        );
+        let settings = cx.global::<Settings>();
+        let twelve_hour = settings.slack.twelve_hour;
+        let message = export::format_slack_message(
+            &snapshots,
+            &settings.slack,
+            settings.include_people,
+            settings.clock_format,
+            self.weekly_series(cx),
+        );
+        let message = match self.marker_export_line() {
+            Some(line) => format!("{}\n{}", message, line),
+            None => message,
+        };
+        cx.write_to_clipboard(ClipboardItem::new_string(message));
+        self.touch(&self.shown_cards(cx), window, cx);
+        let note = source.describe(self.home_tz);
+        window.push_notification(
+            Notification::success(format!("Copied Slack message, {}", note)),
+            cx,
+        );
+        self.warn_series_shifts(&snapshots, source.instant(), twelve_hour, window, cx);
+    }
+
+    // One card for a colleague: its time and offset, how it relates to home, its
+    // working hours, and a link that adds the same card. At the real time, even
+    // while planning.
+    fn share_card(&mut self, name: &str, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(card) = self.card_named(name, cx) else {
+            return;
+        };
+        let now = Utc::now();
+        let settings = cx.global::<Settings>();
+        // Overrides included, as on the cards
+        let shift_at = |city: &WorldTime| {
+            offset_override::active_minutes(city.offset_override.as_ref(), city.tz, now)
+        };
+        let home = self
+            .cities
+            .iter()
+            .map(|city| city.read(cx))
+            .find(|city| city.is_home)
+            .map(|home| {
+                let offset = zone::utc_offset_minutes(home.tz, now) + shift_at(home);
+                (home.name.clone(), offset)
+            });
+        let shared = {
+            let city = card.read(cx);
+            let shift = shift_at(city);
+            let offset = zone::utc_offset_minutes(city.tz, now) + shift;
+            let local = (now + chrono::Duration::minutes(shift as i64)).with_timezone(&city.tz);
+            let hours = city.working_hours.unwrap_or(settings.working_hours);
+            format::SharedCard {
+                name: city.name.clone(),
+                time: settings.clock_format.format(local.time(), offset, false),
+                zone: local.format("%Z").to_string(),
+                utc_offset_minutes: offset,
+                diff_minutes: home
+                    .as_ref()
+                    .map_or(0, |(_, home_offset)| offset - home_offset),
+                home: home
+                    .filter(|_| !city.is_home)
+                    .map(|(home_name, _)| home_name),
+                working_hours: Some((hours.start, hours.end)),
+                link: Some(deep_link::add_link(&city.name, &city.timezone_id)),
+            }
+        };
+        cx.write_to_clipboard(ClipboardItem::new_string(format::share_card(&shared)));
+        self.touch(&[card], window, cx);
+        window.push_notification(
+            Notification::success(format!("Copied {}'s card to share", name)),
+            cx,
+        );
+    }
+
+    // "Berlin 21:45 CET", as the card shows it
+    fn copy_time(&mut self, name: &str, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(card) = self.card_named(name, cx) else {
+            return;
+        };
+        let mut line = {
+            let city = card.read(cx);
+            format!("{} {} {}", city.name, city.time, city.zone_abbreviation)
+        };
+        if let Some(marker) = self.marker_export_line() {
+            line = format!("{}\n{}", line, marker);
+        }
+        cx.write_to_clipboard(ClipboardItem::new_string(line));
+        self.touch(&[card], window, cx);
+        let note = self.display_source().describe(self.home_tz);
+        window.push_notification(
+            Notification::success(format!("Copied {}'s time, {}", name, note)),
+            cx,
+        );
+    }
+
+    // Called after any settings change made through the settings panel
+    fn settings_changed(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.apply_global_shortcut(window, cx);
+        self.apply_http_endpoint(window, cx);
+        self.apply_clock_sync(cx);
+        self.apply_ticker_interval(cx);
+        let opacity = cx.global::<Settings>().window_opacity;
+        window.set_background_appearance(opacity.background());
+        window.set_window_title(cx.global::<Settings>().header.window_title());
+        Self::apply_zoom(window, cx);
+        if let Some(mini_widget) = self.mini_widget {
+            mini_widget
+                .update(cx, |_, window, _| {
+                    window.set_background_appearance(opacity.background())
+                })
+                .ok();
+        }
+        self.refresh_times(cx);
+        self.save_config(window, cx);
+        cx.notify();
+    }
+
+    // The user's zoom with the current display's adjustment. Run again whenever the
+    // window moves or resizes, which is also how crossing onto a display with
+    // another scale factor shows up.
+    fn apply_zoom(window: &mut Window, cx: &App) {
+        let rem_size = px(zoom::rem_size(
+            &cx.global::<Settings>().zoom,
+            window.scale_factor(),
+        ));
+        if window.rem_size() != rem_size {
+            window.set_rem_size(rem_size);
+            window.refresh();
+        }
+    }
+
+    fn window_bounds_changed(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        Self::apply_zoom(window, cx);
+        if window.is_maximized() && cx.global::<Settings>().prevent_maximize {
+            // Restore to original size when maximized
+            window.resize(size(
+                px(window_state::DEFAULT_WIDTH),
+                px(window_state::DEFAULT_HEIGHT),
+            ));
+            return;
+        }
+        // While undocked, keep the placement on the external display so the next
+        // launch with it connected goes back there
+        if self
+            .window_state
+            .as_ref()
+            .is_some_and(|state| window_state::display_missing(state, cx))
+        {
+            return;
+        }
+        self.window_state = Some(WindowState::capture(window, cx));
+        self.save_config_soon(window, cx);
+    }
+
+    fn open_floating_card(
+        &mut self,
+        card: Entity<WorldTime>,
+        saved: Option<&WindowState>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let city = card.read(cx).name.clone();
+        if self
+            .floating_cards
+            .iter()
+            .any(|floating| floating.city == city)
+        {
+            return;
+        }
+        if let Some(state) = floating_card::open_floating_card(cx.entity(), card, saved, cx) {
+            self.floating_cards.push(FloatingCardConfig {
+                city,
+                window: state,
+            });
+            self.save_config(window, cx);
+        }
+    }
+
+    fn floating_card_moved(
+        &mut self,
+        city: &str,
+        state: WindowState,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some(floating) = self
+            .floating_cards
+            .iter_mut()
+            .find(|floating| floating.city == city)
+        {
+            floating.window = state;
+            self.save_config_soon(window, cx);
+        }
+    }
+
+    fn floating_card_closed(&mut self, city: &str, window: &mut Window, cx: &mut Context<Self>) {
+        self.floating_cards.retain(|floating| floating.city != city);
+        self.save_config(window, cx);
+    }
+
+    // The command registry: every command that makes sense right now, per-city ones
+    // included
+    fn commands(&self, cx: &App) -> Vec<AppCommand> {
+        let mut commands = vec![
+            AppCommand::ShowShortcuts,
+            AppCommand::WhatsNew,
+            AppCommand::AddCity,
+            AppCommand::AddCountdown,
+            AppCommand::ImportCities,
+            AppCommand::Undo,
+            AppCommand::ToggleTable,
+            AppCommand::ToggleAutoOrder,
+            AppCommand::PlanMeeting,
+            AppCommand::FindOverlap,
+            AppCommand::ExportCsv,
+            AppCommand::ExportWeek,
+            AppCommand::CopySlack,
+            AppCommand::MiniWidget,
+            AppCommand::Kiosk,
+            AppCommand::OpenSettings,
+            AppCommand::ResetSettings,
+            AppCommand::BackUp,
+            AppCommand::Restore,
+            AppCommand::SaveProfileAs,
+        ];
+        commands.push(AppCommand::SetMarker);
+        if self.marker.is_some() {
+            commands.push(AppCommand::ToggleMarkerOnCards);
+            commands.push(AppCommand::ClearMarker);
+        }
+        if self.cities.len() > 1 {
+            commands.push(AppCommand::RemoveCities);
+            commands.push(AppCommand::ReviewUnused);
+        }
+        if !self.archived_countdowns.is_empty() {
+            commands.push(AppCommand::ToggleArchive);
+        }
+        if self
+            .cities
+            .iter()
+            .any(|city| city.read(cx).alias_of.is_some())
+        {
+            commands.push(AppCommand::UpdateZoneIds);
+        }
+        if cx.global::<Settings>().stack_same_zone {
+            let zones: Vec<&str> = self
+                .cities
+                .iter()
+                .map(|city| city.read(cx).timezone_id.as_str())
+                .collect();
+            commands.extend(stacks::shared_zones(&zones).into_iter().map(|zone| {
+                if self.expanded_stacks.iter().any(|open| open == zone) {
+                    AppCommand::CollapseStack(zone.to_string())
+                } else {
+                    AppCommand::ExpandStack(zone.to_string())
+                }
+            }));
+        }
+        commands.extend(
+            self.profiles
+                .iter()
+                .map(|profile| AppCommand::SwitchProfile(profile.name.clone())),
+        );
+        for city in &self.cities {
+            let city = city.read(cx);
+            if !city.is_home {
+                commands.push(AppCommand::SetHome(city.name.clone()));
+            }
+            commands.push(AppCommand::CopyTime(city.name.clone()));
+            commands.push(AppCommand::ShareCard(city.name.clone()));
+            commands.push(AppCommand::ShowDetails(city.name.clone()));
+            commands.push(AppCommand::AddAlarm(city.name.clone()));
+            commands.push(if city.snoozed {
+                AppCommand::Unsnooze(city.name.clone())
+            } else {
+                AppCommand::Snooze(city.name.clone())
+            });
+            commands.push(AppCommand::OpenInWindow(city.name.clone()));
+            commands.push(AppCommand::Compare(city.name.clone()));
+            commands.push(AppCommand::RemoveCity(city.name.clone()));
+            if city.hidden {
+                commands.push(AppCommand::ShowHidden(city.name.clone()));
+            }
+        }
+        commands
+    }
+
+    fn run_command(&mut self, command: AppCommand, window: &mut Window, cx: &mut Context<Self>) {
+        match command {
+            AppCommand::OpenPalette => commands::open_command_palette(cx.entity(), window, cx),
+            AppCommand::ShowShortcuts => {
+                self.show_shortcuts = true;
+                cx.notify();
+            }
+            AppCommand::WhatsNew => whats_new::open_whats_new(None, window, cx),
+            AppCommand::AddCity => add_city::open_add_city(cx.entity(), "", "", window, cx),
+            AppCommand::AddCountdown => {
+                countdown::open_add_countdown(cx.entity(), None, window, cx)
+            }
+            AppCommand::ToggleArchive => {
+                self.show_archive = !self.show_archive;
+                cx.notify();
+            }
+            AppCommand::ImportCities => self.import_cities(window, cx),
+            AppCommand::BackUp => self.back_up(window, cx),
+            AppCommand::ExpandStack(zone) => self.set_stack_expanded(zone, true, window, cx),
+            AppCommand::CollapseStack(zone) => self.set_stack_expanded(zone, false, window, cx),
+            AppCommand::UpdateZoneIds => {
+                let cards = self.cities.clone();
+                self.update_zone_ids(&cards, window, cx);
+            }
+            AppCommand::Restore => self.open_restore(window, cx),
+            AppCommand::Undo => self.undo(window, cx),
+            AppCommand::ToggleAutoOrder => {
+                cx.update_global::<Settings, _>(|settings, _| {
+                    settings.auto_order = !settings.auto_order
+                });
+                self.settings_changed(window, cx);
+            }
+            AppCommand::ToggleTable => {
+                self.table_view = !self.table_view;
+                self.table_selection = None;
+                self.apply_ticker_interval(cx);
+                cx.notify();
+            }
+            AppCommand::PlanMeeting => self.toggle_planner(window, cx),
+            AppCommand::FindOverlap => overlap_finder::open_overlap_finder(cx.entity(), window, cx),
+            AppCommand::ExportCsv => self.export_csv(window, cx),
+            AppCommand::ExportWeek => self.export_week(window, cx),
+            AppCommand::CopySlack => {
+                let source = self.display_source();
+                self.copy_slack_message(source, window, cx);
+            }
+            AppCommand::MiniWidget => self.open_mini_widget(window, cx),
+            AppCommand::Kiosk => self.enter_kiosk(window, cx),
+            AppCommand::OpenSettings => settings_panel::open_settings(cx.entity(), window, cx),
+            AppCommand::ResetSettings => confirm::confirm_reset_settings(cx.entity(), window, cx),
+            AppCommand::SaveProfileAs => self.open_save_profile(window, cx),
+            AppCommand::SwitchProfile(name) => self.switch_profile(&name, window, cx),
+            AppCommand::SetHome(name) => self.set_home(&name, window, cx),
+            AppCommand::ShowDetails(name) => {
+                if let Some(card) = self.card_named(&name, cx) {
+                    // Edits happen in the detail, so opening it counts
+                    self.touch(std::slice::from_ref(&card), window, cx);
+                    card_detail::open_card_detail(cx.entity(), card, false, window, cx);
+                }
+            }
+            AppCommand::OpenInWindow(name) => {
+                if let Some(card) = self.card_named(&name, cx) {
+                    self.open_floating_card(card, None, window, cx);
+                }
+            }
+            AppCommand::RemoveCity(name) => self.confirm_remove_city(name, window, cx),
+            AppCommand::ReviewUnused => self.review_unused(window, cx),
+            AppCommand::SetMarker => self.set_marker(Some(Utc::now()), window, cx),
+            AppCommand::ClearMarker => self.set_marker(None, window, cx),
+            AppCommand::ToggleMarkerOnCards => {
+                self.marker_on_cards = !self.marker_on_cards;
+                self.refresh_times(cx);
+                self.save_config(window, cx);
+            }
+            AppCommand::ShowHidden(name) => self.set_city_hidden(&name, false, window, cx),
+            AppCommand::RemoveCities => {
+                let names: Vec<String> = self
+                    .cities
+                    .iter()
+                    .map(|card| card.read(cx))
+                    .filter(|card| !card.is_home)
+                    .map(|card| card.name.clone())
+                    .collect();
+                confirm::confirm_remove_cities(cx.entity(), names, window, cx);
+            }
+            AppCommand::CopyTime(name) => self.copy_time(&name, window, cx),
+            AppCommand::ShareCard(name) => self.share_card(&name, window, cx),
+            AppCommand::Snooze(name) => {
+                if let Some(card) = self.card_named(&name, cx) {
+                    card_detail::open_card_detail(cx.entity(), card, false, window, cx);
+                }
+            }
+            AppCommand::Unsnooze(name) => {
+                if let Some(card) = self.card_named(&name, cx) {
+                    self.set_city_snooze(&card, None, window, cx);
+                }
+            }
+            AppCommand::AddAlarm(name) => {
+                if let Some(card) = self.card_named(&name, cx) {
+                    card_detail::open_card_detail(cx.entity(), card, true, window, cx);
+                }
+            }
+            AppCommand::Compare(name) => {
+                // Picking happens on the cards
+                self.table_view = false;
+                self.apply_ticker_interval(cx);
+                self.compare = None;
+                self.compare_pick = Some(name);
+                cx.notify();
+            }
+        }
+    }
+
+    fn handle_shortcut(
+        &mut self,
+        event: &KeyDownEvent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.kiosk.is_some() {
+            self.exit_kiosk(window, cx);
+            cx.stop_propagation();
+            return;
+        }
+        if self.show_shortcuts {
+            if event.keystroke.key == "escape" {
+                self.hide_shortcuts(cx);
+                cx.stop_propagation();
+            }
+            return;
+        }
+        if event.keystroke.key == "escape"
+            && (self.compare.is_some() || self.compare_pick.is_some())
+        {
+            self.exit_compare(cx);
+            cx.stop_propagation();
+            return;
+        }
+        // Setup and open dialogs (the palette among them) keep their keys to themselves
+        if self.onboarding.is_some() || window.has_active_dialog(cx) {
+            return;
+        }
+        let typing = !self.focus_handle.is_focused(window)
+            && !self
+                .cities
+                .iter()
+                .any(|card| card.read(cx).focus_handle.is_focused(window));
+        let command = self.shortcut_commands(cx).into_iter().find(|command| {
+            command.shortcuts().iter().any(|shortcut| {
+                !(typing && commands::is_typed(shortcut))
+                    && commands::shortcut_matches(shortcut, &event.keystroke)
+            })
+        });
+        if let Some(command) = command {
+            cx.stop_propagation();
+            self.run_command(command, window, cx);
+        }
+    }
+
+    // Everything a key can trigger: the registry plus the palette itself
+    fn shortcut_commands(&self, cx: &App) -> Vec<AppCommand> {
+        std::iter::once(AppCommand::OpenPalette)
+            .chain(self.commands(cx))
+            .collect()
+    }
+
+    fn shortcut_rows(&self, cx: &App) -> Vec<ShortcutRow> {
+        let mut rows = shortcut_overlay::shortcut_rows(&self.shortcut_commands(cx));
+        let hotkey = &cx.global::<Settings>().hotkey;
+        if hotkey.enabled {
+            rows.insert(
+                0,
+                ShortcutRow {
+                    area: "General",
+                    label: "Show or hide the window, from any app".to_string(),
+                    keys: hotkey.shortcut.clone(),
+                },
+            );
+        }
+        let snippet = &cx.global::<Settings>().snippet;
+        if snippet.enabled {
+            rows.insert(
+                usize::from(hotkey.enabled),
+                ShortcutRow {
+                    area: "General",
+                    label: "Paste the current times into any app".to_string(),
+                    keys: snippet.shortcut.clone(),
+                },
+            );
+        }
+        rows
+    }
+
+    // While picking the second city, a card click completes the pair instead of
+    // opening details
+    fn card_clicked(&mut self, name: String, window: &mut Window, cx: &mut Context<Self>) {
+        match self.compare_pick.take() {
+            Some(first) if first != name => {
+                self.compare = Some((first, name));
+                cx.notify();
+            }
+            Some(first) => self.compare_pick = Some(first),
+            None => self.run_command(AppCommand::ShowDetails(name), window, cx),
+        }
+    }
+
+    fn exit_compare(&mut self, cx: &mut Context<Self>) {
+        self.compare_pick = None;
+        self.compare = None;
+        cx.notify();
+    }
+
+    // From a dot on the world map; the table has no cards, so switch back to them
+    fn focus_card(&mut self, name: &str, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(card) = self.card_named(name, cx) else {
+            return;
+        };
+        self.touch(std::slice::from_ref(&card), window, cx);
+        window.focus(&card.read(cx).focus_handle);
+        self.table_view = false;
+        self.apply_ticker_interval(cx);
+        cx.notify();
+    }
+
+    fn map_dots(&self, cx: &App) -> Vec<world_map::MapDot> {
+        self.cities
+            .iter()
+            .filter_map(|city| {
+                let city = city.read(cx);
+                city.place().map(|place| world_map::MapDot {
+                    name: city.name.clone(),
+                    place,
+                })
+            })
+            .collect()
+    }
+
+    fn compare_side(&self, name: &str, cx: &App) -> Option<compare::Side> {
+        let city = self.card_named(name, cx)?.read(cx).clone();
+        let settings = cx.global::<Settings>();
+        Some(compare::Side {
+            hours: city.working_hours.unwrap_or(settings.working_hours),
+            dnd: city.do_not_disturb.unwrap_or(settings.do_not_disturb),
+            weekend: city.weekend.unwrap_or(settings.week.weekend),
+            city,
+        })
+    }
+
+    fn enter_kiosk(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if self.kiosk.is_some() {
+            return;
+        }
+        let enter_fullscreen = !window.is_fullscreen();
+        if enter_fullscreen {
+            window.toggle_fullscreen();
+        }
+        self.kiosk = Some(Kiosk::new(enter_fullscreen));
+        window.focus(&self.focus_handle);
+        cx.notify();
+    }
+
+    fn exit_kiosk(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(kiosk) = self.kiosk.take() {
+            if kiosk.entered_fullscreen && window.is_fullscreen() {
+                window.toggle_fullscreen();
+            }
+            cx.notify();
+        }
+    }
+
+    fn advance_kiosk(&mut self, cx: &mut Context<Self>) {
+        let settings = &cx.global::<Settings>().kiosk;
+        let count = kiosk::slide_count(self.cities.len(), settings.show_all_slide);
+        let dwell = Duration::from_secs(settings.dwell_seconds.max(1));
+        if let Some(kiosk) = &mut self.kiosk
+            && kiosk.advance(count, dwell)
+        {
+            cx.notify();
+        }
+    }
+
+    fn hide_shortcuts(&mut self, cx: &mut Context<Self>) {
+        self.show_shortcuts = false;
+        cx.notify();
+    }
+
+    fn set_home(&mut self, name: &str, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(home) = self.card_named(name, cx) else {
+            return;
+        };
+        self.home_tz = home.read(cx).tz;
+        for card in &self.cities {
+            card.update(cx, |card, cx| {
+                card.is_home = card.name == name;
+                cx.notify();
+            });
+        }
+        self.refresh_times(cx);
+        self.save_config(window, cx);
+        cx.notify();
+    }
+
+    fn start_onboarding(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        // A fresh install starts from nothing rather than the built-in list
+        let current = if config::config_path().is_some_and(|path| path.exists()) {
+            self.city_configs(cx)
+        } else {
+            Vec::new()
+        };
+        let onboarding = cx.new(|cx| Onboarding::new(&current, window, cx));
+        cx.subscribe_in(
+            &onboarding,
+            window,
+            |this, _, event, window, cx| match event {
+                OnboardingEvent::Done(cities) => this.finish_onboarding(cities, window, cx),
+            },
+        )
+        .detach();
+        self.onboarding = Some(onboarding);
+        cx.notify();
+    }
+
+    fn finish_onboarding(
+        &mut self,
+        cities: &[CityConfig],
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.onboarding = None;
+        self.replace_cities(cities, window, cx);
+        self.save_config(window, cx);
+    }
+
+    // Rebuilds every card; cities whose zone no longer resolves become error cards
+    fn replace_cities(
+        &mut self,
+        cities: &[CityConfig],
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.home_tz = convert::home_zone(cities).tz;
+        let home_tz = self.home_tz;
+        self.pending_cities.clear();
+        self.city_loader = Task::ready(());
+        // A file from elsewhere may bring cities without ids, or ids already taken
+        let mut cities = cities.to_vec();
+        config::assign_city_ids(&mut cities);
+        let zones = resolve_city_zones(&cities);
+        self.cities.clear();
+        self.broken_cities.clear();
+        for (city, tz) in split_by_zone(cities, &zones, &mut self.broken_cities) {
+            let card = Self::new_card(&city, tz, home_tz, window, cx);
+            self.cities.push(card);
+        }
+        self.refresh_times(cx);
+        self.restore_focus(window, cx);
+        cx.notify();
+    }
+
+    fn undo_snapshot(&self, label: &str, cx: &App) -> Snapshot {
+        Snapshot {
+            label: label.to_string(),
+            cities: self.city_configs(cx),
+            settings: cx.global::<Settings>().clone(),
+        }
+    }
+
+    fn push_undo(&mut self, label: &str, cx: &App) {
+        self.undo.push(self.undo_snapshot(label, cx));
+    }
+
+    // Whatever `apply` changes undoes in one step, however many undoable steps it takes
+    fn undo_transaction(
+        &mut self,
+        label: &str,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+        apply: impl FnOnce(&mut Self, &mut Window, &mut Context<Self>),
+    ) {
+        self.undo.begin(self.undo_snapshot(label, cx));
+        apply(self, window, cx);
+        self.undo.end();
+    }
+
+    fn undo(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(snapshot) = self.undo.pop() else {
+            window.push_notification(Notification::info("Nothing to undo"), cx);
+            return;
+        };
+        cx.set_global(snapshot.settings);
+        self.settings_changed(window, cx);
+        self.replace_cities(&snapshot.cities, window, cx);
+        self.save_config(window, cx);
+        window.push_notification(
+            Notification::success(format!("Undid {}", snapshot.label)),
+            cx,
+        );
+    }
+
+    fn confirm_remove_city(&mut self, name: String, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(card) = self.card_named(&name, cx) else {
+            return;
+        };
+        if !card.read(cx).is_home {
+            confirm::confirm_remove_city(cx.entity(), name, window, cx);
+            return;
+        }
+        let others: Vec<String> = self
+            .cities
+            .iter()
+            .map(|card| card.read(cx).name.clone())
+            .filter(|other| *other != name)
+            .collect();
+        if others.is_empty() {
+            window.push_notification(
+                Notification::warning("The only city can't be removed; add another first"),
+                cx,
+            );
+        } else {
+            confirm::confirm_remove_home(cx.entity(), name, others, window, cx);
+        }
+    }
+
+    // Removing the home city needs `new_home`
+    fn remove_city(
+        &mut self,
+        name: &str,
+        new_home: Option<&str>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.push_undo(&format!("removing {}", name), cx);
+        if let Some(new_home) = new_home {
+            self.set_home(new_home, window, cx);
+        }
+        self.cities.retain(|card| card.read(cx).name != name);
+        self.floating_cards.retain(|floating| floating.city != name);
+        if self.focused_card.as_deref() == Some(name) {
+            self.focused_card = None;
+        }
+        // Its countdowns go back to being cards of their own
+        for countdown in &self.countdowns {
+            countdown.update(cx, |countdown, _| {
+                if countdown.config.pinned_to.as_deref() == Some(name) {
+                    countdown.config.pinned_to = None;
+                }
+            });
+        }
+        self.save_config(window, cx);
+        cx.notify();
+    }
+
+    // Home stays; moving it is a separate step
+    fn remove_cities(&mut self, names: &[String], window: &mut Window, cx: &mut Context<Self>) {
+        let label = match names {
+            [name] => format!("removing {}", name),
+            names => format!("removing {} cities", names.len()),
+        };
+        self.undo_transaction(&label, window, cx, |this, window, cx| {
+            for name in names {
+                if this
+                    .card_named(name, cx)
+                    .is_some_and(|card| !card.read(cx).is_home)
+                {
+                    this.remove_city(name, None, window, cx);
+                }
+            }
+        });
+    }
+
+    fn set_marker(
+        &mut self,
+        marker: Option<DateTime<Utc>>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.marker = marker;
+        self.apply_ticker_interval(cx);
+        self.refresh_times(cx);
+        self.save_config(window, cx);
+    }
+
+    // Closes text exports while a marker is set
+    fn marker_export_line(&self) -> Option<String> {
+        self.marker
+            .map(|marker| marker::export_line(marker, self.home_tz, Utc::now()))
+    }
+
+    // Marks the cards as used just now, for the cleanup review
+    fn touch(&mut self, cards: &[Entity<WorldTime>], window: &mut Window, cx: &mut Context<Self>) {
+        let now = Utc::now();
+        for card in cards {
+            card.update(cx, |card, _| card.last_interacted = now);
+        }
+        self.save_config_soon(window, cx);
+    }
+
+    fn set_city_hidden(
+        &mut self,
+        name: &str,
+        hidden: bool,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(card) = self.card_named(name, cx) else {
+            return;
+        };
+        let verb = if hidden { "hiding" } else { "showing" };
+        self.push_undo(&format!("{} {}", verb, name), cx);
+        card.update(cx, |card, _| card.hidden = hidden);
+        if !hidden {
+            self.touch(&[card], window, cx);
+        }
+        self.save_config(window, cx);
+        cx.notify();
+    }
+
+    // Only lists candidates; hiding or removing one is always the user's click
+    fn review_unused(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let period = cx.global::<Settings>().cleanup.after_days;
+        let usage: Vec<cleanup::Usage> = self
+            .cities
+            .iter()
+            .map(|card| {
+                let card = card.read(cx);
+                cleanup::Usage {
+                    name: card.name.clone(),
+                    is_home: card.is_home,
+                    ephemeral: card.ephemeral,
+                    hidden: card.hidden,
+                    last_interacted: card.last_interacted,
+                }
+            })
+            .collect();
+        let candidates = cleanup::candidates(&usage, Utc::now(), period);
+        if candidates.is_empty() {
+            window.push_notification(
+                Notification::info(format!(
+                    "Every city was used in the last {}",
+                    cleanup::describe_period(period)
+                )),
+                cx,
+            );
+            return;
+        }
+        cleanup::open_review(cx.entity(), candidates, period, window, cx);
+    }
+
+    // Reads the `cities` of a time2rust config file and asks before replacing ours
+    fn import_cities(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let paths = cx.prompt_for_paths(PathPromptOptions {
+            files: true,
+            directories: false,
+            multiple: false,
+            prompt: Some("Import".into()),
+        });
+        cx.spawn_in(window, async move |this, cx| {
+            let Ok(Ok(Some(paths))) = paths.await else {
+                return;
+            };
+            let Some(path) = paths.into_iter().next() else {
+                return;
+            };
+            let result = Config::read_from(&path);
+            this.update_in(cx, |this, window, cx| match result {
+                Ok(config) if config.cities.is_empty() => window.push_notification(
+                    Notification::warning(format!("{} has no cities", path.display())),
+                    cx,
+                ),
+                Ok(config) => {
+                    let summary = confirm::import_summary(&this.city_configs(cx), &config.cities);
+                    confirm::confirm_import(cx.entity(), config.cities, summary, window, cx);
+                }
+                Err(err) => window
+                    .push_notification(Notification::error(format!("Import failed: {}", err)), cx),
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    fn back_up(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let backup = backup::write_backup(&self.current_config(cx), Utc::now());
+        self.save_export(move |_| backup, "time2rust-backup.json", None, window, cx);
+    }
+
+    // Reads and checks the whole bundle, then lets the user pick what to take
+    fn open_restore(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let paths = cx.prompt_for_paths(PathPromptOptions {
+            files: true,
+            directories: false,
+            multiple: false,
+            prompt: Some("Restore".into()),
+        });
+        cx.spawn_in(window, async move |this, cx| {
+            let Ok(Ok(Some(paths))) = paths.await else {
+                return;
+            };
+            let Some(path) = paths.into_iter().next() else {
+                return;
+            };
+            let result = std::fs::read_to_string(&path)
+                .map_err(|err| err.to_string())
+                .and_then(|contents| backup::read_backup(&contents).map_err(|err| err.to_string()));
+            this.update_in(cx, |_, window, cx| match result {
+                Ok(config) => backup::confirm_restore(cx.entity(), config, window, cx),
+                Err(err) => window.push_notification(
+                    Notification::error(format!("Couldn't restore {}: {}", path.display(), err))
+                        .autohide(false),
+                    cx,
+                ),
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    // Takes over a merged config in one go; it has been checked, so nothing here fails
+    // halfway
+    fn restore_backup(&mut self, config: Config, window: &mut Window, cx: &mut Context<Self>) {
+        self.push_undo("the restore", cx);
+        if let Err(err) = self.current_config(cx).back_up() {
+            window.push_notification(Notification::warning(err.to_string()), cx);
+        }
+        cx.set_global(config.settings);
+        self.alarms = config.alarms;
+        let (archived, running): (Vec<_>, Vec<_>) = config
+            .countdowns
+            .into_iter()
+            .partition(|countdown| countdown.completed.is_some());
+        self.archived_countdowns = archived;
+        self.countdowns = running
+            .into_iter()
+            .filter_map(|countdown| Self::new_countdown(countdown, window, cx).ok())
+            .collect();
+        self.active_profile = config.active_profile;
+        self.profiles = config.profiles;
+        self.window_state = config.window;
+        self.table_view = config.table_view;
+        self.table_selection = None;
+        self.focused_card = config.focused_card;
+        self.expanded_stacks = config.expanded_stacks;
+        self.floating_cards
+            .retain(|floating| config.cities.iter().any(|city| city.name == floating.city));
+        self.replace_cities(&config.cities, window, cx);
+        self.open_floating_cards(config.floating_cards, window, cx);
+        self.settings_changed(window, cx);
+        window.push_notification(Notification::success("Restored from backup"), cx);
+    }
+
+    // Rewrites old zone spellings like "US/Central" to their current ids
+    fn update_zone_ids(
+        &mut self,
+        cards: &[Entity<WorldTime>],
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.push_undo("updating timezone names", cx);
+        for card in cards {
+            card.update(cx, |city, cx| {
+                let Some(canonical) = city.alias_of.take() else {
+                    return;
+                };
+                if let Ok(tz) = zone::resolve_zone(canonical) {
+                    city.timezone_id = canonical.to_string();
+                    city.tz = tz;
+                }
+                cx.notify();
+            });
+        }
+        self.save_config(window, cx);
+        cx.notify();
+    }
+
+    fn replace_cities_undoably(
+        &mut self,
+        cities: &[CityConfig],
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.push_undo("the import", cx);
+        self.floating_cards
+            .retain(|floating| cities.iter().any(|city| city.name == floating.city));
+        self.replace_cities(cities, window, cx);
+        self.save_config(window, cx);
+    }
+
+    // "What's new" once per newer version; a fresh install has nothing to catch up on
+    fn catch_up_on_release(
+        &mut self,
+        needs_onboarding: bool,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let last_seen = cx.global::<Settings>().last_seen_version.clone();
+        if !whats_new::is_newer(whats_new::CURRENT, last_seen.as_deref()) {
+            return;
+        }
+        if !needs_onboarding && whats_new::should_show(last_seen.as_deref(), whats_new::CURRENT) {
+            whats_new::open_whats_new(last_seen.as_deref(), window, cx);
+        }
+        cx.update_global::<Settings, _>(|settings, _| {
+            settings.last_seen_version = Some(whats_new::CURRENT.to_string())
+        });
+        self.save_config(window, cx);
+    }
+
+    fn reset_settings(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.push_undo("the settings reset", cx);
+        // Resetting isn't a reason to show "What's new" again
+        let last_seen_version = cx.global::<Settings>().last_seen_version.clone();
+        cx.set_global(Settings {
+            last_seen_version,
+            ..Settings::default()
+        });
+        self.settings_changed(window, cx);
+        self.save_config(window, cx);
+        cx.notify();
+    }
+
+    fn card_named(&self, name: &str, cx: &App) -> Option<Entity<WorldTime>> {
+        self.cities
+            .iter()
+            .find(|card| card.read(cx).name == name)
+            .cloned()
+    }
+
+    // For links and scripts, which may name a card by its id or by its name
+    fn card_by_ref(&self, reference: &str, cx: &App) -> Option<Entity<WorldTime>> {
+        self.cities
+            .iter()
+            .find(|card| card.read(cx).id == reference)
+            .or_else(|| {
+                self.cities
+                    .iter()
+                    .find(|card| card.read(cx).name == reference)
+            })
+            .cloned()
+    }
+
+    // Saves a moment after the last of a burst of changes, like dragging a window
+    fn save_config_soon(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if self.window_state_save_pending {
+            return;
+        }
+        self.window_state_save_pending = true;
+        cx.spawn_in(window, async move |this, cx| {
+            cx.background_executor().timer(Duration::from_secs(1)).await;
+            this.update_in(cx, |this, window, cx| {
+                this.window_state_save_pending = false;
+                this.save_config(window, cx);
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    fn save_config(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let commit = match self.config_store.commit(self.current_config(cx)) {
+            Ok(commit) => commit,
+            Err(err) => {
+                window.push_notification(Notification::error(err.to_string()), cx);
+                return;
+            }
+        };
+        if commit.merged {
+            self.take_merged(commit.config, window, cx);
+            let message = if commit.conflicts.is_empty() {
+                "Picked up changes saved by another window".to_string()
+            } else {
+                format!(
+                    "Merged with changes from another window. {}",
+                    commit.conflicts.join(". ")
+                )
+            };
+            window.push_notification(Notification::warning(message), cx);
+        }
+        if let Some(endpoint) = &self.http_endpoint {
+            endpoint.publish(self.city_configs(cx), cx.global::<Settings>());
+        }
+    }
+
+    // Shows what another window or instance saved, now merged with ours. The
+    // window's own placement and view are left alone.
+    fn take_merged(&mut self, config: Config, window: &mut Window, cx: &mut Context<Self>) {
+        cx.set_global(config.settings);
+        self.alarms = config.alarms;
+        let (archived, running): (Vec<_>, Vec<_>) = config
+            .countdowns
+            .into_iter()
+            .partition(|countdown| countdown.completed.is_some());
+        self.archived_countdowns = archived;
+        self.countdowns = running
+            .into_iter()
+            .filter_map(|countdown| Self::new_countdown(countdown, window, cx).ok())
+            .collect();
+        self.active_profile = config.active_profile;
+        self.profiles = config.profiles;
+        self.marker = config.marker;
+        self.marker_on_cards = config.marker_on_cards;
+        // This run's own cities stay on top of the saved ones
+        let mut cities = config.cities;
+        config::merge_cities(
+            &mut cities,
+            self.city_configs(cx)
+                .into_iter()
+                .filter(|city| city.ephemeral)
+                .collect(),
+        );
+        self.floating_cards
+            .retain(|floating| cities.iter().any(|city| city.name == floating.city));
+        self.replace_cities(&cities, window, cx);
+        self.settings_changed(window, cx);
+    }
+
+    fn current_config(&self, cx: &App) -> Config {
+        Config {
+            settings: cx.global::<Settings>().clone(),
+            cities: self.saved_city_configs(cx),
+            alarms: self.alarms.clone(),
+            countdowns: self
+                .countdowns
+                .iter()
+                .map(|countdown| countdown.read(cx).config.clone())
+                .chain(self.archived_countdowns.iter().cloned())
+                .collect(),
+            window: self.window_state.clone(),
+            table_view: self.table_view,
+            focused_card: self.focused_card.clone(),
+            floating_cards: self.floating_cards.clone(),
+            expanded_stacks: self.expanded_stacks.clone(),
+            marker: self.marker,
+            marker_on_cards: self.marker_on_cards,
+            needs_onboarding: self.onboarding.is_some(),
+            active_profile: self.active_profile.clone(),
+            profiles: self.profiles.clone(),
+            generation: self.config_store.generation(),
+        }
+    }
+
+    // (Re-)registers the global shortcuts from the current settings
+    fn apply_global_shortcut(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let settings = cx.global::<Settings>();
+        let wanted = [
+            (
+                ShortcutAction::ToggleWindow,
+                settings
+                    .hotkey
+                    .enabled
+                    .then(|| settings.hotkey.shortcut.clone()),
+            ),
+            (
+                ShortcutAction::InsertSnippet,
+                settings
+                    .snippet
+                    .enabled
+                    .then(|| settings.snippet.shortcut.clone()),
+            ),
+        ];
+        if self.global_shortcut.is_none() {
+            if wanted.iter().all(|(_, shortcut)| shortcut.is_none()) {
+                return;
+            }
+            match GlobalShortcut::new() {
+                Ok(shortcut) => self.global_shortcut = Some(shortcut),
+                Err(err) => {
+                    window.push_notification(Notification::warning(err).autohide(false), cx);
+                    return;
+                }
+            }
+        }
+
+        for (action, shortcut) in wanted {
+            if let Some(global_shortcut) = self.global_shortcut.as_mut()
+                && let Err(err) = global_shortcut.apply(action, shortcut.as_deref())
+            {
+                window.push_notification(Notification::warning(err).autohide(false), cx);
+            }
+        }
+    }
+
+    // Every card's current values for the snippet template, at the real time
+    // rather than the planner's
+    fn snippet_cities(&self, cx: &App) -> Vec<format::SnippetCity> {
+        let now = Utc::now();
+        let clock_format = cx.global::<Settings>().clock_format;
+        self.cities
+            .iter()
+            .map(|city| {
+                let city = city.read(cx);
+                let shift =
+                    offset_override::active_minutes(city.offset_override.as_ref(), city.tz, now);
+                let local = (now + chrono::Duration::minutes(shift as i64)).with_timezone(&city.tz);
+                let offset = zone::utc_offset_minutes(city.tz, now) + shift;
+                format::SnippetCity {
+                    name: city.name.clone(),
+                    time: clock_format.format(local.time(), offset, false),
+                    zone: local.format("%Z").to_string(),
+                    date: format::weekday_date(local.date_naive()),
+                    home: city.is_home,
+                    shown: !city.hidden,
+                }
+            })
+            .collect()
+    }
+
+    // From the snippet shortcut: puts the filled-in template on the clipboard,
+    // presses paste in the app in front, then puts back what was there before
+    fn insert_snippet(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let template = cx.global::<Settings>().snippet.template.clone();
+        let snippet = format::parse_snippet(&template)
+            .and_then(|parts| format::render_snippet(&parts, &self.snippet_cities(cx)));
+        let snippet = match snippet {
+            Ok(snippet) => snippet,
+            Err(err) => {
+                window.push_notification(
+                    Notification::warning(format!("Couldn't build the snippet: {}", err)),
+                    cx,
+                );
+                return;
+            }
+        };
+        let previous = cx.read_from_clipboard();
+        cx.write_to_clipboard(ClipboardItem::new_string(snippet.clone()));
+        cx.spawn_in(window, async move |this, cx| {
+            // Gives the shortcut's keys a moment to come up
+            cx.background_executor()
+                .timer(Duration::from_millis(150))
+                .await;
+            let pasted = cx.background_spawn(async { paste::send_paste() }).await;
+            // The paste reads the clipboard after the keys are sent, not before
+            cx.background_executor()
+                .timer(Duration::from_millis(400))
+                .await;
+            this.update_in(cx, |_, window, cx| match pasted {
+                Ok(()) => {
+                    let untouched = cx
+                        .read_from_clipboard()
+                        .and_then(|item| item.text())
+                        .is_some_and(|text| text == snippet);
+                    if untouched && let Some(previous) = previous {
+                        cx.write_to_clipboard(previous);
+                    }
+                }
+                Err(err) => window.push_notification(
+                    Notification::warning(format!(
+                        "Copied the snippet, but couldn't paste it: {}",
+                        err
+                    )),
+                    cx,
+                ),
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    // Starts, restarts or stops the local endpoint to match the settings
+    fn apply_http_endpoint(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let settings = cx.global::<Settings>().http.clone();
+        if !settings.enabled {
+            self.http_endpoint = None;
+            return;
+        }
+        if self
+            .http_endpoint
+            .as_ref()
+            .is_some_and(|endpoint| *endpoint.settings() == settings)
+        {
+            return;
+        }
+        self.http_endpoint = None;
+        match HttpEndpoint::start(&settings) {
+            Ok(endpoint) => {
+                endpoint.publish(self.city_configs(cx), cx.global::<Settings>());
+                self.http_endpoint = Some(endpoint);
+            }
+            Err(err) => window.push_notification(Notification::warning(err).autohide(false), cx),
+        }
+    }
+
+    // Asks the time server now and every few hours after. A failed check clears the
+    // last result and says nothing, so a laptop going offline shows no warning.
+    fn apply_clock_sync(&mut self, cx: &mut Context<Self>) {
+        let settings = cx.global::<Settings>().clock_sync.clone();
+        if !settings.enabled {
+            self.clock_sync = None;
+            self.clock_drift = None;
+            return;
+        }
+        if self
+            .clock_sync
+            .as_ref()
+            .is_some_and(|(running, _)| *running == settings)
+        {
+            return;
+        }
+        self.clock_drift = None;
+        let server = settings.server.trim().to_string();
+        let task = cx.spawn(async move |this, cx| {
+            loop {
+                let query = server.clone();
+                let drift = cx
+                    .background_spawn(async move { ntp::query(&query) })
+                    .await
+                    .ok()
+                    .map(|offset| ntp::Drift {
+                        offset,
+                        server: server.clone(),
+                        checked: Utc::now(),
+                    });
+                let updated = this.update(cx, |this, cx| {
+                    this.clock_drift = drift;
+                    cx.notify();
+                });
+                if updated.is_err() {
+                    return;
+                }
+                cx.background_executor().timer(ntp::CHECK_EVERY).await;
+            }
+        });
+        self.clock_sync = Some((settings, task));
+    }
+
+    // Brings the window to the front, or hides it when it already has focus
+    fn open_mini_widget(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if self.mini_widget.is_some() {
+            return;
+        }
+        self.mini_widget =
+            mini_widget::open_mini_widget(cx.entity(), self.always_on_top, window, cx);
+    }
+
+    fn toggle_window_visibility(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if window.is_window_active() {
+            #[cfg(target_os = "macos")]
+            cx.hide();
+            #[cfg(not(target_os = "macos"))]
+            window.minimize_window();
+        } else {
+            self.refresh_times(cx);
+            cx.activate(true);
+            window.activate_window();
+        }
+    }
+}
+
+impl Render for WorldTimeApp {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let viewport = window.viewport_size();
+        if density::tier(f32::from(viewport.width), f32::from(viewport.height)) == Tier::Tiny {
+            return self.render_tiny(window, cx).into_any_element();
+        }
+        div()
+            .track_focus(&self.focus_handle)
+            .capture_key_down(cx.listener(|this, event: &KeyDownEvent, window, cx| {
+                this.handle_shortcut(event, window, cx)
+            }))
+            .flex()
+            .flex_col()
+            .gap_4()
+            .p_6()
+            .bg(cx
+                .global::<Settings>()
+                .window_opacity
+                .apply(cx.theme().background))
+            .size_full()
+            .children(self.onboarding.is_none().then(|| self.header(cx)).flatten())
+            .children(
+                (self.onboarding.is_none() && cx.global::<Settings>().show_world_map).then(|| {
+                    let subsolar = sun::subsolar_point(self.display_instant());
+                    world_map::world_map(self.map_dots(cx), subsolar, cx)
+                }),
+            )
+            .children(
+                self.quick_convert
+                    .as_ref()
+                    .map(|result| quick_convert_answer(result, &self.cities, cx)),
+            )
+            .children(match (&self.planner, self.simulated_instant) {
+                (Some(slider), Some(instant)) => Some(
+                    planner_bar(
+                        slider,
+                        self.planner_offset_minutes,
+                        instant,
+                        self.home_tz,
+                        cx,
+                    )
+                    .into_any_element(),
+                ),
+                (None, Some(instant)) => {
+                    Some(simulated_banner(instant, self.home_tz, cx).into_any_element())
+                }
+                (_, None) => None,
+            })
+            .children(
+                self.ticker_health
+                    .stale_since()
+                    .map(|last_tick| stale_banner(last_tick, self.home_tz)),
+            )
+            .children(
+                self.compare_pick
+                    .clone()
+                    .map(|first| compare_pick_banner(first, cx)),
+            )
+            .map(|this| {
+                if let Some(onboarding) = &self.onboarding {
+                    this.child(h_flex().justify_center().child(onboarding.clone()))
+                } else if self.table_view {
+                    let default_hours = cx.global::<Settings>().working_hours;
+                    let default_weekend = cx.global::<Settings>().week.weekend;
+                    let columns: Vec<hour_table::Column> = self
+                        .cities
+                        .iter()
+                        .filter(|city| !city.read(cx).hidden)
+                        .map(|city| {
+                            let city = city.read(cx);
+                            hour_table::Column {
+                                name: city.name.clone(),
+                                tz: city.tz,
+                                hours: city.working_hours.unwrap_or(default_hours),
+                                weekend: city.weekend.unwrap_or(default_weekend),
+                                is_home: city.is_home,
+                            }
+                        })
+                        .collect();
+                    this.child(hour_table::hour_table(
+                        &columns,
+                        self.display_source(),
+                        self.table_selection,
+                        cx,
+                    ))
+                } else {
+                    let entries = self.grid_entries(cx);
+                    let shown: Vec<&str> = entries
+                        .iter()
+                        .filter_map(|entry| match entry {
+                            GridEntry::Card(card) => Some(card.read(cx).name.as_str()),
+                            GridEntry::Stack(_) => None,
+                        })
+                        .collect();
+                    let countdowns: Vec<Entity<Countdown>> = self
+                        .countdowns
+                        .iter()
+                        .filter(|countdown| {
+                            countdown::is_standalone(
+                                countdown.read(cx).config.pinned_to.as_deref(),
+                                &shown,
+                            )
+                        })
+                        .cloned()
+                        .collect();
+                    let animate = animations_enabled(cx);
+                    // Less the window's padding on either side
+                    let available_width = window.viewport_size().width - px(48.0);
+                    let trailing =
+                        self.pending_cities
+                            .iter()
+                            .map(|city| placeholder_card(city).into_any_element())
+                            .chain(self.broken_cities.iter().map(|(city, reason)| {
+                                error_card(city, reason, cx).into_any_element()
+                            }))
+                            .collect();
+                    this.child(city_grid(
+                        entries,
+                        trailing,
+                        &countdowns,
+                        self.reorder_generation,
+                        animate,
+                        available_width,
+                        cx,
+                    ))
+                    .children(
+                        (self.show_archive && !self.archived_countdowns.is_empty())
+                            .then(|| archive::archive_section(&self.archived_countdowns, cx)),
+                    )
+                }
+            })
+            .children(self.kiosk.as_ref().map(|kiosk| {
+                let cities: Vec<WorldTime> = self
+                    .cities
+                    .iter()
+                    .map(|city| city.read(cx).clone())
+                    .collect();
+                let now = self.display_instant();
+                kiosk::kiosk_view(kiosk.slide, cities, now, cx)
+            }))
+            .children(
+                self.compare
+                    .as_ref()
+                    .and_then(|(first, second)| {
+                        Some((
+                            self.compare_side(first, cx)?,
+                            self.compare_side(second, cx)?,
+                        ))
+                    })
+                    .map(|(first, second)| {
+                        compare::compare_view(first, second, self.display_source(), cx)
+                    }),
+            )
+            .children(self.show_shortcuts.then(|| {
+                let rows = self.shortcut_rows(cx);
+                shortcut_overlay::shortcut_overlay(rows, cx)
+            }))
+            .children(Root::render_sheet_layer(window, cx))
+            .children(Root::render_dialog_layer(window, cx))
+            .children(Root::render_notification_layer(window, cx))
+            .into_any_element()
+    }
+}
+
+impl WorldTimeApp {
+    // Below the minimum size only home's time fits; shortcuts and dialogs still work
+    // A hidden header leaves only the UTC clock, if that's on, as a slim line
+    fn header(&self, cx: &mut Context<Self>) -> Option<AnyElement> {
+        let settings = cx.global::<Settings>();
+        let header = settings.header.clone();
+        let utc_now = header
+            .utc_clock
+            .then(|| (Utc::now(), settings.show_seconds));
+        if header.hidden {
+            return utc_now.map(|(now, show_seconds)| {
+                h_flex()
+                    .justify_end()
+                    .child(utc_clock(now, show_seconds))
+                    .into_any_element()
+            });
+        }
+        let badges = HeaderBadges {
+            always_on_top: self.always_on_top,
+            clock_change: self.clock_change.clone(),
+            clock_drift: self
+                .clock_drift
+                .clone()
+                .filter(|drift| drift.exceeds(settings.clock_sync.threshold_secs)),
+            suppressed: self.suppressed_badge(cx),
+            marker: self.marker.map(|marker| (marker, self.marker_on_cards)),
+        };
+        Some(
+            app_header(
+                header.title(),
+                utc_now,
+                &self.quick_convert_input,
+                self.table_view,
+                badges,
+                self.archived_countdowns.len(),
+                cx,
+            )
+            .into_any_element(),
+        )
+    }
+
+    fn render_tiny(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let home = self
+            .cities
+            .iter()
+            .map(|city| city.read(cx))
+            .find(|city| city.is_home)
+            .map(|city| {
+                (
+                    city.name.clone(),
+                    city.time.clone(),
+                    city.zone_abbreviation.clone(),
+                )
+            });
+        div()
+            .track_focus(&self.focus_handle)
+            .capture_key_down(cx.listener(|this, event: &KeyDownEvent, window, cx| {
+                this.handle_shortcut(event, window, cx)
+            }))
+            .flex()
+            .flex_col()
+            .gap_1()
+            .p_2()
+            .bg(cx
+                .global::<Settings>()
+                .window_opacity
+                .apply(cx.theme().background))
+            .size_full()
+            .children(home.map(|(name, time, abbreviation)| {
+                h_flex()
+                    .gap_2()
+                    .child(div().child(name).text_sm())
+                    .child(
+                        div()
+                            .child(time)
+                            .text_xl()
+                            .font_weight(gpui::FontWeight::BOLD),
+                    )
+                    .child(div().child(abbreviation).text_xs())
+            }))
+            .child(settings_panel::hint("Enlarge the window to see every city"))
+            .children(Root::render_dialog_layer(window, cx))
+            .children(Root::render_notification_layer(window, cx))
+    }
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let mut config = Config::load().unwrap_or_else(|err| {
+        eprintln!("time2rust: {}, using defaults", err);
+        Config::default()
+    });
+    if cli.reset_onboarding {
+        config.needs_onboarding = true;
+    }
+    let env_cities = std::env::var(config::CITIES_ENV_VAR).ok();
+    let warnings = config.apply_city_overrides(env_cities.as_deref(), &cli.cities);
+
+    if cli.is_terminal_mode() {
+        for warning in &warnings {
+            eprintln!("time2rust: {}", warning);
+        }
+        return cli::run(cli, &config);
+    }
+
+    // A second launch (e.g. from clicking a time2rust:// link) hands off to the first
+    let (sender, inbox) = mpsc::channel();
+    let messages: Vec<String> = cli.open_url.into_iter().collect();
+    if let Instance::Forwarded = single_instance::acquire(&messages, sender.clone()) {
+        return ExitCode::SUCCESS;
+    }
+    if let Err(err) = deep_link::register_url_scheme() {
+        eprintln!("time2rust: couldn't register time2rust:// links: {}", err);
+    }
+    let control = cli.stdin_control.then(|| {
+        let (commands, control) = mpsc::channel();
+        stdin_control::spawn_reader(commands);
+        control
+    });
+    run_gui(
+        config,
+        warnings,
+        sender,
+        inbox,
+        cli.kiosk,
+        cli.interval,
+        control,
+    );
+    ExitCode::SUCCESS
+}
+
+fn run_gui(
+    config: Config,
+    warnings: Vec<String>,
+    sender: mpsc::Sender<String>,
+    inbox: Receiver<String>,
+    kiosk: bool,
+    interval_override: Option<UpdateInterval>,
+    control: Option<Receiver<ControlCommand>>,
+) {
+    let application = Application::new();
+    // macOS delivers scheme links through the app delegate rather than argv
+    application.on_open_urls(move |urls| {
+        for url in urls {
+            let _ = sender.send(url);
+        }
+    });
+    application.run(move |cx: &mut App| {
+        // This must be called before using any GPUI Component features.
+        gpui_component::init(cx);
+
+        cx.set_global(config.settings.clone());
+
+        // Handle window closing - quit app when last window closes
+        cx.on_window_closed(|cx| {
+            if cx.windows().is_empty() {
+                cx.quit();
+            }
+        })
+        .detach();
+        cx.on_app_quit(|_| {
+            single_instance::release();
+            async {}
+        })
+        .detach();
+
+        let (bounds, display_id) = window_state::initial_bounds(config.window.as_ref(), cx);
+        cx.open_window(
+            WindowOptions {
+                window_bounds: Some(bounds),
+                display_id,
+                window_min_size: Some(window_state::min_size()),
+                kind: if config.settings.always_on_top {
+                    WindowKind::PopUp
+                } else {
+                    WindowKind::Normal
+                },
+                window_background: config.settings.window_opacity.background(),
+                titlebar: Some(TitlebarOptions {
+                    title: Some(SharedString::from(
+                        config.settings.header.window_title().to_string(),
+                    )),
+                    ..Default::default()
+                }),
+                show: true,
+                ..Default::default()
+            },
+            |window, cx| {
+                let app = cx.new(|cx| {
+                    // Prevent window maximization, and remember where the window is
+                    cx.observe_window_bounds(window, |this: &mut WorldTimeApp, window, cx| {
+                        this.window_bounds_changed(window, cx)
+                    })
+                    .detach();
+
+                    WorldTimeApp::new(
+                        config,
+                        warnings,
+                        inbox,
+                        interval_override,
+                        control,
+                        window,
+                        cx,
+                    )
+                });
+                if kiosk {
+                    let app = app.clone();
+                    window.defer(cx, move |window, cx| {
+                        app.update(cx, |app, cx| app.enter_kiosk(window, cx))
+                    });
+                }
+                cx.new(|cx| Root::new(app, window, cx))
+            },
+        )
+        .unwrap();
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_hundred_cities_load_a_batch_at_a_time() {
+        // Exact ids, ids that need the case-insensitive scan, and ones that fail
+        let ids = [
+            "Europe/Berlin",
+            "asia/tokyo",
+            "America/New_York",
+            "Mars/Base",
+        ];
+        let cities: Vec<CityConfig> = (0..100)
+            .map(|i| CityConfig::new(&format!("City {}", i), ids[i % ids.len()], i == 0))
+            .collect();
+        let zones = resolve_city_zones(&cities);
+        assert_eq!(zones.len(), 100);
+        assert_eq!(zones[1], Ok(chrono_tz::Asia::Tokyo));
+        assert!(
+            zones[3]
+                .as_ref()
+                .is_err_and(|err| err.contains("Mars/Base"))
+        );
+
+        // The first batch becomes cards while the other 92 are still placeholders
+        let mut pending = cities;
+        let mut broken = Vec::new();
+        let mut batches = zones.chunks(CITY_BATCH);
+        let first = take_batch(&mut pending, batches.next().unwrap(), &mut broken);
+        let names: Vec<&str> = first.iter().map(|(city, _)| city.name.as_str()).collect();
+        assert_eq!(
+            names,
+            ["City 0", "City 1", "City 2", "City 4", "City 5", "City 6"]
+        );
+        assert_eq!(broken.len(), 2);
+        assert_eq!(pending.len(), 100 - CITY_BATCH);
+        assert_eq!(pending[0].name, "City 8");
+
+        let mut loaded = first.len();
+        let mut rest = 0;
+        for batch in batches {
+            assert!(batch.len() <= CITY_BATCH);
+            loaded += take_batch(&mut pending, batch, &mut broken).len();
+            rest += 1;
+        }
+        assert_eq!(rest, 12);
+        assert!(pending.is_empty());
+        assert_eq!((loaded, broken.len()), (75, 25));
+    }
+
+    #[test]
+    fn cities_that_dont_resolve_stay_out_of_the_offset_order() {
+        let cities = vec![
+            CityConfig::new("Tokyo", "Asia/Tokyo", false),
+            CityConfig::new("Base", "Mars/Base", false),
+            CityConfig::new("New York", "America/New_York", true),
+            CityConfig::new("Berlin", "Europe/Berlin", false),
+            CityConfig::new("Typo", "Europe/Berln", false),
+        ];
+        let zones = resolve_city_zones(&cities);
+        let mut broken = Vec::new();
+        let resolved = split_by_zone(cities, &zones, &mut broken);
+
+        let broken: Vec<(&str, &str)> = broken
+            .iter()
+            .map(|(city, reason)| (city.name.as_str(), reason.as_str()))
+            .collect();
+        assert_eq!(broken.len(), 2);
+        assert_eq!(broken[0].0, "Base");
+        assert!(broken[0].1.contains("Mars/Base"));
+        assert_eq!(broken[1].0, "Typo");
+
+        // What apply_order sorts: the cards only
+        let now = Utc::now();
+        let offsets: Vec<(String, i32)> = resolved
+            .iter()
+            .map(|(city, tz)| (city.name.clone(), zone::utc_offset_minutes(*tz, now)))
+            .collect();
+        let order: Vec<&str> = ordering::by_local_time(&offsets)
+            .into_iter()
+            .map(|index| offsets[index].0.as_str())
+            .collect();
+        assert_eq!(order, ["New York", "Berlin", "Tokyo"]);
+    }
 }
@@ -0,0 +1,44 @@
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
+use time2rust::format;
+
+// "01:23:45", or "2d 01:23:45" past a day; a marker from the future reads as zero
+pub fn elapsed(marker: DateTime<Utc>, now: DateTime<Utc>) -> String {
+    let seconds = (now - marker).num_seconds().max(0);
+    let clock = format!(
+        "{:02}:{:02}:{:02}",
+        seconds / 3600 % 24,
+        seconds / 60 % 60,
+        seconds % 60
+    );
+    match seconds / 86_400 {
+        0 => clock,
+        days => format!("{}d {}", days, clock),
+    }
+}
+
+// "⏱ 01:23:45 since marker", for the header
+pub fn header_label(marker: DateTime<Utc>, now: DateTime<Utc>) -> String {
+    format!("⏱ {} since marker", elapsed(marker, now))
+}
+
+// "started 03:12 their time", with the weekday when it wasn't today there
+pub fn card_line(marker: DateTime<Utc>, tz: Tz, now: DateTime<Utc>) -> String {
+    let local = marker.with_timezone(&tz).naive_local();
+    let time = if local.date() == now.with_timezone(&tz).date_naive() {
+        format::clock_time(local.time(), false)
+    } else {
+        format::weekday_time(local)
+    };
+    format!("started {} their time", time)
+}
+
+// "⏱ Marker set Thu 09:12 (Europe/Berlin), 01:23:45 ago", appended to text exports
+pub fn export_line(marker: DateTime<Utc>, home_tz: Tz, now: DateTime<Utc>) -> String {
+    format!(
+        "⏱ Marker set {} ({}), {} ago",
+        format::weekday_time(marker.with_timezone(&home_tz).naive_local()),
+        home_tz.name(),
+        elapsed(marker, now)
+    )
+}
@@ -0,0 +1,157 @@
+use crate::{WorldTimeApp, config::Settings};
+use gpui::{
+    AnyWindowHandle, App, Bounds, Context, Entity, FocusHandle, KeyDownEvent, MouseButton,
+    SharedString, TitlebarOptions, Window, WindowBounds, WindowKind, WindowOptions, div, point,
+    prelude::*, px, rgb, size,
+};
+use gpui_component::{
+    ActiveTheme as _, Root,
+    menu::{ContextMenuExt as _, PopupMenuItem},
+    v_flex,
+};
+
+const WIDTH: f32 = 200.0;
+const ROW_HEIGHT: f32 = 28.0;
+const PADDING: f32 = 12.0;
+
+// A frameless stack of names and times in place of the main window, which is
+// minimized until the widget is dismissed
+pub fn open_mini_widget(
+    app: Entity<WorldTimeApp>,
+    always_on_top: bool,
+    window: &mut Window,
+    cx: &mut App,
+) -> Option<AnyWindowHandle> {
+    let main_window = window.window_handle();
+    let main_bounds = window.bounds();
+    let rows = app.read(cx).cities.len().max(1) as f32;
+    let widget_size = size(px(WIDTH), px(rows * ROW_HEIGHT + 2.0 * PADDING));
+    // Top-right corner of where the main window was
+    let origin = point(
+        main_bounds.origin.x + main_bounds.size.width - widget_size.width,
+        main_bounds.origin.y,
+    );
+
+    let handle = cx
+        .open_window(
+            WindowOptions {
+                window_bounds: Some(WindowBounds::Windowed(Bounds::new(origin, widget_size))),
+                titlebar: None::<TitlebarOptions>,
+                kind: if always_on_top {
+                    WindowKind::PopUp
+                } else {
+                    WindowKind::Normal
+                },
+                window_background: cx.global::<Settings>().window_opacity.background(),
+                is_resizable: false,
+                is_minimizable: false,
+                show: true,
+                ..Default::default()
+            },
+            |window, cx| {
+                let widget = cx.new(|cx| MiniWidget::new(app, main_window, window, cx));
+                cx.new(|cx| Root::new(widget, window, cx))
+            },
+        )
+        .ok()?;
+    window.minimize_window();
+    Some(handle.into())
+}
+
+pub struct MiniWidget {
+    app: Entity<WorldTimeApp>,
+    main_window: AnyWindowHandle,
+    focus_handle: FocusHandle,
+}
+
+impl MiniWidget {
+    fn new(
+        app: Entity<WorldTimeApp>,
+        main_window: AnyWindowHandle,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        // The main window keeps ticking the cards; redraw whenever one changes
+        for city in app.read(cx).cities.clone() {
+            cx.observe(&city, |_, _, cx| cx.notify()).detach();
+        }
+        let focus_handle = cx.focus_handle();
+        window.focus(&focus_handle);
+        MiniWidget {
+            app,
+            main_window,
+            focus_handle,
+        }
+    }
+
+    fn dismiss(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.app.update(cx, |app, _| app.mini_widget = None);
+        self.main_window
+            .update(cx, |_, window, _| window.activate_window())
+            .ok();
+        window.remove_window();
+    }
+}
+
+impl Render for MiniWidget {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let cities: Vec<_> = self
+            .app
+            .read(cx)
+            .cities
+            .iter()
+            .map(|city| {
+                let city = city.read(cx);
+                (city.name.clone(), city.time.clone(), city.is_home)
+            })
+            .collect();
+
+        v_flex()
+            .id("mini-widget")
+            .track_focus(&self.focus_handle)
+            .size_full()
+            .p(px(PADDING))
+            .bg(cx
+                .global::<Settings>()
+                .window_opacity
+                .apply(cx.theme().background))
+            .on_key_down(cx.listener(|this, event: &KeyDownEvent, window, cx| {
+                if event.keystroke.key == "escape" {
+                    this.dismiss(window, cx);
+                }
+            }))
+            // No titlebar, so the body is the drag handle
+            .on_mouse_down(MouseButton::Left, |_, window, _| window.start_window_move())
+            .context_menu({
+                let widget = cx.entity();
+                move |menu, _window, _cx| {
+                    let widget = widget.clone();
+                    menu.item(PopupMenuItem::new("Back to full window").on_click(
+                        move |_, window, cx| {
+                            widget.update(cx, |widget, cx| widget.dismiss(window, cx))
+                        },
+                    ))
+                }
+            })
+            .children(cities.into_iter().map(|(name, time, is_home)| {
+                div()
+                    .id(SharedString::from(format!("mini-{}", name)))
+                    .h(px(ROW_HEIGHT))
+                    .flex()
+                    .items_center()
+                    .justify_between()
+                    .child(div().child(name).text_sm().text_color(if is_home {
+                        rgb(0x3b82f6)
+                    } else {
+                        rgb(0x6b7280)
+                    }))
+                    .child(
+                        div()
+                            .child(time)
+                            .text_lg()
+                            .font_weight(gpui::FontWeight::BOLD)
+                            .text_color(rgb(0x111827)),
+                    )
+            }))
+    }
+}
@@ -0,0 +1,233 @@
+use chrono::{DateTime, Duration, Utc};
+use std::net::UdpSocket;
+
+// How often the background check asks the time server
+pub const CHECK_EVERY: std::time::Duration = std::time::Duration::from_secs(3 * 60 * 60);
+// A reply later than this is given up on; the check stays silent either way
+const TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
+
+const PACKET_LEN: usize = 48;
+// Seconds from the NTP epoch (1900-01-01) to the Unix one
+const UNIX_OFFSET: i64 = 2_208_988_800;
+
+// A 64-bit NTP timestamp: seconds since 1900 in the high half, a binary fraction
+// of a second in the low half
+pub fn to_ntp(at: DateTime<Utc>) -> u64 {
+    let seconds = (at.timestamp() + UNIX_OFFSET) as u64;
+    let fraction = ((at.timestamp_subsec_nanos() as u64) << 32) / 1_000_000_000;
+    (seconds << 32) | fraction
+}
+
+// Era 0 only, which runs until 2036
+pub fn from_ntp(timestamp: u64) -> Option<DateTime<Utc>> {
+    let seconds = (timestamp >> 32) as i64 - UNIX_OFFSET;
+    let nanos = ((timestamp & 0xffff_ffff) * 1_000_000_000) >> 32;
+    DateTime::from_timestamp(seconds, nanos as u32)
+}
+
+// An SNTP v4 client request; the transmit time comes back as the reply's origin,
+// which is how a stray or spoofed reply is told apart
+pub fn request(sent: DateTime<Utc>) -> [u8; PACKET_LEN] {
+    let mut packet = [0; PACKET_LEN];
+    packet[0] = 0b00_100_011; // no leap warning, version 4, client
+    packet[40..48].copy_from_slice(&to_ntp(sent).to_be_bytes());
+    packet
+}
+
+// When the server got the request and when it sent the reply
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Reply {
+    pub received: DateTime<Utc>,
+    pub transmitted: DateTime<Utc>,
+}
+
+pub fn parse_reply(packet: &[u8], sent: DateTime<Utc>) -> Result<Reply, String> {
+    if packet.len() < PACKET_LEN {
+        return Err(format!("Reply too short: {} bytes", packet.len()));
+    }
+    let leap = packet[0] >> 6;
+    let mode = packet[0] & 0b111;
+    let stratum = packet[1];
+    if mode != 4 {
+        return Err(format!("Not a server reply (mode {})", mode));
+    }
+    if leap == 3 {
+        return Err("The server's clock isn't synchronized".to_string());
+    }
+    // Stratum 0 is a "kiss-o'-death", asking clients to back off
+    if stratum == 0 {
+        return Err("The server refused the request".to_string());
+    }
+    let timestamp = |at: usize| u64::from_be_bytes(packet[at..at + 8].try_into().unwrap());
+    if timestamp(24) != to_ntp(sent) {
+        return Err("The reply doesn't answer this request".to_string());
+    }
+    let received = from_ntp(timestamp(32)).ok_or("Bad receive time")?;
+    let transmitted = from_ntp(timestamp(40)).ok_or("Bad transmit time")?;
+    Ok(Reply {
+        received,
+        transmitted,
+    })
+}
+
+// How far the system clock is behind the server's; negative when it's ahead. The
+// usual NTP estimate, which assumes the way there and back take as long.
+pub fn offset(sent: DateTime<Utc>, reply: &Reply, arrived: DateTime<Utc>) -> Duration {
+    ((reply.received - sent) + (reply.transmitted - arrived)) / 2
+}
+
+// Blocks for up to a few seconds, so it's only run off the UI thread
+pub fn query(server: &str) -> Result<Duration, String> {
+    let socket = UdpSocket::bind("0.0.0.0:0").map_err(|err| err.to_string())?;
+    socket
+        .set_read_timeout(Some(TIMEOUT))
+        .map_err(|err| err.to_string())?;
+    socket
+        .connect((server, 123))
+        .map_err(|err| err.to_string())?;
+    let sent = Utc::now();
+    socket.send(&request(sent)).map_err(|err| err.to_string())?;
+    let mut buffer = [0; PACKET_LEN];
+    let len = socket.recv(&mut buffer).map_err(|err| err.to_string())?;
+    let arrived = Utc::now();
+    let reply = parse_reply(&buffer[..len], sent)?;
+    Ok(offset(sent, &reply, arrived))
+}
+
+// The last successful check
+#[derive(Debug, Clone, PartialEq)]
+pub struct Drift {
+    pub offset: Duration,
+    pub server: String,
+    pub checked: DateTime<Utc>,
+}
+
+impl Drift {
+    pub fn exceeds(&self, threshold_secs: u32) -> bool {
+        self.offset.abs() > Duration::seconds(threshold_secs as i64)
+    }
+
+    // "The system clock is 7.3 s behind pool.ntp.org"
+    pub fn describe(&self) -> String {
+        let millis = self.offset.num_milliseconds();
+        format!(
+            "The system clock is {:.1} s {} {}",
+            millis.abs() as f64 / 1000.0,
+            if millis > 0 { "behind" } else { "ahead of" },
+            self.server
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(text: &str) -> DateTime<Utc> {
+        text.parse().unwrap()
+    }
+
+    // A server reply to a request sent at `sent`
+    fn reply_packet(
+        sent: DateTime<Utc>,
+        received: DateTime<Utc>,
+        transmitted: DateTime<Utc>,
+    ) -> Vec<u8> {
+        let mut packet = vec![0; PACKET_LEN];
+        packet[0] = 0b00_100_100; // no leap warning, version 4, server
+        packet[1] = 2;
+        packet[24..32].copy_from_slice(&to_ntp(sent).to_be_bytes());
+        packet[32..40].copy_from_slice(&to_ntp(received).to_be_bytes());
+        packet[40..48].copy_from_slice(&to_ntp(transmitted).to_be_bytes());
+        packet
+    }
+
+    #[test]
+    fn ntp_timestamps_round_trip() {
+        assert_eq!(to_ntp(DateTime::UNIX_EPOCH), (UNIX_OFFSET as u64) << 32);
+        // Half a second is the top bit of the fraction
+        assert_eq!(
+            to_ntp(at("1970-01-01T00:00:00.5Z")),
+            (UNIX_OFFSET as u64) << 32 | 1 << 31
+        );
+        for text in [
+            "2026-03-20T14:46:00Z",
+            "2026-03-20T14:46:00.25Z",
+            "2036-02-07T06:28:15Z",
+        ] {
+            assert_eq!(from_ntp(to_ntp(at(text))), Some(at(text)), "{}", text);
+        }
+        // Fractions that aren't a power of two lose at most a nanosecond
+        let uneven = at("2026-03-20T14:46:00.123456789Z");
+        let back = from_ntp(to_ntp(uneven)).unwrap();
+        assert!((uneven - back).abs() <= Duration::nanoseconds(1));
+    }
+
+    #[test]
+    fn a_reply_to_this_request_is_read() {
+        let sent = at("2026-03-20T12:00:00Z");
+        let (received, transmitted) = (at("2026-03-20T12:00:07.5Z"), at("2026-03-20T12:00:07.75Z"));
+        let packet = reply_packet(sent, received, transmitted);
+        assert_eq!(
+            parse_reply(&packet, sent),
+            Ok(Reply {
+                received,
+                transmitted
+            })
+        );
+        // Someone else's request
+        assert_eq!(
+            parse_reply(&packet, sent + Duration::seconds(1)),
+            Err("The reply doesn't answer this request".to_string())
+        );
+        assert_eq!(request(sent)[0] & 0b111, 3);
+    }
+
+    #[test]
+    fn short_and_unwanted_packets_are_rejected() {
+        let sent = at("2026-03-20T12:00:00Z");
+        let good = reply_packet(sent, sent, sent);
+        assert_eq!(
+            parse_reply(&good[..47], sent),
+            Err("Reply too short: 47 bytes".to_string())
+        );
+        assert_eq!(
+            parse_reply(&request(sent), sent),
+            Err("Not a server reply (mode 3)".to_string())
+        );
+
+        let mut kiss_of_death = good.clone();
+        kiss_of_death[1] = 0;
+        assert_eq!(
+            parse_reply(&kiss_of_death, sent),
+            Err("The server refused the request".to_string())
+        );
+        let mut unsynchronized = good;
+        unsynchronized[0] |= 0b11 << 6;
+        assert_eq!(
+            parse_reply(&unsynchronized, sent),
+            Err("The server's clock isn't synchronized".to_string())
+        );
+    }
+
+    #[test]
+    fn offset_splits_the_round_trip_evenly() {
+        // The server is 10 s ahead and each way takes 100 ms
+        let sent = at("2026-03-20T12:00:00Z");
+        let reply = Reply {
+            received: at("2026-03-20T12:00:10.1Z"),
+            transmitted: at("2026-03-20T12:00:10.2Z"),
+        };
+        let arrived = at("2026-03-20T12:00:00.3Z");
+        assert_eq!(offset(sent, &reply, arrived), Duration::seconds(10));
+
+        // 2 s behind, with 100 ms there and 400 ms back: the estimate is off by half
+        // the difference
+        let reply = Reply {
+            received: at("2026-03-20T11:59:58.1Z"),
+            transmitted: at("2026-03-20T11:59:58.1Z"),
+        };
+        let arrived = at("2026-03-20T12:00:00.5Z");
+        assert_eq!(offset(sent, &reply, arrived), Duration::milliseconds(-2150));
+    }
+}
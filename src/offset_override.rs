@@ -0,0 +1,87 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+use time2rust::format;
+
+const MAX_MINUTES: i32 = 3 * 60;
+
+// A manual shift on top of a zone's own offset, for when a government changes its
+// clocks faster than the tz database ships. Lapses at local midnight on `until`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct OffsetOverride {
+    pub minutes: i32,
+    pub until: NaiveDate,
+}
+
+impl OffsetOverride {
+    pub fn is_active(&self, tz: Tz, now: DateTime<Utc>) -> bool {
+        now.with_timezone(&tz).date_naive() < self.until
+    }
+
+    // Like "+1h until Apr 5"
+    pub fn describe(&self) -> String {
+        format!(
+            "{} until {}",
+            format_shift(self.minutes),
+            format::month_day(self.until)
+        )
+    }
+}
+
+// Minutes to add to the zone's offset at `now`; 0 once the override has lapsed
+pub fn active_minutes(offset_override: Option<&OffsetOverride>, tz: Tz, now: DateTime<Utc>) -> i32 {
+    offset_override
+        .filter(|offset_override| offset_override.is_active(tz, now))
+        .map_or(0, |offset_override| offset_override.minutes)
+}
+
+// "+1h", "-30m", "+1h 30m"
+pub fn format_shift(minutes: i32) -> String {
+    let sign = if minutes < 0 { '-' } else { '+' };
+    let (hours, minutes) = (minutes.abs() / 60, minutes.abs() % 60);
+    match (hours, minutes) {
+        (0, minutes) => format!("{}{}m", sign, minutes),
+        (hours, 0) => format!("{}{}h", sign, hours),
+        (hours, minutes) => format!("{}{}h {}m", sign, hours, minutes),
+    }
+}
+
+// Hours as "+1", "-0.5", "+1:30" or "1h", in quarter hours up to three hours either way
+pub fn parse_shift(input: &str) -> Result<i32, String> {
+    let invalid = || {
+        format!(
+            "\"{}\" should be hours like +1, -0.5 or +1:30",
+            input.trim()
+        )
+    };
+    let trimmed = input.trim().trim_end_matches(['h', 'H']);
+    let (negative, magnitude) = match trimmed.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, trimmed.strip_prefix('+').unwrap_or(trimmed)),
+    };
+    let minutes = match magnitude.split_once(':') {
+        Some((hours, minutes)) => {
+            let hours: i32 = hours.parse().map_err(|_| invalid())?;
+            let minutes: i32 = minutes.parse().map_err(|_| invalid())?;
+            if minutes >= 60 {
+                return Err(invalid());
+            }
+            hours * 60 + minutes
+        }
+        None => {
+            let hours: f64 = magnitude.parse().map_err(|_| invalid())?;
+            if !hours.is_finite() {
+                return Err(invalid());
+            }
+            (hours * 60.0).round() as i32
+        }
+    };
+    let minutes = if negative { -minutes } else { minutes };
+    if minutes == 0 || minutes % 15 != 0 || minutes.abs() > MAX_MINUTES {
+        return Err(format!(
+            "Use a non-zero shift in quarter hours, up to {}h either way",
+            MAX_MINUTES / 60
+        ));
+    }
+    Ok(minutes)
+}
@@ -0,0 +1,212 @@
+use crate::{
+    config::CityConfig,
+    settings_panel::{hint, section_title},
+    zone,
+};
+use chrono_tz::Tz;
+use gpui::{Context, Entity, EventEmitter, SharedString, Window, div, prelude::*, px, rgb};
+use gpui_component::{
+    Sizable,
+    button::{Button, ButtonVariants as _},
+    checkbox::Checkbox,
+    h_flex,
+    input::{Input, InputEvent, InputState},
+    v_flex,
+};
+
+// Offered on first launch; nothing is on until the user ticks it
+const SUGGESTED_CITIES: &[(&str, &str)] = &[
+    ("San Francisco", "America/Los_Angeles"),
+    ("New York", "America/New_York"),
+    ("São Paulo", "America/Sao_Paulo"),
+    ("London", "Europe/London"),
+    ("Berlin", "Europe/Berlin"),
+    ("Dubai", "Asia/Dubai"),
+    ("Mumbai", "Asia/Kolkata"),
+    ("Singapore", "Asia/Singapore"),
+    ("Tokyo", "Asia/Tokyo"),
+    ("Sydney", "Australia/Sydney"),
+];
+
+pub enum OnboardingEvent {
+    // Home first, then the chosen cities
+    Done(Vec<CityConfig>),
+}
+
+struct Choice {
+    city: CityConfig,
+    selected: bool,
+}
+
+// Shown in place of the grid until the user has picked a home and some cities
+pub struct Onboarding {
+    home_tz: Tz,
+    detection_error: Option<String>,
+    home_name_input: Entity<InputState>,
+    choices: Vec<Choice>,
+    add_input: Entity<InputState>,
+    add_error: Option<SharedString>,
+}
+
+impl EventEmitter<OnboardingEvent> for Onboarding {}
+
+impl Onboarding {
+    // `current` are the cities already configured when re-running; they start ticked
+    pub fn new(current: &[CityConfig], window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let current_home = current.iter().find(|city| city.home);
+        let (home_tz, detection_error) = match zone::system_zone() {
+            Ok(tz) => (tz, None),
+            Err(err) => (chrono_tz::UTC, Some(err)),
+        };
+        let home_name = current_home
+            .filter(|home| home.timezone == home_tz.name())
+            .map_or_else(|| zone::zone_city_name(home_tz), |home| home.name.clone());
+        let home_name_input = cx.new(|cx| {
+            InputState::new(window, cx)
+                .placeholder("Name for home")
+                .default_value(home_name)
+        });
+
+        let mut choices: Vec<Choice> = current
+            .iter()
+            .filter(|city| !city.ephemeral && city.timezone != home_tz.name())
+            .map(|city| Choice {
+                // Keeps what else the city carries, like its people
+                city: CityConfig {
+                    home: false,
+                    ..city.clone()
+                },
+                selected: true,
+            })
+            .collect();
+        for (name, timezone) in SUGGESTED_CITIES {
+            let taken = *timezone == home_tz.name()
+                || choices
+                    .iter()
+                    .any(|choice| choice.city.timezone == *timezone);
+            if !taken {
+                choices.push(Choice {
+                    city: CityConfig::new(name, timezone, false),
+                    selected: false,
+                });
+            }
+        }
+
+        let add_input =
+            cx.new(|cx| InputState::new(window, cx).placeholder("Another city or timezone"));
+        cx.subscribe_in(&add_input, window, |this, input, event, window, cx| {
+            if let InputEvent::PressEnter { .. } = event {
+                let query = input.read(cx).value().trim().to_string();
+                this.add_city(&query, window, cx);
+            }
+        })
+        .detach();
+
+        Onboarding {
+            home_tz,
+            detection_error,
+            home_name_input,
+            choices,
+            add_input,
+            add_error: None,
+        }
+    }
+
+    fn add_city(&mut self, query: &str, window: &mut Window, cx: &mut Context<Self>) {
+        let tz = zone::resolve_zone(query)
+            .map_err(|err| err.to_string())
+            .or_else(|err| zone::find_zone_by_city(query).ok_or(err));
+        match tz {
+            Ok(tz) => {
+                let name = if query.contains('/') {
+                    zone::zone_city_name(tz)
+                } else {
+                    query.to_string()
+                };
+                self.choices.push(Choice {
+                    city: CityConfig::new(&name, tz.name(), false),
+                    selected: true,
+                });
+                self.add_input
+                    .update(cx, |input, cx| input.set_value("", window, cx));
+                self.add_error = None;
+            }
+            Err(err) => self.add_error = Some(err.into()),
+        }
+        cx.notify();
+    }
+
+    fn finish(&mut self, cx: &mut Context<Self>) {
+        let home_name = self.home_name_input.read(cx).value().trim().to_string();
+        let home_name = if home_name.is_empty() {
+            zone::zone_city_name(self.home_tz)
+        } else {
+            home_name
+        };
+        let cities = std::iter::once(CityConfig::new(&home_name, self.home_tz.name(), true))
+            .chain(
+                self.choices
+                    .iter()
+                    .filter(|choice| choice.selected)
+                    .map(|choice| choice.city.clone()),
+            )
+            .collect();
+        cx.emit(OnboardingEvent::Done(cities));
+    }
+}
+
+impl Render for Onboarding {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .gap_6()
+            .max_w(px(520.0))
+            .child(
+                div()
+                    .child("Welcome to time2rust")
+                    .text_2xl()
+                    .font_weight(gpui::FontWeight::BOLD),
+            )
+            .child(
+                v_flex()
+                    .gap_2()
+                    .child(section_title("Home"))
+                    .child(Input::new(&self.home_name_input))
+                    .child(hint(match &self.detection_error {
+                        None => format!("Detected timezone: {}", self.home_tz.name()),
+                        Some(err) => format!("{}; using UTC", err),
+                    })),
+            )
+            .child(
+                v_flex()
+                    .gap_2()
+                    .child(section_title("Cities to show"))
+                    .child(h_flex().flex_wrap().gap_3().children(
+                        self.choices.iter().enumerate().map(|(index, choice)| {
+                            Checkbox::new(("onboarding-city", index))
+                                .label(choice.city.name.clone())
+                                .checked(choice.selected)
+                                .on_click(cx.listener(move |this, checked: &bool, _, cx| {
+                                    this.choices[index].selected = *checked;
+                                    cx.notify();
+                                }))
+                        }),
+                    ))
+                    .child(Input::new(&self.add_input))
+                    .children(
+                        self.add_error
+                            .clone()
+                            .map(|error| div().child(error).text_sm().text_color(rgb(0xef4444))),
+                    )
+                    .child(hint("Press Enter to add; more cities can be added later")),
+            )
+            .child(
+                h_flex().justify_end().child(
+                    Button::new("finish-onboarding")
+                        .label("Start")
+                        .small()
+                        .primary()
+                        .on_click(cx.listener(|this, _, _, cx| this.finish(cx))),
+                ),
+            )
+    }
+}
@@ -0,0 +1,13 @@
+// Positions of `cities` (name, UTC offset in minutes) from the earliest local time
+// to the latest, with cities sharing an offset in alphabetical order
+pub fn by_local_time(cities: &[(String, i32)]) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..cities.len()).collect();
+    order.sort_by(|&a, &b| {
+        let (name_a, offset_a) = &cities[a];
+        let (name_b, offset_b) = &cities[b];
+        offset_a
+            .cmp(offset_b)
+            .then_with(|| name_a.to_lowercase().cmp(&name_b.to_lowercase()))
+    });
+    order
+}
@@ -0,0 +1,206 @@
+use crate::{
+    WorldTimeApp,
+    config::Settings,
+    planner::{self, DoNotDisturb, Overlap, OverlapWindow, WorkingHours},
+    settings_panel::{hint, section_title},
+    week::Weekend,
+};
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
+use gpui::{App, Entity, Window, div, prelude::*, px, rgb};
+use gpui_component::{
+    Sizable, WindowExt as _,
+    button::{Button, ButtonVariants as _},
+    h_flex, v_flex,
+};
+use time2rust::format;
+
+const SEARCH_DAYS: i64 = 7;
+const MAX_CANDIDATES: usize = 8;
+
+// Lists the best meeting windows over the next week for the cities on screen,
+// hidden ones left out
+pub fn open_overlap_finder(app: Entity<WorldTimeApp>, window: &mut Window, cx: &mut App) {
+    let default_hours = cx.global::<Settings>().working_hours;
+    let default_dnd = cx.global::<Settings>().do_not_disturb;
+    let default_weekend = cx.global::<Settings>().week.weekend;
+    let (cities, home_tz) = {
+        let app = app.read(cx);
+        let cities: Vec<(String, Tz, WorkingHours, DoNotDisturb, Weekend)> = app
+            .cities
+            .iter()
+            .map(|city| city.read(cx))
+            .filter(|city| !city.hidden)
+            .map(|city| {
+                (
+                    city.name.clone(),
+                    city.tz,
+                    city.working_hours.unwrap_or(default_hours),
+                    city.do_not_disturb.unwrap_or(default_dnd),
+                    city.weekend.unwrap_or(default_weekend),
+                )
+            })
+            .collect();
+        (cities, app.home_tz)
+    };
+    let zones: Vec<_> = cities
+        .iter()
+        .map(|(_, tz, hours, dnd, weekend)| (*tz, *hours, *dnd, *weekend))
+        .collect();
+    let overlap = planner::find_overlaps(&zones, Utc::now(), SEARCH_DAYS, MAX_CANDIDATES);
+    let names: Vec<String> = cities.into_iter().map(|(name, ..)| name).collect();
+
+    window.open_sheet(cx, move |sheet, _window, _cx| {
+        sheet
+            .title("Find overlap")
+            .child(overlap_list(&app, &overlap, &names, &zones, home_tz))
+    });
+}
+
+fn overlap_list(
+    app: &Entity<WorldTimeApp>,
+    overlap: &Overlap,
+    names: &[String],
+    zones: &[(Tz, WorkingHours, DoNotDisturb, Weekend)],
+    home_tz: Tz,
+) -> impl IntoElement {
+    v_flex()
+        .gap_4()
+        .child(section_title("Next 7 days"))
+        .child(hint(summary(overlap, names.len())))
+        .children(
+            overlap
+                .windows
+                .iter()
+                .enumerate()
+                .map(|(index, window)| candidate(app, index, window, names, zones, home_tz)),
+        )
+}
+
+fn summary(overlap: &Overlap, city_count: usize) -> String {
+    if overlap.windows.is_empty() {
+        "Nobody's working hours fall in the next 7 days outside do-not-disturb".to_string()
+    } else if overlap.complete {
+        "Everyone is within working hours at these times".to_string()
+    } else {
+        format!(
+            "No time works for everyone. Best {} of {} instead:",
+            overlap.windows[0].working_count(),
+            city_count
+        )
+    }
+}
+
+fn candidate(
+    app: &Entity<WorldTimeApp>,
+    index: usize,
+    window: &OverlapWindow,
+    names: &[String],
+    zones: &[(Tz, WorkingHours, DoNotDisturb, Weekend)],
+    home_tz: Tz,
+) -> impl IntoElement {
+    let local_range = |tz: Tz| {
+        format!(
+            "{}–{}",
+            format::clock_time(window.start.with_timezone(&tz).time(), false),
+            format::clock_time(window.end.with_timezone(&tz).time(), false)
+        )
+    };
+    let left_out: Vec<&str> = names
+        .iter()
+        .zip(&window.working)
+        .filter(|(_, working)| !**working)
+        .map(|(name, _)| name.as_str())
+        .collect();
+    let start: DateTime<Utc> = window.start;
+    let app = app.clone();
+
+    v_flex()
+        .gap_1()
+        .p_2()
+        .rounded(px(6.0))
+        .bg(rgb(0xf9fafb))
+        .child(
+            h_flex()
+                .justify_between()
+                .child(
+                    div()
+                        .child(format!(
+                            "{} {} at home ({})",
+                            format::weekday_date(window.start.with_timezone(&home_tz).date_naive()),
+                            local_range(home_tz),
+                            format_duration(window.minutes())
+                        ))
+                        .text_sm()
+                        .font_weight(gpui::FontWeight::BOLD),
+                )
+                .child(
+                    Button::new(("show-overlap", index))
+                        .label("Show")
+                        .small()
+                        .ghost()
+                        .on_click(move |_, _, cx| {
+                            app.update(cx, |app, cx| app.set_simulated_instant(Some(start), cx))
+                        }),
+                ),
+        )
+        .child(hint(
+            names
+                .iter()
+                .zip(zones)
+                .map(|(name, (tz, ..))| format!("{} {}", name, local_range(*tz)))
+                .collect::<Vec<_>>()
+                .join(" · "),
+        ))
+        .children((!left_out.is_empty()).then(|| {
+            div()
+                .child(format!("Outside hours: {}", left_out.join(", ")))
+                .text_xs()
+                .text_color(rgb(0xf59e0b))
+        }))
+}
+
+fn format_duration(minutes: i64) -> String {
+    match (minutes / 60, minutes % 60) {
+        (0, minutes) => format!("{}m", minutes),
+        (hours, 0) => format!("{}h", hours),
+        (hours, minutes) => format!("{}h {}m", hours, minutes),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn durations_read_in_hours_and_minutes() {
+        assert_eq!(format_duration(45), "45m");
+        assert_eq!(format_duration(120), "2h");
+        assert_eq!(format_duration(210), "3h 30m");
+        assert_eq!(format_duration(0), "0m");
+    }
+
+    #[test]
+    fn the_summary_says_how_many_can_make_it() {
+        let start = "2026-06-01T13:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        let window = |working: Vec<bool>| OverlapWindow {
+            start,
+            end: start + chrono::Duration::hours(2),
+            working,
+        };
+        let overlap = |windows, complete| Overlap { windows, complete };
+
+        assert_eq!(
+            summary(&overlap(vec![window(vec![true, true, true])], true), 3),
+            "Everyone is within working hours at these times"
+        );
+        assert_eq!(
+            summary(&overlap(vec![window(vec![true, false, true])], false), 3),
+            "No time works for everyone. Best 2 of 3 instead:"
+        );
+        assert_eq!(
+            summary(&overlap(Vec::new(), false), 3),
+            "Nobody's working hours fall in the next 7 days outside do-not-disturb"
+        );
+    }
+}
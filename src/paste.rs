@@ -0,0 +1,48 @@
+use std::process::Command;
+
+// Presses the platform's paste keys in whatever app is in front, with the helper
+// each platform has for it: System Events on macOS, SendKeys on Windows, and
+// xdotool, or wtype under Wayland, elsewhere
+pub fn send_paste() -> Result<(), String> {
+    #[cfg(target_os = "macos")]
+    let mut command = {
+        let mut command = Command::new("osascript");
+        command.args([
+            "-e",
+            "tell application \"System Events\" to keystroke \"v\" using command down",
+        ]);
+        command
+    };
+    #[cfg(target_os = "windows")]
+    let mut command = {
+        let mut command = Command::new("powershell");
+        command.args([
+            "-NoProfile",
+            "-Command",
+            "(New-Object -ComObject WScript.Shell).SendKeys('^v')",
+        ]);
+        command
+    };
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    let mut command = if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+        let mut command = Command::new("wtype");
+        command.args(["-M", "ctrl", "v", "-m", "ctrl"]);
+        command
+    } else {
+        // Lets go of the shortcut's own modifiers first, or the app would see
+        // ctrl+alt+v
+        let mut command = Command::new("xdotool");
+        command.args(["key", "--clearmodifiers", "ctrl+v"]);
+        command
+    };
+
+    let program = command.get_program().to_string_lossy().into_owned();
+    let status = command
+        .status()
+        .map_err(|err| format!("couldn't run {}: {}", program, err))?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(format!("{} exited with {}", program, status))
+    }
+}
@@ -0,0 +1,62 @@
+use gpui::{div, prelude::*, px, rgb};
+use gpui_component::h_flex;
+
+// "Anna Schmidt" -> "AS", "tom" -> "T"
+pub fn initials(name: &str) -> String {
+    name.split_whitespace()
+        .filter_map(|word| word.chars().next())
+        .take(2)
+        .flat_map(char::to_uppercase)
+        .collect()
+}
+
+// "Berlin (Anna, Tom)", or just "Berlin" when nobody is there
+pub fn with_people(city: &str, people: &[String]) -> String {
+    if people.is_empty() {
+        city.to_string()
+    } else {
+        format!("{} ({})", city, people.join(", "))
+    }
+}
+
+// Trims, and refuses blanks and names already on the card (ignoring case)
+pub fn add_person(people: &[String], name: &str) -> Result<Vec<String>, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Enter a name".to_string());
+    }
+    if people
+        .iter()
+        .any(|person| person.to_lowercase() == name.to_lowercase())
+    {
+        return Err(format!("{} is already here", name));
+    }
+    let mut people = people.to_vec();
+    people.push(name.to_string());
+    Ok(people)
+}
+
+// Avatar circles with initials, each followed by the name
+pub fn people_chips(people: Vec<String>) -> impl IntoElement {
+    h_flex()
+        .flex_wrap()
+        .justify_center()
+        .gap_2()
+        .children(people.into_iter().map(|person| {
+            h_flex()
+                .gap_1()
+                .child(
+                    div()
+                        .flex()
+                        .items_center()
+                        .justify_center()
+                        .size(px(18.0))
+                        .rounded_full()
+                        .bg(rgb(0xdbeafe))
+                        .text_color(rgb(0x1e40af))
+                        .text_size(px(9.0))
+                        .child(initials(&person)),
+                )
+                .child(div().child(person).text_xs().text_color(rgb(0x4b5563)))
+        }))
+}
@@ -0,0 +1,119 @@
+use crate::{
+    config::CityConfig,
+    convert::{self, NamedZone},
+    relative, zone,
+};
+use chrono::{DateTime, Datelike, Days, Duration, NaiveDate, NaiveTime, Utc, Weekday};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+use time2rust::format;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "repeat", rename_all = "lowercase")]
+pub enum Recurrence {
+    Once { date: NaiveDate },
+    Weekly { weekday: Weekday },
+}
+
+// An event shown on every card, e.g. "All-hands, Thursdays 16:00 UTC". The time
+// is kept in the event's own zone so DST elsewhere moves it for everyone else.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PinnedEvent {
+    pub title: String,
+    pub timezone: String,
+    pub time: NaiveTime,
+    #[serde(flatten)]
+    pub recurrence: Recurrence,
+}
+
+// An ongoing occurrence stays shown until this long after it starts
+const STILL_RELEVANT: Duration = Duration::hours(1);
+
+impl PinnedEvent {
+    // The occurrence to show: the current or next one; a past one-off is None
+    pub fn occurrence(&self, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let tz = zone::resolve_zone(&self.timezone).ok()?;
+        let at = |date: NaiveDate| convert::resolve_local(date.and_time(self.time), tz).instant;
+        match self.recurrence {
+            Recurrence::Once { date } => Some(at(date)).filter(|at| *at + STILL_RELEVANT > now),
+            Recurrence::Weekly { weekday } => {
+                let today = (now - STILL_RELEVANT).with_timezone(&tz).date_naive();
+                (0..8)
+                    .filter_map(|days| today.checked_add_days(Days::new(days)))
+                    .filter(|date| date.weekday() == weekday)
+                    .map(at)
+                    .find(|at| *at + STILL_RELEVANT > now)
+            }
+        }
+    }
+
+    // "All-hands: 11:00", with "+1 day"/"-1 day" when the city is on another calendar day
+    pub fn card_line(&self, now: DateTime<Utc>, city_tz: Tz) -> Option<String> {
+        let tz = zone::resolve_zone(&self.timezone).ok()?;
+        let at = self.occurrence(now)?;
+        let local = at.with_timezone(&city_tz);
+        let day_note = match relative::relative_day(tz, city_tz, at).days() {
+            0 => String::new(),
+            days => format!(" {}", format::day_offset(days)),
+        };
+        Some(format!(
+            "{}: {}{}",
+            self.title,
+            format::clock_time(local.time(), false),
+            day_note
+        ))
+    }
+
+    pub fn describe(&self) -> String {
+        let when = match self.recurrence {
+            Recurrence::Once { date } => format::weekday_date(date),
+            Recurrence::Weekly { weekday } => format!("every {}", weekday),
+        };
+        format!(
+            "{}, {} {} {}",
+            self.title,
+            when,
+            format::clock_time(self.time, false),
+            self.timezone
+        )
+    }
+}
+
+// Parses "thu 16:00 UTC" (weekly) or "2025-05-01 16:00 Berlin" / "tomorrow 09:00"
+// (one-off). The zone defaults to `default_zone`, as with quick conversions.
+pub fn parse_pinned_event(
+    title: &str,
+    when: &str,
+    cities: &[CityConfig],
+    default_zone: &NamedZone,
+    now: DateTime<Utc>,
+) -> Result<PinnedEvent, String> {
+    let title = title.trim();
+    if title.is_empty() {
+        return Err("Give the event a title".to_string());
+    }
+    let when = when.trim();
+    let weekday = when
+        .split_once(char::is_whitespace)
+        .and_then(|(word, rest)| word.parse::<Weekday>().ok().map(|day| (day, rest)));
+    let (query, weekday) = match weekday {
+        Some((weekday, rest)) => (rest, Some(weekday)),
+        None => (when, None),
+    };
+    let conversion = convert::convert_query(query, cities, default_zone, now)?;
+    let local = conversion
+        .resolved
+        .instant
+        .with_timezone(&conversion.from.tz);
+    Ok(PinnedEvent {
+        title: title.to_string(),
+        timezone: conversion.from.tz.name().to_string(),
+        time: local.time(),
+        recurrence: match weekday {
+            Some(weekday) => Recurrence::Weekly { weekday },
+            None => Recurrence::Once {
+                date: local.date_naive(),
+            },
+        },
+    })
+}
@@ -0,0 +1,125 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use url::Url;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Coordinates {
+    pub lat: f64,
+    pub lon: f64,
+}
+
+impl fmt::Display for Coordinates {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:.4}, {:.4}", self.lat, self.lon)
+    }
+}
+
+// Parses "52.52, 13.405" (or space-separated) as latitude then longitude
+pub fn parse_coordinates(input: &str) -> Result<Coordinates, String> {
+    let invalid = || format!("\"{}\" should look like 52.52, 13.405", input.trim());
+    let mut parts = input
+        .split([',', ' '])
+        .map(str::trim)
+        .filter(|part| !part.is_empty());
+    let (Some(lat), Some(lon), None) = (parts.next(), parts.next(), parts.next()) else {
+        return Err(invalid());
+    };
+    let lat: f64 = lat.parse().map_err(|_| invalid())?;
+    let lon: f64 = lon.parse().map_err(|_| invalid())?;
+    if !(-90.0..=90.0).contains(&lat) || !(-180.0..=180.0).contains(&lon) {
+        return Err(format!("{} is outside the valid range", input.trim()));
+    }
+    Ok(Coordinates { lat, lon })
+}
+
+// Major cities and their airport codes; names are matched case-insensitively
+const PLACES: &[(&str, &str, f64, f64)] = &[
+    ("Amsterdam", "AMS", 52.3676, 4.9041),
+    ("Athens", "ATH", 37.9838, 23.7275),
+    ("Atlanta", "ATL", 33.7490, -84.3880),
+    ("Auckland", "AKL", -36.8485, 174.7633),
+    ("Austin", "AUS", 30.2672, -97.7431),
+    ("Bangalore", "BLR", 12.9716, 77.5946),
+    ("Bangkok", "BKK", 13.7563, 100.5018),
+    ("Barcelona", "BCN", 41.3874, 2.1686),
+    ("Beijing", "PEK", 39.9042, 116.4074),
+    ("Berlin", "BER", 52.5200, 13.4050),
+    ("Boston", "BOS", 42.3601, -71.0589),
+    ("Bucharest", "OTP", 44.4268, 26.1025),
+    ("Buenos Aires", "EZE", -34.6037, -58.3816),
+    ("Cairo", "CAI", 30.0444, 31.2357),
+    ("Chicago", "ORD", 41.8781, -87.6298),
+    ("Denver", "DEN", 39.7392, -104.9903),
+    ("Dubai", "DXB", 25.2048, 55.2708),
+    ("Dublin", "DUB", 53.3498, -6.2603),
+    ("Helsinki", "HEL", 60.1699, 24.9384),
+    ("Hong Kong", "HKG", 22.3193, 114.1694),
+    ("Istanbul", "IST", 41.0082, 28.9784),
+    ("Jakarta", "CGK", -6.2088, 106.8456),
+    ("Johannesburg", "JNB", -26.2041, 28.0473),
+    ("Kolkata", "CCU", 22.5726, 88.3639),
+    ("Lagos", "LOS", 6.5244, 3.3792),
+    ("Lisbon", "LIS", 38.7223, -9.1393),
+    ("London", "LHR", 51.5074, -0.1278),
+    ("Los Angeles", "LAX", 34.0522, -118.2437),
+    ("Madrid", "MAD", 40.4168, -3.7038),
+    ("Mexico City", "MEX", 19.4326, -99.1332),
+    ("Miami", "MIA", 25.7617, -80.1918),
+    ("Moscow", "SVO", 55.7558, 37.6173),
+    ("Mumbai", "BOM", 19.0760, 72.8777),
+    ("Nairobi", "NBO", -1.2921, 36.8219),
+    ("New York", "JFK", 40.7128, -74.0060),
+    ("NYC", "LGA", 40.7128, -74.0060),
+    ("Paris", "CDG", 48.8566, 2.3522),
+    ("Prague", "PRG", 50.0755, 14.4378),
+    ("Rome", "FCO", 41.9028, 12.4964),
+    ("San Francisco", "SFO", 37.7749, -122.4194),
+    ("São Paulo", "GRU", -23.5558, -46.6396),
+    ("Seattle", "SEA", 47.6062, -122.3321),
+    ("Seoul", "ICN", 37.5665, 126.9780),
+    ("Shanghai", "PVG", 31.2304, 121.4737),
+    ("Singapore", "SIN", 1.3521, 103.8198),
+    ("Stockholm", "ARN", 59.3293, 18.0686),
+    ("Sydney", "SYD", -33.8688, 151.2093),
+    ("Taipei", "TPE", 25.0330, 121.5654),
+    ("Tokyo", "HND", 35.6762, 139.6503),
+    ("Toronto", "YYZ", 43.6532, -79.3832),
+    ("Vancouver", "YVR", 49.2827, -123.1207),
+    ("Vienna", "VIE", 48.2082, 16.3738),
+    ("Warsaw", "WAW", 52.2297, 21.0122),
+    ("Zurich", "ZRH", 47.3769, 8.5417),
+];
+
+// Looks a card name up as a city or airport code, then tries the zone's own city
+// ("America/New_York" → New York)
+pub fn lookup(name: &str, timezone_id: &str) -> Option<Coordinates> {
+    let zone_city = timezone_id
+        .rsplit('/')
+        .next()
+        .unwrap_or("")
+        .replace('_', " ");
+    [name.trim(), zone_city.as_str()]
+        .into_iter()
+        .find_map(|query| {
+            PLACES
+                .iter()
+                .find(|(city, code, _, _)| {
+                    city.eq_ignore_ascii_case(query) || code.eq_ignore_ascii_case(query)
+                })
+                .map(|&(_, _, lat, lon)| Coordinates { lat, lon })
+        })
+}
+
+// A map centred on the coordinates, or a place search when they're unknown
+pub fn maps_url(name: &str, coordinates: Option<Coordinates>) -> String {
+    match coordinates {
+        Some(Coordinates { lat, lon }) => format!(
+            "https://www.openstreetmap.org/?mlat={lat:.4}&mlon={lon:.4}#map=11/{lat:.4}/{lon:.4}"
+        ),
+        None => {
+            let mut url = Url::parse("https://www.openstreetmap.org/search").unwrap();
+            url.query_pairs_mut().append_pair("query", name);
+            url.to_string()
+        }
+    }
+}
@@ -0,0 +1,550 @@
+use crate::week::{self, Weekend};
+use chrono::{DateTime, Duration, NaiveTime, Timelike, Utc};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+
+// A city's working day in its own local time; `end` before `start` means a night shift
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct WorkingHours {
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+}
+
+impl Default for WorkingHours {
+    fn default() -> Self {
+        WorkingHours {
+            start: NaiveTime::from_hms_opt(9, 0, 0).unwrap(),
+            end: NaiveTime::from_hms_opt(17, 0, 0).unwrap(),
+        }
+    }
+}
+
+impl WorkingHours {
+    pub fn contains(&self, time: NaiveTime) -> bool {
+        range_contains(self.start, self.end, time)
+    }
+}
+
+// When a city must not be called at all, in its own local time. Unlike working
+// hours, which are a preference, this is a hard limit for planning.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct DoNotDisturb {
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+}
+
+impl Default for DoNotDisturb {
+    fn default() -> Self {
+        DoNotDisturb {
+            start: NaiveTime::from_hms_opt(22, 0, 0).unwrap(),
+            end: NaiveTime::from_hms_opt(7, 0, 0).unwrap(),
+        }
+    }
+}
+
+impl DoNotDisturb {
+    pub fn contains(&self, time: NaiveTime) -> bool {
+        range_contains(self.start, self.end, time)
+    }
+}
+
+// `end` before `start` wraps past midnight
+pub fn range_contains(start: NaiveTime, end: NaiveTime, time: NaiveTime) -> bool {
+    if start <= end {
+        start <= time && time < end
+    } else {
+        time >= start || time < end
+    }
+}
+
+// How close to its working day a city would be at the planned time
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WorkStatus {
+    Working,
+    Edge, // within EDGE_MINUTES of the working day: possible, but early or late
+    Off,
+    DoNotDisturb,
+}
+
+const EDGE_MINUTES: i64 = 90;
+
+pub fn work_status(time: NaiveTime, hours: &WorkingHours, dnd: &DoNotDisturb) -> WorkStatus {
+    if dnd.contains(time) {
+        return WorkStatus::DoNotDisturb;
+    }
+    if hours.contains(time) {
+        return WorkStatus::Working;
+    }
+    let edge = Duration::minutes(EDGE_MINUTES);
+    if hours.contains(time + edge) || hours.contains(time - edge) {
+        WorkStatus::Edge
+    } else {
+        WorkStatus::Off
+    }
+}
+
+// Step size of the planner slider and the granularity of proposed slots
+pub const SLOT_MINUTES: i64 = 15;
+
+// The slot `offset_minutes` from `now`, snapped to a slot boundary so proposals
+// read as 14:15 rather than 14:07
+pub fn slot_at(now: DateTime<Utc>, offset_minutes: i64) -> DateTime<Utc> {
+    let target = now + Duration::minutes(offset_minutes);
+    let into_slot = (target.minute() as i64 % SLOT_MINUTES) * 60 + target.second() as i64;
+    let snapped = target - Duration::seconds(into_slot);
+    snapped.with_nanosecond(0).unwrap_or(snapped)
+}
+
+// A stretch of time during which the same set of cities is working
+#[derive(Debug, Clone, PartialEq)]
+pub struct OverlapWindow {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    pub working: Vec<bool>, // per input city
+}
+
+impl OverlapWindow {
+    pub fn minutes(&self) -> i64 {
+        (self.end - self.start).num_minutes()
+    }
+
+    pub fn working_count(&self) -> usize {
+        self.working.iter().filter(|working| **working).count()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Overlap {
+    pub windows: Vec<OverlapWindow>, // longest first
+    // False when nobody-left-out windows don't exist and these are the best partial ones
+    pub complete: bool,
+}
+
+// Scans `days` from `from` in slot steps for times when every city is within its
+// working hours on one of its working days. When there are none, falls back to the windows covering the most
+// cities (e.g. the best 2 of 3). Times when any city is in do-not-disturb are
+// never suggested, not even as a partial window.
+pub fn find_overlaps(
+    cities: &[(Tz, WorkingHours, DoNotDisturb, Weekend)],
+    from: DateTime<Utc>,
+    days: i64,
+    limit: usize,
+) -> Overlap {
+    let slot = Duration::minutes(SLOT_MINUTES);
+    let end = from + Duration::days(days);
+    let mut start = slot_at(from, 0);
+    if start < from {
+        start += slot;
+    }
+
+    let mut windows: Vec<OverlapWindow> = Vec::new();
+    let mut instant = start;
+    while instant < end {
+        let blocked = cities
+            .iter()
+            .any(|(tz, _, dnd, _)| dnd.contains(instant.with_timezone(tz).time()));
+        if blocked {
+            instant += slot;
+            continue;
+        }
+        let working: Vec<bool> = cities
+            .iter()
+            .map(|(tz, hours, _, weekend)| {
+                week::working_at(instant.with_timezone(tz).naive_local(), hours, *weekend)
+            })
+            .collect();
+        match windows.last_mut() {
+            Some(window) if window.end == instant && window.working == working => {
+                window.end = instant + slot
+            }
+            _ => windows.push(OverlapWindow {
+                start: instant,
+                end: instant + slot,
+                working,
+            }),
+        }
+        instant += slot;
+    }
+
+    let best = windows
+        .iter()
+        .map(OverlapWindow::working_count)
+        .max()
+        .unwrap_or(0);
+    // Windows where nobody works aren't worth suggesting
+    if best == 0 {
+        return Overlap {
+            windows: Vec::new(),
+            complete: cities.is_empty(),
+        };
+    }
+    let mut windows: Vec<OverlapWindow> = windows
+        .into_iter()
+        .filter(|window| window.working_count() == best)
+        .collect();
+    windows.sort_by(|a, b| b.minutes().cmp(&a.minutes()).then(a.start.cmp(&b.start)));
+    windows.truncate(limit);
+    Overlap {
+        windows,
+        complete: best == cities.len(),
+    }
+}
+
+// Coarse parts of a city's day, for the timeline band
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DayPart {
+    Night,
+    Working,
+    Evening, // awake but outside working hours, early mornings included
+}
+
+const NIGHT_END: u32 = 7;
+const NIGHT_START: u32 = 22;
+
+pub fn day_part(time: NaiveTime, hours: &WorkingHours) -> DayPart {
+    if hours.contains(time) {
+        DayPart::Working
+    } else if time.hour() < NIGHT_END || time.hour() >= NIGHT_START {
+        DayPart::Night
+    } else {
+        DayPart::Evening
+    }
+}
+
+// The longest stretch of the home day when both sides are within working hours
+// and neither is in do-not-disturb, as home-local start and end. The day is taken
+// as a circle, so a stretch across midnight comes back whole with `end` before
+// `start`, like 22:00–02:00, and a day good throughout as 00:00–00:00; with
+// no good stretch at all it's None. Offsets are minutes east of UTC.
+pub fn call_window(
+    home_offset: i32,
+    home: (&WorkingHours, &DoNotDisturb),
+    remote_offset: i32,
+    remote: (&WorkingHours, &DoNotDisturb),
+) -> Option<(NaiveTime, NaiveTime)> {
+    let shift = Duration::minutes((remote_offset - home_offset) as i64);
+    let slots = 24 * 60 / SLOT_MINUTES;
+    let slot_time = |slot: i64| NaiveTime::MIN + Duration::minutes(slot % slots * SLOT_MINUTES);
+    let good: Vec<bool> = (0..slots)
+        .map(|slot| {
+            let time = slot_time(slot);
+            let remote_time = time + shift;
+            home.0.contains(time)
+                && !home.1.contains(time)
+                && remote.0.contains(remote_time)
+                && !remote.1.contains(remote_time)
+        })
+        .collect();
+    // Starting the scan just after a bad slot means no run is cut at the seam
+    let Some(first_bad) = good.iter().position(|good| !good) else {
+        return Some((NaiveTime::MIN, NaiveTime::MIN));
+    };
+    let mut best: Option<(i64, i64)> = None; // start slot, length
+    let mut run_start = None;
+    for step in 1..=slots {
+        let slot = first_bad as i64 + step;
+        match (good[(slot % slots) as usize], run_start) {
+            (true, None) => run_start = Some(slot),
+            (false, Some(start)) => {
+                if best.is_none_or(|(_, length)| slot - start > length) {
+                    best = Some((start, slot - start));
+                }
+                run_start = None;
+            }
+            _ => {}
+        }
+    }
+    best.map(|(start, length)| (slot_time(start), slot_time(start + length)))
+}
+
+// One side of the overlap score: where a city is and when it works
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WorkWeek {
+    pub tz: Tz,
+    pub hours: WorkingHours,
+    pub weekend: Weekend,
+}
+
+impl WorkWeek {
+    fn working_at(&self, instant: DateTime<Utc>) -> bool {
+        week::working_at(
+            instant.with_timezone(&self.tz).naive_local(),
+            &self.hours,
+            self.weekend,
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OverlapScore {
+    pub score: u8, // 0–100
+    pub shared_minutes: i64,
+    pub home_minutes: i64,
+}
+
+// How much of home's working time over the 7 days from `from` the other city is
+// working too, scored 0–100. Each slot is read in each city's own zone, so a DST
+// change during the week counts from when it happens. None when home has no
+// working time that week.
+pub fn overlap_score(
+    home: &WorkWeek,
+    other: &WorkWeek,
+    from: DateTime<Utc>,
+) -> Option<OverlapScore> {
+    let slots = 7 * 24 * 60 / SLOT_MINUTES;
+    let (home_slots, shared_slots) = (0..slots)
+        .map(|slot| from + Duration::minutes(slot * SLOT_MINUTES))
+        .filter(|instant| home.working_at(*instant))
+        .fold((0, 0), |(home_slots, shared_slots), instant| {
+            (
+                home_slots + 1,
+                shared_slots + other.working_at(instant) as i64,
+            )
+        });
+    (home_slots > 0).then(|| OverlapScore {
+        score: ((shared_slots * 100 + home_slots / 2) / home_slots) as u8,
+        shared_minutes: shared_slots * SLOT_MINUTES,
+        home_minutes: home_slots * SLOT_MINUTES,
+    })
+}
+
+// "Overlap 88/100: for 35h of your 40h working week, NYC is working too (next 7 days)"
+pub fn describe_overlap(overlap: &OverlapScore, city: &str) -> String {
+    let hours = |minutes: i64| match minutes % 60 {
+        0 => format!("{}h", minutes / 60),
+        rest => format!("{}h {}m", minutes / 60, rest),
+    };
+    format!(
+        "Overlap {}/100: for {} of your {} working week, {} is working too (next 7 days)",
+        overlap.score,
+        hours(overlap.shared_minutes),
+        hours(overlap.home_minutes),
+        city
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Weekday::*};
+    use chrono_tz::{America, Asia, Europe};
+
+    fn hm(hour: u32, minute: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(hour, minute, 0).unwrap()
+    }
+
+    fn hours(start: u32, end: u32) -> WorkingHours {
+        WorkingHours {
+            start: hm(start, 0),
+            end: hm(end, 0),
+        }
+    }
+
+    // Start and end the same: never in effect
+    const NO_DND: DoNotDisturb = DoNotDisturb {
+        start: NaiveTime::MIN,
+        end: NaiveTime::MIN,
+    };
+
+    #[test]
+    fn a_wrapped_range_runs_through_midnight() {
+        let (start, end) = (hm(22, 0), hm(6, 0));
+        for time in [hm(22, 0), hm(23, 59), hm(0, 0), hm(5, 59)] {
+            assert!(range_contains(start, end, time), "{}", time);
+        }
+        for time in [hm(6, 0), hm(12, 0), hm(21, 59)] {
+            assert!(!range_contains(start, end, time), "{}", time);
+        }
+        assert!(range_contains(hm(9, 0), hm(17, 0), hm(9, 0)));
+        assert!(!range_contains(hm(9, 0), hm(17, 0), hm(17, 0)));
+        assert!(!range_contains(hm(9, 0), hm(9, 0), hm(9, 0)));
+    }
+
+    #[test]
+    fn one_wrapped_range_comes_back_whole() {
+        // Home works nights; the other city, on the same offset, around the clock
+        let around_the_clock = WorkingHours {
+            start: NaiveTime::MIN,
+            end: hm(23, 59),
+        };
+        let window = call_window(0, (&hours(22, 6), &NO_DND), 0, (&around_the_clock, &NO_DND));
+        assert_eq!(window, Some((hm(22, 0), hm(6, 0))));
+    }
+
+    #[test]
+    fn two_wrapped_ranges_overlap_across_midnight() {
+        // 20:00–04:00 an hour east is 19:00–03:00 for home
+        let window = call_window(0, (&hours(22, 6), &NO_DND), 60, (&hours(20, 4), &NO_DND));
+        assert_eq!(window, Some((hm(22, 0), hm(3, 0))));
+    }
+
+    #[test]
+    fn a_wrapped_range_meets_an_unwrapped_one() {
+        // 09:00–17:00 ten hours east is 23:00–07:00 for home
+        let window = call_window(0, (&hours(22, 6), &NO_DND), 600, (&hours(9, 17), &NO_DND));
+        assert_eq!(window, Some((hm(23, 0), hm(6, 0))));
+        // Do-not-disturb over the whole night shift leaves nothing
+        let dnd = DoNotDisturb::default();
+        let window = call_window(0, (&hours(22, 6), &dnd), 600, (&hours(9, 17), &NO_DND));
+        assert_eq!(window, None);
+    }
+
+    #[test]
+    fn a_day_good_throughout_is_midnight_to_midnight() {
+        let around_the_clock = WorkingHours {
+            start: NaiveTime::MIN,
+            end: hm(23, 59),
+        };
+        let both = (&around_the_clock, &NO_DND);
+        assert_eq!(
+            call_window(0, both, 330, both),
+            Some((NaiveTime::MIN, NaiveTime::MIN))
+        );
+    }
+
+    fn work_week(tz: Tz) -> WorkWeek {
+        WorkWeek {
+            tz,
+            hours: hours(9, 17),
+            weekend: Weekend::default(),
+        }
+    }
+
+    #[test]
+    fn austin_and_new_york_overlap_most_of_the_day() {
+        let monday = Utc.with_ymd_and_hms(2026, 6, 1, 0, 0, 0).unwrap();
+        let score = overlap_score(
+            &work_week(America::Chicago),
+            &work_week(America::New_York),
+            monday,
+        );
+        // New York has gone home for Austin's last hour
+        assert_eq!(
+            score,
+            Some(OverlapScore {
+                score: 88,
+                shared_minutes: 5 * 7 * 60,
+                home_minutes: 5 * 8 * 60,
+            })
+        );
+    }
+
+    #[test]
+    fn austin_and_tokyo_share_no_working_time() {
+        let monday = Utc.with_ymd_and_hms(2026, 6, 1, 0, 0, 0).unwrap();
+        let score = overlap_score(
+            &work_week(America::Chicago),
+            &work_week(Asia::Tokyo),
+            monday,
+        )
+        .unwrap();
+        assert_eq!(score.score, 0);
+        assert_eq!(score.shared_minutes, 0);
+
+        let no_days = WorkWeek {
+            weekend: Weekend::from([Mon, Tue, Wed, Thu, Fri, Sat, Sun].to_vec()),
+            ..work_week(America::Chicago)
+        };
+        assert_eq!(
+            overlap_score(&no_days, &work_week(Asia::Tokyo), monday),
+            None
+        );
+    }
+
+    fn utc(hour: u32, minute: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 6, 1, hour, minute, 0).unwrap()
+    }
+
+    fn nine_to_five(tz: Tz) -> (Tz, WorkingHours, DoNotDisturb, Weekend) {
+        (tz, hours(9, 17), NO_DND, Weekend::default())
+    }
+
+    fn spans(overlap: &Overlap) -> Vec<(DateTime<Utc>, DateTime<Utc>, Vec<bool>)> {
+        overlap
+            .windows
+            .iter()
+            .map(|window| (window.start, window.end, window.working.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn everyone_working_makes_a_complete_overlap() {
+        // Monday in June: London works 08–16 UTC, Berlin 07–15 and New York 13–21
+        let cities = [
+            nine_to_five(Europe::London),
+            nine_to_five(Europe::Berlin),
+            nine_to_five(America::New_York),
+        ];
+        let overlap = find_overlaps(&cities, utc(0, 0), 1, 3);
+        assert!(overlap.complete);
+        assert_eq!(
+            spans(&overlap),
+            [(utc(13, 0), utc(15, 0), vec![true, true, true])]
+        );
+
+        // A start inside a slot rounds up to the next one
+        let overlap = find_overlaps(&cities, utc(13, 7), 1, 3);
+        assert_eq!(overlap.windows[0].start, utc(13, 15));
+    }
+
+    #[test]
+    fn without_a_full_overlap_the_best_two_of_three_come_back() {
+        // Kolkata works 03:30–11:30 UTC, so it meets London but never New York
+        let cities = [
+            nine_to_five(Europe::London),
+            nine_to_five(America::New_York),
+            nine_to_five(Asia::Kolkata),
+        ];
+        let overlap = find_overlaps(&cities, utc(0, 0), 1, 3);
+        assert!(!overlap.complete);
+        assert_eq!(
+            spans(&overlap),
+            [
+                (utc(8, 0), utc(11, 30), vec![true, false, true]),
+                (utc(13, 0), utc(16, 0), vec![true, true, false]),
+            ]
+        );
+    }
+
+    #[test]
+    fn do_not_disturb_splits_partial_windows_too() {
+        // London's 10–11 local is 09–10 UTC
+        let mut london = nine_to_five(Europe::London);
+        london.2 = DoNotDisturb {
+            start: hm(10, 0),
+            end: hm(11, 0),
+        };
+        let cities = [
+            london,
+            nine_to_five(America::New_York),
+            nine_to_five(Asia::Kolkata),
+        ];
+        let overlap = find_overlaps(&cities, utc(0, 0), 1, 2);
+        assert!(!overlap.complete);
+        assert_eq!(
+            spans(&overlap),
+            [
+                (utc(13, 0), utc(16, 0), vec![true, true, false]),
+                (utc(10, 0), utc(11, 30), vec![true, false, true]),
+            ]
+        );
+    }
+
+    #[test]
+    fn nobody_working_suggests_nothing() {
+        // Saturday and Sunday
+        let saturday = Utc.with_ymd_and_hms(2026, 6, 6, 0, 0, 0).unwrap();
+        let cities = [
+            nine_to_five(Europe::London),
+            nine_to_five(America::New_York),
+        ];
+        assert_eq!(
+            find_overlaps(&cities, saturday, 2, 3),
+            Overlap {
+                windows: Vec::new(),
+                complete: false,
+            }
+        );
+        assert!(find_overlaps(&[], saturday, 2, 3).complete);
+    }
+}
@@ -0,0 +1,281 @@
+use crate::{WorldTimeApp, config::CityConfig, window_state::WindowState};
+use gpui::{App, Context, Entity, SharedString, Window, div, prelude::*, rgb};
+use gpui_component::{
+    WindowExt as _,
+    button::ButtonVariant,
+    dialog::DialogButtonProps,
+    input::{Input, InputState},
+    v_flex,
+};
+use serde::{Deserialize, Serialize};
+
+pub const DEFAULT_NAME: &str = "Default";
+
+// A named set of cities to switch to. The active profile's cities are the ones in
+// Config::cities; only the others are kept here, each its own copy.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Profile {
+    pub name: String,
+    pub cities: Vec<CityConfig>,
+    // How the window was left while this profile was active; None until it has been
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub layout: Option<Layout>,
+}
+
+// The window's placement and view, remembered per profile
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Layout {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub window: Option<WindowState>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub table_view: bool,
+    // The card that last had keyboard focus
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub focused_card: Option<String>,
+}
+
+// The remembered card if it's still there; one removed since is forgotten
+pub fn restore_focus(remembered: Option<String>, cards: &[&str]) -> Option<String> {
+    remembered.filter(|name| cards.contains(&name.as_str()))
+}
+
+// A trimmed name that neither the active profile nor a saved one uses, `except`
+// aside so a rename may keep its own name
+pub fn validate_name(
+    name: &str,
+    active: &str,
+    saved: &[Profile],
+    except: Option<&str>,
+) -> Result<String, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Give the profile a name".to_string());
+    }
+    let taken = std::iter::once(active)
+        .chain(saved.iter().map(|profile| profile.name.as_str()))
+        .filter(|existing| Some(*existing) != except)
+        .any(|existing| existing.eq_ignore_ascii_case(name));
+    if taken {
+        return Err(format!("There's already a profile called \"{}\"", name));
+    }
+    Ok(name.to_string())
+}
+
+// Stores the current cities and layout under the active name and makes `target`
+// active, returning what it had saved; None if there's no saved profile by that name
+pub fn switch(
+    saved: &mut Vec<Profile>,
+    active: &mut String,
+    current: Vec<CityConfig>,
+    layout: Layout,
+    target: &str,
+) -> Option<Profile> {
+    let index = saved.iter().position(|profile| profile.name == target)?;
+    let target = saved.remove(index);
+    saved.push(Profile {
+        name: std::mem::replace(active, target.name.clone()),
+        cities: current,
+        layout: Some(layout),
+    });
+    Some(target)
+}
+
+// Saves a copy of `cities` as a new profile; it has no layout until it has been
+// switched away from
+pub fn save_copy(saved: &mut Vec<Profile>, name: String, cities: &[CityConfig]) {
+    saved.push(Profile {
+        name,
+        cities: cities.to_vec(),
+        layout: None,
+    });
+}
+
+// Asks for a profile name, for saving a copy or renaming; `apply` gets a name
+// that has passed `validate` and closes the dialog
+pub fn open_name_dialog(
+    title: &str,
+    ok_text: &str,
+    initial: &str,
+    validate: impl Fn(&str, &App) -> Result<String, String> + 'static,
+    apply: impl Fn(String, &mut Window, &mut App) + 'static,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    let initial = initial.to_string();
+    let form = cx.new(|cx| ProfileNameForm::new(&initial, window, cx));
+    let (title, ok_text) = (title.to_string(), ok_text.to_string());
+    let validate = std::rc::Rc::new(validate);
+    let apply = std::rc::Rc::new(apply);
+    window.open_dialog(cx, move |dialog, _window, _cx| {
+        dialog
+            .title(title.clone())
+            .child(form.clone())
+            .confirm()
+            .button_props(DialogButtonProps::default().ok_text(ok_text.clone()))
+            .on_ok({
+                let form = form.clone();
+                let validate = validate.clone();
+                let apply = apply.clone();
+                move |_, window, cx| {
+                    let name = form.read(cx).input.read(cx).value().to_string();
+                    match validate(&name, cx) {
+                        Ok(name) => {
+                            apply(name, window, cx);
+                            true
+                        }
+                        Err(err) => {
+                            form.update(cx, |form, cx| {
+                                form.error = Some(err.into());
+                                cx.notify();
+                            });
+                            false
+                        }
+                    }
+                }
+            })
+    });
+}
+
+pub struct ProfileNameForm {
+    input: Entity<InputState>,
+    error: Option<SharedString>,
+}
+
+impl ProfileNameForm {
+    fn new(initial: &str, window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let initial = initial.to_string();
+        let input = cx.new(|cx| {
+            InputState::new(window, cx)
+                .placeholder("Profile name")
+                .default_value(initial)
+        });
+        input.update(cx, |input, cx| input.focus(window, cx));
+        ProfileNameForm { input, error: None }
+    }
+}
+
+impl Render for ProfileNameForm {
+    fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex().gap_2().child(Input::new(&self.input)).children(
+            self.error
+                .clone()
+                .map(|error| div().child(error).text_sm().text_color(rgb(0xef4444))),
+        )
+    }
+}
+
+pub fn confirm_delete_profile(
+    app: Entity<WorldTimeApp>,
+    name: String,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    window.open_dialog(cx, move |dialog, _window, _cx| {
+        dialog
+            .title(format!("Delete the \"{}\" profile?", name))
+            .child(crate::settings_panel::hint(
+                "Its cities go with it; other profiles aren't affected",
+            ))
+            .confirm()
+            .button_props(
+                DialogButtonProps::default()
+                    .ok_text("Delete")
+                    .ok_variant(ButtonVariant::Danger),
+            )
+            .on_ok({
+                let app = app.clone();
+                let name = name.clone();
+                move |_, window, cx| {
+                    app.update(cx, |app, cx| app.delete_profile(&name, window, cx));
+                    true
+                }
+            })
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn focus_returns_to_a_card_that_is_still_there() {
+        let cards = ["Austin", "Berlin", "Tokyo"];
+        assert_eq!(
+            restore_focus(Some("Berlin".to_string()), &cards),
+            Some("Berlin".to_string())
+        );
+        assert_eq!(restore_focus(None, &cards), None);
+    }
+
+    #[test]
+    fn a_missing_card_is_forgotten() {
+        // Removed while another profile was showing, or renamed
+        assert_eq!(
+            restore_focus(Some("Lagos".to_string()), &["Austin", "Berlin"]),
+            None
+        );
+        assert_eq!(restore_focus(Some("berlin".to_string()), &["Berlin"]), None);
+        assert_eq!(restore_focus(Some("Berlin".to_string()), &[]), None);
+    }
+
+    fn austin_and_tokyo() -> Vec<CityConfig> {
+        let mut austin = CityConfig::new("Austin", "America/Chicago", true);
+        austin.people = vec!["Ana".to_string()];
+        vec![austin, CityConfig::new("Tokyo", "Asia/Tokyo", false)]
+    }
+
+    #[test]
+    fn profiles_survive_a_round_trip_through_the_config() {
+        let profile = Profile {
+            name: "Travel".to_string(),
+            cities: austin_and_tokyo(),
+            layout: Some(Layout {
+                window: Some(WindowState {
+                    x: 10.0,
+                    y: 20.0,
+                    width: 800.0,
+                    height: 600.0,
+                    display: Some("a-display".to_string()),
+                    maximized: false,
+                }),
+                table_view: true,
+                focused_card: Some("Tokyo".to_string()),
+            }),
+        };
+        let json = serde_json::to_string(&profile).unwrap();
+        assert_eq!(serde_json::from_str::<Profile>(&json).unwrap(), profile);
+
+        // One saved before layouts were kept
+        let json = r#"{"name":"Old","cities":[]}"#;
+        let old: Profile = serde_json::from_str(json).unwrap();
+        assert_eq!(old.layout, None);
+        assert_eq!(serde_json::to_string(&old).unwrap(), json);
+    }
+
+    #[test]
+    fn a_saved_copy_keeps_its_own_cities() {
+        let mut cities = austin_and_tokyo();
+        let mut saved = Vec::new();
+        save_copy(&mut saved, "Copy".to_string(), &cities);
+
+        cities[0].people.push("Tom".to_string());
+        cities[1].name = "Osaka".to_string();
+        cities.pop();
+        assert_eq!(saved[0].cities, austin_and_tokyo());
+
+        // And switching to it hands the copy back, not the cities it came from
+        let mut active = DEFAULT_NAME.to_string();
+        let copy = switch(
+            &mut saved,
+            &mut active,
+            cities.clone(),
+            Layout::default(),
+            "Copy",
+        )
+        .unwrap();
+        assert_eq!(copy.cities, austin_and_tokyo());
+        assert_eq!(active, "Copy");
+        assert_eq!(saved[0].name, DEFAULT_NAME);
+        assert_eq!(saved[0].cities, cities);
+    }
+}
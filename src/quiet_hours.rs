@@ -0,0 +1,126 @@
+use crate::planner;
+use chrono::{DateTime, NaiveTime, Utc};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+
+// A nightly stretch, in home time, when nothing rings or pops up
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct QuietHours {
+    pub enabled: bool,
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+}
+
+impl Default for QuietHours {
+    fn default() -> Self {
+        QuietHours {
+            enabled: false,
+            start: NaiveTime::from_hms_opt(22, 0, 0).unwrap(),
+            end: NaiveTime::from_hms_opt(7, 0, 0).unwrap(),
+        }
+    }
+}
+
+// The one check every alarm sound and desktop notification goes through first.
+// Home time decides, not the alarm's city, so a Berlin alarm can't wake Sydney.
+pub fn is_quiet(quiet: &QuietHours, home_tz: Tz, now: DateTime<Utc>) -> bool {
+    quiet.enabled
+        && planner::range_contains(quiet.start, quiet.end, now.with_timezone(&home_tz).time())
+}
+
+// What an alarm does when it comes due during quiet hours
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WhenQuiet {
+    #[default]
+    Hold, // rings once quiet hours are over
+    Drop,
+}
+
+// An alarm that came due during quiet hours, kept for the morning badge
+#[derive(Debug, Clone)]
+pub struct Suppressed {
+    pub summary: String,
+    pub body: String,
+    pub when_quiet: WhenQuiet,
+    pub delivered: bool, // a held one, shown since quiet hours ended
+}
+
+// Badge text and the tooltip listing each one
+pub fn describe_suppressed(suppressed: &[Suppressed]) -> (String, String) {
+    let label = match suppressed.len() {
+        1 => "🔕 1 alarm during quiet hours".to_string(),
+        count => format!("🔕 {} alarms during quiet hours", count),
+    };
+    let details = suppressed
+        .iter()
+        .map(|alarm| match alarm.when_quiet {
+            WhenQuiet::Hold => alarm.summary.clone(),
+            WhenQuiet::Drop => format!("{} (dropped)", alarm.summary),
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    (label, details)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use chrono_tz::{Australia, Europe};
+
+    fn utc(day: u32, hour: u32, minute: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 7, day, hour, minute, 0).unwrap()
+    }
+
+    fn enabled() -> QuietHours {
+        QuietHours {
+            enabled: true,
+            ..QuietHours::default()
+        }
+    }
+
+    #[test]
+    fn quiet_hours_wrap_past_midnight() {
+        // 22:00–07:00 in UTC itself
+        let quiet = enabled();
+        for now in [utc(1, 22, 0), utc(1, 23, 59), utc(2, 0, 0), utc(2, 6, 59)] {
+            assert!(is_quiet(&quiet, chrono_tz::UTC, now), "{}", now);
+        }
+        // The end minute is already loud again, as is the minute before the start
+        for now in [utc(2, 7, 0), utc(1, 21, 59), utc(1, 12, 0)] {
+            assert!(!is_quiet(&quiet, chrono_tz::UTC, now), "{}", now);
+        }
+        assert!(!is_quiet(
+            &QuietHours::default(),
+            chrono_tz::UTC,
+            utc(1, 23, 0)
+        ));
+    }
+
+    #[test]
+    fn a_window_inside_one_day() {
+        let quiet = QuietHours {
+            enabled: true,
+            start: NaiveTime::from_hms_opt(13, 0, 0).unwrap(),
+            end: NaiveTime::from_hms_opt(14, 30, 0).unwrap(),
+        };
+        assert!(is_quiet(&quiet, chrono_tz::UTC, utc(1, 13, 0)));
+        assert!(is_quiet(&quiet, chrono_tz::UTC, utc(1, 14, 29)));
+        assert!(!is_quiet(&quiet, chrono_tz::UTC, utc(1, 14, 30)));
+        assert!(!is_quiet(&quiet, chrono_tz::UTC, utc(1, 23, 0)));
+    }
+
+    #[test]
+    fn home_time_decides() {
+        let quiet = enabled();
+        // 21:00 UTC is 23:00 in Berlin and 07:00 the next morning in Sydney
+        let now = utc(1, 21, 0);
+        assert!(is_quiet(&quiet, Europe::Berlin, now));
+        assert!(!is_quiet(&quiet, Australia::Sydney, now));
+        // 20:59 UTC is still 06:59 in Sydney
+        assert!(is_quiet(&quiet, Australia::Sydney, utc(1, 20, 59)));
+        assert!(!is_quiet(&quiet, chrono_tz::UTC, now));
+    }
+}
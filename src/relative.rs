@@ -0,0 +1,187 @@
+use chrono::{DateTime, Timelike, Utc};
+use chrono_tz::Tz;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DayBucket {
+    Night,        // 00–05
+    EarlyMorning, // 05–08
+    Morning,      // 08–12
+    Afternoon,    // 12–17
+    Evening,      // 17–21
+    LateNight,    // 21–24
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RelativeDay {
+    Yesterday,
+    Today,
+    Tomorrow,
+    Days(i64), // two or more either way, as between UTC+14 and UTC−12
+}
+
+impl RelativeDay {
+    fn from_days(days: i64) -> Self {
+        match days {
+            -1 => RelativeDay::Yesterday,
+            0 => RelativeDay::Today,
+            1 => RelativeDay::Tomorrow,
+            days => RelativeDay::Days(days),
+        }
+    }
+
+    pub fn days(self) -> i64 {
+        match self {
+            RelativeDay::Yesterday => -1,
+            RelativeDay::Today => 0,
+            RelativeDay::Tomorrow => 1,
+            RelativeDay::Days(days) => days,
+        }
+    }
+}
+
+pub fn day_bucket(hour: u32) -> DayBucket {
+    match hour {
+        0..5 => DayBucket::Night,
+        5..8 => DayBucket::EarlyMorning,
+        8..12 => DayBucket::Morning,
+        12..17 => DayBucket::Afternoon,
+        17..21 => DayBucket::Evening,
+        _ => DayBucket::LateNight,
+    }
+}
+
+// Which calendar day a city is on at `instant`, counted from the home zone's day.
+// Every feature that says "tomorrow" or "+1 day" goes through this.
+pub fn relative_day(home_tz: Tz, city_tz: Tz, instant: DateTime<Utc>) -> RelativeDay {
+    let home = instant.with_timezone(&home_tz).date_naive();
+    let local = instant.with_timezone(&city_tz).date_naive();
+    RelativeDay::from_days((local - home).num_days())
+}
+
+pub fn relative_phrase(
+    home_tz: Tz,
+    city_tz: Tz,
+    instant: DateTime<Utc>,
+) -> (DayBucket, RelativeDay) {
+    let hour = instant.with_timezone(&city_tz).hour();
+    (day_bucket(hour), relative_day(home_tz, city_tz, instant))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use chrono_tz::{America, Asia, Etc, Pacific};
+    use time2rust::format;
+
+    fn utc(day: u32, hour: u32, minute: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 7, day, hour, minute, 0).unwrap()
+    }
+
+    #[test]
+    fn the_day_flips_at_the_city_midnight() {
+        // Tokyo's midnight is 15:00 UTC, 11:00 in New York
+        let (home, city) = (America::New_York, Asia::Tokyo);
+        assert_eq!(relative_day(home, city, utc(1, 14, 59)), RelativeDay::Today);
+        assert_eq!(
+            relative_day(home, city, utc(1, 15, 0)),
+            RelativeDay::Tomorrow
+        );
+        assert_eq!(
+            relative_day(city, home, utc(1, 15, 0)),
+            RelativeDay::Yesterday
+        );
+    }
+
+    #[test]
+    fn the_day_flips_back_at_the_home_midnight() {
+        // New York's midnight is 04:00 UTC, 13:00 in Tokyo
+        let (home, city) = (America::New_York, Asia::Tokyo);
+        assert_eq!(
+            relative_day(home, city, utc(2, 3, 59)),
+            RelativeDay::Tomorrow
+        );
+        assert_eq!(relative_day(home, city, utc(2, 4, 0)), RelativeDay::Today);
+        assert_eq!(
+            relative_day(city, home, utc(2, 3, 59)),
+            RelativeDay::Yesterday
+        );
+        assert_eq!(relative_day(city, home, utc(2, 4, 0)), RelativeDay::Today);
+    }
+
+    #[test]
+    fn utc_plus_14_and_minus_12_are_two_days_apart_for_two_hours() {
+        // Kiritimati reaches Jul 2 at 10:00 UTC, when UTC−12 is still at 22:00
+        // on Jun 30; UTC−12 reaches Jul 1 at 12:00 UTC
+        let (west, east) = (Etc::GMTPlus12, Pacific::Kiritimati);
+        assert_eq!(
+            relative_day(west, east, utc(1, 9, 59)),
+            RelativeDay::Tomorrow
+        );
+        assert_eq!(
+            relative_day(west, east, utc(1, 10, 0)),
+            RelativeDay::Days(2)
+        );
+        assert_eq!(
+            relative_day(west, east, utc(1, 11, 59)),
+            RelativeDay::Days(2)
+        );
+        assert_eq!(
+            relative_day(west, east, utc(1, 12, 0)),
+            RelativeDay::Tomorrow
+        );
+        assert_eq!(
+            relative_day(east, west, utc(1, 10, 0)),
+            RelativeDay::Days(-2)
+        );
+        assert_eq!(RelativeDay::Days(-2).days(), -2);
+    }
+
+    #[test]
+    fn the_phrase_uses_the_city_hour() {
+        let phrase = relative_phrase(America::New_York, Asia::Tokyo, utc(1, 15, 0));
+        assert_eq!(phrase, (DayBucket::Night, RelativeDay::Tomorrow));
+        let phrase = relative_phrase(America::New_York, Asia::Tokyo, utc(1, 14, 0));
+        assert_eq!(phrase, (DayBucket::LateNight, RelativeDay::Today));
+    }
+
+    #[test]
+    fn pago_pago_and_kiritimati_over_a_year() {
+        // 25 hours apart all year, neither observing DST: Kiritimati is a day
+        // ahead, and two for the hour Pago Pago spends at 23:00
+        let (west, east) = (Pacific::Pago_Pago, Pacific::Kiritimati);
+        let start = Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap();
+        for hour in 0..365 * 24 {
+            let instant = start + chrono::Duration::hours(hour);
+            let diff = crate::zone::utc_offset_minutes(east, instant)
+                - crate::zone::utc_offset_minutes(west, instant);
+            assert_eq!(diff, 1500, "{}", instant);
+            assert_eq!(format::ahead_behind(diff), "1d 1h ahead");
+            assert_eq!(format::ahead_behind(-diff), "1d 1h behind");
+
+            let days = if instant.with_timezone(&west).hour() == 23 {
+                2
+            } else {
+                1
+            };
+            assert_eq!(
+                relative_day(west, east, instant).days(),
+                days,
+                "{}",
+                instant
+            );
+            assert_eq!(
+                relative_day(east, west, instant).days(),
+                -days,
+                "{}",
+                instant
+            );
+
+            // The mirror of Kiritimati's current time lands on the same day count
+            let their = instant.with_timezone(&east).time();
+            let (yours, offset) = crate::convert::mirror_time(instant, east, their, west);
+            assert_eq!(yours, instant.with_timezone(&west).time());
+            assert_eq!(offset, -days, "{}", instant);
+        }
+    }
+}
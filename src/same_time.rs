@@ -0,0 +1,25 @@
+use std::collections::HashMap;
+
+// For each card given as (name, HH:MM shown, zone id), the first other card showing
+// the same HH:MM from a different zone. One pass with a map, so long lists stay cheap.
+pub fn same_time_partners(cards: &[(&str, &str, &str)]) -> Vec<Option<String>> {
+    let mut by_time: HashMap<&str, Vec<usize>> = HashMap::new();
+    for (index, (_, time, _)) in cards.iter().enumerate() {
+        // Seconds would keep matching cards apart only by when they were formatted
+        by_time.entry(hours_minutes(time)).or_default().push(index);
+    }
+    cards
+        .iter()
+        .enumerate()
+        .map(|(index, (_, time, zone))| {
+            by_time[hours_minutes(time)]
+                .iter()
+                .find(|&&other| other != index && cards[other].2 != *zone)
+                .map(|&other| cards[other].0.to_string())
+        })
+        .collect()
+}
+
+fn hours_minutes(time: &str) -> &str {
+    time.get(..5).unwrap_or(time)
+}
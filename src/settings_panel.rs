@@ -0,0 +1,1565 @@
+use crate::{
+    WorldTimeApp, cleanup,
+    clock_format::ClockFormat,
+    config::{ClockSyncSettings, HeaderSettings, SeriesSettings, Settings},
+    config_backups, confirm, convert, diagnostics,
+    diff_style::DiffStyle,
+    i18n::Language,
+    pinned_events, profiles,
+    ticker::UpdateInterval,
+    week::WeekConfig,
+    window_state::Opacity,
+    zoom,
+};
+use chrono::Utc;
+use gpui::{App, Context, Entity, SharedString, Window, div, prelude::*, rgb};
+use gpui_component::{
+    Sizable, WindowExt as _,
+    button::{Button, ButtonVariants as _},
+    checkbox::Checkbox,
+    h_flex,
+    input::{Input, InputEvent, InputState},
+    slider::{Slider, SliderEvent, SliderState},
+    v_flex,
+};
+use time2rust::format;
+
+// Settings live in a side sheet so the clocks stay visible while editing
+pub fn open_settings(app: Entity<WorldTimeApp>, window: &mut Window, cx: &mut App) {
+    let panel = cx.new(|cx| SettingsPanel::new(app, window, cx));
+    window.open_sheet(cx, move |sheet, _window, _cx| {
+        sheet.title("Settings").child(panel.clone())
+    });
+}
+
+pub struct SettingsPanel {
+    app: Entity<WorldTimeApp>,
+    shortcut_input: Entity<InputState>,
+    snippet_shortcut_input: Entity<InputState>,
+    snippet_template_input: Entity<InputState>,
+    snippet_error: Option<SharedString>,
+    separator_input: Entity<InputState>,
+    header_title_input: Entity<InputState>,
+    work_start_input: Entity<InputState>,
+    work_end_input: Entity<InputState>,
+    working_hours_error: Option<SharedString>,
+    dnd_start_input: Entity<InputState>,
+    dnd_end_input: Entity<InputState>,
+    dnd_error: Option<SharedString>,
+    quiet_start_input: Entity<InputState>,
+    quiet_end_input: Entity<InputState>,
+    quiet_error: Option<SharedString>,
+    event_title_input: Entity<InputState>,
+    event_when_input: Entity<InputState>,
+    event_error: Option<SharedString>,
+    opacity_slider: Entity<SliderState>,
+    dwell_input: Entity<InputState>,
+    dwell_error: Option<SharedString>,
+    interval_input: Entity<InputState>,
+    interval_error: Option<SharedString>,
+    horizon_input: Entity<InputState>,
+    horizon_error: Option<SharedString>,
+    mirror_input: Entity<InputState>,
+    mirror_error: Option<SharedString>,
+    http_port_input: Entity<InputState>,
+    http_port_error: Option<SharedString>,
+    ntp_server_input: Entity<InputState>,
+}
+
+impl SettingsPanel {
+    fn new(app: Entity<WorldTimeApp>, window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let settings = cx.global::<Settings>().clone();
+
+        let shortcut_input = cx.new(|cx| {
+            InputState::new(window, cx)
+                .placeholder("ctrl+alt+t")
+                .default_value(settings.hotkey.shortcut)
+        });
+        cx.subscribe_in(&shortcut_input, window, |this, input, event, window, cx| {
+            if let InputEvent::PressEnter { .. } = event {
+                let shortcut = input.read(cx).value().trim().to_string();
+                this.update_settings(window, cx, |settings| settings.hotkey.shortcut = shortcut);
+            }
+        })
+        .detach();
+
+        let snippet_shortcut_input = cx.new(|cx| {
+            InputState::new(window, cx)
+                .placeholder("ctrl+alt+i")
+                .default_value(settings.snippet.shortcut.clone())
+        });
+        cx.subscribe_in(
+            &snippet_shortcut_input,
+            window,
+            |this, input, event, window, cx| {
+                if let InputEvent::PressEnter { .. } = event {
+                    let shortcut = input.read(cx).value().trim().to_string();
+                    this.update_settings(window, cx, |settings| {
+                        settings.snippet.shortcut = shortcut
+                    });
+                }
+            },
+        )
+        .detach();
+
+        let snippet_template_input = cx.new(|cx| {
+            InputState::new(window, cx)
+                .placeholder("{Chicago} / {Berlin} / {Helsinki}")
+                .default_value(settings.snippet.template.clone())
+        });
+        cx.subscribe_in(
+            &snippet_template_input,
+            window,
+            |this, input, event, window, cx| {
+                if let InputEvent::PressEnter { .. } = event {
+                    let template = input.read(cx).value().trim().to_string();
+                    match this.snippet_preview(&template, cx) {
+                        Ok(_) => {
+                            this.snippet_error = None;
+                            this.update_settings(window, cx, |settings| {
+                                settings.snippet.template = template
+                            });
+                        }
+                        Err(err) => {
+                            this.snippet_error = Some(err.into());
+                            cx.notify();
+                        }
+                    }
+                }
+            },
+        )
+        .detach();
+
+        let header_title_input = cx.new(|cx| {
+            InputState::new(window, cx)
+                .placeholder(HeaderSettings::DEFAULT_TITLE)
+                .default_value(settings.header.title.clone())
+        });
+        cx.subscribe_in(
+            &header_title_input,
+            window,
+            |this, input, event, window, cx| {
+                if let InputEvent::PressEnter { .. } = event {
+                    let title = input.read(cx).value().trim().to_string();
+                    this.update_settings(window, cx, |settings| settings.header.title = title);
+                }
+            },
+        )
+        .detach();
+
+        let separator_input = cx.new(|cx| {
+            InputState::new(window, cx)
+                .placeholder(" · ")
+                .default_value(settings.slack.separator)
+        });
+        cx.subscribe_in(
+            &separator_input,
+            window,
+            |this, input, event, window, cx| {
+                if let InputEvent::Change = event {
+                    let separator = input.read(cx).value().to_string();
+                    this.update_settings(window, cx, |settings| {
+                        settings.slack.separator = separator
+                    });
+                }
+            },
+        )
+        .detach();
+
+        let work_start_input = cx.new(|cx| {
+            InputState::new(window, cx)
+                .placeholder("09:00")
+                .default_value(format::clock_time(settings.working_hours.start, false))
+        });
+        let work_end_input = cx.new(|cx| {
+            InputState::new(window, cx)
+                .placeholder("17:00")
+                .default_value(format::clock_time(settings.working_hours.end, false))
+        });
+        for input in [&work_start_input, &work_end_input] {
+            cx.subscribe_in(input, window, |this, _, event, window, cx| {
+                if let InputEvent::PressEnter { .. } = event {
+                    this.apply_working_hours(window, cx);
+                }
+            })
+            .detach();
+        }
+
+        let dnd_start_input = cx.new(|cx| {
+            InputState::new(window, cx)
+                .placeholder("22:00")
+                .default_value(format::clock_time(settings.do_not_disturb.start, false))
+        });
+        let dnd_end_input = cx.new(|cx| {
+            InputState::new(window, cx)
+                .placeholder("07:00")
+                .default_value(format::clock_time(settings.do_not_disturb.end, false))
+        });
+        for input in [&dnd_start_input, &dnd_end_input] {
+            cx.subscribe_in(input, window, |this, _, event, window, cx| {
+                if let InputEvent::PressEnter { .. } = event {
+                    this.apply_do_not_disturb(window, cx);
+                }
+            })
+            .detach();
+        }
+
+        let quiet_start_input = cx.new(|cx| {
+            InputState::new(window, cx)
+                .placeholder("22:00")
+                .default_value(format::clock_time(settings.quiet_hours.start, false))
+        });
+        let quiet_end_input = cx.new(|cx| {
+            InputState::new(window, cx)
+                .placeholder("07:00")
+                .default_value(format::clock_time(settings.quiet_hours.end, false))
+        });
+        for input in [&quiet_start_input, &quiet_end_input] {
+            cx.subscribe_in(input, window, |this, _, event, window, cx| {
+                if let InputEvent::PressEnter { .. } = event {
+                    this.apply_quiet_hours(window, cx);
+                }
+            })
+            .detach();
+        }
+
+        let event_title_input =
+            cx.new(|cx| InputState::new(window, cx).placeholder("Title, e.g. All-hands"));
+        let event_when_input =
+            cx.new(|cx| InputState::new(window, cx).placeholder("thu 16:00 UTC"));
+        cx.subscribe_in(&event_when_input, window, |this, _, event, window, cx| {
+            if let InputEvent::PressEnter { .. } = event {
+                this.add_pinned_event(window, cx);
+            }
+        })
+        .detach();
+
+        let opacity_slider = cx.new(|_| {
+            SliderState::new()
+                .min(Opacity::MIN as f32)
+                .max(Opacity::MAX as f32)
+                .step(5.0)
+                .default_value(settings.window_opacity.percent() as f32)
+        });
+        cx.subscribe_in(
+            &opacity_slider,
+            window,
+            |this, _, event: &SliderEvent, window, cx| {
+                let SliderEvent::Change(value) = event;
+                let opacity = Opacity::new(value.start().round() as u8);
+                this.update_settings(window, cx, |settings| settings.window_opacity = opacity);
+            },
+        )
+        .detach();
+
+        let dwell_input = cx.new(|cx| {
+            InputState::new(window, cx)
+                .placeholder("10")
+                .default_value(settings.kiosk.dwell_seconds.to_string())
+        });
+        cx.subscribe_in(&dwell_input, window, |this, input, event, window, cx| {
+            if let InputEvent::PressEnter { .. } = event {
+                let value = input.read(cx).value().trim().to_string();
+                match value.parse::<u64>() {
+                    Ok(seconds) if (1..=3600).contains(&seconds) => {
+                        this.dwell_error = None;
+                        this.update_settings(window, cx, |settings| {
+                            settings.kiosk.dwell_seconds = seconds
+                        });
+                    }
+                    _ => {
+                        this.dwell_error = Some("Use a number of seconds from 1 to 3600".into());
+                        cx.notify();
+                    }
+                }
+            }
+        })
+        .detach();
+
+        let interval_input = cx.new(|cx| {
+            InputState::new(window, cx)
+                .placeholder("60")
+                .default_value(settings.update_interval.seconds().to_string())
+        });
+        cx.subscribe_in(&interval_input, window, |this, input, event, window, cx| {
+            if let InputEvent::PressEnter { .. } = event {
+                let value = input.read(cx).value().trim().to_string();
+                let interval = value
+                    .parse::<u64>()
+                    .map_err(|_| "Use a whole number of seconds".to_string())
+                    .and_then(UpdateInterval::new);
+                match interval {
+                    Ok(interval) => {
+                        this.interval_error = None;
+                        this.update_settings(window, cx, |settings| {
+                            settings.update_interval = interval
+                        });
+                    }
+                    Err(err) => {
+                        this.interval_error = Some(err.into());
+                        cx.notify();
+                    }
+                }
+            }
+        })
+        .detach();
+
+        let horizon_input = cx.new(|cx| {
+            InputState::new(window, cx)
+                .placeholder("21")
+                .default_value(settings.clock_change.horizon_days.to_string())
+        });
+        cx.subscribe_in(&horizon_input, window, |this, input, event, window, cx| {
+            if let InputEvent::PressEnter { .. } = event {
+                let value = input.read(cx).value().trim().to_string();
+                match value.parse::<u32>() {
+                    Ok(days) if days <= 365 => {
+                        this.horizon_error = None;
+                        this.update_settings(window, cx, |settings| {
+                            settings.clock_change.horizon_days = days
+                        });
+                    }
+                    _ => {
+                        this.horizon_error = Some("Use a number of days from 0 to 365".into());
+                        cx.notify();
+                    }
+                }
+            }
+        })
+        .detach();
+
+        let mirror_input = cx.new(|cx| {
+            InputState::new(window, cx)
+                .placeholder("End of their working day")
+                .default_value(
+                    settings
+                        .mirror
+                        .their_time
+                        .map(|time| format::clock_time(time, false))
+                        .unwrap_or_default(),
+                )
+        });
+        cx.subscribe_in(&mirror_input, window, |this, input, event, window, cx| {
+            if let InputEvent::PressEnter { .. } = event {
+                let value = input.read(cx).value().trim().to_string();
+                let their_time = match value.as_str() {
+                    "" => Ok(None),
+                    value => convert::parse_time(value).map(Some).ok_or(()),
+                };
+                match their_time {
+                    Ok(their_time) => {
+                        this.mirror_error = None;
+                        this.update_settings(window, cx, |settings| {
+                            settings.mirror.their_time = their_time
+                        });
+                    }
+                    Err(()) => {
+                        this.mirror_error = Some("Use a time like 17:00, or leave it empty".into());
+                        cx.notify();
+                    }
+                }
+            }
+        })
+        .detach();
+
+        let http_port_input = cx.new(|cx| {
+            InputState::new(window, cx)
+                .placeholder("7312")
+                .default_value(settings.http.port.to_string())
+        });
+        cx.subscribe_in(
+            &http_port_input,
+            window,
+            |this, input, event, window, cx| {
+                if let InputEvent::PressEnter { .. } = event {
+                    match input.read(cx).value().trim().parse::<u16>() {
+                        Ok(port) if port > 0 => {
+                            this.http_port_error = None;
+                            this.update_settings(window, cx, |settings| settings.http.port = port);
+                        }
+                        _ => {
+                            this.http_port_error = Some("Use a port from 1 to 65535".into());
+                            cx.notify();
+                        }
+                    }
+                }
+            },
+        )
+        .detach();
+
+        let ntp_server_input = cx.new(|cx| {
+            InputState::new(window, cx)
+                .placeholder("pool.ntp.org")
+                .default_value(settings.clock_sync.server.clone())
+        });
+        cx.subscribe_in(
+            &ntp_server_input,
+            window,
+            |this, input, event, window, cx| {
+                if let InputEvent::PressEnter { .. } = event {
+                    let server = input.read(cx).value().trim().to_string();
+                    let server = if server.is_empty() {
+                        ClockSyncSettings::default().server
+                    } else {
+                        server
+                    };
+                    this.update_settings(window, cx, |settings| {
+                        settings.clock_sync.server = server
+                    });
+                }
+            },
+        )
+        .detach();
+
+        SettingsPanel {
+            app,
+            shortcut_input,
+            snippet_shortcut_input,
+            snippet_template_input,
+            snippet_error: None,
+            separator_input,
+            header_title_input,
+            work_start_input,
+            work_end_input,
+            working_hours_error: None,
+            dnd_start_input,
+            dnd_end_input,
+            dnd_error: None,
+            quiet_start_input,
+            quiet_end_input,
+            quiet_error: None,
+            event_title_input,
+            event_when_input,
+            event_error: None,
+            opacity_slider,
+            dwell_input,
+            dwell_error: None,
+            interval_input,
+            interval_error: None,
+            horizon_input,
+            horizon_error: None,
+            mirror_input,
+            mirror_error: None,
+            http_port_input,
+            http_port_error: None,
+            ntp_server_input,
+        }
+    }
+
+    // The template filled in with the cards as they are now; an error names the
+    // bad placeholder or the unknown city
+    fn snippet_preview(&self, template: &str, cx: &App) -> Result<String, String> {
+        let parts = format::parse_snippet(template)?;
+        format::render_snippet(&parts, &self.app.read(cx).snippet_cities(cx))
+    }
+
+    fn apply_working_hours(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let start = self.work_start_input.read(cx).value().trim().to_string();
+        let end = self.work_end_input.read(cx).value().trim().to_string();
+        match (convert::parse_time(&start), convert::parse_time(&end)) {
+            (Some(start), Some(end)) => {
+                self.working_hours_error = None;
+                self.update_settings(window, cx, |settings| {
+                    settings.working_hours.start = start;
+                    settings.working_hours.end = end;
+                });
+            }
+            _ => {
+                self.working_hours_error = Some("Use times like 09:00 and 17:00".into());
+                cx.notify();
+            }
+        }
+    }
+
+    fn apply_do_not_disturb(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let start = self.dnd_start_input.read(cx).value().trim().to_string();
+        let end = self.dnd_end_input.read(cx).value().trim().to_string();
+        match (convert::parse_time(&start), convert::parse_time(&end)) {
+            (Some(start), Some(end)) => {
+                self.dnd_error = None;
+                self.update_settings(window, cx, |settings| {
+                    settings.do_not_disturb.start = start;
+                    settings.do_not_disturb.end = end;
+                });
+            }
+            _ => {
+                self.dnd_error = Some("Use times like 22:00 and 07:00".into());
+                cx.notify();
+            }
+        }
+    }
+
+    fn apply_quiet_hours(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let start = self.quiet_start_input.read(cx).value().trim().to_string();
+        let end = self.quiet_end_input.read(cx).value().trim().to_string();
+        match (convert::parse_time(&start), convert::parse_time(&end)) {
+            (Some(start), Some(end)) => {
+                self.quiet_error = None;
+                self.update_settings(window, cx, |settings| {
+                    settings.quiet_hours.start = start;
+                    settings.quiet_hours.end = end;
+                });
+            }
+            _ => {
+                self.quiet_error = Some("Use times like 22:00 and 07:00".into());
+                cx.notify();
+            }
+        }
+    }
+
+    fn add_pinned_event(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let title = self.event_title_input.read(cx).value().to_string();
+        let when = self.event_when_input.read(cx).value().to_string();
+        let cities = self.app.read(cx).city_configs(cx);
+        let home = convert::home_zone(&cities);
+        match pinned_events::parse_pinned_event(&title, &when, &cities, &home, Utc::now()) {
+            Ok(event) => {
+                self.event_error = None;
+                for input in [&self.event_title_input, &self.event_when_input] {
+                    input.update(cx, |input, cx| input.set_value("", window, cx));
+                }
+                self.update_settings(window, cx, |settings| settings.pinned_events.push(event));
+            }
+            Err(err) => {
+                self.event_error = Some(err.into());
+                cx.notify();
+            }
+        }
+    }
+
+    // Applies a change to the global settings, then lets the app persist and react to it
+    fn update_settings(
+        &self,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+        update: impl FnOnce(&mut Settings),
+    ) {
+        cx.update_global::<Settings, _>(|settings, _| update(settings));
+        self.app
+            .update(cx, |app, cx| app.settings_changed(window, cx));
+        cx.notify();
+    }
+}
+
+pub fn section_title(title: &str) -> impl IntoElement {
+    div()
+        .child(title.to_string())
+        .text_sm()
+        .font_weight(gpui::FontWeight::BOLD)
+}
+
+pub fn hint(text: impl Into<SharedString>) -> impl IntoElement {
+    div().child(text.into()).text_xs().text_color(rgb(0x6b7280))
+}
+
+impl Render for SettingsPanel {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let settings = cx.global::<Settings>().clone();
+        let scale = window.scale_factor();
+        let (active_profile, saved_profiles) = {
+            let app = self.app.read(cx);
+            let saved: Vec<String> = app
+                .profiles
+                .iter()
+                .map(|profile| profile.name.clone())
+                .collect();
+            (app.active_profile.clone(), saved)
+        };
+
+        v_flex()
+            .gap_6()
+            .child(
+                v_flex()
+                    .gap_2()
+                    .child(section_title("Global shortcut"))
+                    .child(
+                        Checkbox::new("hotkey-enabled")
+                            .label("Show/hide the window from anywhere")
+                            .checked(settings.hotkey.enabled)
+                            .on_click(cx.listener(|this, checked: &bool, window, cx| {
+                                let enabled = *checked;
+                                this.update_settings(window, cx, |settings| {
+                                    settings.hotkey.enabled = enabled
+                                });
+                            })),
+                    )
+                    .child(Input::new(&self.shortcut_input))
+                    .child(hint(
+                        "Press Enter to apply, e.g. ctrl+alt+t or cmd+shift+space",
+                    )),
+            )
+            .child(
+                v_flex()
+                    .gap_2()
+                    .child(section_title("Paste the current times"))
+                    .child(
+                        Checkbox::new("snippet-enabled")
+                            .label("Paste a line of times into any app with a shortcut")
+                            .checked(settings.snippet.enabled)
+                            .on_click(cx.listener(|this, checked: &bool, window, cx| {
+                                let enabled = *checked;
+                                this.update_settings(window, cx, |settings| {
+                                    settings.snippet.enabled = enabled
+                                });
+                            })),
+                    )
+                    .child(Input::new(&self.snippet_shortcut_input))
+                    .child(Input::new(&self.snippet_template_input))
+                    .children(
+                        self.snippet_error
+                            .clone()
+                            .map(|error| div().child(error).text_sm().text_color(rgb(0xef4444))),
+                    )
+                    .children(
+                        self.snippet_preview(&settings.snippet.template, cx)
+                            .ok()
+                            .map(|preview| hint(format!("Now: {}", preview))),
+                    )
+                    .child(hint(
+                        "{Berlin} gives \"20:32 CET\"; add :time, :zone or :date for one part. {home} is home, {all} every shown city. The clipboard is restored afterwards. Press Enter to apply",
+                    )),
+            )
+            .child(
+                v_flex()
+                    .gap_2()
+                    .child(section_title("Slack message"))
+                    .child(
+                        Checkbox::new("slack-emoji")
+                            .label("Start with 📅")
+                            .checked(settings.slack.emoji)
+                            .on_click(cx.listener(|this, checked: &bool, window, cx| {
+                                let emoji = *checked;
+                                this.update_settings(window, cx, |settings| {
+                                    settings.slack.emoji = emoji
+                                });
+                            })),
+                    )
+                    .child(
+                        Checkbox::new("slack-12h")
+                            .label("12-hour times (9:00 AM)")
+                            .checked(settings.slack.twelve_hour)
+                            .on_click(cx.listener(|this, checked: &bool, window, cx| {
+                                let twelve_hour = *checked;
+                                this.update_settings(window, cx, |settings| {
+                                    settings.slack.twelve_hour = twelve_hour
+                                });
+                            })),
+                    )
+                    .child(Input::new(&self.separator_input))
+                    .child(hint("Separator between cities"))
+                    .child(
+                        Checkbox::new("include-people")
+                            .label("Name the people in each city")
+                            .checked(settings.include_people)
+                            .on_click(cx.listener(|this, checked: &bool, window, cx| {
+                                let include = *checked;
+                                this.update_settings(window, cx, |settings| {
+                                    settings.include_people = include
+                                });
+                            })),
+                    )
+                    .child(hint("Like \"Berlin (Anna, Tom)\"; also adds a column to CSV exports"))
+                    .child(
+                        Checkbox::new("overrides-in-exports")
+                            .label("Apply manual offset overrides")
+                            .checked(settings.overrides_in_exports)
+                            .on_click(cx.listener(|this, checked: &bool, window, cx| {
+                                let apply = *checked;
+                                this.update_settings(window, cx, |settings| {
+                                    settings.overrides_in_exports = apply
+                                });
+                            })),
+                    )
+                    .child(hint("Also in CSV exports; otherwise they use the tz database's times")),
+            )
+            .child(
+                v_flex()
+                    .gap_2()
+                    .child(section_title("Weekly meetings"))
+                    .child(
+                        Checkbox::new("series-weekly")
+                            .label("Export meetings as a weekly series")
+                            .checked(settings.series.weekly)
+                            .on_click(cx.listener(|this, checked: &bool, window, cx| {
+                                let weekly = *checked;
+                                this.update_settings(window, cx, |settings| {
+                                    settings.series.weekly = weekly
+                                });
+                            })),
+                    )
+                    .child(h_flex().gap_1().children(
+                        SeriesSettings::OCCURRENCE_CHOICES.into_iter().map(|occurrences| {
+                            let button = Button::new(("series-occurrences", occurrences as usize))
+                                .label(format!("{} weeks", occurrences))
+                                .small()
+                                .on_click(cx.listener(move |this, _, window, cx| {
+                                    this.update_settings(window, cx, |settings| {
+                                        settings.series.occurrences = occurrences
+                                    });
+                                }));
+                            if occurrences == settings.series.occurrences {
+                                button.primary()
+                            } else {
+                                button.ghost()
+                            }
+                        }),
+                    ))
+                    .child(hint(
+                        "The .ics repeats and the Slack message says so; both note where a city's time moves with a clock change",
+                    )),
+            )
+            .child(
+                v_flex()
+                    .gap_2()
+                    .child(section_title("Unused cities"))
+                    .child(div().child("Offer cities untouched for over").text_sm())
+                    .child(
+                        h_flex()
+                            .gap_1()
+                            .children(cleanup::PERIODS.into_iter().map(|days| {
+                                let button = Button::new(("cleanup-period", days as usize))
+                                    .label(cleanup::describe_period(days))
+                                    .small()
+                                    .on_click(cx.listener(move |this, _, window, cx| {
+                                        this.update_settings(window, cx, |settings| {
+                                            settings.cleanup.after_days = days
+                                        });
+                                    }));
+                                if days == settings.cleanup.after_days {
+                                    button.primary()
+                                } else {
+                                    button.ghost()
+                                }
+                            })),
+                    )
+                    .child(
+                        Button::new("review-unused")
+                            .label("Review unused cities…")
+                            .small()
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                window.close_sheet(cx);
+                                this.app
+                                    .update(cx, |app, cx| app.review_unused(window, cx));
+                            })),
+                    )
+                    .child(hint(
+                        "Focusing, copying, editing or exporting counts as use; nothing is removed without asking",
+                    )),
+            )
+            .child(
+                v_flex()
+                    .gap_2()
+                    .child(section_title("Pinned events"))
+                    .children(settings.pinned_events.iter().enumerate().map(
+                        |(index, event)| {
+                            h_flex()
+                                .justify_between()
+                                .child(div().child(event.describe()).text_sm())
+                                .child(
+                                    Button::new(("remove-pinned-event", index))
+                                        .label("Remove")
+                                        .small()
+                                        .ghost()
+                                        .on_click(cx.listener(move |this, _, window, cx| {
+                                            this.update_settings(window, cx, |settings| {
+                                                if index < settings.pinned_events.len() {
+                                                    settings.pinned_events.remove(index);
+                                                }
+                                            });
+                                        })),
+                                )
+                        },
+                    ))
+                    .child(Input::new(&self.event_title_input))
+                    .child(Input::new(&self.event_when_input))
+                    .children(
+                        self.event_error
+                            .clone()
+                            .map(|error| div().child(error).text_sm().text_color(rgb(0xef4444))),
+                    )
+                    .child(hint(
+                        "Shown on every card. Start with a weekday to repeat weekly; press Enter to add",
+                    )),
+            )
+            .child(
+                v_flex()
+                    .gap_2()
+                    .child(section_title("Cards"))
+                    .child(
+                        Checkbox::new("show-timeline")
+                            .label("Show the next 24 hours on each card")
+                            .checked(settings.show_timeline)
+                            .on_click(cx.listener(|this, checked: &bool, window, cx| {
+                                let show_timeline = *checked;
+                                this.update_settings(window, cx, |settings| {
+                                    settings.show_timeline = show_timeline
+                                });
+                            })),
+                    )
+                    .child(hint("Night, working hours and evening, on a shared time axis"))
+                    .child(
+                        Checkbox::new("show-mirror")
+                            .label("Show what a time of theirs is for you (\"their 17:00 = your 10:00\")")
+                            .checked(settings.mirror.enabled)
+                            .on_click(cx.listener(|this, checked: &bool, window, cx| {
+                                let enabled = *checked;
+                                this.update_settings(window, cx, |settings| {
+                                    settings.mirror.enabled = enabled
+                                });
+                            })),
+                    )
+                    .child(Input::new(&self.mirror_input))
+                    .children(
+                        self.mirror_error
+                            .clone()
+                            .map(|error| div().child(error).text_sm().text_color(rgb(0xef4444))),
+                    )
+                    .child(hint(
+                        "Their local time; empty uses the end of each city's working day. Press Enter to apply",
+                    ))
+                    .child(
+                        Checkbox::new("auto-order")
+                            .label("Keep cities ordered from earliest to latest local time")
+                            .checked(settings.auto_order)
+                            .on_click(cx.listener(|this, checked: &bool, window, cx| {
+                                let auto_order = *checked;
+                                this.update_settings(window, cx, |settings| {
+                                    settings.auto_order = auto_order
+                                });
+                            })),
+                    )
+                    .child(hint("Re-sorts by itself when clocks change or cities are added"))
+                    .child(
+                        Checkbox::new("stack-same-zone")
+                            .label("Stack cities that share a time zone into one card")
+                            .checked(settings.stack_same_zone)
+                            .on_click(cx.listener(|this, checked: &bool, window, cx| {
+                                let stack_same_zone = *checked;
+                                this.update_settings(window, cx, |settings| {
+                                    settings.stack_same_zone = stack_same_zone
+                                });
+                            })),
+                    )
+                    .child(hint("Cities in the same zone are listed alphabetically either way"))
+                    .child(div().child("Write differences as").text_sm())
+                    .child(
+                        h_flex()
+                            .gap_2()
+                            .children(DiffStyle::ALL.iter().enumerate().map(|(index, &style)| {
+                                let button = Button::new(("diff-style", index))
+                                    .label(style.label())
+                                    .small()
+                                    .on_click(cx.listener(move |this, _, window, cx| {
+                                        this.update_settings(window, cx, |settings| {
+                                            settings.diff_style = style
+                                        });
+                                    }));
+                                if style == settings.diff_style {
+                                    button.primary()
+                                } else {
+                                    button.ghost()
+                                }
+                            }))
+                            .child(
+                                Button::new("reset-diff-styles")
+                                    .label("Use on every card")
+                                    .small()
+                                    .ghost()
+                                    .on_click(cx.listener(|this, _, window, cx| {
+                                        this.app
+                                            .update(cx, |app, cx| app.reset_diff_styles(window, cx));
+                                    })),
+                            ),
+                    )
+                    .child(hint(
+                        "Click a card's difference to cycle its wording for that card alone",
+                    ))
+                    .child(
+                        Checkbox::new("show-world-map")
+                            .label("Show a world map with day and night")
+                            .checked(settings.show_world_map)
+                            .on_click(cx.listener(|this, checked: &bool, window, cx| {
+                                let show = *checked;
+                                this.update_settings(window, cx, |settings| {
+                                    settings.show_world_map = show
+                                });
+                            })),
+                    )
+                    .child(hint("Click a city's dot to jump to its card"))
+                    .child(
+                        Checkbox::new("show-day-stats")
+                            .label("Show how much of each city's day is gone")
+                            .checked(settings.show_day_stats)
+                            .on_click(cx.listener(|this, checked: &bool, window, cx| {
+                                let show = *checked;
+                                this.update_settings(window, cx, |settings| {
+                                    settings.show_day_stats = show
+                                });
+                            })),
+                    )
+                    .child(hint("And how long until their working day starts"))
+                    .child(
+                        Checkbox::new("show-workday")
+                            .label("Show how far each city is into its working day")
+                            .checked(settings.show_workday)
+                            .on_click(cx.listener(|this, checked: &bool, window, cx| {
+                                let show = *checked;
+                                this.update_settings(window, cx, |settings| {
+                                    settings.show_workday = show
+                                });
+                            })),
+                    )
+                    .child(hint("Uses each city's working hours, with Saturday and Sunday off"))
+                    .child(
+                        Checkbox::new("show-overlap-score")
+                            .label("Score how much of your working week each city shares")
+                            .checked(!settings.hide_overlap_score)
+                            .on_click(cx.listener(|this, checked: &bool, window, cx| {
+                                let hide = !*checked;
+                                this.update_settings(window, cx, |settings| {
+                                    settings.hide_overlap_score = hide
+                                });
+                            })),
+                    )
+                    .child(hint("A 0–100 badge over the next 7 days; hover it for the hours"))
+                    .child(
+                        Checkbox::new("show-relative-phrase")
+                            .label("Describe each city's time (\"early morning tomorrow\")")
+                            .checked(settings.show_relative_phrase)
+                            .on_click(cx.listener(|this, checked: &bool, window, cx| {
+                                let show = *checked;
+                                this.update_settings(window, cx, |settings| {
+                                    settings.show_relative_phrase = show
+                                });
+                            })),
+                    )
+                    .child(h_flex().gap_1().children(Language::ALL.map(|language| {
+                        Button::new(SharedString::from(format!("language-{:?}", language)))
+                            .label(language.name())
+                            .small()
+                            .map(|button| {
+                                if settings.language == language {
+                                    button.primary()
+                                } else {
+                                    button.ghost()
+                                }
+                            })
+                            .on_click(cx.listener(move |this, _, window, cx| {
+                                this.update_settings(window, cx, |settings| {
+                                    settings.language = language
+                                });
+                            }))
+                    })))
+                    .child(hint("Language of the description and of the \"Δ hours\" difference")),
+            )
+            .child(
+                v_flex()
+                    .gap_2()
+                    .child(section_title("Working hours"))
+                    .child(
+                        h_flex()
+                            .gap_2()
+                            .child(Input::new(&self.work_start_input))
+                            .child("to")
+                            .child(Input::new(&self.work_end_input)),
+                    )
+                    .children(
+                        self.working_hours_error
+                            .clone()
+                            .map(|error| div().child(error).text_sm().text_color(rgb(0xef4444))),
+                    )
+                    .child(hint(
+                        "Local time in each city, used by the meeting planner; 22:00 to 06:00 runs overnight. Press Enter to apply",
+                    )),
+            )
+            .child(
+                v_flex()
+                    .gap_2()
+                    .child(section_title("Week"))
+                    .child(div().child("Weeks start on").text_sm())
+                    .child(h_flex().gap_1().children(
+                        WeekConfig::default().days().into_iter().map(|day| {
+                            let button = Button::new(("first-day", day.num_days_from_monday()))
+                                .label(day.to_string())
+                                .small()
+                                .on_click(cx.listener(move |this, _, window, cx| {
+                                    this.update_settings(window, cx, |settings| {
+                                        settings.week.first_day = day
+                                    });
+                                }));
+                            if day == settings.week.first_day {
+                                button.primary()
+                            } else {
+                                button.ghost()
+                            }
+                        }),
+                    ))
+                    .child(div().child("Days off").text_sm())
+                    .child(h_flex().gap_1().children(settings.week.days().into_iter().map(
+                        |day| {
+                            let button = Button::new(("weekend-day", day.num_days_from_monday()))
+                                .label(day.to_string())
+                                .small()
+                                .on_click(cx.listener(move |this, _, window, cx| {
+                                    this.update_settings(window, cx, |settings| {
+                                        settings.week.weekend = settings.week.weekend.toggled(day)
+                                    });
+                                }));
+                            if settings.week.weekend.contains(day) {
+                                button.primary()
+                            } else {
+                                button.ghost()
+                            }
+                        },
+                    )))
+                    .child(hint(
+                        "Used by the week export, hour table, overlap finder and workday line. A city's details can give it its own days off",
+                    )),
+            )
+            .child(
+                v_flex()
+                    .gap_2()
+                    .child(section_title("Do not disturb"))
+                    .child(
+                        h_flex()
+                            .gap_2()
+                            .child(Input::new(&self.dnd_start_input))
+                            .child("to")
+                            .child(Input::new(&self.dnd_end_input)),
+                    )
+                    .children(
+                        self.dnd_error
+                            .clone()
+                            .map(|error| div().child(error).text_sm().text_color(rgb(0xef4444))),
+                    )
+                    .child(hint(
+                        "Local time in each city; never suggested for meetings. An end before the start runs past midnight. Press Enter to apply",
+                    )),
+            )
+            .child(
+                v_flex()
+                    .gap_2()
+                    .child(section_title("Quiet hours"))
+                    .child(
+                        Checkbox::new("quiet-hours")
+                            .label("Keep alarms silent overnight")
+                            .checked(settings.quiet_hours.enabled)
+                            .on_click(cx.listener(|this, checked: &bool, window, cx| {
+                                let enabled = *checked;
+                                this.update_settings(window, cx, |settings| {
+                                    settings.quiet_hours.enabled = enabled
+                                });
+                            })),
+                    )
+                    .child(
+                        h_flex()
+                            .gap_2()
+                            .child(Input::new(&self.quiet_start_input))
+                            .child("to")
+                            .child(Input::new(&self.quiet_end_input)),
+                    )
+                    .children(
+                        self.quiet_error
+                            .clone()
+                            .map(|error| div().child(error).text_sm().text_color(rgb(0xef4444))),
+                    )
+                    .child(hint(
+                        "Home time. Alarms due then ring afterwards, or are skipped if set to; press Enter to apply",
+                    )),
+            )
+            .child(
+                v_flex()
+                    .gap_2()
+                    .child(section_title("Window"))
+                    .child(
+                        Checkbox::new("prevent-maximize")
+                            .label("Keep the window at its default size")
+                            .checked(settings.prevent_maximize)
+                            .on_click(cx.listener(|this, checked: &bool, window, cx| {
+                                let prevent = *checked;
+                                this.update_settings(window, cx, |settings| {
+                                    settings.prevent_maximize = prevent
+                                });
+                            })),
+                    )
+                    .child(hint("Maximizing snaps back to 800×600"))
+                    .child(
+                        Checkbox::new("always-on-top")
+                            .label("Keep the window above other windows")
+                            .checked(settings.always_on_top)
+                            .on_click(cx.listener(|this, checked: &bool, window, cx| {
+                                let on_top = *checked;
+                                this.update_settings(window, cx, |settings| {
+                                    settings.always_on_top = on_top
+                                });
+                            })),
+                    )
+                    .child(hint("Takes effect the next time time2rust starts"))
+                    .child(
+                        Checkbox::new("reduce-motion")
+                            .label("Reduce motion")
+                            .checked(settings.reduce_motion)
+                            .on_click(cx.listener(|this, checked: &bool, window, cx| {
+                                let reduce = *checked;
+                                this.update_settings(window, cx, |settings| {
+                                    settings.reduce_motion = reduce
+                                });
+                            })),
+                    )
+                    .child(hint("Cards change in place instead of fading"))
+                    .child(div().child(format!(
+                        "Background opacity: {}%",
+                        settings.window_opacity.percent()
+                    )))
+                    .child(Slider::new(&self.opacity_slider))
+                    .child(hint(
+                        "Cards stay opaque. On X11 this needs a compositing window manager",
+                    ))
+                    .child(div().child("Zoom").text_sm())
+                    .child(h_flex().gap_1().children(zoom::PRESETS.into_iter().enumerate().map(
+                        |(index, factor)| {
+                            let button = Button::new(("zoom", index))
+                                .label(zoom::percent(factor))
+                                .small()
+                                .on_click(cx.listener(move |this, _, window, cx| {
+                                    this.update_settings(window, cx, |settings| {
+                                        settings.zoom.factor = factor
+                                    });
+                                }));
+                            if factor == settings.zoom.factor {
+                                button.primary()
+                            } else {
+                                button.ghost()
+                            }
+                        },
+                    )))
+                    .child(
+                        div()
+                            .child(format!(
+                                "On {} displays like this one, also",
+                                zoom::describe_scale(scale)
+                            ))
+                            .text_sm(),
+                    )
+                    .child(h_flex().gap_1().children(
+                        zoom::ADJUSTMENTS.into_iter().enumerate().map(|(index, factor)| {
+                            let button = Button::new(("zoom-display", index))
+                                .label(zoom::percent(factor))
+                                .small()
+                                .on_click(cx.listener(move |this, _, window, cx| {
+                                    this.update_settings(window, cx, |settings| {
+                                        settings.zoom.set_adjustment(scale, factor)
+                                    });
+                                }));
+                            if factor == settings.zoom.adjustment(scale) {
+                                button.primary()
+                            } else {
+                                button.ghost()
+                            }
+                        }),
+                    ))
+                    .child(hint(
+                        "For mixed screens: the window re-scales as it moves to a display with another scale factor",
+                    )),
+            )
+            .child(
+                v_flex()
+                    .gap_2()
+                    .child(section_title("Updates"))
+                    .child(
+                        h_flex()
+                            .gap_2()
+                            .child(div().w_24().child(Input::new(&self.interval_input)))
+                            .child("seconds between updates"),
+                    )
+                    .children(
+                        self.interval_error
+                            .clone()
+                            .map(|error| div().child(error).text_sm().text_color(rgb(0xef4444))),
+                    )
+                    .child(hint(
+                        "1 to 900; whole minutes update right as the minute changes",
+                    ))
+                    .child(
+                        Checkbox::new("show-seconds")
+                            .label("Show seconds")
+                            .checked(settings.show_seconds)
+                            .on_click(cx.listener(|this, checked: &bool, window, cx| {
+                                let show = *checked;
+                                this.update_settings(window, cx, |settings| {
+                                    settings.show_seconds = show
+                                });
+                            })),
+                    )
+                    .child(hint("Updates every second while on"))
+                    .child(div().child("Write times as").text_sm())
+                    .child(h_flex().gap_2().children(
+                        ClockFormat::ALL.iter().enumerate().map(|(index, &format)| {
+                            let button = Button::new(("clock-format", index))
+                                .label(format.label())
+                                .small()
+                                .on_click(cx.listener(move |this, _, window, cx| {
+                                    this.update_settings(window, cx, |settings| {
+                                        settings.clock_format = format
+                                    });
+                                }));
+                            if format == settings.clock_format {
+                                button.primary()
+                            } else {
+                                button.ghost()
+                            }
+                        }),
+                    ))
+                    .child(hint(
+                        "Military time adds the zone letter, Z for UTC; half-hour zones get their offset",
+                    )),
+            )
+            .child(
+                v_flex()
+                    .gap_2()
+                    .child(section_title("Clock changes"))
+                    .child(
+                        h_flex()
+                            .gap_2()
+                            .child(div().w_24().child(Input::new(&self.horizon_input)))
+                            .child("days ahead"),
+                    )
+                    .children(
+                        self.horizon_error
+                            .clone()
+                            .map(|error| div().child(error).text_sm().text_color(rgb(0xef4444))),
+                    )
+                    .child(hint(
+                        "How early the header starts counting down to home's next DST change; 0 hides it",
+                    )),
+            )
+            .child(
+                v_flex()
+                    .gap_2()
+                    .child(section_title("Header"))
+                    .child(
+                        Checkbox::new("header-hidden")
+                            .label("Hide the header")
+                            .checked(settings.header.hidden)
+                            .on_click(cx.listener(|this, checked: &bool, window, cx| {
+                                let hidden = *checked;
+                                this.update_settings(window, cx, |settings| {
+                                    settings.header.hidden = hidden
+                                });
+                            })),
+                    )
+                    .child(hint(
+                        "Its buttons stay in the command palette and the menus",
+                    ))
+                    .child(Input::new(&self.header_title_input))
+                    .child(hint("Your own title; empty for the usual one. Press Enter to apply"))
+                    .child(
+                        Checkbox::new("header-window-title")
+                            .label("Use it as the window title too")
+                            .checked(settings.header.window_title)
+                            .on_click(cx.listener(|this, checked: &bool, window, cx| {
+                                let window_title = *checked;
+                                this.update_settings(window, cx, |settings| {
+                                    settings.header.window_title = window_title
+                                });
+                            })),
+                    )
+                    .child(
+                        Checkbox::new("header-utc-clock")
+                            .label("Show a UTC clock on the right")
+                            .checked(settings.header.utc_clock)
+                            .on_click(cx.listener(|this, checked: &bool, window, cx| {
+                                let utc_clock = *checked;
+                                this.update_settings(window, cx, |settings| {
+                                    settings.header.utc_clock = utc_clock
+                                });
+                            })),
+                    ),
+            )
+            .child(
+                v_flex()
+                    .gap_2()
+                    .child(section_title("Kiosk"))
+                    .child(
+                        h_flex()
+                            .gap_2()
+                            .child(div().w_24().child(Input::new(&self.dwell_input)))
+                            .child("seconds per city"),
+                    )
+                    .children(
+                        self.dwell_error
+                            .clone()
+                            .map(|error| div().child(error).text_sm().text_color(rgb(0xef4444))),
+                    )
+                    .child(
+                        Checkbox::new("kiosk-all-slide")
+                            .label("Show all cities together after each round")
+                            .checked(settings.kiosk.show_all_slide)
+                            .on_click(cx.listener(|this, checked: &bool, window, cx| {
+                                let show = *checked;
+                                this.update_settings(window, cx, |settings| {
+                                    settings.kiosk.show_all_slide = show
+                                });
+                            })),
+                    )
+                    .child(
+                        Checkbox::new("kiosk-header")
+                            .label("Show the header's title and UTC clock above the slides")
+                            .checked(settings.kiosk.show_header)
+                            .on_click(cx.listener(|this, checked: &bool, window, cx| {
+                                let show = *checked;
+                                this.update_settings(window, cx, |settings| {
+                                    settings.kiosk.show_header = show
+                                });
+                            })),
+                    )
+                    .child(hint(
+                        "Start it from the command palette or with --kiosk; any key or click exits",
+                    )),
+            )
+            .child(
+                v_flex()
+                    .gap_2()
+                    .child(section_title("Profiles"))
+                    .child(
+                        h_flex()
+                            .justify_between()
+                            .child(
+                                div()
+                                    .child(format!("{} (showing)", active_profile))
+                                    .text_sm(),
+                            )
+                            .child(
+                                Button::new("rename-active-profile")
+                                    .label("Rename…")
+                                    .small()
+                                    .ghost()
+                                    .on_click(cx.listener(move |this, _, window, cx| {
+                                        window.close_sheet(cx);
+                                        let name = this.app.read(cx).active_profile.clone();
+                                        this.app.update(cx, |app, cx| {
+                                            app.open_rename_profile(name, window, cx)
+                                        });
+                                    })),
+                            ),
+                    )
+                    .children(saved_profiles.into_iter().enumerate().map(|(index, name)| {
+                        h_flex()
+                            .justify_between()
+                            .child(div().child(name.clone()).text_sm())
+                            .child(
+                                h_flex()
+                                    .gap_1()
+                                    .child(
+                                        Button::new(("switch-profile", index))
+                                            .label("Switch")
+                                            .small()
+                                            .ghost()
+                                            .on_click(cx.listener({
+                                                let name = name.clone();
+                                                move |this, _, window, cx| {
+                                                    window.close_sheet(cx);
+                                                    this.app.update(cx, |app, cx| {
+                                                        app.switch_profile(&name, window, cx)
+                                                    });
+                                                }
+                                            })),
+                                    )
+                                    .child(
+                                        Button::new(("rename-profile", index))
+                                            .label("Rename…")
+                                            .small()
+                                            .ghost()
+                                            .on_click(cx.listener({
+                                                let name = name.clone();
+                                                move |this, _, window, cx| {
+                                                    window.close_sheet(cx);
+                                                    let name = name.clone();
+                                                    this.app.update(cx, |app, cx| {
+                                                        app.open_rename_profile(name, window, cx)
+                                                    });
+                                                }
+                                            })),
+                                    )
+                                    .child(
+                                        Button::new(("delete-profile", index))
+                                            .label("Delete…")
+                                            .small()
+                                            .ghost()
+                                            .on_click(cx.listener(move |this, _, window, cx| {
+                                                window.close_sheet(cx);
+                                                profiles::confirm_delete_profile(
+                                                    this.app.clone(),
+                                                    name.clone(),
+                                                    window,
+                                                    cx,
+                                                );
+                                            })),
+                                    ),
+                            )
+                    }))
+                    .child(
+                        Button::new("save-profile-as")
+                            .label("Save current setup as new profile…")
+                            .small()
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                window.close_sheet(cx);
+                                this.app
+                                    .update(cx, |app, cx| app.open_save_profile(window, cx));
+                            })),
+                    )
+                    .child(hint(
+                        "Each profile keeps its own cities; a new one starts as a copy of what's showing",
+                    )),
+            )
+            .child(
+                v_flex()
+                    .gap_2()
+                    .child(section_title("Local endpoint"))
+                    .child(
+                        Checkbox::new("http-enabled")
+                            .label("Serve the times to dashboards on this computer")
+                            .checked(settings.http.enabled)
+                            .on_click(cx.listener(|this, checked: &bool, window, cx| {
+                                let enabled = *checked;
+                                this.update_settings(window, cx, |settings| {
+                                    settings.http.enabled = enabled
+                                });
+                            })),
+                    )
+                    .child(
+                        h_flex()
+                            .gap_2()
+                            .child(format!("http://{}:", settings.http.address))
+                            .child(div().w_24().child(Input::new(&self.http_port_input)))
+                            .child("/times"),
+                    )
+                    .children(
+                        self.http_port_error
+                            .clone()
+                            .map(|error| div().child(error).text_sm().text_color(rgb(0xef4444))),
+                    )
+                    .child(hint(
+                        "Read-only, loopback only; the same JSON as time2rust --json. Press Enter to apply",
+                    )),
+            )
+            .child(
+                v_flex()
+                    .gap_2()
+                    .child(section_title("Clock check"))
+                    .child(
+                        Checkbox::new("clock-sync-enabled")
+                            .label("Compare the system clock with a time server every few hours")
+                            .checked(settings.clock_sync.enabled)
+                            .on_click(cx.listener(|this, checked: &bool, window, cx| {
+                                let enabled = *checked;
+                                this.update_settings(window, cx, |settings| {
+                                    settings.clock_sync.enabled = enabled
+                                });
+                            })),
+                    )
+                    .child(
+                        h_flex()
+                            .gap_2()
+                            .child(div().child("Server").text_sm())
+                            .child(div().w_48().child(Input::new(&self.ntp_server_input))),
+                    )
+                    .child(div().child("Warn when it's off by more than").text_sm())
+                    .child(h_flex().gap_1().children(
+                        ClockSyncSettings::THRESHOLD_CHOICES.into_iter().map(|secs| {
+                            let button = Button::new(("clock-sync-threshold", secs as usize))
+                                .label(format!("{} s", secs))
+                                .small()
+                                .on_click(cx.listener(move |this, _, window, cx| {
+                                    this.update_settings(window, cx, |settings| {
+                                        settings.clock_sync.threshold_secs = secs
+                                    });
+                                }));
+                            if secs == settings.clock_sync.threshold_secs {
+                                button.primary()
+                            } else {
+                                button.ghost()
+                            }
+                        }),
+                    ))
+                    .child(hint(
+                        "Off by default; it sends one small UDP packet per check. An unreachable server shows nothing. Press Enter to apply the server",
+                    )),
+            )
+            .child(
+                v_flex()
+                    .gap_2()
+                    .child(section_title("Setup"))
+                    .child(
+                        Button::new("run-diagnostics")
+                            .label("Run diagnostics")
+                            .small()
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                window.close_sheet(cx);
+                                diagnostics::open_diagnostics(this.app.clone(), window, cx);
+                            })),
+                    )
+                    .child(hint(
+                        "Checks the zones, the config file, the window position and the timer, with a report to copy",
+                    ))
+                    .child(
+                        Button::new("rerun-onboarding")
+                            .label("Run first-time setup again")
+                            .small()
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                window.close_sheet(cx);
+                                this.app
+                                    .update(cx, |app, cx| app.start_onboarding(window, cx));
+                            })),
+                    )
+                    .child(hint("Pick home and cities again; the current ones start ticked"))
+                    .child(
+                        Button::new("reset-settings")
+                            .label("Reset settings to defaults…")
+                            .small()
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                window.close_sheet(cx);
+                                confirm::confirm_reset_settings(this.app.clone(), window, cx);
+                            })),
+                    )
+                    .child(
+                        h_flex()
+                            .gap_2()
+                            .child(
+                                Button::new("back-up")
+                                    .label("Back up everything…")
+                                    .small()
+                                    .on_click(cx.listener(|this, _, window, cx| {
+                                        window.close_sheet(cx);
+                                        this.app.update(cx, |app, cx| app.back_up(window, cx));
+                                    })),
+                            )
+                            .child(
+                                Button::new("restore")
+                                    .label("Restore from backup…")
+                                    .small()
+                                    .on_click(cx.listener(|this, _, window, cx| {
+                                        window.close_sheet(cx);
+                                        this.app.update(cx, |app, cx| app.open_restore(window, cx));
+                                    })),
+                            )
+                            .child(
+                                Button::new("restore-save")
+                                    .label("Restore an earlier save…")
+                                    .small()
+                                    .on_click(cx.listener(|this, _, window, cx| {
+                                        window.close_sheet(cx);
+                                        config_backups::open_backup_picker(
+                                            this.app.clone(),
+                                            window,
+                                            cx,
+                                        );
+                                    })),
+                            ),
+                    )
+                    .child(hint(
+                        "Cities, profiles, settings, alarms, countdowns and window placement in one file",
+                    )),
+            )
+    }
+}
@@ -0,0 +1,88 @@
+use crate::{
+    WorldTimeApp,
+    commands::{self, AppCommand},
+    settings_panel::section_title,
+};
+use gpui::{Context, MouseButton, SharedString, div, prelude::*, px};
+use gpui_component::{ActiveTheme as _, h_flex, v_flex};
+
+pub struct ShortcutRow {
+    pub area: &'static str,
+    pub label: String,
+    pub keys: String,
+}
+
+// One row per command with a shortcut, in registry order, so the overlay lists
+// exactly what the key handler responds to
+pub fn shortcut_rows(commands: &[AppCommand]) -> Vec<ShortcutRow> {
+    commands
+        .iter()
+        .filter(|command| !command.shortcuts().is_empty())
+        .map(|command| ShortcutRow {
+            area: command.area(),
+            label: command.label(),
+            keys: command
+                .shortcuts()
+                .iter()
+                .map(|shortcut| commands::shortcut_label(shortcut))
+                .collect::<Vec<_>>()
+                .join(" / "),
+        })
+        .collect()
+}
+
+// Covers the window until Escape or a click
+pub fn shortcut_overlay(
+    rows: Vec<ShortcutRow>,
+    cx: &mut Context<WorldTimeApp>,
+) -> impl IntoElement {
+    let mut groups: Vec<(&'static str, Vec<ShortcutRow>)> = Vec::new();
+    for row in rows {
+        match groups.iter_mut().find(|(area, _)| *area == row.area) {
+            Some((_, group)) => group.push(row),
+            None => groups.push((row.area, vec![row])),
+        }
+    }
+    let muted = cx.theme().muted_foreground;
+
+    div()
+        .absolute()
+        .inset_0()
+        .flex()
+        .items_center()
+        .justify_center()
+        .bg(cx.theme().overlay)
+        .on_mouse_down(
+            MouseButton::Left,
+            cx.listener(|this, _, _, cx| this.hide_shortcuts(cx)),
+        )
+        .child(
+            v_flex()
+                .gap_4()
+                .p_6()
+                .min_w(px(360.0))
+                .bg(cx.theme().popover)
+                .text_color(cx.theme().popover_foreground)
+                .border_1()
+                .border_color(cx.theme().border)
+                .rounded(px(8.0))
+                .child(div().child("Keyboard shortcuts").text_lg())
+                .children(groups.into_iter().map(|(area, rows)| {
+                    v_flex()
+                        .gap_1()
+                        .child(section_title(area))
+                        .children(rows.into_iter().map(|row| {
+                            h_flex()
+                                .justify_between()
+                                .gap_6()
+                                .child(div().child(row.label).text_sm())
+                                .child(
+                                    div()
+                                        .child(SharedString::from(row.keys))
+                                        .text_sm()
+                                        .text_color(muted),
+                                )
+                        }))
+                })),
+        )
+}
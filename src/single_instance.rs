@@ -0,0 +1,159 @@
+use std::{
+    fs,
+    io::{BufRead, BufReader, Write},
+    net::{Ipv4Addr, TcpListener, TcpStream},
+    path::PathBuf,
+    sync::{OnceLock, mpsc::Sender},
+    time::Duration,
+};
+
+// Sent by a second launch that has nothing else to say
+pub const ACTIVATE: &str = "activate";
+// The primary's greeting. A port left behind by a crash may since belong to some
+// other program, so nothing is sent until this comes back.
+const GREETING: &str = "time2rust-instance";
+
+// The port this process recorded, so quitting only removes its own file
+static RECORDED_PORT: OnceLock<u16> = OnceLock::new();
+
+pub enum Instance {
+    // We're the first instance; messages from later launches arrive on the inbox
+    Primary,
+    // Another instance is running and has received our messages
+    Forwarded,
+}
+
+// The primary instance listens on a loopback port recorded next to the config file
+fn port_file() -> Option<PathBuf> {
+    crate::config::config_path().map(|path| path.with_file_name("instance.port"))
+}
+
+fn recorded_port() -> Option<u16> {
+    fs::read_to_string(port_file()?).ok()?.trim().parse().ok()
+}
+
+fn forward(messages: &[String]) -> Option<()> {
+    forward_to(recorded_port()?, messages)
+}
+
+fn forward_to(port: u16, messages: &[String]) -> Option<()> {
+    let address = (Ipv4Addr::LOCALHOST, port).into();
+    let timeout = Duration::from_millis(250);
+    let mut stream = TcpStream::connect_timeout(&address, timeout).ok()?;
+    stream.set_read_timeout(Some(timeout)).ok()?;
+    let mut greeting = String::new();
+    BufReader::new(&stream).read_line(&mut greeting).ok()?;
+    if greeting.trim_end() != GREETING {
+        return None;
+    }
+    let mut payload = String::new();
+    for message in messages.iter().map(String::as_str).chain([ACTIVATE]) {
+        payload.push_str(message);
+        payload.push('\n');
+    }
+    stream.write_all(payload.as_bytes()).ok()
+}
+
+// Either hands `messages` to an already running instance, or becomes the primary
+// instance and starts relaying messages from future launches into `inbox`
+pub fn acquire(messages: &[String], inbox: Sender<String>) -> Instance {
+    if forward(messages).is_some() {
+        return Instance::Forwarded;
+    }
+    for message in messages {
+        let _ = inbox.send(message.clone());
+    }
+
+    match TcpListener::bind((Ipv4Addr::LOCALHOST, 0)) {
+        Ok(listener) => {
+            let port = listener.local_addr().map(|address| address.port());
+            if let (Some(path), Ok(port)) = (port_file(), port) {
+                let _ = fs::create_dir_all(path.parent().unwrap_or(&path));
+                match fs::write(&path, port.to_string()) {
+                    Ok(()) => {
+                        let _ = RECORDED_PORT.set(port);
+                    }
+                    Err(err) => eprintln!("time2rust: couldn't record instance port: {}", err),
+                }
+            }
+            std::thread::spawn(move || serve(listener, inbox));
+        }
+        // Still usable without the channel, later launches just open their own window
+        Err(err) => eprintln!("time2rust: single-instance listener unavailable: {}", err),
+    }
+    Instance::Primary
+}
+
+// Greets each launch that connects, then relays its lines into `inbox`
+fn serve(listener: TcpListener, inbox: Sender<String>) {
+    for mut stream in listener.incoming().flatten() {
+        if writeln!(stream, "{}", GREETING).is_err() {
+            continue;
+        }
+        for line in BufReader::new(stream).lines().map_while(Result::ok) {
+            if inbox.send(line).is_err() {
+                return;
+            }
+        }
+    }
+}
+
+// On quit: forgets the port, unless a later instance has recorded its own since
+pub fn release() {
+    let Some(port) = RECORDED_PORT.get() else {
+        return;
+    };
+    if recorded_port() == Some(*port)
+        && let Some(path) = port_file()
+    {
+        let _ = fs::remove_file(path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+
+    #[test]
+    fn forwards_to_a_primary_that_greets() {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let (inbox, received) = mpsc::channel();
+        std::thread::spawn(move || serve(listener, inbox));
+
+        let link = "time2rust://add?tz=Asia%2FTokyo".to_string();
+        assert_eq!(forward_to(port, std::slice::from_ref(&link)), Some(()));
+        let timeout = Duration::from_secs(2);
+        assert_eq!(received.recv_timeout(timeout).unwrap(), link);
+        assert_eq!(received.recv_timeout(timeout).unwrap(), ACTIVATE);
+    }
+
+    #[test]
+    fn sends_nothing_to_another_program_on_the_port() {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let stranger = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            writeln!(stream, "SSH-2.0-OpenSSH_9.6").unwrap();
+            stream
+                .set_read_timeout(Some(Duration::from_millis(500)))
+                .unwrap();
+            let mut received = String::new();
+            let _ = BufReader::new(stream).read_line(&mut received);
+            received
+        });
+
+        assert_eq!(forward_to(port, &["activate".to_string()]), None);
+        assert_eq!(stranger.join().unwrap(), "");
+    }
+
+    #[test]
+    fn gives_up_on_a_silent_listener() {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+        let port = listener.local_addr().unwrap().port();
+        // Accepted by the backlog but never greeted
+        assert_eq!(forward_to(port, &[]), None);
+        drop(listener);
+    }
+}
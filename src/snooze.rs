@@ -0,0 +1,31 @@
+use chrono::{DateTime, Days, NaiveDate, Utc};
+use chrono_tz::Tz;
+use time2rust::format;
+
+// Quick picks in the card detail, as days from today
+pub const PRESETS: &[(&str, u64)] = &[("Tomorrow", 1), ("A week", 7), ("Two weeks", 14)];
+
+// A snoozed card keeps its time and difference but drops its other badges and
+// warnings. Like a manual override, the snooze lapses at local midnight on `until`.
+pub fn is_active(until: Option<NaiveDate>, tz: Tz, now: DateTime<Utc>) -> bool {
+    until.is_some_and(|until| now.with_timezone(&tz).date_naive() < until)
+}
+
+pub fn preset_until(today: NaiveDate, days: u64) -> NaiveDate {
+    today.checked_add_days(Days::new(days)).unwrap_or(today)
+}
+
+// A date like 2025-04-05, after `today`
+pub fn parse_until(input: &str, today: NaiveDate) -> Result<NaiveDate, String> {
+    let input = input.trim();
+    match NaiveDate::parse_from_str(input, "%Y-%m-%d") {
+        Ok(until) if until > today => Ok(until),
+        Ok(_) => Err("The snooze has to last past today".to_string()),
+        Err(_) => Err(format!("\"{}\" should be a date like 2025-04-05", input)),
+    }
+}
+
+// "Snoozed until Apr 5"
+pub fn describe(until: NaiveDate) -> String {
+    format!("Snoozed until {}", format::month_day(until))
+}
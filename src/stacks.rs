@@ -0,0 +1,51 @@
+use std::collections::HashMap;
+
+// One place in the card grid: a card of its own, or the cards sharing a zone
+#[derive(Debug, Clone, PartialEq)]
+pub enum GridItem {
+    Card(usize),
+    Stack { zone: String, members: Vec<usize> },
+}
+
+// Groups cards (given by zone id, in grid order) that share a zone into a stack
+// where the first of them is, unless that zone's stack has been expanded
+pub fn grid_items(zones: &[&str], expanded: &[String]) -> Vec<GridItem> {
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for zone in zones {
+        *counts.entry(zone).or_default() += 1;
+    }
+    let mut items = Vec::new();
+    let mut stacks: HashMap<&str, usize> = HashMap::new();
+    for (index, &zone) in zones.iter().enumerate() {
+        if counts[zone] < 2 || expanded.iter().any(|open| open == zone) {
+            items.push(GridItem::Card(index));
+            continue;
+        }
+        match stacks.get(zone) {
+            Some(&item) => {
+                if let GridItem::Stack { members, .. } = &mut items[item] {
+                    members.push(index);
+                }
+            }
+            None => {
+                stacks.insert(zone, items.len());
+                items.push(GridItem::Stack {
+                    zone: zone.to_string(),
+                    members: vec![index],
+                });
+            }
+        }
+    }
+    items
+}
+
+// Zones more than one card is in, in grid order
+pub fn shared_zones<'a>(zones: &[&'a str]) -> Vec<&'a str> {
+    let mut shared: Vec<&str> = Vec::new();
+    for (index, zone) in zones.iter().enumerate() {
+        if zones[index + 1..].contains(zone) && !shared.contains(zone) {
+            shared.push(zone);
+        }
+    }
+    shared
+}
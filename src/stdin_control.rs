@@ -0,0 +1,85 @@
+use crate::config::{self, CityConfig};
+use std::{
+    io::{self, BufRead},
+    path::PathBuf,
+    sync::mpsc::Sender,
+    thread,
+};
+
+// One line of `--stdin-control` input
+#[derive(Debug, Clone, PartialEq)]
+pub enum ControlCommand {
+    Add(CityConfig),
+    Remove(Vec<String>), // one or more, separated by ';'; names or card ids
+    SetHome(String),     // a name or a card id
+    Snapshot(PathBuf),
+}
+
+const USAGE: &str = "expected add NAME=TZ, remove CITY[; CITY…], set-home CITY or snapshot PATH, where CITY is a name or a card id";
+
+pub fn parse_command(line: &str) -> Result<ControlCommand, String> {
+    let (verb, argument) = line
+        .trim()
+        .split_once(char::is_whitespace)
+        .map_or((line.trim(), ""), |(verb, argument)| {
+            (verb, argument.trim())
+        });
+    let required = |what: &str| {
+        if argument.is_empty() {
+            Err(format!("{} needs {}", verb, what))
+        } else {
+            Ok(argument.to_string())
+        }
+    };
+    match verb {
+        "add" => {
+            let entry = required("a city, like Tokyo=Asia/Tokyo")?;
+            let (mut cities, errors) = config::parse_city_list(&entry);
+            match (cities.pop(), errors.first()) {
+                (_, Some(error)) => Err(error.clone()),
+                (Some(city), None) if cities.is_empty() => Ok(ControlCommand::Add(city)),
+                _ => Err("add takes one city at a time".to_string()),
+            }
+        }
+        "remove" => {
+            let names: Vec<String> = required("a city name")?
+                .split(';')
+                .map(str::trim)
+                .filter(|name| !name.is_empty())
+                .map(str::to_string)
+                .collect();
+            if names.is_empty() {
+                return Err("remove needs a city name".to_string());
+            }
+            Ok(ControlCommand::Remove(names))
+        }
+        "set-home" => required("a city name").map(ControlCommand::SetHome),
+        "snapshot" => required("a file path")
+            .map(PathBuf::from)
+            .map(ControlCommand::Snapshot),
+        verb => Err(format!("unknown command \"{}\"; {}", verb, USAGE)),
+    }
+}
+
+// Reads commands until stdin closes; the app keeps running after that.
+// Blank lines and lines starting with '#' are skipped.
+pub fn spawn_reader(commands: Sender<ControlCommand>) {
+    thread::spawn(move || {
+        for (index, line) in io::stdin().lock().lines().enumerate() {
+            let Ok(line) = line else {
+                break;
+            };
+            if line.trim().is_empty() || line.trim_start().starts_with('#') {
+                continue;
+            }
+            match parse_command(&line) {
+                Ok(command) => {
+                    if commands.send(command).is_err() {
+                        break;
+                    }
+                }
+                Err(err) => eprintln!("time2rust: stdin line {}: {}", index + 1, err),
+            }
+        }
+    });
+}
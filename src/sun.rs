@@ -0,0 +1,138 @@
+use crate::places::Coordinates;
+use chrono::{DateTime, Datelike, Timelike, Utc};
+use std::f64::consts::PI;
+
+// Where the sun is directly overhead at `now`, from the NOAA approximations for
+// declination and the equation of time; good to a fraction of a degree
+pub fn subsolar_point(now: DateTime<Utc>) -> Coordinates {
+    let hours = now.hour() as f64 + now.minute() as f64 / 60.0 + now.second() as f64 / 3600.0;
+    let gamma = 2.0 * PI / 365.0 * (now.ordinal0() as f64 + (hours - 12.0) / 24.0);
+    let equation_of_time = 229.18
+        * (0.000075 + 0.001868 * gamma.cos()
+            - 0.032077 * gamma.sin()
+            - 0.014615 * (2.0 * gamma).cos()
+            - 0.040849 * (2.0 * gamma).sin());
+    let declination = 0.006918 - 0.399912 * gamma.cos() + 0.070257 * gamma.sin()
+        - 0.006758 * (2.0 * gamma).cos()
+        + 0.000907 * (2.0 * gamma).sin()
+        - 0.002697 * (3.0 * gamma).cos()
+        + 0.00148 * (3.0 * gamma).sin();
+    let lon = -15.0 * (hours - 12.0 + equation_of_time / 60.0);
+    Coordinates {
+        lat: declination.to_degrees(),
+        lon: (lon + 180.0).rem_euclid(360.0) - 180.0,
+    }
+}
+
+// Whether the sun is above the horizon at `place`
+pub fn is_daylight(place: Coordinates, subsolar: Coordinates) -> bool {
+    let (lat, sun_lat) = (place.lat.to_radians(), subsolar.lat.to_radians());
+    let hour_angle = (place.lon - subsolar.lon).to_radians();
+    lat.sin() * sun_lat.sin() + lat.cos() * sun_lat.cos() * hour_angle.cos() > 0.0
+}
+
+// Latitude of the day/night boundary at `lon`. At the equinoxes the boundary is
+// a pair of meridians; a tiny minimum declination keeps it a steep curve instead.
+pub fn terminator_latitude(lon: f64, subsolar: Coordinates) -> f64 {
+    let declination = match subsolar.lat {
+        lat if lat.abs() < 0.01 => 0.01_f64.copysign(lat).to_radians(),
+        lat => lat.to_radians(),
+    };
+    let hour_angle = (lon - subsolar.lon).to_radians();
+    (-hour_angle.cos() / declination.tan()).atan().to_degrees()
+}
+
+// The pole that is in night: the south one while the sun is north of the equator
+pub fn night_pole_latitude(subsolar: Coordinates) -> f64 {
+    if subsolar.lat >= 0.0 { -90.0 } else { 90.0 }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(month: u32, day: u32, hour: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, month, day, hour, 0, 0).unwrap()
+    }
+
+    fn place(lat: f64, lon: f64) -> Coordinates {
+        Coordinates { lat, lon }
+    }
+
+    fn assert_near(actual: f64, expected: f64, tolerance: f64) {
+        assert!(
+            (actual - expected).abs() <= tolerance,
+            "{} is not within {} of {}",
+            actual,
+            tolerance,
+            expected
+        );
+    }
+
+    #[test]
+    fn the_sun_crosses_the_equator_at_the_equinox() {
+        // 14:46 UTC on Mar 20 2026; at noon UTC it's over the prime meridian, give
+        // or take the equation of time
+        let sun = subsolar_point(at(3, 20, 12));
+        assert_near(sun.lat, 0.0, 0.5);
+        assert_near(sun.lon, 0.0, 3.0);
+        // Night moves from the north pole to the south one around it
+        assert_eq!(night_pole_latitude(subsolar_point(at(3, 10, 12))), 90.0);
+        assert_eq!(night_pole_latitude(subsolar_point(at(3, 31, 12))), -90.0);
+    }
+
+    #[test]
+    fn the_sun_reaches_the_tropics_at_the_solstices() {
+        let june = subsolar_point(at(6, 21, 12));
+        assert_near(june.lat, 23.44, 0.3);
+        assert_eq!(night_pole_latitude(june), -90.0);
+
+        let december = subsolar_point(at(12, 21, 12));
+        assert_near(december.lat, -23.44, 0.3);
+        assert_eq!(night_pole_latitude(december), 90.0);
+
+        // Six hours later the sun is a quarter of the way round, further west
+        let evening = subsolar_point(at(6, 21, 18));
+        assert_near(evening.lon - june.lon, -90.0, 0.1);
+    }
+
+    #[test]
+    fn the_terminator_touches_the_polar_circles() {
+        let june = subsolar_point(at(6, 21, 12));
+        // Night reaches up to the Arctic Circle at midnight, day down to the
+        // Antarctic one at noon
+        assert_near(terminator_latitude(june.lon + 180.0, june), 66.56, 0.3);
+        assert_near(terminator_latitude(june.lon, june), -66.56, 0.3);
+        // And crosses the equator at sunrise and sunset
+        assert_near(terminator_latitude(june.lon + 90.0, june), 0.0, 1e-9);
+        assert_near(terminator_latitude(june.lon - 90.0, june), 0.0, 1e-9);
+
+        // At the equinox it's all but two meridians
+        let equinox = Coordinates { lat: 0.0, lon: 0.0 };
+        assert_near(terminator_latitude(170.0, equinox), 89.0, 1.0);
+        assert_near(terminator_latitude(10.0, equinox), -89.0, 1.0);
+    }
+
+    #[test]
+    fn daylight_at_known_places() {
+        let (london, tromso, sydney, quito) = (
+            place(51.51, -0.13),
+            place(69.65, 18.96),
+            place(-33.87, 151.21),
+            place(-0.18, -78.47),
+        );
+        let june_noon = subsolar_point(at(6, 21, 12));
+        let june_midnight = subsolar_point(at(6, 21, 0));
+
+        assert!(is_daylight(london, june_noon));
+        assert!(!is_daylight(london, june_midnight));
+        // The midnight sun
+        assert!(is_daylight(tromso, june_midnight));
+        // 22:00 in Sydney, 07:00 in Quito
+        assert!(!is_daylight(sydney, june_noon));
+        assert!(is_daylight(quito, june_noon));
+        // And the polar night in December
+        assert!(!is_daylight(tromso, subsolar_point(at(12, 21, 11))));
+    }
+}
@@ -0,0 +1,300 @@
+use crate::config::Settings;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+// Seconds between refreshes of the cards
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct UpdateInterval(u64);
+
+impl UpdateInterval {
+    pub const MIN: u64 = 1;
+    pub const MAX: u64 = 15 * 60;
+
+    pub fn new(seconds: u64) -> Result<Self, String> {
+        if (Self::MIN..=Self::MAX).contains(&seconds) {
+            Ok(UpdateInterval(seconds))
+        } else {
+            Err(format!(
+                "update interval must be {} to {} seconds, not {}",
+                Self::MIN,
+                Self::MAX,
+                seconds
+            ))
+        }
+    }
+
+    // Hand-edited configs may hold anything, so clamp on read too
+    pub fn seconds(self) -> u64 {
+        self.0.clamp(Self::MIN, Self::MAX)
+    }
+}
+
+impl Default for UpdateInterval {
+    fn default() -> Self {
+        UpdateInterval(60)
+    }
+}
+
+// At most this long between ticks while a now line is on screen, so it creeps
+// along rather than jumping
+const NOW_LINE_INTERVAL: u64 = 60;
+
+// What the ticker actually runs at: a run-only override wins over the setting,
+// a card showing seconds or the running marker needs a tick every second whatever
+// was chosen, and a visible now line one a minute
+pub fn effective_interval(
+    show_seconds: bool,
+    now_line: bool,
+    settings: &Settings,
+    run_override: Option<UpdateInterval>,
+) -> Duration {
+    if show_seconds {
+        return Duration::from_secs(1);
+    }
+    let mut seconds = run_override.unwrap_or(settings.update_interval).seconds();
+    if now_line {
+        seconds = seconds.min(NOW_LINE_INTERVAL);
+    }
+    Duration::from_secs(seconds)
+}
+
+// Slack for a busy executor before a late tick counts as missed
+const STALE_GRACE: Duration = Duration::from_secs(5);
+
+// Time until the next refresh. Whole-second intervals land on multiples of the
+// interval, so whole minutes flip HH:MM the moment the minute changes rather than
+// up to a minute late, and one-second ticks stay on the second.
+pub fn next_tick_delay(now: DateTime<Utc>, interval: Duration) -> Duration {
+    let interval_ms = interval.as_millis().max(1) as i64;
+    if interval_ms % 1000 != 0 {
+        return interval;
+    }
+    let into_interval = now.timestamp_millis().rem_euclid(interval_ms);
+    Duration::from_millis((interval_ms - into_interval) as u64)
+}
+
+// Interval boundaries that passed between when a tick was due and when its
+// refresh finished. The next delay is measured from the clock, so these are
+// folded into the one refresh that just ran rather than replayed.
+pub fn missed_ticks(due: DateTime<Utc>, done: DateTime<Utc>, interval: Duration) -> u64 {
+    let late_ms = (done - due).num_milliseconds();
+    if late_ms <= 0 {
+        return 0;
+    }
+    late_ms as u64 / interval.as_millis().max(1) as u64
+}
+
+// Ticks land at most one interval apart, so a gap of two means the ticker has
+// missed one and stopped or stalled
+pub fn is_stale(last_tick: DateTime<Utc>, now: DateTime<Utc>, interval: Duration) -> bool {
+    let allowed =
+        chrono::Duration::from_std(interval * 2 + STALE_GRACE).unwrap_or(chrono::Duration::MAX);
+    now - last_tick > allowed
+}
+
+// Whether the ticker keeps up: a check that finds it stale raises the flag, and
+// the next tick that does run lowers it
+#[derive(Debug, Clone, Copy)]
+pub struct TickerHealth {
+    last_tick: DateTime<Utc>,
+    stale_since: Option<DateTime<Utc>>, // the last tick before it stalled
+}
+
+impl TickerHealth {
+    pub fn new(now: DateTime<Utc>) -> Self {
+        TickerHealth {
+            last_tick: now,
+            stale_since: None,
+        }
+    }
+
+    pub fn last_tick(&self) -> DateTime<Utc> {
+        self.last_tick
+    }
+
+    pub fn stale_since(&self) -> Option<DateTime<Utc>> {
+        self.stale_since
+    }
+
+    // True when this tick ends a stale spell
+    pub fn ticked(&mut self, now: DateTime<Utc>) -> bool {
+        self.last_tick = now;
+        self.stale_since.take().is_some()
+    }
+
+    // True only for the check that first finds the ticker stale
+    pub fn check(&mut self, now: DateTime<Utc>, interval: Duration) -> bool {
+        if self.stale_since.is_some() || !is_stale(self.last_tick, now, interval) {
+            return false;
+        }
+        self.stale_since = Some(self.last_tick);
+        true
+    }
+
+    // After the system clock jumps, the old tick time means nothing
+    pub fn reset(&mut self, now: DateTime<Utc>) {
+        *self = TickerHealth::new(now);
+    }
+}
+
+// Any step back is a clock change, but the wall clock also runs ahead of the
+// monotonic one across a sleep, so only a big forward step counts
+const BACKWARD_JUMP: chrono::Duration = chrono::Duration::seconds(2);
+const FORWARD_JUMP: chrono::Duration = chrono::Duration::seconds(60);
+
+// Compares the wall clock with the monotonic one to spot the system time being
+// changed underneath the app
+#[derive(Debug, Clone, Copy)]
+pub struct ClockWatch {
+    wall: DateTime<Utc>,
+    monotonic: Instant,
+}
+
+impl ClockWatch {
+    pub fn new(wall: DateTime<Utc>, monotonic: Instant) -> Self {
+        ClockWatch { wall, monotonic }
+    }
+
+    // How far the wall clock moved beyond the real time that passed, when that's
+    // a jump rather than drift
+    pub fn check(&mut self, wall: DateTime<Utc>, monotonic: Instant) -> Option<chrono::Duration> {
+        let elapsed =
+            chrono::Duration::from_std(monotonic.saturating_duration_since(self.monotonic))
+                .unwrap_or(chrono::Duration::MAX);
+        let jump = (wall - self.wall) - elapsed;
+        *self = ClockWatch::new(wall, monotonic);
+        (jump < -BACKWARD_JUMP || jump > FORWARD_JUMP).then_some(jump)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(seconds: i64) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 3, 20, 9, 0, 0).unwrap() + chrono::Duration::seconds(seconds)
+    }
+
+    #[test]
+    fn stale_only_after_a_missed_tick() {
+        let minute = Duration::from_secs(60);
+        // Two intervals and the grace are still fine
+        assert!(!is_stale(at(0), at(125), minute));
+        assert!(is_stale(at(0), at(126), minute));
+        assert!(!is_stale(at(0), at(7), Duration::from_secs(1)));
+        assert!(is_stale(at(0), at(8), Duration::from_secs(1)));
+    }
+
+    #[test]
+    fn stale_flag_flips_on_after_a_missed_tick_and_off_after_the_next() {
+        let minute = Duration::from_secs(60);
+        let mut health = TickerHealth::new(at(0));
+        assert!(!health.check(at(60), minute));
+        assert!(!health.ticked(at(60)));
+
+        // The tick at 120 never comes
+        assert!(!health.check(at(150), minute));
+        assert!(health.check(at(200), minute));
+        assert_eq!(health.stale_since(), Some(at(60)));
+        // Raised once, not on every check while it lasts
+        assert!(!health.check(at(260), minute));
+        assert_eq!(health.stale_since(), Some(at(60)));
+
+        assert!(health.ticked(at(261)));
+        assert_eq!(health.stale_since(), None);
+        assert_eq!(health.last_tick(), at(261));
+        assert!(!health.check(at(300), minute));
+    }
+
+    #[test]
+    fn a_reset_clears_the_flag() {
+        let mut health = TickerHealth::new(at(0));
+        assert!(health.check(at(600), Duration::from_secs(60)));
+        health.reset(at(600));
+        assert_eq!(health.stale_since(), None);
+        assert_eq!(health.last_tick(), at(600));
+    }
+
+    #[test]
+    fn clock_watch_reports_a_step_backwards() {
+        let start = Instant::now();
+        let mut watch = ClockWatch::new(at(0), start);
+        // Ten real seconds, and the wall clock agrees
+        assert_eq!(watch.check(at(10), start + Duration::from_secs(10)), None);
+        // Ten more real seconds, but the clock was set back 43 minutes
+        let jump = watch.check(at(20 - 43 * 60), start + Duration::from_secs(20));
+        assert_eq!(jump, Some(chrono::Duration::minutes(-43)));
+        // Measured from the new time from then on
+        assert_eq!(
+            watch.check(at(30 - 43 * 60), start + Duration::from_secs(30)),
+            None
+        );
+    }
+
+    #[test]
+    fn clock_watch_ignores_drift_and_sleep_sized_gaps() {
+        let start = Instant::now();
+        let mut watch = ClockWatch::new(at(0), start);
+        // A second's drift either way
+        assert_eq!(watch.check(at(9), start + Duration::from_secs(10)), None);
+        assert_eq!(watch.check(at(21), start + Duration::from_secs(20)), None);
+        // Up to a minute ahead is what a short sleep looks like
+        assert_eq!(watch.check(at(90), start + Duration::from_secs(30)), None);
+        let jump = watch.check(at(3690), start + Duration::from_secs(40));
+        assert_eq!(jump, Some(chrono::Duration::seconds(3590)));
+    }
+
+    #[test]
+    fn next_tick_lands_on_the_interval_boundary() {
+        let minute = Duration::from_secs(60);
+        assert_eq!(next_tick_delay(at(0), minute), minute);
+        assert_eq!(next_tick_delay(at(59), minute), Duration::from_secs(1));
+        let late = at(15) + chrono::Duration::milliseconds(250);
+        assert_eq!(next_tick_delay(late, minute), Duration::from_millis(44_750));
+        assert_eq!(
+            next_tick_delay(late, Duration::from_secs(1)),
+            Duration::from_millis(750)
+        );
+        // Fractional intervals aren't aligned
+        let fraction = Duration::from_millis(1500);
+        assert_eq!(next_tick_delay(late, fraction), fraction);
+    }
+
+    #[test]
+    fn missed_ticks_counts_whole_intervals_past_due() {
+        let minute = Duration::from_secs(60);
+        assert_eq!(missed_ticks(at(60), at(59), minute), 0);
+        assert_eq!(missed_ticks(at(60), at(60), minute), 0);
+        assert_eq!(missed_ticks(at(60), at(119), minute), 0);
+        assert_eq!(missed_ticks(at(60), at(120), minute), 1);
+        assert_eq!(missed_ticks(at(60), at(210), minute), 2);
+    }
+
+    #[test]
+    fn a_slow_refresh_is_followed_by_one_catch_up_tick() {
+        // The ticker loop on a simulated clock: wait for the next boundary, then
+        // run the refresh, which takes 150s on the second tick and 1s otherwise
+        let minute = Duration::from_secs(60);
+        let mut now = at(0);
+        let mut runs = Vec::new();
+        let mut missed = Vec::new();
+        for tick in 0..4 {
+            let due = now + chrono::Duration::from_std(next_tick_delay(now, minute)).unwrap();
+            now = due;
+            let started = now;
+            now += chrono::Duration::seconds(if tick == 1 { 150 } else { 1 });
+            runs.push((started, now));
+            missed.push(missed_ticks(due, now, minute));
+        }
+        // No refresh starts before the last one finished
+        assert!(runs.windows(2).all(|pair| pair[1].0 >= pair[0].1));
+        // The boundaries at 180 and 240 passed during the slow one; a single
+        // refresh at 300 catches up instead of two running back to back
+        let starts: Vec<_> = runs.iter().map(|run| run.0).collect();
+        assert_eq!(starts, [at(60), at(120), at(300), at(360)]);
+        assert_eq!(missed, [0, 2, 0, 0]);
+    }
+}
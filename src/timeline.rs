@@ -0,0 +1,117 @@
+use crate::planner::{self, DayPart, SLOT_MINUTES, WorkingHours};
+use chrono::{DateTime, Duration, DurationRound, Utc};
+use chrono_tz::Tz;
+use gpui::{SharedString, div, prelude::*, px, relative, rgb};
+use gpui_component::tooltip::Tooltip;
+use time2rust::format;
+
+pub const HOURS: i64 = 24;
+const CELLS: i64 = HOURS * 60 / SLOT_MINUTES;
+
+// The shared absolute axis every card's band is drawn on: the next 24 hours
+// starting at the top of the current hour
+#[derive(Debug, Clone)]
+pub struct TimelineAxis {
+    pub start: DateTime<Utc>,
+    pub now: DateTime<Utc>,
+    pub live: bool, // the clock rather than a picked time, which holds the line still
+    // Local times in every city at each cell, shown on hover
+    pub labels: Vec<SharedString>,
+}
+
+impl TimelineAxis {
+    pub fn new(now: DateTime<Utc>, live: bool, cities: &[(String, Tz)]) -> Self {
+        let start = now.duration_trunc(Duration::hours(1)).unwrap_or(now);
+        let labels = (0..CELLS)
+            .map(|cell| {
+                let instant = cell_start(start, cell);
+                cities
+                    .iter()
+                    .map(|(name, tz)| {
+                        format!(
+                            "{} {}",
+                            name,
+                            format::weekday_time(instant.with_timezone(tz).naive_local())
+                        )
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n")
+                    .into()
+            })
+            .collect();
+        TimelineAxis {
+            start,
+            now,
+            live,
+            labels,
+        }
+    }
+}
+
+fn cell_start(start: DateTime<Utc>, cell: i64) -> DateTime<Utc> {
+    start + Duration::minutes(cell * SLOT_MINUTES)
+}
+
+// One card's band: the part of the day at each cell, in that city's own zone, so a
+// DST change inside the window shifts the colors where it happens
+#[derive(Debug, Clone)]
+pub struct TimelineBand {
+    pub axis: TimelineAxis,
+    pub parts: Vec<DayPart>,
+}
+
+impl TimelineBand {
+    pub fn new(axis: TimelineAxis, tz: Tz, hours: &WorkingHours) -> Self {
+        let parts = (0..CELLS)
+            .map(|cell| {
+                let local = cell_start(axis.start, cell).with_timezone(&tz).time();
+                planner::day_part(local, hours)
+            })
+            .collect();
+        TimelineBand { axis, parts }
+    }
+}
+
+fn part_color(part: DayPart) -> gpui::Rgba {
+    match part {
+        DayPart::Night => rgb(0x1e3a8a),
+        DayPart::Working => rgb(0x22c55e),
+        DayPart::Evening => rgb(0xfbbf24),
+    }
+}
+
+pub fn timeline_band(id: &str, band: &TimelineBand) -> impl IntoElement {
+    // Real minutes on an absolute axis, so DST changes in the window don't move it
+    let elapsed = (band.axis.now - band.axis.start).num_seconds() as f32 / 60.0;
+    let now_fraction = (elapsed / (HOURS * 60) as f32).clamp(0.0, 1.0);
+
+    div()
+        .relative()
+        .flex()
+        .w_full()
+        .h(px(10.0))
+        .rounded(px(2.0))
+        .overflow_hidden()
+        .children(band.parts.iter().enumerate().map(|(cell, part)| {
+            let label = band.axis.labels[cell].clone();
+            div()
+                .id(SharedString::from(format!("{}-timeline-{}", id, cell)))
+                .flex_1()
+                .h_full()
+                .bg(part_color(*part))
+                .tooltip(move |window, cx| Tooltip::new(label.clone()).build(window, cx))
+        }))
+        .child(
+            div()
+                .absolute()
+                .top_0()
+                .bottom_0()
+                .left(relative(now_fraction))
+                .w(px(2.0))
+                .bg(if band.axis.live {
+                    rgb(0xef4444)
+                } else {
+                    rgb(0x9ca3af)
+                }),
+        )
+}
@@ -0,0 +1,120 @@
+use crate::config::{CityConfig, Settings};
+
+// Kept small: undo is for "oops, not that", not for history
+const LIMIT: usize = 20;
+
+// What the city list and settings looked like before a destructive action
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    pub label: String,
+    pub cities: Vec<CityConfig>,
+    pub settings: Settings,
+}
+
+#[derive(Debug, Default)]
+pub struct UndoStack {
+    entries: Vec<Snapshot>,
+    open: usize, // transactions in progress; nested ones fold into the outermost
+}
+
+impl UndoStack {
+    // Inside a transaction the snapshot taken when it began already covers this
+    pub fn push(&mut self, snapshot: Snapshot) {
+        if self.open > 0 {
+            return;
+        }
+        if self.entries.len() == LIMIT {
+            self.entries.remove(0);
+        }
+        self.entries.push(snapshot);
+    }
+
+    pub fn pop(&mut self) -> Option<Snapshot> {
+        self.entries.pop()
+    }
+
+    // Starts a group of changes that undo as one step, back to `snapshot`
+    pub fn begin(&mut self, snapshot: Snapshot) {
+        self.push(snapshot);
+        self.open += 1;
+    }
+
+    pub fn end(&mut self) {
+        self.open = self.open.saturating_sub(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(label: &str, cities: &[CityConfig]) -> Snapshot {
+        Snapshot {
+            label: label.to_string(),
+            cities: cities.to_vec(),
+            settings: Settings::default(),
+        }
+    }
+
+    fn names(cities: &[CityConfig]) -> Vec<&str> {
+        cities.iter().map(|city| city.name.as_str()).collect()
+    }
+
+    #[test]
+    fn one_undo_restores_a_bulk_delete_in_order() {
+        let mut cities: Vec<CityConfig> = ["Austin", "Berlin", "Tokyo", "Lagos", "Lima"]
+            .iter()
+            .enumerate()
+            .map(|(i, name)| CityConfig::new(name, "Etc/UTC", i == 0))
+            .collect();
+        let mut undo = UndoStack::default();
+        undo.push(snapshot("adding Lima", &cities[..4]));
+
+        // As removing several cards does: each removal pushes its own step
+        undo.begin(snapshot("removing 3 cities", &cities));
+        for name in ["Tokyo", "Berlin", "Lima"] {
+            undo.push(snapshot(&format!("removing {}", name), &cities));
+            cities.retain(|city| city.name != name);
+        }
+        undo.end();
+        assert_eq!(names(&cities), ["Austin", "Lagos"]);
+
+        let restored = undo.pop().unwrap();
+        assert_eq!(restored.label, "removing 3 cities");
+        assert_eq!(
+            names(&restored.cities),
+            ["Austin", "Berlin", "Tokyo", "Lagos", "Lima"]
+        );
+        // The step before the transaction is next, not a leftover removal
+        assert_eq!(undo.pop().unwrap().label, "adding Lima");
+        assert!(undo.pop().is_none());
+    }
+
+    #[test]
+    fn nested_transactions_fold_into_the_outermost() {
+        let mut undo = UndoStack::default();
+        undo.begin(snapshot("outer", &[]));
+        undo.begin(snapshot("inner", &[]));
+        undo.end();
+        undo.push(snapshot("still inside", &[]));
+        undo.end();
+        undo.push(snapshot("after", &[]));
+        assert_eq!(undo.pop().unwrap().label, "after");
+        assert_eq!(undo.pop().unwrap().label, "outer");
+        assert!(undo.pop().is_none());
+    }
+
+    #[test]
+    fn only_the_last_steps_are_kept() {
+        let mut undo = UndoStack::default();
+        for step in 0..LIMIT + 5 {
+            undo.push(snapshot(&step.to_string(), &[]));
+        }
+        let labels: Vec<String> = std::iter::from_fn(|| undo.pop())
+            .map(|snapshot| snapshot.label)
+            .collect();
+        assert_eq!(labels.len(), LIMIT);
+        assert_eq!(labels.first().map(String::as_str), Some("24"));
+        assert_eq!(labels.last().map(String::as_str), Some("5"));
+    }
+}
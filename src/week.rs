@@ -0,0 +1,218 @@
+use crate::planner::WorkingHours;
+use chrono::{Datelike, Days, NaiveDate, NaiveDateTime, Weekday};
+use serde::{Deserialize, Serialize};
+
+// The days a city doesn't work, stored as ["Sat", "Sun"]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(from = "Vec<Weekday>", into = "Vec<Weekday>")]
+pub struct Weekend(u8); // bit n is n days from Monday
+
+impl Weekend {
+    pub const SAT_SUN: Weekend = Weekend(1 << 5 | 1 << 6);
+
+    pub fn contains(self, day: Weekday) -> bool {
+        self.0 & 1 << day.num_days_from_monday() != 0
+    }
+
+    pub fn toggled(self, day: Weekday) -> Weekend {
+        Weekend(self.0 ^ 1 << day.num_days_from_monday())
+    }
+
+    pub fn is_day_off(self, date: NaiveDate) -> bool {
+        self.contains(date.weekday())
+    }
+
+    // "Fri, Sat", or "none"
+    pub fn describe(self) -> String {
+        let days: Vec<Weekday> = self.into();
+        if days.is_empty() {
+            return "none".to_string();
+        }
+        days.iter()
+            .map(Weekday::to_string)
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+impl Default for Weekend {
+    fn default() -> Self {
+        Weekend::SAT_SUN
+    }
+}
+
+impl From<Vec<Weekday>> for Weekend {
+    fn from(days: Vec<Weekday>) -> Self {
+        Weekend(
+            days.iter()
+                .fold(0, |bits, day| bits | 1 << day.num_days_from_monday()),
+        )
+    }
+}
+
+impl From<Weekend> for Vec<Weekday> {
+    fn from(weekend: Weekend) -> Self {
+        WEEK.into_iter()
+            .filter(|day| weekend.contains(*day))
+            .collect()
+    }
+}
+
+const WEEK: [Weekday; 7] = [
+    Weekday::Mon,
+    Weekday::Tue,
+    Weekday::Wed,
+    Weekday::Thu,
+    Weekday::Fri,
+    Weekday::Sat,
+    Weekday::Sun,
+];
+
+// How weeks are laid out for everything weekly: the week export, the hour table's
+// shading, the overlap finder and the workday line. Cities can have their own weekend.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WeekConfig {
+    pub first_day: Weekday,
+    pub weekend: Weekend, // for cities without their own
+}
+
+impl Default for WeekConfig {
+    fn default() -> Self {
+        WeekConfig {
+            first_day: Weekday::Mon,
+            weekend: Weekend::SAT_SUN,
+        }
+    }
+}
+
+impl WeekConfig {
+    // The seven days in order, from `first_day`
+    pub fn days(self) -> [Weekday; 7] {
+        let mut days = WEEK;
+        days.rotate_left(self.first_day.num_days_from_monday() as usize);
+        days
+    }
+
+    // The first day of the week `date` falls in
+    pub fn week_start(self, date: NaiveDate) -> NaiveDate {
+        let back = date.weekday().days_since(self.first_day);
+        date.checked_sub_days(Days::new(back as u64))
+            .unwrap_or(date)
+    }
+}
+
+// Within working hours on a working day. A shift belongs to the day it starts on, so
+// Friday's night shift still counts on Saturday morning while Saturday stays off.
+pub fn working_at(local: NaiveDateTime, hours: &WorkingHours, weekend: Weekend) -> bool {
+    if !hours.contains(local.time()) {
+        return false;
+    }
+    let night_shift_tail = hours.end <= hours.start && local.time() < hours.end;
+    let shift_day = if night_shift_tail {
+        local.date().pred_opt().unwrap_or(local.date())
+    } else {
+        local.date()
+    };
+    !weekend.is_day_off(shift_day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::planner::{self, DoNotDisturb};
+    use chrono::{NaiveTime, TimeZone, Utc};
+
+    // Fri Jul 3 2026 at `hour`, `days` later
+    fn at(days: u64, hour: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2026, 7, 3)
+            .unwrap()
+            .checked_add_days(Days::new(days))
+            .unwrap()
+            .and_hms_opt(hour, 0, 0)
+            .unwrap()
+    }
+
+    fn hours(start: u32, end: u32) -> WorkingHours {
+        WorkingHours {
+            start: NaiveTime::from_hms_opt(start, 0, 0).unwrap(),
+            end: NaiveTime::from_hms_opt(end, 0, 0).unwrap(),
+        }
+    }
+
+    #[test]
+    fn a_night_shift_belongs_to_the_day_it_starts() {
+        let (night, weekend) = (hours(22, 6), Weekend::default());
+        assert!(working_at(at(0, 23), &night, weekend));
+        // Friday's shift runs into Saturday; Saturday's own doesn't start
+        assert!(working_at(at(1, 2), &night, weekend));
+        assert!(!working_at(at(1, 6), &night, weekend));
+        assert!(!working_at(at(1, 22), &night, weekend));
+        // Sunday's doesn't either, so Monday morning is free until 22:00
+        assert!(!working_at(at(3, 2), &night, weekend));
+        assert!(working_at(at(3, 22), &night, weekend));
+    }
+
+    #[test]
+    fn a_day_shift_stays_on_its_day() {
+        let (day, weekend) = (hours(9, 17), Weekend::default());
+        assert!(working_at(at(0, 9), &day, weekend));
+        assert!(!working_at(at(0, 17), &day, weekend));
+        assert!(!working_at(at(1, 12), &day, weekend));
+        assert!(working_at(
+            at(1, 12),
+            &day,
+            Weekend::from(vec![Weekday::Fri])
+        ));
+    }
+
+    #[test]
+    fn a_friday_saturday_weekend_in_the_overlap_finder() {
+        // London works 08–16 UTC in July, Dubai 05–13 UTC but not on Fri or Sat
+        let no_dnd = DoNotDisturb {
+            start: NaiveTime::MIN,
+            end: NaiveTime::MIN,
+        };
+        let cities = [
+            (
+                chrono_tz::Europe::London,
+                hours(9, 17),
+                no_dnd,
+                Weekend::default(),
+            ),
+            (
+                chrono_tz::Asia::Dubai,
+                hours(9, 17),
+                no_dnd,
+                Weekend::from(vec![Weekday::Fri, Weekday::Sat]),
+            ),
+        ];
+        let utc = |day, hour| Utc.with_ymd_and_hms(2026, 7, day, hour, 0, 0).unwrap();
+
+        // Friday to Monday: only Monday has both
+        let overlap = planner::find_overlaps(&cities, utc(3, 0), 4, 3);
+        assert!(overlap.complete);
+        let windows: Vec<_> = overlap
+            .windows
+            .iter()
+            .map(|window| (window.start, window.end))
+            .collect();
+        assert_eq!(windows, [(utc(6, 8), utc(6, 13))]);
+
+        // Friday to Sunday: London on Friday, Dubai on Sunday, never together
+        let overlap = planner::find_overlaps(&cities, utc(3, 0), 3, 3);
+        assert!(!overlap.complete);
+        let windows: Vec<_> = overlap
+            .windows
+            .iter()
+            .map(|window| (window.start, window.end, window.working.clone()))
+            .collect();
+        assert_eq!(
+            windows,
+            [
+                (utc(3, 8), utc(3, 16), vec![true, false]),
+                (utc(5, 5), utc(5, 13), vec![false, true]),
+            ]
+        );
+    }
+}
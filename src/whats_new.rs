@@ -0,0 +1,215 @@
+use crate::settings_panel::{hint, section_title};
+use gpui::{App, Window, div, prelude::*};
+use gpui_component::{WindowExt as _, dialog::DialogButtonProps, v_flex};
+use std::cmp::Ordering;
+
+pub struct Release {
+    pub version: &'static str,
+    pub notes: &'static [&'static str],
+}
+
+// Newest first; add an entry when bumping the version in Cargo.toml
+pub const RELEASES: &[Release] = &[Release {
+    version: "0.1.0",
+    notes: &[
+        "Plan meetings across cities and find overlapping working hours",
+        "Profiles: keep several sets of cities and switch between them",
+        "Kiosk mode for a wall display",
+        "Cities sharing a time zone can stack into one card",
+        "Click a card's difference to cycle how it's worded",
+        "Choose the first day of the week and each city's days off",
+        "Earlier saves of the config are kept and can be restored",
+    ],
+}];
+
+pub const CURRENT: &str = env!("CARGO_PKG_VERSION");
+
+// MAJOR.MINOR.PATCH with an optional -prerelease; build metadata is ignored
+#[derive(Debug, PartialEq, Eq)]
+struct Version {
+    core: [u64; 3],
+    pre: Vec<String>,
+}
+
+fn parse_version(text: &str) -> Option<Version> {
+    let text = text.trim().trim_start_matches('v');
+    let text = text.split('+').next()?;
+    let (core, pre) = match text.split_once('-') {
+        Some((core, pre)) => (core, pre.split('.').map(str::to_string).collect()),
+        None => (text, Vec::new()),
+    };
+    let mut parts = core.split('.').map(|part| part.parse::<u64>().ok());
+    let version = Version {
+        core: [parts.next()??, parts.next()??, parts.next()??],
+        pre,
+    };
+    parts.next().is_none().then_some(version)
+}
+
+// Numeric identifiers compare as numbers and sort before alphanumeric ones
+fn compare_identifiers(a: &str, b: &str) -> Ordering {
+    match (a.parse::<u64>(), b.parse::<u64>()) {
+        (Ok(a), Ok(b)) => a.cmp(&b),
+        (Ok(_), Err(_)) => Ordering::Less,
+        (Err(_), Ok(_)) => Ordering::Greater,
+        (Err(_), Err(_)) => a.cmp(b),
+    }
+}
+
+impl Ord for Version {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.core.cmp(&other.core).then_with(|| {
+            // A prerelease comes before its release
+            match (self.pre.is_empty(), other.pre.is_empty()) {
+                (true, true) => Ordering::Equal,
+                (true, false) => Ordering::Greater,
+                (false, true) => Ordering::Less,
+                (false, false) => self
+                    .pre
+                    .iter()
+                    .zip(&other.pre)
+                    .map(|(a, b)| compare_identifiers(a, b))
+                    .find(|ordering| ordering.is_ne())
+                    .unwrap_or_else(|| self.pre.len().cmp(&other.pre.len())),
+            }
+        })
+    }
+}
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+// Releases after `last_seen` up to and including `current`. Nothing seen yet, or a
+// version that doesn't parse, counts as having seen none; going back to an older
+// version shows nothing.
+pub fn unseen<'a>(
+    releases: &'a [Release],
+    last_seen: Option<&str>,
+    current: &str,
+) -> Vec<&'a Release> {
+    let Some(current) = parse_version(current) else {
+        return Vec::new();
+    };
+    let last_seen = last_seen.and_then(parse_version);
+    releases
+        .iter()
+        .filter(|release| {
+            parse_version(release.version).is_some_and(|version| {
+                version <= current && last_seen.as_ref().is_none_or(|seen| version > *seen)
+            })
+        })
+        .collect()
+}
+
+// Whether `version` is past `than`, so the last seen version only ever moves forward
+pub fn is_newer(version: &str, than: Option<&str>) -> bool {
+    match (parse_version(version), than.and_then(parse_version)) {
+        (Some(version), Some(than)) => version > than,
+        (version, _) => version.is_some(),
+    }
+}
+
+pub fn should_show(last_seen: Option<&str>, current: &str) -> bool {
+    !unseen(RELEASES, last_seen, current).is_empty()
+}
+
+// "0.3" for "0.3.1"
+fn short_version(version: &str) -> String {
+    version.splitn(3, '.').take(2).collect::<Vec<_>>().join(".")
+}
+
+// Everything since `last_seen`, or every release when reopened from the palette
+pub fn open_whats_new(last_seen: Option<&str>, window: &mut Window, cx: &mut App) {
+    let mut releases = unseen(RELEASES, last_seen, CURRENT);
+    if releases.is_empty() {
+        releases = RELEASES.iter().collect();
+    }
+    let several = releases.len() > 1;
+    window.open_dialog(cx, move |dialog, _window, _cx| {
+        dialog
+            .title(format!("What's new in {}", short_version(CURRENT)))
+            .child(
+                v_flex()
+                    .gap_3()
+                    .children(releases.iter().map(|release| {
+                        v_flex()
+                            .gap_1()
+                            .children(several.then(|| section_title(release.version)))
+                            .children(
+                                release
+                                    .notes
+                                    .iter()
+                                    .map(|note| div().child(format!("• {}", note)).text_sm()),
+                            )
+                    }))
+                    .child(hint("Reopen this from the command palette: What's new")),
+            )
+            .alert()
+            .button_props(DialogButtonProps::default().ok_text("Got it"))
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HISTORY: &[Release] = &[
+        Release {
+            version: "0.10.0",
+            notes: &["Ten"],
+        },
+        Release {
+            version: "0.9.1",
+            notes: &["Nine, patched"],
+        },
+        Release {
+            version: "0.9.0",
+            notes: &["Nine"],
+        },
+    ];
+
+    fn versions(releases: Vec<&Release>) -> Vec<&str> {
+        releases.iter().map(|release| release.version).collect()
+    }
+
+    #[test]
+    fn is_newer_compares_each_part_as_a_number() {
+        assert!(is_newer("0.1.0", None));
+        assert!(!is_newer("0.1.0", Some("0.1.0")));
+        assert!(is_newer("0.1.1", Some("0.1.0")));
+        assert!(is_newer("0.10.0", Some("0.9.0")));
+        assert!(!is_newer("0.9.0", Some("0.10.0")));
+        assert!(is_newer("1.0.0", Some("1.0.0-rc.1")));
+        // A bad last seen version counts as none; a bad new one never replaces it
+        assert!(is_newer("0.1.0", Some("banana")));
+        assert!(!is_newer("0.1", Some("0.0.9")));
+        assert!(!is_newer("banana", None));
+    }
+
+    #[test]
+    fn first_run_and_the_same_version() {
+        assert!(should_show(None, CURRENT));
+        assert!(!should_show(Some(CURRENT), CURRENT));
+        assert!(should_show(Some("garbage"), CURRENT));
+        assert!(!should_show(None, "not-a-version"));
+        assert_eq!(
+            versions(unseen(HISTORY, None, "0.10.0")),
+            ["0.10.0", "0.9.1", "0.9.0"]
+        );
+    }
+
+    #[test]
+    fn a_patch_bump_shows_only_its_own_notes() {
+        assert_eq!(versions(unseen(HISTORY, Some("0.9.0"), "0.9.1")), ["0.9.1"]);
+        assert_eq!(
+            versions(unseen(HISTORY, Some("0.9.0"), "0.10.0")),
+            ["0.10.0", "0.9.1"]
+        );
+        // Nothing when going back, or to a version with no notes of its own
+        assert!(unseen(HISTORY, Some("0.10.0"), "0.9.1").is_empty());
+        assert!(unseen(HISTORY, Some("0.10.0"), "0.10.1").is_empty());
+    }
+}
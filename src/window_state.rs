@@ -0,0 +1,287 @@
+use gpui::{
+    App, Bounds, DisplayId, Hsla, Pixels, Size, Window, WindowBackgroundAppearance, WindowBounds,
+    point, px, size,
+};
+use serde::{Deserialize, Serialize};
+
+pub const DEFAULT_WIDTH: f32 = 800.0;
+pub const DEFAULT_HEIGHT: f32 = 600.0;
+// Small enough for a corner of the screen, large enough that the header still fits
+pub const MIN_WIDTH: f32 = 480.0;
+pub const MIN_HEIGHT: f32 = 320.0;
+
+// How much of the window must still be on a display to restore it there
+const MIN_VISIBLE: f32 = 50.0;
+// Gap kept from the display edges when a window has to be moved onto it
+const MARGIN: f32 = 24.0;
+
+// Window background opacity in percent; cards stay opaque so text keeps its contrast
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Opacity(u8);
+
+impl Opacity {
+    pub const MIN: u8 = 60;
+    pub const MAX: u8 = 100;
+
+    pub fn new(percent: u8) -> Self {
+        Opacity(percent.clamp(Self::MIN, Self::MAX))
+    }
+
+    // Hand-edited configs may hold anything, so clamp on read too
+    pub fn percent(self) -> u8 {
+        self.0.clamp(Self::MIN, Self::MAX)
+    }
+
+    pub fn background(self) -> WindowBackgroundAppearance {
+        if self.percent() < Self::MAX {
+            WindowBackgroundAppearance::Transparent
+        } else {
+            WindowBackgroundAppearance::Opaque
+        }
+    }
+
+    pub fn apply(self, color: Hsla) -> Hsla {
+        color.opacity(self.percent() as f32 / 100.0)
+    }
+}
+
+impl Default for Opacity {
+    fn default() -> Self {
+        Opacity(Self::MAX)
+    }
+}
+
+// Where the window was last left, in global screen coordinates
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WindowState {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+    // Stable display id, so the window returns to the same monitor
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub display: Option<String>,
+    #[serde(default)]
+    pub maximized: bool,
+}
+
+impl WindowState {
+    // While maximized these are the restore bounds, so un-maximizing after a
+    // restart still returns to the old size
+    pub fn capture(window: &Window, cx: &App) -> Self {
+        let window_bounds = window.window_bounds();
+        let bounds = window_bounds.get_bounds();
+        WindowState {
+            x: f32::from(bounds.origin.x),
+            y: f32::from(bounds.origin.y),
+            width: f32::from(bounds.size.width),
+            height: f32::from(bounds.size.height),
+            display: window
+                .display(cx)
+                .and_then(|display| display.uuid().ok())
+                .map(|uuid| uuid.to_string()),
+            maximized: matches!(window_bounds, WindowBounds::Maximized(_)),
+        }
+    }
+
+    // Never smaller than the minimum, whatever a tiling WM or a hand edit left behind
+    pub fn bounds(&self) -> Bounds<Pixels> {
+        let (width, height) = clamp_size(self.width, self.height);
+        Bounds::new(point(px(self.x), px(self.y)), size(px(width), px(height)))
+    }
+}
+
+fn default_size() -> Size<Pixels> {
+    size(px(DEFAULT_WIDTH), px(DEFAULT_HEIGHT))
+}
+
+pub fn min_size() -> Size<Pixels> {
+    size(px(MIN_WIDTH), px(MIN_HEIGHT))
+}
+
+// At least the minimum each way; a size that isn't a number falls back to the default
+pub fn clamp_size(width: f32, height: f32) -> (f32, f32) {
+    let clamp = |value: f32, min: f32, default: f32| {
+        if value.is_finite() {
+            value.max(min)
+        } else {
+            default
+        }
+    };
+    (
+        clamp(width, MIN_WIDTH, DEFAULT_WIDTH),
+        clamp(height, MIN_HEIGHT, DEFAULT_HEIGHT),
+    )
+}
+
+// The saved placement checked against the displays connected now
+pub fn initial_bounds(state: Option<&WindowState>, cx: &App) -> (WindowBounds, Option<DisplayId>) {
+    let Some(state) = state else {
+        return (
+            WindowBounds::Windowed(Bounds::centered(None, default_size(), cx)),
+            None,
+        );
+    };
+    let (bounds, display_id) = restored_bounds(state, cx);
+    if state.maximized {
+        (WindowBounds::Maximized(bounds), display_id)
+    } else {
+        (WindowBounds::Windowed(bounds), display_id)
+    }
+}
+
+// Brings the open window to a saved state, checked against the displays the same
+// way as at startup. gpui can't move a window once it's open, so only the size and
+// whether it's maximized change now; the position is used from the next launch.
+pub fn apply(state: &WindowState, window: &mut Window, cx: &App) {
+    let (bounds, _) = initial_bounds(Some(state), cx);
+    if window.is_maximized() != state.maximized {
+        window.zoom_window();
+    }
+    if !state.maximized {
+        window.resize(bounds.get_bounds().size);
+    }
+}
+
+fn restored_bounds(state: &WindowState, cx: &App) -> (Bounds<Pixels>, Option<DisplayId>) {
+    // Primary first, so it takes a window whose display is gone
+    let primary = cx.primary_display().map(|display| display.id());
+    let mut displays: Vec<(DisplayId, Bounds<Pixels>)> = cx
+        .displays()
+        .iter()
+        .map(|display| (display.id(), display.bounds()))
+        .collect();
+    displays.sort_by_key(|(id, _)| Some(*id) != primary);
+    let saved_display = saved_display(state, cx);
+
+    match place_on_displays(state.bounds(), saved_display, &displays) {
+        Some((display_id, bounds)) => (bounds, Some(display_id)),
+        None => (state.bounds(), None),
+    }
+}
+
+fn saved_display(state: &WindowState, cx: &App) -> Option<DisplayId> {
+    cx.displays()
+        .iter()
+        .find(|display| {
+            display
+                .uuid()
+                .is_ok_and(|uuid| Some(uuid.to_string()) == state.display)
+        })
+        .map(|display| display.id())
+}
+
+// Whether the display the state was saved on is gone, e.g. after undocking
+pub fn display_missing(state: &WindowState, cx: &App) -> bool {
+    state.display.is_some() && saved_display(state, cx).is_none()
+}
+
+// Where saved bounds should go given the connected displays: unchanged if enough of
+// them is visible (on the saved display by preference), otherwise pulled onto the
+// saved display, or the first one, keeping MARGIN from its edges and shrinking to fit
+pub fn place_on_displays<D: Copy + PartialEq>(
+    saved: Bounds<Pixels>,
+    saved_display: Option<D>,
+    displays: &[(D, Bounds<Pixels>)],
+) -> Option<(D, Bounds<Pixels>)> {
+    let visible_on = |display_bounds: &Bounds<Pixels>| {
+        let overlap = display_bounds.intersect(&saved);
+        overlap.size.width >= px(MIN_VISIBLE) && overlap.size.height >= px(MIN_VISIBLE)
+    };
+    let saved_entry = displays.iter().find(|(id, _)| Some(*id) == saved_display);
+
+    if let Some((id, _)) = saved_entry.filter(|(_, bounds)| visible_on(bounds)) {
+        return Some((*id, saved));
+    }
+    if let Some((id, _)) = displays.iter().find(|(_, bounds)| visible_on(bounds)) {
+        return Some((*id, saved));
+    }
+    let (id, display) = saved_entry.or(displays.first())?;
+    let margin = px(MARGIN);
+    let width = saved.size.width.min(display.size.width - margin * 2.0);
+    let height = saved.size.height.min(display.size.height - margin * 2.0);
+    let clamp = |value: Pixels, low: Pixels, high: Pixels| value.max(low).min(high.max(low));
+    let x = clamp(
+        saved.origin.x,
+        display.origin.x + margin,
+        display.origin.x + display.size.width - margin - width,
+    );
+    let y = clamp(
+        saved.origin.y,
+        display.origin.y + margin,
+        display.origin.y + display.size.height - margin - height,
+    );
+    Some((*id, Bounds::new(point(x, y), size(width, height))))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rect(x: f32, y: f32, width: f32, height: f32) -> Bounds<Pixels> {
+        Bounds::new(point(px(x), px(y)), size(px(width), px(height)))
+    }
+
+    // A 1920×1080 primary with a 1280×1024 display to its right
+    fn displays() -> Vec<(u32, Bounds<Pixels>)> {
+        vec![
+            (1, rect(0.0, 0.0, 1920.0, 1080.0)),
+            (2, rect(1920.0, 0.0, 1280.0, 1024.0)),
+        ]
+    }
+
+    #[test]
+    fn a_window_whose_display_is_gone() {
+        // Still visible on another display: left where it was
+        let saved = rect(2000.0, 100.0, 800.0, 600.0);
+        assert_eq!(
+            place_on_displays(saved, Some(3), &displays()),
+            Some((2, saved))
+        );
+        // Off every display: pulled onto the first, inside the margin
+        let saved = rect(4000.0, 100.0, 800.0, 600.0);
+        assert_eq!(
+            place_on_displays(saved, Some(3), &displays()),
+            Some((1, rect(1096.0, 100.0, 800.0, 600.0)))
+        );
+        assert_eq!(place_on_displays(saved, Some(3), &[]), None);
+    }
+
+    #[test]
+    fn a_window_partly_off_screen() {
+        // MIN_VISIBLE of it is enough to leave it alone
+        let saved = rect(-750.0, 100.0, 800.0, 600.0);
+        assert_eq!(
+            place_on_displays(saved, Some(1), &displays()),
+            Some((1, saved))
+        );
+        // Less, and it comes back onto its display
+        let saved = rect(-760.0, 500.0, 800.0, 600.0);
+        assert_eq!(
+            place_on_displays(saved, Some(1), &displays()),
+            Some((1, rect(24.0, 456.0, 800.0, 600.0)))
+        );
+        // Hanging off the saved display's edge onto the next: whichever shows it
+        let saved = rect(1900.0, 100.0, 800.0, 600.0);
+        assert_eq!(
+            place_on_displays(saved, Some(1), &displays()),
+            Some((2, saved))
+        );
+    }
+
+    #[test]
+    fn an_oversized_window_shrinks_to_fit() {
+        let saved = rect(5000.0, 5000.0, 3000.0, 2000.0);
+        assert_eq!(
+            place_on_displays(saved, Some(2), &displays()),
+            Some((2, rect(1944.0, 24.0, 1232.0, 976.0)))
+        );
+        // Covering a display counts as visible, so that one isn't touched
+        let saved = rect(-100.0, -100.0, 3000.0, 2000.0);
+        assert_eq!(
+            place_on_displays(saved, Some(1), &displays()),
+            Some((1, saved))
+        );
+    }
+}
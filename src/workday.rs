@@ -0,0 +1,177 @@
+use crate::{convert, planner::WorkingHours, week::Weekend};
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use chrono_tz::Tz;
+use time2rust::format;
+
+// Where a city is relative to its working day right now
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Workday {
+    Working {
+        started: Duration,
+        ends_in: Duration,
+    },
+    Before {
+        starts_in: Duration,
+    },
+    DayOff,
+}
+
+// The shift that starts on `date`, as real instants; a night shift ends the next
+// day, and DST days make the span 23 or 25 hours of wall clock
+fn shift(tz: Tz, date: NaiveDate, hours: &WorkingHours) -> (DateTime<Utc>, DateTime<Utc>) {
+    let end_date = if hours.end <= hours.start {
+        date.succ_opt().unwrap_or(date)
+    } else {
+        date
+    };
+    (
+        convert::resolve_local(date.and_time(hours.start), tz).instant,
+        convert::resolve_local(end_date.and_time(hours.end), tz).instant,
+    )
+}
+
+// A shift belongs to the day it starts on, so Friday's night shift runs into
+// Saturday morning while Saturday itself stays off
+pub fn workday(now: DateTime<Utc>, tz: Tz, hours: &WorkingHours, weekend: Weekend) -> Workday {
+    let is_workday = |date: NaiveDate| !weekend.is_day_off(date);
+    let today = now.with_timezone(&tz).date_naive();
+    let yesterday = today.pred_opt().unwrap_or(today);
+    for date in [yesterday, today] {
+        let (start, end) = shift(tz, date, hours);
+        if is_workday(date) && start <= now && now < end {
+            return Workday::Working {
+                started: now - start,
+                ends_in: end - now,
+            };
+        }
+    }
+    if !is_workday(today) {
+        return Workday::DayOff;
+    }
+    today
+        .iter_days()
+        .take(8)
+        .filter(|date| is_workday(*date))
+        .map(|date| shift(tz, date, hours).0)
+        .find(|start| *start > now)
+        .map_or(Workday::DayOff, |start| Workday::Before {
+            starts_in: start - now,
+        })
+}
+
+// Rounded up, so the last minute reads "1m" rather than "0m"
+fn minutes(duration: Duration) -> i64 {
+    (duration.num_seconds() + 59) / 60
+}
+
+// Like "started 2h 5m ago · ends in 5h 55m", "starts in 9h 0m" or "weekend"
+pub fn workday_line(now: DateTime<Utc>, tz: Tz, hours: &WorkingHours, weekend: Weekend) -> String {
+    match workday(now, tz, hours, weekend) {
+        Workday::Working { started, ends_in } => format!(
+            "started {} ago · ends in {}",
+            format::remaining(started.num_minutes()),
+            format::remaining(minutes(ends_in))
+        ),
+        Workday::Before { starts_in } => {
+            format!("starts in {}", format::remaining(minutes(starts_in)))
+        }
+        Workday::DayOff => "weekend".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{NaiveTime, TimeZone};
+    use chrono_tz::Europe::Berlin;
+
+    fn hours(start: u32, end: u32) -> WorkingHours {
+        WorkingHours {
+            start: NaiveTime::from_hms_opt(start, 0, 0).unwrap(),
+            end: NaiveTime::from_hms_opt(end, 0, 0).unwrap(),
+        }
+    }
+
+    fn utc(month: u32, day: u32, hour: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, month, day, hour, 0, 0).unwrap()
+    }
+
+    fn working(started: i64, ends_in: i64) -> Workday {
+        Workday::Working {
+            started: Duration::hours(started),
+            ends_in: Duration::hours(ends_in),
+        }
+    }
+
+    #[test]
+    fn a_night_shift_runs_past_midnight() {
+        // Wed Jul 1, Berlin on UTC+2
+        let night = hours(22, 6);
+        let weekend = Weekend::default();
+        assert_eq!(
+            workday(utc(7, 1, 21), Berlin, &night, weekend),
+            working(1, 7)
+        );
+        assert_eq!(
+            workday(utc(7, 2, 1), Berlin, &night, weekend),
+            working(5, 3)
+        );
+        assert_eq!(
+            workday(utc(7, 2, 5), Berlin, &night, weekend),
+            Workday::Before {
+                starts_in: Duration::hours(15)
+            }
+        );
+        assert_eq!(
+            workday_line(utc(7, 2, 1), Berlin, &night, weekend),
+            "started 5h 0m ago · ends in 3h 0m"
+        );
+    }
+
+    #[test]
+    fn friday_night_runs_into_a_saturday_off() {
+        let night = hours(22, 6);
+        let weekend = Weekend::default();
+        // Sat Jul 4, 02:00 and 07:00 in Berlin
+        assert_eq!(
+            workday(utc(7, 4, 0), Berlin, &night, weekend),
+            working(4, 4)
+        );
+        assert_eq!(
+            workday(utc(7, 4, 5), Berlin, &night, weekend),
+            Workday::DayOff
+        );
+    }
+
+    #[test]
+    fn a_night_shift_over_a_clock_change_is_an_hour_short_or_long() {
+        let night = hours(22, 6);
+        let every_day = Weekend::from(Vec::new());
+        // Sat Mar 28 22:00 CET to Sun 06:00 CEST is seven real hours
+        assert_eq!(
+            workday(utc(3, 29, 3), Berlin, &night, every_day),
+            working(6, 1)
+        );
+        // Sat Oct 24 22:00 CEST to Sun 06:00 CET is nine
+        assert_eq!(
+            workday(utc(10, 25, 3), Berlin, &night, every_day),
+            working(7, 2)
+        );
+    }
+
+    #[test]
+    fn a_whole_day_shift_spans_23_and_25_hour_days() {
+        let all_day = hours(0, 0);
+        let every_day = Weekend::from(Vec::new());
+        // Sun Mar 29 starts at 23:00 UTC the day before and ends at 22:00 UTC
+        assert_eq!(
+            workday(utc(3, 29, 10), Berlin, &all_day, every_day),
+            working(11, 12)
+        );
+        // Sun Oct 25 starts at 22:00 UTC the day before and ends at 23:00 UTC
+        assert_eq!(
+            workday(utc(10, 25, 12), Berlin, &all_day, every_day),
+            working(14, 11)
+        );
+    }
+}
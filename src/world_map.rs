@@ -0,0 +1,737 @@
+use crate::{WorldTimeApp, places::Coordinates, sun};
+use gpui::{
+    Bounds, Context, Hsla, PathBuilder, Pixels, Point, SharedString, Window, canvas, div, fill,
+    point, prelude::*, px, relative, rgb,
+};
+use gpui_component::{ActiveTheme as _, tooltip::Tooltip};
+
+// Latitudes shown; the polar caps would only add empty rows
+const TOP: f64 = 80.0;
+const BOTTOM: f64 = -60.0;
+const DOT: f32 = 8.0;
+
+// Rough coastlines as (lon, lat) rings; enough to place a city at a glance
+const LAND: &[&[(f64, f64)]] = &[
+    // North America
+    &[
+        (-168.0, 66.0),
+        (-162.0, 70.0),
+        (-156.0, 71.3),
+        (-141.0, 69.6),
+        (-128.0, 70.0),
+        (-115.0, 68.5),
+        (-95.0, 68.0),
+        (-87.0, 67.0),
+        (-82.0, 66.5),
+        (-86.0, 64.0),
+        (-94.0, 61.0),
+        (-93.0, 58.8),
+        (-87.0, 56.0),
+        (-82.0, 55.0),
+        (-79.5, 51.5),
+        (-79.0, 54.0),
+        (-77.0, 60.0),
+        (-78.0, 62.5),
+        (-72.0, 62.0),
+        (-69.0, 59.0),
+        (-64.0, 60.3),
+        (-61.0, 56.0),
+        (-56.0, 52.0),
+        (-60.0, 47.0),
+        (-66.0, 45.0),
+        (-70.0, 43.0),
+        (-76.0, 35.0),
+        (-81.0, 31.0),
+        (-80.0, 25.5),
+        (-83.0, 29.5),
+        (-90.0, 29.0),
+        (-97.0, 27.5),
+        (-97.0, 22.0),
+        (-94.0, 18.5),
+        (-90.5, 19.5),
+        (-87.0, 21.5),
+        (-88.0, 16.0),
+        (-83.0, 15.0),
+        (-83.0, 10.0),
+        (-77.5, 8.5),
+        (-80.0, 7.5),
+        (-86.0, 11.0),
+        (-92.0, 14.5),
+        (-105.5, 20.0),
+        (-106.0, 23.0),
+        (-114.5, 31.5),
+        (-112.5, 28.0),
+        (-109.5, 23.0),
+        (-112.0, 25.0),
+        (-115.0, 28.0),
+        (-117.0, 32.5),
+        (-121.0, 35.0),
+        (-124.0, 40.0),
+        (-124.0, 47.0),
+        (-123.0, 49.0),
+        (-133.0, 56.0),
+        (-140.0, 60.0),
+        (-148.0, 61.0),
+        (-153.0, 58.0),
+        (-158.0, 57.0),
+        (-165.0, 54.5),
+        (-160.0, 59.0),
+        (-165.0, 61.0),
+        (-166.0, 64.0),
+    ],
+    // Canadian Arctic
+    &[
+        (-120.0, 72.0),
+        (-100.0, 73.0),
+        (-95.0, 77.0),
+        (-80.0, 80.0),
+        (-62.0, 82.0),
+        (-75.0, 79.0),
+        (-90.0, 76.0),
+        (-105.0, 78.0),
+        (-118.0, 77.0),
+    ],
+    &[
+        (-80.0, 73.0),
+        (-70.0, 70.0),
+        (-62.0, 66.0),
+        (-66.0, 62.0),
+        (-75.0, 64.0),
+        (-80.0, 68.0),
+    ],
+    // Greenland
+    &[
+        (-73.0, 78.0),
+        (-60.0, 82.0),
+        (-30.0, 83.5),
+        (-18.0, 81.0),
+        (-20.0, 75.0),
+        (-22.0, 70.0),
+        (-32.0, 68.0),
+        (-40.0, 65.0),
+        (-43.0, 60.0),
+        (-50.0, 64.0),
+        (-54.0, 68.0),
+        (-57.0, 75.0),
+        (-68.0, 77.0),
+    ],
+    &[
+        (-85.0, 21.9),
+        (-82.0, 23.2),
+        (-77.5, 21.8),
+        (-74.2, 20.2),
+        (-77.7, 19.9),
+        (-80.0, 21.8),
+        (-82.5, 22.2),
+    ],
+    &[
+        (-74.4, 18.4),
+        (-72.8, 19.9),
+        (-69.0, 19.7),
+        (-68.4, 18.5),
+        (-71.5, 17.7),
+    ],
+    // South America
+    &[
+        (-77.0, 8.5),
+        (-72.0, 12.0),
+        (-64.0, 10.5),
+        (-60.0, 8.5),
+        (-52.0, 5.0),
+        (-50.0, 0.0),
+        (-44.0, -2.5),
+        (-35.0, -5.5),
+        (-39.0, -13.0),
+        (-41.0, -22.0),
+        (-48.0, -26.0),
+        (-53.0, -34.0),
+        (-58.0, -34.5),
+        (-57.0, -38.0),
+        (-62.0, -39.0),
+        (-65.0, -42.0),
+        (-68.0, -50.0),
+        (-69.0, -52.5),
+        (-68.0, -55.0),
+        (-72.0, -53.5),
+        (-75.0, -48.0),
+        (-73.5, -40.0),
+        (-71.5, -30.0),
+        (-70.0, -18.0),
+        (-76.0, -14.0),
+        (-81.0, -5.0),
+        (-80.0, -1.0),
+        (-77.5, 3.5),
+        (-78.0, 7.0),
+    ],
+    &[
+        (-24.0, 65.5),
+        (-22.0, 66.4),
+        (-15.0, 66.5),
+        (-13.5, 65.0),
+        (-18.0, 63.4),
+        (-22.5, 63.8),
+    ],
+    // Great Britain and Ireland
+    &[
+        (-5.7, 50.1),
+        (1.4, 51.2),
+        (1.7, 52.7),
+        (0.0, 53.5),
+        (-1.6, 55.5),
+        (-2.0, 57.0),
+        (-3.5, 58.6),
+        (-5.0, 58.6),
+        (-6.2, 56.5),
+        (-5.0, 55.0),
+        (-3.0, 54.5),
+        (-3.0, 53.3),
+        (-4.7, 52.8),
+        (-5.0, 51.6),
+        (-3.0, 51.2),
+    ],
+    &[
+        (-6.0, 52.0),
+        (-6.0, 54.0),
+        (-7.5, 55.3),
+        (-10.0, 54.0),
+        (-10.3, 51.8),
+        (-8.0, 51.6),
+    ],
+    // Africa
+    &[
+        (-17.0, 21.0),
+        (-17.0, 14.7),
+        (-13.0, 9.0),
+        (-8.0, 4.5),
+        (-2.0, 5.0),
+        (4.0, 6.3),
+        (9.0, 4.0),
+        (9.5, 1.0),
+        (12.0, -5.0),
+        (13.5, -12.0),
+        (11.8, -17.0),
+        (15.0, -27.0),
+        (18.4, -34.0),
+        (20.0, -34.8),
+        (25.5, -34.0),
+        (31.0, -29.5),
+        (32.7, -26.0),
+        (35.5, -24.0),
+        (35.0, -19.5),
+        (40.5, -15.0),
+        (39.5, -7.0),
+        (41.5, -1.5),
+        (48.0, 5.0),
+        (51.0, 11.8),
+        (43.5, 11.5),
+        (39.5, 15.5),
+        (37.0, 21.0),
+        (34.0, 27.5),
+        (32.5, 30.0),
+        (29.0, 31.0),
+        (25.0, 31.8),
+        (20.0, 30.5),
+        (19.5, 32.0),
+        (11.0, 33.0),
+        (10.0, 37.0),
+        (3.0, 36.8),
+        (-2.0, 35.2),
+        (-6.0, 35.8),
+        (-9.5, 30.5),
+        (-13.0, 27.5),
+    ],
+    &[
+        (49.2, -12.0),
+        (50.5, -15.5),
+        (47.2, -25.0),
+        (44.0, -24.5),
+        (43.3, -21.5),
+        (44.5, -16.5),
+    ],
+    // Eurasia
+    &[
+        (-9.0, 36.9),
+        (-9.5, 39.0),
+        (-9.0, 43.0),
+        (-1.5, 43.5),
+        (-1.2, 46.0),
+        (-4.5, 48.5),
+        (-1.5, 49.7),
+        (2.0, 51.0),
+        (4.0, 51.5),
+        (5.0, 53.3),
+        (8.5, 53.8),
+        (8.3, 57.0),
+        (10.5, 57.7),
+        (10.5, 54.5),
+        (14.0, 54.0),
+        (19.5, 54.5),
+        (21.0, 56.5),
+        (24.0, 57.5),
+        (24.0, 59.4),
+        (29.5, 60.0),
+        (22.5, 60.5),
+        (21.5, 63.0),
+        (25.0, 65.5),
+        (22.0, 65.8),
+        (17.5, 62.5),
+        (19.0, 60.0),
+        (16.0, 56.2),
+        (12.6, 56.0),
+        (11.0, 58.9),
+        (7.0, 58.0),
+        (5.0, 59.0),
+        (5.5, 62.0),
+        (12.0, 66.0),
+        (15.0, 68.5),
+        (19.0, 70.0),
+        (25.0, 71.1),
+        (31.0, 70.0),
+        (33.0, 69.3),
+        (41.0, 67.0),
+        (40.0, 64.5),
+        (44.0, 66.0),
+        (44.0, 68.5),
+        (53.0, 68.5),
+        (60.0, 69.5),
+        (68.0, 69.0),
+        (67.0, 71.5),
+        (72.0, 72.7),
+        (80.0, 72.5),
+        (85.0, 74.0),
+        (95.0, 76.0),
+        (104.0, 77.7),
+        (113.0, 74.0),
+        (128.0, 72.5),
+        (140.0, 72.5),
+        (150.0, 71.5),
+        (160.0, 69.7),
+        (170.0, 70.0),
+        (180.0, 69.0),
+        (180.0, 65.0),
+        (174.0, 61.5),
+        (166.0, 60.0),
+        (163.0, 58.0),
+        (162.0, 56.0),
+        (156.5, 51.0),
+        (156.0, 57.0),
+        (160.0, 61.5),
+        (155.0, 59.5),
+        (142.0, 59.3),
+        (135.0, 54.5),
+        (141.0, 52.0),
+        (140.5, 48.5),
+        (135.0, 43.5),
+        (130.0, 42.5),
+        (129.5, 40.0),
+        (128.5, 38.0),
+        (129.4, 35.5),
+        (126.5, 34.5),
+        (126.0, 37.0),
+        (124.5, 40.0),
+        (121.5, 39.0),
+        (122.0, 40.8),
+        (117.7, 39.0),
+        (119.0, 37.0),
+        (122.5, 37.0),
+        (119.0, 35.0),
+        (121.5, 31.5),
+        (122.0, 29.5),
+        (119.5, 25.5),
+        (116.5, 23.0),
+        (110.5, 21.0),
+        (108.0, 21.5),
+        (106.5, 20.0),
+        (106.0, 18.0),
+        (109.0, 15.0),
+        (109.0, 11.5),
+        (105.0, 8.7),
+        (105.0, 10.5),
+        (103.0, 10.5),
+        (100.5, 13.5),
+        (99.5, 10.0),
+        (100.3, 6.5),
+        (103.4, 4.0),
+        (104.0, 1.3),
+        (101.3, 2.8),
+        (100.3, 5.5),
+        (98.3, 8.0),
+        (98.6, 10.0),
+        (98.0, 16.5),
+        (94.0, 16.0),
+        (94.5, 19.5),
+        (92.0, 21.5),
+        (90.0, 22.0),
+        (86.5, 20.0),
+        (80.3, 15.5),
+        (80.0, 10.0),
+        (77.5, 8.0),
+        (76.0, 10.0),
+        (73.0, 17.0),
+        (72.5, 21.0),
+        (68.5, 23.5),
+        (66.5, 25.3),
+        (61.5, 25.2),
+        (57.3, 25.8),
+        (56.3, 27.2),
+        (54.0, 26.7),
+        (51.3, 27.9),
+        (50.0, 30.0),
+        (48.5, 30.0),
+        (48.5, 28.2),
+        (50.0, 26.0),
+        (51.5, 25.3),
+        (51.5, 24.0),
+        (54.5, 24.2),
+        (56.3, 26.2),
+        (56.5, 24.5),
+        (59.8, 22.5),
+        (57.8, 19.0),
+        (55.0, 17.0),
+        (52.0, 15.5),
+        (45.0, 12.8),
+        (43.3, 12.7),
+        (42.8, 15.0),
+        (39.0, 21.5),
+        (35.0, 28.0),
+        (34.3, 28.0),
+        (32.6, 30.0),
+        (34.9, 32.0),
+        (35.9, 35.5),
+        (36.0, 36.8),
+        (32.5, 36.1),
+        (29.0, 36.5),
+        (27.3, 37.5),
+        (26.3, 40.0),
+        (23.0, 40.5),
+        (24.0, 38.0),
+        (22.5, 36.5),
+        (21.5, 37.5),
+        (21.0, 38.5),
+        (19.5, 41.5),
+        (13.5, 45.5),
+        (12.3, 44.5),
+        (16.0, 41.5),
+        (18.5, 40.0),
+        (16.0, 38.0),
+        (15.6, 38.0),
+        (15.5, 40.0),
+        (12.0, 42.0),
+        (10.0, 44.0),
+        (7.5, 43.8),
+        (3.0, 43.3),
+        (3.2, 42.0),
+        (0.5, 40.5),
+        (-0.5, 38.5),
+        (-2.0, 36.7),
+        (-5.5, 36.0),
+    ],
+    &[
+        (11.0, 78.5),
+        (16.0, 80.0),
+        (27.0, 80.0),
+        (22.0, 77.5),
+        (16.5, 76.5),
+    ],
+    &[
+        (52.0, 71.5),
+        (57.0, 70.7),
+        (60.0, 74.0),
+        (69.0, 76.8),
+        (62.0, 76.2),
+    ],
+    &[(79.8, 8.0), (81.0, 6.0), (81.8, 7.5), (80.2, 9.8)],
+    // Japan
+    &[
+        (130.0, 31.0),
+        (131.5, 31.4),
+        (132.0, 33.8),
+        (135.0, 33.5),
+        (140.0, 35.0),
+        (141.0, 38.0),
+        (141.5, 41.5),
+        (143.0, 42.0),
+        (145.5, 43.3),
+        (141.7, 45.4),
+        (140.0, 42.5),
+        (140.0, 40.5),
+        (139.7, 38.5),
+        (136.8, 37.2),
+        (133.0, 35.5),
+        (131.0, 34.3),
+        (129.7, 33.2),
+    ],
+    &[(120.1, 23.0), (121.0, 25.3), (121.9, 24.9), (120.8, 22.0)],
+    &[
+        (120.0, 18.5),
+        (122.3, 18.5),
+        (122.0, 16.0),
+        (124.0, 12.5),
+        (126.0, 9.5),
+        (126.0, 6.5),
+        (124.0, 6.0),
+        (122.0, 7.0),
+        (123.0, 10.0),
+        (120.5, 14.5),
+        (120.0, 16.0),
+    ],
+    // Indonesia and New Guinea
+    &[
+        (95.3, 5.6),
+        (97.5, 5.2),
+        (100.3, 2.0),
+        (104.0, -1.0),
+        (106.0, -3.0),
+        (105.8, -5.8),
+        (104.5, -5.8),
+        (101.0, -2.5),
+        (98.7, 1.7),
+    ],
+    &[
+        (105.2, -6.8),
+        (108.0, -6.2),
+        (112.6, -6.9),
+        (114.6, -7.7),
+        (114.5, -8.7),
+        (110.0, -8.1),
+        (106.5, -7.4),
+    ],
+    &[
+        (109.0, 1.5),
+        (110.0, -0.5),
+        (110.2, -2.9),
+        (114.5, -3.8),
+        (116.3, -3.5),
+        (116.8, -1.0),
+        (117.8, 1.0),
+        (119.0, 5.0),
+        (117.0, 7.0),
+        (115.5, 5.0),
+        (113.0, 3.0),
+        (111.0, 1.8),
+    ],
+    &[
+        (131.0, -1.2),
+        (134.0, -0.8),
+        (138.0, -1.6),
+        (141.0, -2.6),
+        (146.0, -5.6),
+        (147.8, -6.5),
+        (150.5, -10.5),
+        (147.0, -10.0),
+        (143.5, -9.0),
+        (142.0, -9.2),
+        (139.0, -8.0),
+        (137.8, -5.3),
+        (134.0, -4.0),
+        (132.0, -2.8),
+    ],
+    // Australia and New Zealand
+    &[
+        (113.5, -22.0),
+        (114.2, -26.0),
+        (115.0, -34.3),
+        (118.0, -35.0),
+        (123.5, -33.9),
+        (131.0, -31.5),
+        (135.5, -34.8),
+        (137.8, -32.7),
+        (138.0, -35.5),
+        (140.5, -38.0),
+        (144.0, -38.3),
+        (146.5, -39.0),
+        (150.0, -37.5),
+        (153.6, -28.2),
+        (153.0, -25.0),
+        (150.8, -22.5),
+        (146.0, -18.5),
+        (145.3, -14.9),
+        (143.5, -14.0),
+        (142.5, -10.7),
+        (141.5, -13.0),
+        (141.5, -17.0),
+        (140.0, -17.7),
+        (136.0, -15.5),
+        (137.0, -12.0),
+        (132.5, -11.3),
+        (130.0, -13.0),
+        (129.0, -15.0),
+        (126.0, -14.0),
+        (122.0, -17.7),
+        (121.0, -19.5),
+        (117.0, -20.7),
+        (114.0, -21.8),
+    ],
+    &[
+        (144.6, -40.7),
+        (148.3, -40.9),
+        (148.0, -43.2),
+        (146.8, -43.6),
+        (145.2, -42.2),
+    ],
+    &[
+        (172.7, -34.4),
+        (175.9, -37.3),
+        (178.5, -37.7),
+        (177.0, -39.3),
+        (176.0, -41.3),
+        (174.7, -41.3),
+        (174.6, -39.0),
+        (173.8, -39.2),
+        (174.2, -37.0),
+    ],
+    &[
+        (172.7, -40.5),
+        (174.3, -41.7),
+        (172.8, -43.5),
+        (171.0, -45.0),
+        (169.0, -46.7),
+        (166.5, -46.0),
+        (168.3, -44.0),
+        (171.0, -42.0),
+    ],
+];
+
+// Inland seas cut back out of the land
+const WATER: &[&[(f64, f64)]] = &[
+    // Black Sea
+    &[
+        (27.8, 42.5),
+        (28.2, 44.5),
+        (30.5, 46.4),
+        (33.0, 46.0),
+        (33.6, 44.5),
+        (36.5, 45.3),
+        (39.5, 47.1),
+        (38.0, 45.0),
+        (40.0, 43.4),
+        (41.6, 41.5),
+        (36.0, 41.7),
+        (31.0, 41.1),
+        (29.0, 41.2),
+    ],
+    // Caspian Sea
+    &[
+        (49.0, 46.5),
+        (53.0, 47.0),
+        (53.5, 45.0),
+        (51.0, 44.5),
+        (53.0, 42.0),
+        (54.0, 37.5),
+        (51.0, 36.8),
+        (49.0, 37.6),
+        (49.5, 40.5),
+        (47.5, 43.0),
+        (47.5, 45.5),
+    ],
+];
+
+pub struct MapDot {
+    pub name: String,
+    pub place: Coordinates,
+}
+
+// Where a point lands on the strip, as fractions of its width and height
+fn project(lon: f64, lat: f64) -> (f32, f32) {
+    let x = (lon + 180.0) / 360.0;
+    let y = (TOP - lat) / (TOP - BOTTOM);
+    (x.clamp(0.0, 1.0) as f32, y.clamp(0.0, 1.0) as f32)
+}
+
+fn to_pixels(bounds: Bounds<Pixels>, (lon, lat): (f64, f64)) -> Point<Pixels> {
+    let (x, y) = project(lon, lat);
+    point(
+        bounds.origin.x + bounds.size.width * x,
+        bounds.origin.y + bounds.size.height * y,
+    )
+}
+
+fn paint_ring(bounds: Bounds<Pixels>, ring: &[(f64, f64)], color: Hsla, window: &mut Window) {
+    let mut builder = PathBuilder::fill();
+    for (index, &vertex) in ring.iter().enumerate() {
+        let at = to_pixels(bounds, vertex);
+        if index == 0 {
+            builder.move_to(at);
+        } else {
+            builder.line_to(at);
+        }
+    }
+    builder.close();
+    if let Ok(path) = builder.build() {
+        window.paint_path(path, color);
+    }
+}
+
+// The night side: the terminator curve from edge to edge, closed over the dark pole
+fn night_ring(subsolar: Coordinates) -> Vec<(f64, f64)> {
+    let pole = sun::night_pole_latitude(subsolar);
+    (-180..=180)
+        .step_by(3)
+        .map(|lon| (lon as f64, sun::terminator_latitude(lon as f64, subsolar)))
+        .chain([(180.0, pole), (-180.0, pole)])
+        .collect()
+}
+
+pub fn world_map(
+    dots: Vec<MapDot>,
+    subsolar: Coordinates,
+    cx: &mut Context<WorldTimeApp>,
+) -> impl IntoElement {
+    let ocean = cx.theme().secondary;
+    let land = cx.theme().muted_foreground.opacity(0.35);
+    let night = gpui::black().opacity(0.3);
+
+    div()
+        .relative()
+        .w_full()
+        .h(px(110.0))
+        .rounded(px(4.0))
+        .overflow_hidden()
+        .child(
+            canvas(
+                |_, _, _| {},
+                move |bounds, _, window, _| {
+                    window.paint_quad(fill(bounds, ocean));
+                    for ring in LAND {
+                        paint_ring(bounds, ring, land, window);
+                    }
+                    for ring in WATER {
+                        paint_ring(bounds, ring, ocean, window);
+                    }
+                    paint_ring(bounds, &night_ring(subsolar), night, window);
+                },
+            )
+            .size_full(),
+        )
+        .children(dots.into_iter().enumerate().map(|(index, dot)| {
+            let (x, y) = project(dot.place.lon, dot.place.lat);
+            let daylight = sun::is_daylight(dot.place, subsolar);
+            let label = SharedString::from(format!(
+                "{} ({})",
+                dot.name,
+                if daylight { "day" } else { "night" }
+            ));
+            let name = dot.name;
+            div()
+                .id(("map-dot", index))
+                .absolute()
+                .left(relative(x))
+                .top(relative(y))
+                .ml(px(-DOT / 2.0))
+                .mt(px(-DOT / 2.0))
+                .size(px(DOT))
+                .rounded_full()
+                .border_1()
+                .border_color(rgb(0xffffff))
+                .bg(if daylight {
+                    rgb(0xf59e0b)
+                } else {
+                    rgb(0x6366f1)
+                })
+                .cursor_pointer()
+                .tooltip(move |window, cx| Tooltip::new(label.clone()).build(window, cx))
+                .on_click(
+                    cx.listener(move |this, _, window, cx| this.focus_card(&name, window, cx)),
+                )
+        }))
+}
@@ -0,0 +1,450 @@
+use crate::{commands, offset_override};
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use chrono_tz::{OffsetComponents, TZ_VARIANTS, Tz};
+use std::fmt;
+use time2rust::format;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnknownZone(pub String);
+
+impl fmt::Display for UnknownZone {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown timezone \"{}\"", self.0)
+    }
+}
+
+// Resolves an IANA id like "Europe/Berlin", ignoring case as a convenience
+pub fn resolve_zone(id: &str) -> Result<Tz, UnknownZone> {
+    let id = id.trim();
+    if let Ok(tz) = id.parse::<Tz>() {
+        return Ok(tz);
+    }
+    TZ_VARIANTS
+        .iter()
+        .find(|tz| tz.name().eq_ignore_ascii_case(id))
+        .copied()
+        .ok_or_else(|| UnknownZone(id.to_string()))
+}
+
+// This machine's zone, as the OS reports it
+pub fn system_zone() -> Result<Tz, String> {
+    let id = iana_time_zone::get_timezone()
+        .map_err(|err| format!("couldn't detect this machine's timezone: {}", err))?;
+    resolve_zone(&id).map_err(|err| err.to_string())
+}
+
+// The city part of a zone id, readable: America/New_York → "New York"
+pub fn zone_city_name(tz: Tz) -> String {
+    tz.name()
+        .rsplit('/')
+        .next()
+        .unwrap_or(tz.name())
+        .replace('_', " ")
+}
+
+// Finds the zone named after a city, e.g. "new york" → America/New_York
+pub fn find_zone_by_city(city: &str) -> Option<Tz> {
+    let city = city.trim().replace(' ', "_");
+    TZ_VARIANTS
+        .iter()
+        .find(|tz| {
+            tz.name()
+                .rsplit('/')
+                .next()
+                .is_some_and(|name| name.eq_ignore_ascii_case(&city))
+        })
+        .copied()
+}
+
+// The closest zone id to something that isn't one: a city name first, then the
+// best fuzzy match over the ids
+pub fn suggest_zone(query: &str) -> Option<Tz> {
+    find_zone_by_city(query).or_else(|| {
+        TZ_VARIANTS
+            .iter()
+            .filter_map(|tz| commands::fuzzy_score(query, tz.name()).map(|score| (score, *tz)))
+            .min_by_key(|(score, _)| *score)
+            .map(|(_, tz)| tz)
+    })
+}
+
+// Offset from UTC in minutes at the given instant, DST included; the cards use it too
+pub use time2rust::card::utc_offset_minutes;
+
+// Like "UTC+05:30" or "UTC-06:00"
+pub fn format_utc_offset(minutes: i32) -> String {
+    let sign = if minutes < 0 { '-' } else { '+' };
+    let minutes = minutes.abs();
+    format!("UTC{}{:02}:{:02}", sign, minutes / 60, minutes % 60)
+}
+
+// True while the zone is observing daylight saving time
+pub fn is_dst(tz: Tz, instant: DateTime<Utc>) -> bool {
+    standard_shift_minutes(tz, instant) != 0
+}
+
+fn dst_minutes(tz: Tz, instant: DateTime<Utc>) -> i32 {
+    tz.offset_from_utc_datetime(&instant.naive_utc())
+        .dst_offset()
+        .num_minutes() as i32
+}
+
+// How far the zone's offset is from its standard one right now: +60 in summer
+// time, +120 under double summer time, +30 on Lord Howe. The tz data stores a few
+// zones with a negative DST instead, Dublin in winter and Casablanca in Ramadan,
+// so the lowest value of the past year counts as standard: Dublin is +1h in
+// summer, and Casablanca, which kept summer time for good, +1h outside Ramadan.
+pub fn standard_shift_minutes(tz: Tz, instant: DateTime<Utc>) -> i32 {
+    let lowest = (0..=53)
+        .map(|weeks| dst_minutes(tz, instant - Duration::weeks(weeks)))
+        .min()
+        .unwrap_or(0)
+        .min(0);
+    dst_minutes(tz, instant) - lowest
+}
+
+// The offset the zone keeps outside summer time, like +60 for Berlin all year
+pub fn standard_offset_minutes(tz: Tz, instant: DateTime<Utc>) -> i32 {
+    utc_offset_minutes(tz, instant) - standard_shift_minutes(tz, instant)
+}
+
+// "1h ahead of its standard UTC+10:00", or "On its standard UTC+01:00"
+pub fn describe_standard_shift(tz: Tz, instant: DateTime<Utc>) -> String {
+    let shift = standard_shift_minutes(tz, instant);
+    let standard = format_utc_offset(standard_offset_minutes(tz, instant));
+    match shift {
+        0 => format!("On its standard {}", standard),
+        shift => format!(
+            "{} {} its standard {}",
+            format_shift_magnitude(shift),
+            if shift > 0 { "ahead of" } else { "behind" },
+            standard
+        ),
+    }
+}
+
+// "1h", "30m", "1h 30m"
+fn format_shift_magnitude(minutes: i32) -> String {
+    offset_override::format_shift(minutes.abs())
+        .trim_start_matches('+')
+        .to_string()
+}
+
+// A future shift in a zone's UTC offset
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OffsetChange {
+    pub at: DateTime<Utc>, // first instant with the new offset
+    pub from_minutes: i32,
+    pub to_minutes: i32,
+}
+
+const CHANGE_SEARCH_DAYS: i64 = 400;
+
+// The instant `value` next changes when walking from `from`, forwards for a step
+// of 1 and backwards for -1. Steps a day at a time, then narrows to the second,
+// so it works the same for northern, southern and no-DST zones. Forwards that's
+// the first instant with the new value; backwards, the first with the current one.
+fn find_change(
+    from: DateTime<Utc>,
+    step: i64,
+    value: impl Fn(DateTime<Utc>) -> i32,
+) -> Option<DateTime<Utc>> {
+    let current = value(from);
+    let mut same = from;
+    for day in 1..=CHANGE_SEARCH_DAYS {
+        let other = from + Duration::days(day * step);
+        if value(other) == current {
+            same = other;
+            continue;
+        }
+        // Transitions fall on whole seconds
+        let (mut same, mut other) = (same.timestamp(), other.timestamp());
+        while (other - same).abs() > 1 {
+            let middle = same + (other - same) / 2;
+            if value(DateTime::from_timestamp(middle, 0)?) == current {
+                same = middle;
+            } else {
+                other = middle;
+            }
+        }
+        return DateTime::from_timestamp(if step > 0 { other } else { same }, 0);
+    }
+    None
+}
+
+// The next offset change after `after`, if any within a little over a year
+pub fn next_offset_change(tz: Tz, after: DateTime<Utc>) -> Option<OffsetChange> {
+    let at = find_change(after, 1, |instant| utc_offset_minutes(tz, instant))?;
+    Some(OffsetChange {
+        at,
+        from_minutes: utc_offset_minutes(tz, after),
+        to_minutes: utc_offset_minutes(tz, at),
+    })
+}
+
+// How long two zones have been this far apart and when that next changes. None
+// on either side means not within a little over a year.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DiffSpan {
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+    pub next_diff_minutes: Option<i32>,
+}
+
+// Walks the difference itself rather than each zone's changes, so two zones
+// switching at the same instant don't count as a change
+pub fn diff_span(home: Tz, remote: Tz, at: DateTime<Utc>) -> DiffSpan {
+    let diff = |instant| utc_offset_minutes(remote, instant) - utc_offset_minutes(home, instant);
+    let until = find_change(at, 1, diff);
+    DiffSpan {
+        since: find_change(at, -1, diff),
+        until,
+        next_diff_minutes: until.map(diff),
+    }
+}
+
+// "DST ends Sun Oct 26 → UTC+01:00", dated in the zone itself
+pub fn describe_offset_change(tz: Tz, change: &OffsetChange) -> String {
+    let label = match (
+        is_dst(tz, change.at - Duration::seconds(1)),
+        is_dst(tz, change.at),
+    ) {
+        (false, true) => "DST starts",
+        (true, false) => "DST ends",
+        _ => "Offset changes",
+    };
+    format!(
+        "{} {} → {}",
+        label,
+        format::weekday_date(change.at.with_timezone(&tz).date_naive()),
+        format_utc_offset(change.to_minutes)
+    )
+}
+
+// "clocks go forward in 12 days", counting hours on the final day
+pub fn describe_change_countdown(change: &OffsetChange, now: DateTime<Utc>) -> String {
+    let direction = if change.to_minutes > change.from_minutes {
+        "forward"
+    } else {
+        "back"
+    };
+    let left = change.at - now;
+    let when = match (left.num_days(), left.num_hours()) {
+        (0, 0) => "within the hour".to_string(),
+        (0, 1) => "in 1 hour".to_string(),
+        (0, hours) => format!("in {} hours", hours),
+        (1, _) => "in 1 day".to_string(),
+        (days, _) => format!("in {} days", days),
+    };
+    format!("clocks go {} {}", direction, when)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use chrono_tz::{Africa, America, Asia, Australia, Europe};
+
+    fn utc(year: i32, month: u32, day: u32, hour: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(year, month, day, hour, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn tokyo_has_no_offset_change_coming() {
+        assert_eq!(next_offset_change(Asia::Tokyo, utc(2026, 3, 1, 0)), None);
+    }
+
+    #[test]
+    fn sydney_changes_in_april_and_october() {
+        // AEDT ends at 03:00 on Sun Apr 5 2026, 16:00 UTC the day before
+        let autumn = OffsetChange {
+            at: utc(2026, 4, 4, 16),
+            from_minutes: 660,
+            to_minutes: 600,
+        };
+        assert_eq!(
+            next_offset_change(Australia::Sydney, utc(2026, 1, 15, 0)),
+            Some(autumn)
+        );
+        // And comes back at 02:00 on Sun Oct 4
+        let spring = OffsetChange {
+            at: utc(2026, 10, 3, 16),
+            from_minutes: 600,
+            to_minutes: 660,
+        };
+        assert_eq!(
+            next_offset_change(Australia::Sydney, utc(2026, 6, 1, 0)),
+            Some(spring)
+        );
+    }
+
+    #[test]
+    fn after_on_the_transition_day() {
+        // 00:30 on Sydney's Apr 5, hours before the change
+        assert_eq!(
+            next_offset_change(
+                Australia::Sydney,
+                utc(2026, 4, 4, 13) + Duration::minutes(30)
+            )
+            .map(|change| change.at),
+            Some(utc(2026, 4, 4, 16))
+        );
+        // A second before it, it's still this change; at it, October's is next
+        let change = next_offset_change(
+            Australia::Sydney,
+            utc(2026, 4, 4, 16) - Duration::seconds(1),
+        );
+        assert_eq!(change.map(|change| change.at), Some(utc(2026, 4, 4, 16)));
+        let change = next_offset_change(Australia::Sydney, utc(2026, 4, 4, 16)).unwrap();
+        assert_eq!(change.at, utc(2026, 10, 3, 16));
+        assert_eq!(change.from_minutes, 600);
+        // Later the same local day, after the change
+        assert_eq!(
+            next_offset_change(Australia::Sydney, utc(2026, 4, 5, 0)).map(|change| change.at),
+            Some(utc(2026, 10, 3, 16))
+        );
+    }
+
+    #[test]
+    fn diff_span_in_the_spring_mismatch_weeks() {
+        // The US springs forward on Mar 9 2025, Europe on Mar 30
+        let span = diff_span(America::New_York, Europe::Berlin, utc(2025, 3, 15, 12));
+        assert_eq!(
+            span,
+            DiffSpan {
+                since: Some(utc(2025, 3, 9, 7)),
+                until: Some(utc(2025, 3, 30, 1)),
+                next_diff_minutes: Some(360),
+            }
+        );
+    }
+
+    #[test]
+    fn diff_span_in_the_autumn_mismatch_week() {
+        // Europe falls back on Oct 26 2025, the US on Nov 2
+        let span = diff_span(America::New_York, Europe::Berlin, utc(2025, 10, 28, 12));
+        assert_eq!(
+            span,
+            DiffSpan {
+                since: Some(utc(2025, 10, 26, 1)),
+                until: Some(utc(2025, 11, 2, 6)),
+                next_diff_minutes: Some(360),
+            }
+        );
+    }
+
+    #[test]
+    fn diff_span_over_the_summer_runs_between_the_european_changes() {
+        let span = diff_span(America::New_York, Europe::Berlin, utc(2025, 7, 1, 12));
+        assert_eq!(span.since, Some(utc(2025, 3, 30, 1)));
+        assert_eq!(span.until, Some(utc(2025, 10, 26, 1)));
+        assert_eq!(span.next_diff_minutes, Some(300));
+    }
+
+    #[test]
+    fn diff_span_between_zones_without_dst_never_ends() {
+        let span = diff_span(Asia::Tokyo, Asia::Kolkata, utc(2025, 7, 1, 12));
+        assert_eq!(
+            span,
+            DiffSpan {
+                since: None,
+                until: None,
+                next_diff_minutes: None,
+            }
+        );
+    }
+
+    #[test]
+    fn southern_summer_time_is_in_january() {
+        let january = utc(2026, 1, 15, 12);
+        assert_eq!(standard_shift_minutes(Australia::Sydney, january), 60);
+        assert_eq!(standard_offset_minutes(Australia::Sydney, january), 600);
+        assert_eq!(
+            describe_standard_shift(Australia::Sydney, january),
+            "1h ahead of its standard UTC+10:00"
+        );
+        assert_eq!(standard_shift_minutes(America::Santiago, january), 60);
+        assert_eq!(standard_offset_minutes(America::Santiago, january), -240);
+        assert_eq!(standard_shift_minutes(Australia::Lord_Howe, january), 30);
+
+        let july = utc(2026, 7, 15, 12);
+        assert_eq!(standard_shift_minutes(Australia::Sydney, july), 0);
+        assert_eq!(standard_shift_minutes(America::Santiago, july), 0);
+    }
+
+    #[test]
+    fn negative_dst_zones_count_their_lowest_offset_as_standard() {
+        // Dublin's tz data puts winter on -1h; it reads as summer time in July
+        assert_eq!(
+            standard_shift_minutes(Europe::Dublin, utc(2026, 1, 15, 12)),
+            0
+        );
+        assert_eq!(
+            standard_shift_minutes(Europe::Dublin, utc(2026, 7, 15, 12)),
+            60
+        );
+        assert_eq!(
+            describe_standard_shift(Europe::Dublin, utc(2026, 7, 15, 12)),
+            "1h ahead of its standard UTC+00:00"
+        );
+        // Casablanca drops to UTC+0 for Ramadan and is on +1 the rest of the year
+        assert_eq!(
+            standard_shift_minutes(Africa::Casablanca, utc(2026, 3, 1, 12)),
+            0
+        );
+        assert_eq!(
+            standard_shift_minutes(Africa::Casablanca, utc(2026, 7, 15, 12)),
+            60
+        );
+        assert_eq!(
+            standard_offset_minutes(Africa::Casablanca, utc(2026, 7, 15, 12)),
+            0
+        );
+    }
+
+    // What a card anchored to standard time shows: the difference between the two
+    // standard offsets, as the cards work it out each tick
+    fn live_and_standard(tz: Tz, home: Tz, instant: DateTime<Utc>) -> (i32, i32) {
+        (
+            utc_offset_minutes(tz, instant) - utc_offset_minutes(home, instant),
+            standard_offset_minutes(tz, instant) - standard_offset_minutes(home, instant),
+        )
+    }
+
+    #[test]
+    fn standard_diff_leaves_summer_time_out() {
+        let july = utc(2026, 7, 1, 12);
+        assert_eq!(live_and_standard(Europe::Berlin, Tz::UTC, july), (120, 60));
+        // Brisbane has no summer time; New York's takes an hour off the gap
+        assert_eq!(
+            live_and_standard(Australia::Brisbane, America::New_York, july),
+            (14 * 60, 15 * 60)
+        );
+        assert_eq!(
+            live_and_standard(America::New_York, Europe::Berlin, july),
+            (-360, -360)
+        );
+        // Summer on both sides, half a year apart
+        assert_eq!(
+            live_and_standard(Australia::Sydney, Europe::London, utc(2026, 1, 15, 12)),
+            (11 * 60, 10 * 60)
+        );
+        assert_eq!(
+            live_and_standard(Australia::Sydney, Europe::London, july),
+            (9 * 60, 10 * 60)
+        );
+    }
+
+    #[test]
+    fn standard_diff_matches_live_in_winter() {
+        let january = utc(2026, 1, 15, 12);
+        assert_eq!(
+            live_and_standard(Europe::Berlin, Tz::UTC, january),
+            (60, 60)
+        );
+        assert_eq!(
+            live_and_standard(Australia::Brisbane, America::New_York, january),
+            (15 * 60, 15 * 60)
+        );
+    }
+}
@@ -0,0 +1,152 @@
+use serde::{Deserialize, Serialize};
+
+// gpui's own rem size; text and spacing are in rems, so scaling it scales the UI
+pub const BASE_REM: f32 = 16.0;
+pub const PRESETS: [f32; 6] = [0.8, 0.9, 1.0, 1.1, 1.25, 1.5];
+// Per display, on top of the user's zoom
+pub const ADJUSTMENTS: [f32; 5] = [0.8, 0.9, 1.0, 1.1, 1.25];
+const MIN_REM: f32 = 8.0;
+const MAX_REM: f32 = 48.0;
+// Scale factors this close count as the same kind of display, e.g. 1.5 and 1.49
+const SCALE_TOLERANCE: f32 = 0.05;
+
+// An extra factor for displays at one scale factor, e.g. 1.1 on the 1x external
+// monitor so it matches the 2x laptop panel
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ScaleZoom {
+    pub scale: f32,
+    pub factor: f32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ZoomSettings {
+    pub factor: f32, // the user's own, one of PRESETS
+    pub by_scale: Vec<ScaleZoom>,
+}
+
+impl Default for ZoomSettings {
+    fn default() -> Self {
+        ZoomSettings {
+            factor: 1.0,
+            by_scale: Vec::new(),
+        }
+    }
+}
+
+impl ZoomSettings {
+    // 1.0 for a display without its own adjustment
+    pub fn adjustment(&self, scale: f32) -> f32 {
+        self.by_scale
+            .iter()
+            .find(|zoom| (zoom.scale - scale).abs() < SCALE_TOLERANCE)
+            .map_or(1.0, |zoom| or_one(zoom.factor))
+    }
+
+    // 1.0 drops the entry rather than keeping a no-op
+    pub fn set_adjustment(&mut self, scale: f32, factor: f32) {
+        self.by_scale
+            .retain(|zoom| (zoom.scale - scale).abs() >= SCALE_TOLERANCE);
+        if factor != 1.0 {
+            self.by_scale.push(ScaleZoom { scale, factor });
+        }
+    }
+}
+
+// The rem size for a window on a display at `scale`: the user's zoom times that
+// display's adjustment, kept within reason and rounded to whole device pixels so
+// text stays crisp at any scale factor
+pub fn rem_size(settings: &ZoomSettings, scale: f32) -> f32 {
+    let scale = or_one(scale);
+    let rem =
+        (BASE_REM * or_one(settings.factor) * settings.adjustment(scale)).clamp(MIN_REM, MAX_REM);
+    (rem * scale).round() / scale
+}
+
+// Hand-edited configs may hold anything; a factor or scale that isn't a positive
+// number counts as 1.0, as a NaN would get through the clamp
+fn or_one(value: f32) -> f32 {
+    if value.is_finite() && value > 0.0 {
+        value
+    } else {
+        1.0
+    }
+}
+
+// "1x", "1.5x", "2x"
+pub fn describe_scale(scale: f32) -> String {
+    format!("{}x", (scale * 100.0).round() / 100.0)
+}
+
+// "110%"
+pub fn percent(factor: f32) -> String {
+    format!("{}%", (factor * 100.0).round())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn zoom(factor: f32, by_scale: &[(f32, f32)]) -> ZoomSettings {
+        ZoomSettings {
+            factor,
+            by_scale: by_scale
+                .iter()
+                .map(|&(scale, factor)| ScaleZoom { scale, factor })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn rem_size_lands_on_whole_device_pixels() {
+        let default = ZoomSettings::default();
+        for scale in [1.0, 1.25, 1.5, 1.75, 2.0, 3.0] {
+            assert_eq!(rem_size(&default, scale), BASE_REM, "{}x", scale);
+        }
+        // 17.6px of rem becomes 18, 35 or 26 device pixels
+        let larger = zoom(1.1, &[]);
+        assert_eq!(rem_size(&larger, 1.0), 18.0);
+        assert_eq!(rem_size(&larger, 2.0), 17.5);
+        assert_eq!(rem_size(&larger, 1.5) * 1.5, 26.0);
+        assert_eq!(rem_size(&zoom(0.9, &[]), 1.25), 14.4);
+    }
+
+    #[test]
+    fn a_display_adjustment_applies_at_its_scale_only() {
+        // The 1x external monitor made to match the 2x laptop panel
+        let settings = zoom(1.0, &[(1.0, 1.25)]);
+        assert_eq!(rem_size(&settings, 1.0), 20.0);
+        assert_eq!(rem_size(&settings, 2.0), BASE_REM);
+        assert_eq!(zoom(1.0, &[(1.5, 1.1)]).adjustment(1.49), 1.1);
+        assert_eq!(zoom(1.0, &[(1.5, 1.1)]).adjustment(1.25), 1.0);
+    }
+
+    #[test]
+    fn nonsense_factors_and_scales_count_as_one() {
+        assert_eq!(rem_size(&zoom(1.0, &[(1.0, f32::NAN)]), 1.0), BASE_REM);
+        assert_eq!(rem_size(&zoom(1.0, &[(1.0, -2.0)]), 1.0), BASE_REM);
+        assert_eq!(rem_size(&zoom(f32::NAN, &[]), 2.0), BASE_REM);
+        assert_eq!(rem_size(&zoom(0.0, &[]), 1.0), BASE_REM);
+        assert_eq!(rem_size(&ZoomSettings::default(), f32::NAN), BASE_REM);
+        assert_eq!(rem_size(&ZoomSettings::default(), 0.0), BASE_REM);
+        // Sane but extreme ones are clamped
+        assert_eq!(rem_size(&zoom(10.0, &[]), 1.0), MAX_REM);
+        assert_eq!(rem_size(&zoom(0.1, &[]), 1.0), MIN_REM);
+    }
+
+    #[test]
+    fn setting_an_adjustment_of_one_drops_it() {
+        let mut settings = ZoomSettings::default();
+        settings.set_adjustment(1.0, 1.1);
+        settings.set_adjustment(1.02, 1.25);
+        assert_eq!(
+            settings.by_scale,
+            [ScaleZoom {
+                scale: 1.02,
+                factor: 1.25
+            }]
+        );
+        settings.set_adjustment(1.0, 1.0);
+        assert!(settings.by_scale.is_empty());
+    }
+}